@@ -0,0 +1,365 @@
+# Text shown to the player, in American English
+#
+# Every other language falls back to these messages when a translation is missing
+
+## Side panel
+
+label-fill = Fill
+label-center = Center
+label-corner = Corner
+label-color = Color
+label-new = New
+label-reset = Reset
+label-solve = Solve
+label-hint = Hint
+label-scramble = Scramble
+label-settings = Settings
+hint-cooldown = { $seconds }s
+
+tooltip-enter-digit = Enter { $digit } in the selected cells ({ $digit })
+tooltip-paint-color = Paint color { $digit } (T, then { $digit })
+tooltip-fill = Fill in digits (Q)
+tooltip-center-mark = Center marks (W)
+tooltip-corner-mark = Corner marks (E)
+tooltip-color = Color cells (T)
+tooltip-new = New puzzle (N)
+tooltip-reset = Reset the board (X)
+tooltip-solve = Show the solution (V)
+tooltip-remix = Remix the puzzle with new clues for the same solution (Y)
+tooltip-hint = Fill in a cell, adding to your time (J)
+tooltip-find-mistake = Find a mistake among conflicting digits (F)
+tooltip-check = Check your digits against the solution (D)
+tooltip-fill-candidates = Fill in all candidates as center marks (C)
+tooltip-coach = Ask for more help (Z)
+tooltip-stop-training = Stop practising (Escape)
+tooltip-toggle-rules = Show or hide the rules of this puzzle
+tooltip-skip-tutorial = Skip the tutorial (Escape)
+tooltip-lock = Lock in the givens and start playing (Ctrl + L)
+tooltip-minimize = Remove every given the puzzle doesn't need (Ctrl + M)
+tooltip-scramble = Scramble the unfilled cells into a new practice position (P)
+tooltip-settings = Change the settings (Ctrl + ,)
+
+## Status lines
+
+hud-remaining-cells = { $cells } { $cells ->
+        [one] cell
+       *[other] cells
+    } remaining ({ $percent }% complete)
+hud-mistakes = Mistakes: { $mistakes }
+hud-limited-mistakes = Mistakes: { $mistakes }/{ $limit }
+hud-seed = Seed: { $seed }
+hud-par-time = Par: { $time }
+hud-generating = Making a new puzzle…
+
+## Dialogs
+
+dialog-discard = Discard
+dialog-bookmark = Bookmark
+dialog-reset = Reset
+dialog-remix = Remix
+dialog-quit = Quit
+dialog-cancel = Cancel
+dialog-close = Close
+dialog-new-puzzle = New puzzle
+dialog-try-again = Try again
+dialog-levels = Levels
+dialog-play-later = Play later
+dialog-star = Star
+dialog-unstar = Unstar
+dialog-previous = Previous
+dialog-next = Next
+dialog-play = Play
+
+confirm-new-puzzle = Discard current progress?
+confirm-reset = Clear the board and start over?
+confirm-remix = Remix the puzzle and lose current progress?
+confirm-quit = Quit and lose current progress?
+confirm-progress = { $percent }% complete after { $time }
+
+game-over =
+    Game over!
+    { $mistakes } mistakes made
+
+victory-solved = Solved in { $time }!
+victory-penalty = Including { $penalty } of penalties for { $hints } { $hints ->
+        [one] hint
+       *[other] hints
+    }
+victory-par = Par { $par }
+victory-par-medal = Par { $par }, { $medal } medal!
+victory-placed-by-player = You placed { $count }
+victory-placed-by-hints = hints placed { $count }
+victory-placed-by-solver = the solver placed { $count }
+victory-new-best = New personal best for { $difficulty } puzzles!
+victory-best-times = Best times
+victory-best-time = { $difficulty }: { $time }
+
+medal-gold = gold
+medal-silver = silver
+medal-bronze = bronze
+
+difficulty-easy = easy
+difficulty-hard = hard
+difficulty-invalid = invalid
+
+## Settings
+
+settings-title = Settings
+settings-language = Language
+settings-on = On
+settings-off = Off
+settings-scramble = Cells emptied by scrambling
+settings-grid-size = Board size for new puzzles
+grid-size-four = 4x4
+grid-size-six = 6x6
+grid-size-nine = 9x9
+grid-size-sixteen = 16x16
+grid-size-samurai = Samurai
+
+seed-entry-button = Play a seed
+seed-entry-title = Type the seed of the puzzle to play (Backspace to erase)
+seed-entry = Seed: { $seed }
+profiles-button = Profiles
+profiles-title = Who is playing?
+profiles-current = Playing as { $name }
+profiles-new = New profile
+profile-unnamed = Player { $number }
+profile-name-entry-title = Type a name for the new profile (Backspace to erase)
+profile-name-entry = Name: { $name }
+dialog-create = Create
+toast-profile-switched = Now playing as { $name }
+
+sound-placement = Placing digits
+sound-erase = Erasing cells
+sound-invalid = Blocked edits
+sound-hint = Hints
+sound-completion = Solving the puzzle
+
+## Statistics
+
+stats-title = Statistics
+stats-empty = Solve a puzzle to start keeping statistics
+stats-solved = { $difficulty }: { $count } solved, best { $best }, average { $average }
+stats-help = { $hints } { $hints ->
+        [one] hint
+       *[other] hints
+    } for { $penalty } of penalties, { $mistakes } { $mistakes ->
+        [one] mistake
+       *[other] mistakes
+    }
+stats-streak = Streak of { $streak }, { $best } at best
+
+## Collections and the archive
+
+collection-progress = Page { $page } of { $pages }, { $solved } of { $puzzles } solved
+collection-solved = solved
+collection-starred = starred
+collection-solved-starred = solved, starred
+
+archive-title = Archive ({ $filter })
+archive-show = Show: { $filter }
+archive-search = Search: { $search } (Backspace to erase)
+archive-search-hint = Type to search by title, seed or givens
+archive-filter-all = all
+archive-filter-starred = starred
+archive-filter-unsolved = unsolved
+archive-filter-easy = easy
+archive-filter-hard = hard
+
+## Campaign
+
+campaign-progress =
+    Campaign
+    { $stars } of { $total } stars earned
+campaign-locked = Solve a level to unlock the next
+
+level-first-steps = First steps
+level-warming-up = Warming up
+level-sparse-squares = Sparse squares
+level-uneven-ground = Uneven ground
+level-beyond-singles = Beyond singles
+level-tough-choices = Tough choices
+level-deep-thought = Deep thought
+level-the-summit = The summit
+
+## Tutorial
+
+tutorial-select-cell =
+    Click an empty cell, or use the arrow keys, to select it.
+    Hold Shift or drag to select several.
+tutorial-fill-cell =
+    Type a digit, or press one of the number buttons,
+    to fill in the selected cell.
+tutorial-center-mark-mode =
+    Press W, or the highlighted button,
+    to switch to center marks.
+tutorial-add-center-mark =
+    Type a few digits to note the candidates
+    in the middle of a cell.
+tutorial-corner-mark-mode =
+    Press E, or the highlighted button,
+    to switch to corner marks.
+tutorial-add-corner-mark =
+    Corner marks show where a digit must go in a square.
+    Type a digit to add one.
+tutorial-fill-mode =
+    Press Q, or the highlighted button,
+    to go back to filling in cells.
+tutorial-ask-for-hint =
+    Stuck? Press the highlighted Hint button
+    to fill in a cell for you.
+tutorial-finish =
+    That's everything you need!
+    Press Enter to start playing, or / to see this again.
+tutorial-skip = Skip
+
+## Technique trainer
+
+trainer-select =
+    Technique trainer
+    Which technique would you like to practise?
+trainer-help = Help
+trainer-done = Done
+trainer-introduction = This puzzle needs a { $technique }. Can you find it?
+trainer-fill-candidates = Press C to fill in the candidates first.
+trainer-look-in = Look in { $house }.
+trainer-look-at = Look at { $cells } and the digit { $digits }.
+trainer-placement = So { $digit } goes in { $cell }.
+trainer-elimination = { $digit } from { $cell }
+trainer-eliminations = So you can remove { $eliminations } from the center marks.
+trainer-precise = You removed exactly the candidates the technique rules out.
+trainer-imprecise = You also removed { $wrong } { $wrong ->
+        [one] candidate
+       *[other] candidates
+    } the technique doesn't rule out, for a precision of { $precision }%.
+trainer-completed =
+    Well spotted, that's a { $technique }!
+    Press G to practise another technique.
+
+technique-naked-single = naked single
+technique-hidden-single = hidden single
+technique-pointing-pair = pointing pair
+technique-naked-pair = naked pair
+technique-hidden-pair = hidden pair
+technique-naked-single-description = Look for a cell with only one candidate left.
+technique-hidden-single-description = Look for a digit that can only go in one cell of a row, column or square.
+technique-pointing-pair-description = Look for a digit whose candidates in a square all lie in one row or column.
+technique-naked-pair-description = Look for two cells in the same row, column or square with the same two candidates.
+technique-hidden-pair-description = Look for two digits that can only go in the same two cells of a row, column or square.
+technique-naked-single-explanation = { $cell } has only one candidate left, so it must be { $digit }.
+technique-hidden-single-explanation = In { $house }, { $digit } can only go in { $cell }.
+technique-pointing-pair-explanation = In { $house }, { $digit } can only go in { $cells }, which share a line, so you can remove { $eliminations }.
+technique-naked-pair-explanation = { $cells } can only hold { $digits } between them, so you can remove { $eliminations }.
+technique-hidden-pair-explanation = In { $house }, { $digits } can only go in { $cells }, so you can remove { $eliminations }.
+
+house-row = row { $number }
+house-column = column { $number }
+house-square = the { $square ->
+        [0] top left
+        [1] top
+        [2] top right
+        [3] left
+        [4] center
+        [5] right
+        [6] bottom left
+        [7] bottom
+       *[8] bottom right
+    } square
+
+list-and = { $items } and { $last }
+
+## Puzzle setter
+
+setter-instructions = Enter the givens of your puzzle, then lock them in to play it.
+setter-counting = Counting solutions…
+setter-unique = One solution, ready to lock in
+setter-no-solutions = No solutions: some of the givens contradict each other
+setter-multiple-solutions = { $count } solutions: add givens until only one is left
+setter-solution-limit = At least { $count } solutions: add givens until only one is left
+setter-undetermined = Too many possibilities to count: add givens to narrow them down
+setter-lock = Lock
+setter-minimize = Minimize
+setter-minimizing = Minimizing: checked { $checked } of { $total } givens, removed { $removed }
+
+## Notifications
+
+toast-unreadable-puzzle = Could not read that puzzle
+toast-no-solution = That puzzle has no solution
+toast-unsupported-constraints = That puzzle uses rules that can't be played yet: { $constraints }
+toast-clashing-givens = That puzzle gives { $digit } twice, in { $first } and { $second }
+toast-broken-constraint = The givens of that puzzle break its rules at { $cell }
+toast-variant-on = New puzzles will follow the { $rule } rule
+toast-variant-off = New puzzles will no longer follow the { $rule } rule
+toast-unsatisfiable-variants = No puzzle can follow all of these rules at once, so the current puzzle is kept
+toast-not-unique = That puzzle has more than one solution, so its pencilmarks can't be relied on
+toast-undetermined = That puzzle is too open for the solver to check it has a solution, so it can't be loaded
+toast-invalid-seed = Seeds are whole numbers below 18446744073709551616
+toast-multiple-solutions = That puzzle has more than one solution, so your digits are checked against just one of them
+toast-minimize-not-unique = Only a puzzle with exactly one solution can be minimized
+toast-minimized = Removed { $removed } { $removed ->
+        [one] given
+       *[other] givens
+    } the puzzle didn't need
+toast-open-failed = Could not open { $path }
+toast-unreadable-file = Could not read the puzzle in { $path }
+toast-saved = Saved to { $path }
+toast-save-failed = Could not save to { $path }
+toast-sdk-grid-size = Only 9x9 puzzles can be saved as .sdk files
+toast-link-copied = Copied a link to this puzzle to the clipboard
+toast-link-logged = Could not copy the link, it has been written to the log instead
+toast-link-samurai = Samurai puzzles can't be shared as f-puzzles links
+toast-link-unsupported = This puzzle uses rules f-puzzles can't hold, so sharing it as a link would lose them
+toast-hint-cooldown = Next hint available in { $time }
+toast-hint-penalty = Hint used, adding { $time } to your time
+toast-level-unlocked = Unlocked level { $level }: { $name }
+toast-all-stars = All { $stars } stars on { $name }!
+toast-no-training-puzzle = Could not find a puzzle that needs a { $technique }
+toast-likelihoods-estimated = Likelihoods are rough estimates from random completions of the board, which are not all equally likely to be picked
+
+## Variant rules
+
+variant-anti-knight = anti-knight
+variant-anti-king = anti-king
+variant-non-consecutive = non-consecutive
+variant-jigsaw = jigsaw
+variant-windoku = windoku
+variant-diagonals = X-sudoku
+variant-disjoint-groups = disjoint groups
+
+rules-title = Rules
+rules-classic = Place the digits 1 to 9 once each in every row, column and box.
+rules-samurai = Each of the five grids is a sudoku of its own: rows and columns stop at the edges of their grid, while the boxes where grids overlap belong to both.
+rules-jigsaw = The boxes are the irregular regions outlined in bold.
+rules-extra-regions = The shaded regions can't repeat a digit either.
+rules-anti-knight = Cells a chess knight's move apart can't contain the same digit.
+rules-anti-king = Cells that touch, even diagonally, can't contain the same digit.
+rules-non-consecutive = Digits in cells sharing an edge can't be consecutive.
+rules-disjoint-groups = Cells in the same place within their boxes can't contain the same digit.
+rules-diagonals = Digits can't repeat along a diagonal marked with a thin line.
+rules-killer = Digits in a cage can't repeat, and add up to the sum in its corner.
+rules-thermometer = Digits along a thermometer increase from the bulb.
+rules-whisper = Neighbouring digits on a green line differ by at least 5.
+rules-renban = A purple line holds a set of consecutive digits, in any order.
+rules-palindrome = A gray line without a bulb reads the same from either end.
+rules-between = Digits along a line between two circles lie strictly between the digits in the circles.
+rules-quadruple = The digits in a circle on a corner appear in the four cells around it.
+rules-little-killer = Digits along an arrow outside the grid add up to the sum beside it, and may repeat.
+rules-kropki-white = Digits either side of a white dot are consecutive.
+rules-kropki-black = One digit either side of a black dot is double the other.
+rules-kropki-all-white = Every white dot is given.
+rules-kropki-all-black = Every black dot is given.
+rules-xv = Digits either side of an X add up to { $x }, and either side of a V to { $v }.
+rules-xv-all-given = Every X and V is given.
+
+## Saved files
+
+save-campaign = campaign progress
+save-stats = statistics
+save-archive = puzzle archive
+save-best-times = best times
+save-tutorial = tutorial progress
+save-settings = settings
+save-profiles = profiles
+toast-save-recovered = Your { $file } file was damaged, so it was set aside and restored from the latest backup
+toast-save-lost = Your { $file } file was damaged and none of its backups could be read, so it starts afresh; the damaged file was kept aside
+toast-save-repaired = Your { $file } file was damaged on disk, so it was set aside and saved again
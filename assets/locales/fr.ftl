@@ -0,0 +1,365 @@
+# Texte affiché au joueur, en français
+#
+# Les messages manquants sont affichés en anglais
+
+## Panneau latéral
+
+label-fill = Chiffres
+label-center = Centre
+label-corner = Coins
+label-color = Couleur
+label-new = Nouveau
+label-reset = Effacer
+label-solve = Solution
+label-hint = Indice
+label-scramble = Mélanger
+label-settings = Réglages
+hint-cooldown = { $seconds } s
+
+tooltip-enter-digit = Placer { $digit } dans les cases sélectionnées ({ $digit })
+tooltip-paint-color = Peindre avec la couleur { $digit } (T, puis { $digit })
+tooltip-fill = Placer des chiffres (Q)
+tooltip-center-mark = Notes au centre (W)
+tooltip-corner-mark = Notes dans les coins (E)
+tooltip-color = Colorier les cases (T)
+tooltip-new = Nouvelle grille (N)
+tooltip-reset = Effacer la grille (X)
+tooltip-solve = Afficher la solution (V)
+tooltip-remix = Refaire la grille avec de nouveaux indices pour la même solution (Y)
+tooltip-hint = Remplir une case, en ajoutant à votre temps (J)
+tooltip-find-mistake = Trouver une erreur parmi les chiffres en conflit (F)
+tooltip-check = Vérifier vos chiffres avec la solution (D)
+tooltip-fill-candidates = Noter tous les candidats au centre (C)
+tooltip-coach = Demander plus d'aide (Z)
+tooltip-stop-training = Arrêter l'entraînement (Échap)
+tooltip-toggle-rules = Afficher ou masquer les règles de cette grille
+tooltip-skip-tutorial = Passer le tutoriel (Échap)
+tooltip-lock = Verrouiller les chiffres donnés et commencer à jouer (Ctrl + L)
+tooltip-minimize = Retirer tous les chiffres donnés dont la grille n'a pas besoin (Ctrl + M)
+tooltip-scramble = Mélanger les cases non remplies en une nouvelle position d'entraînement (P)
+tooltip-settings = Modifier les réglages (Ctrl + ,)
+
+## Lignes d'état
+
+hud-remaining-cells = { $cells } { $cells ->
+        [one] case restante
+       *[other] cases restantes
+    } ({ $percent } % terminé)
+hud-mistakes = Erreurs : { $mistakes }
+hud-limited-mistakes = Erreurs : { $mistakes }/{ $limit }
+hud-seed = Graine : { $seed }
+hud-par-time = Par : { $time }
+hud-generating = Création d'une nouvelle grille…
+
+## Dialogues
+
+dialog-discard = Abandonner
+dialog-bookmark = Marquer
+dialog-reset = Effacer
+dialog-remix = Refaire
+dialog-quit = Quitter
+dialog-cancel = Annuler
+dialog-close = Fermer
+dialog-new-puzzle = Nouvelle grille
+dialog-try-again = Réessayer
+dialog-levels = Niveaux
+dialog-play-later = Plus tard
+dialog-star = Favori
+dialog-unstar = Retirer
+dialog-previous = Précédent
+dialog-next = Suivant
+dialog-play = Jouer
+
+confirm-new-puzzle = Abandonner la partie en cours ?
+confirm-reset = Effacer la grille et recommencer ?
+confirm-remix = Refaire la grille et perdre la partie en cours ?
+confirm-quit = Quitter et perdre la partie en cours ?
+confirm-progress = { $percent } % terminé après { $time }
+
+game-over =
+    Partie terminée !
+    { $mistakes } erreurs commises
+
+victory-solved = Résolu en { $time } !
+victory-penalty = Dont { $penalty } de pénalités pour { $hints } { $hints ->
+        [one] indice
+       *[other] indices
+    }
+victory-par = Par { $par }
+victory-par-medal = Par { $par }, médaille { $medal } !
+victory-placed-by-player = Vous avez placé { $count }
+victory-placed-by-hints = les indices ont placé { $count }
+victory-placed-by-solver = le solveur a placé { $count }
+victory-new-best = Nouveau record en difficulté { $difficulty } !
+victory-best-times = Meilleurs temps
+victory-best-time = { $difficulty } : { $time }
+
+medal-gold = d'or
+medal-silver = d'argent
+medal-bronze = de bronze
+
+difficulty-easy = facile
+difficulty-hard = difficile
+difficulty-invalid = invalide
+
+## Réglages
+
+settings-title = Réglages
+settings-language = Langue
+settings-on = Activé
+settings-off = Désactivé
+settings-scramble = Cases vidées par le mélange
+settings-grid-size = Taille de la grille des nouvelles parties
+grid-size-four = 4x4
+grid-size-six = 6x6
+grid-size-nine = 9x9
+grid-size-sixteen = 16x16
+grid-size-samurai = Samouraï
+
+seed-entry-button = Jouer une graine
+seed-entry-title = Tapez la graine de la grille à jouer (Retour arrière pour effacer)
+seed-entry = Graine : { $seed }
+profiles-button = Profils
+profiles-title = Qui joue ?
+profiles-current = Profil actuel : { $name }
+profiles-new = Nouveau profil
+profile-unnamed = Joueur { $number }
+profile-name-entry-title = Tapez le nom du nouveau profil (Retour arrière pour effacer)
+profile-name-entry = Nom : { $name }
+dialog-create = Créer
+toast-profile-switched = Vous jouez désormais en tant que { $name }
+
+sound-placement = Placement des chiffres
+sound-erase = Effacement des cases
+sound-invalid = Modifications refusées
+sound-hint = Indices
+sound-completion = Grille résolue
+
+## Statistiques
+
+stats-title = Statistiques
+stats-empty = Résolvez une grille pour commencer les statistiques
+stats-solved = { $difficulty } : { $count } résolues, record { $best }, moyenne { $average }
+stats-help = { $hints } { $hints ->
+        [one] indice
+       *[other] indices
+    } pour { $penalty } de pénalités, { $mistakes } { $mistakes ->
+        [one] erreur
+       *[other] erreurs
+    }
+stats-streak = Série de { $streak }, { $best } au mieux
+
+## Collections et archives
+
+collection-progress = Page { $page } sur { $pages }, { $solved } sur { $puzzles } résolues
+collection-solved = résolue
+collection-starred = favorite
+collection-solved-starred = résolue, favorite
+
+archive-title = Archives ({ $filter })
+archive-show = Afficher : { $filter }
+archive-search = Recherche : { $search } (Retour arrière pour effacer)
+archive-search-hint = Tapez pour chercher par titre, graine ou chiffres donnés
+archive-filter-all = toutes
+archive-filter-starred = favorites
+archive-filter-unsolved = non résolues
+archive-filter-easy = faciles
+archive-filter-hard = difficiles
+
+## Campagne
+
+campaign-progress =
+    Campagne
+    { $stars } étoiles gagnées sur { $total }
+campaign-locked = Résolvez un niveau pour débloquer le suivant
+
+level-first-steps = Premiers pas
+level-warming-up = Échauffement
+level-sparse-squares = Carrés clairsemés
+level-uneven-ground = Terrain accidenté
+level-beyond-singles = Au-delà des singletons
+level-tough-choices = Choix difficiles
+level-deep-thought = Réflexion profonde
+level-the-summit = Le sommet
+
+## Tutoriel
+
+tutorial-select-cell =
+    Cliquez sur une case vide, ou utilisez les flèches, pour la sélectionner.
+    Maintenez Maj ou faites glisser pour en sélectionner plusieurs.
+tutorial-fill-cell =
+    Tapez un chiffre, ou appuyez sur un des boutons de chiffres,
+    pour remplir la case sélectionnée.
+tutorial-center-mark-mode =
+    Appuyez sur W, ou sur le bouton en surbrillance,
+    pour passer aux notes au centre.
+tutorial-add-center-mark =
+    Tapez quelques chiffres pour noter les candidats
+    au centre d'une case.
+tutorial-corner-mark-mode =
+    Appuyez sur E, ou sur le bouton en surbrillance,
+    pour passer aux notes dans les coins.
+tutorial-add-corner-mark =
+    Les notes dans les coins montrent où un chiffre doit aller dans un carré.
+    Tapez un chiffre pour en ajouter une.
+tutorial-fill-mode =
+    Appuyez sur Q, ou sur le bouton en surbrillance,
+    pour recommencer à remplir les cases.
+tutorial-ask-for-hint =
+    Bloqué ? Appuyez sur le bouton Indice en surbrillance
+    pour qu'une case soit remplie pour vous.
+tutorial-finish =
+    Vous savez tout ce qu'il faut !
+    Appuyez sur Entrée pour jouer, ou sur / pour revoir ceci.
+tutorial-skip = Passer
+
+## Entraînement aux techniques
+
+trainer-select =
+    Entraînement aux techniques
+    Quelle technique voulez-vous pratiquer ?
+trainer-help = Aide
+trainer-done = Terminé
+trainer-introduction = Cette grille demande la technique « { $technique } ». Saurez-vous la trouver ?
+trainer-fill-candidates = Appuyez d'abord sur C pour noter les candidats.
+trainer-look-in = Regardez dans { $house }.
+trainer-look-at = Regardez { $cells } et le chiffre { $digits }.
+trainer-placement = Donc { $digit } va en { $cell }.
+trainer-elimination = { $digit } de { $cell }
+trainer-eliminations = Vous pouvez donc retirer { $eliminations } des notes au centre.
+trainer-precise = Vous avez retiré exactement les candidats que la technique exclut.
+trainer-imprecise = Vous avez aussi retiré { $wrong } { $wrong ->
+        [one] candidat
+       *[other] candidats
+    } que la technique n'exclut pas, pour une précision de { $precision } %.
+trainer-completed =
+    Bien vu, c'est la technique « { $technique } » !
+    Appuyez sur G pour pratiquer une autre technique.
+
+technique-naked-single = singleton nu
+technique-hidden-single = singleton caché
+technique-pointing-pair = paire pointante
+technique-naked-pair = paire nue
+technique-hidden-pair = paire cachée
+technique-naked-single-description = Cherchez une case où il ne reste qu'un seul candidat.
+technique-hidden-single-description = Cherchez un chiffre qui ne peut aller que dans une case d'une ligne, colonne ou carré.
+technique-pointing-pair-description = Cherchez un chiffre dont les candidats d'un carré sont tous sur la même ligne ou colonne.
+technique-naked-pair-description = Cherchez deux cases d'une même ligne, colonne ou carré avec les deux mêmes candidats.
+technique-hidden-pair-description = Cherchez deux chiffres qui ne peuvent aller que dans les deux mêmes cases d'une ligne, colonne ou carré.
+technique-naked-single-explanation = Il ne reste qu'un candidat en { $cell } : c'est forcément { $digit }.
+technique-hidden-single-explanation = Dans { $house }, { $digit } ne peut aller qu'en { $cell }.
+technique-pointing-pair-explanation = Dans { $house }, { $digit } ne peut aller qu'en { $cells }, sur une même ligne : vous pouvez donc retirer { $eliminations }.
+technique-naked-pair-explanation = { $cells } ne peuvent contenir que { $digits } : vous pouvez donc retirer { $eliminations }.
+technique-hidden-pair-explanation = Dans { $house }, { $digits } ne peuvent aller qu'en { $cells } : vous pouvez donc retirer { $eliminations }.
+
+house-row = la ligne { $number }
+house-column = la colonne { $number }
+house-square = le carré { $square ->
+        [0] en haut à gauche
+        [1] en haut
+        [2] en haut à droite
+        [3] à gauche
+        [4] du centre
+        [5] à droite
+        [6] en bas à gauche
+        [7] en bas
+       *[8] en bas à droite
+    }
+
+list-and = { $items } et { $last }
+
+## Création de grilles
+
+setter-instructions = Entrez les chiffres donnés de votre grille, puis verrouillez-les pour y jouer.
+setter-counting = Comptage des solutions…
+setter-unique = Une seule solution, prête à être verrouillée
+setter-no-solutions = Aucune solution : certains chiffres donnés se contredisent
+setter-multiple-solutions = { $count } solutions : ajoutez des chiffres jusqu'à n'en laisser qu'une
+setter-solution-limit = Au moins { $count } solutions : ajoutez des chiffres jusqu'à n'en laisser qu'une
+setter-undetermined = Trop de possibilités à compter : ajoutez des chiffres pour les réduire
+setter-lock = Verrouiller
+setter-minimize = Minimiser
+setter-minimizing = Minimisation : { $checked } chiffres sur { $total } vérifiés, { $removed } retirés
+
+## Notifications
+
+toast-unreadable-puzzle = Impossible de lire cette grille
+toast-no-solution = Cette grille n'a pas de solution
+toast-unsupported-constraints = Cette grille utilise des règles qui ne sont pas encore jouables : { $constraints }
+toast-clashing-givens = Cette grille donne deux fois le { $digit }, en { $first } et { $second }
+toast-broken-constraint = Les chiffres donnés de cette grille enfreignent ses règles en { $cell }
+toast-variant-on = Les nouvelles grilles suivront la règle { $rule }
+toast-variant-off = Les nouvelles grilles ne suivront plus la règle { $rule }
+toast-unsatisfiable-variants = Aucune grille ne peut suivre toutes ces règles à la fois, la grille actuelle est donc conservée
+toast-not-unique = Cette grille a plusieurs solutions, ses candidats ne sont donc pas fiables
+toast-undetermined = Cette grille est trop ouverte pour que le solveur vérifie qu'elle a une solution, elle ne peut donc pas être chargée
+toast-invalid-seed = Les graines sont des nombres entiers inférieurs à 18446744073709551616
+toast-multiple-solutions = Cette grille a plusieurs solutions, vos chiffres ne sont vérifiés que par rapport à l'une d'elles
+toast-minimize-not-unique = Seule une grille avec exactement une solution peut être minimisée
+toast-minimized = { $removed } { $removed ->
+        [one] chiffre donné retiré
+       *[other] chiffres donnés retirés
+    }, la grille n'en avait pas besoin
+toast-open-failed = Impossible d'ouvrir { $path }
+toast-unreadable-file = Impossible de lire la grille dans { $path }
+toast-saved = Enregistré dans { $path }
+toast-save-failed = Impossible d'enregistrer dans { $path }
+toast-sdk-grid-size = Seules les grilles 9x9 peuvent être enregistrées en fichier .sdk
+toast-link-copied = Lien vers cette grille copié dans le presse-papiers
+toast-link-logged = Impossible de copier le lien, il a été écrit dans le journal
+toast-link-samurai = Les grilles samouraï ne peuvent pas être partagées en lien f-puzzles
+toast-link-unsupported = Cette grille utilise des règles que f-puzzles ne sait pas écrire, et un lien les perdrait
+toast-hint-cooldown = Prochain indice disponible dans { $time }
+toast-hint-penalty = Indice utilisé, { $time } ajouté à votre temps
+toast-level-unlocked = Niveau { $level } débloqué : { $name }
+toast-all-stars = Les { $stars } étoiles sur { $name } !
+toast-no-training-puzzle = Aucune grille trouvée pour la technique « { $technique } »
+toast-likelihoods-estimated = Les probabilités sont des estimations grossières tirées de complétions aléatoires de la grille, qui n'ont pas toutes la même chance d'être choisies
+
+## Règles des variantes
+
+variant-anti-knight = anti-cavalier
+variant-anti-king = anti-roi
+variant-non-consecutive = non-consécutive
+variant-jigsaw = des régions irrégulières
+variant-windoku = windoku
+variant-diagonals = sudoku X
+variant-disjoint-groups = groupes disjoints
+
+rules-title = Règles
+rules-classic = Placez les chiffres de 1 à 9 une fois chacun dans chaque ligne, colonne et région.
+rules-samurai = Chacune des cinq grilles est un sudoku à part entière : les lignes et colonnes s'arrêtent au bord de leur grille, tandis que les régions où les grilles se chevauchent appartiennent aux deux.
+rules-jigsaw = Les régions sont les formes irrégulières tracées en gras.
+rules-extra-regions = Les régions grisées ne peuvent pas non plus répéter un chiffre.
+rules-anti-knight = Deux cases séparées d'un saut de cavalier ne peuvent pas contenir le même chiffre.
+rules-anti-king = Deux cases qui se touchent, même en diagonale, ne peuvent pas contenir le même chiffre.
+rules-non-consecutive = Deux cases côte à côte ne peuvent pas contenir des chiffres consécutifs.
+rules-disjoint-groups = Les cases à la même place dans leur région ne peuvent pas contenir le même chiffre.
+rules-diagonals = Les chiffres ne se répètent pas sur une diagonale tracée d'un trait fin.
+rules-killer = Les chiffres d'une cage ne se répètent pas, et leur somme est indiquée dans son coin.
+rules-thermometer = Les chiffres d'un thermomètre croissent depuis le bulbe.
+rules-whisper = Les chiffres voisins sur une ligne verte diffèrent d'au moins 5.
+rules-renban = Une ligne violette contient des chiffres consécutifs, dans n'importe quel ordre.
+rules-palindrome = Une ligne grise sans bulbe se lit de la même façon dans les deux sens.
+rules-between = Les chiffres d'une ligne entre deux cercles sont strictement compris entre les chiffres des cercles.
+rules-quadruple = Les chiffres d'un cercle posé sur un coin apparaissent dans les quatre cases autour.
+rules-little-killer = La somme des chiffres le long d'une flèche hors de la grille est indiquée à côté, et ils peuvent se répéter.
+rules-kropki-white = Les chiffres de part et d'autre d'un point blanc sont consécutifs.
+rules-kropki-black = L'un des chiffres de part et d'autre d'un point noir est le double de l'autre.
+rules-kropki-all-white = Tous les points blancs sont indiqués.
+rules-kropki-all-black = Tous les points noirs sont indiqués.
+rules-xv = La somme des chiffres de part et d'autre d'un X vaut { $x }, et d'un V { $v }.
+rules-xv-all-given = Tous les X et V sont indiqués.
+
+## Fichiers de sauvegarde
+
+save-campaign = progression de la campagne
+save-stats = statistiques
+save-archive = archive des grilles
+save-best-times = meilleurs temps
+save-tutorial = progression du tutoriel
+save-settings = réglages
+save-profiles = profils
+toast-save-recovered = Le fichier { $file } était endommagé : il a été mis de côté et restauré depuis la dernière sauvegarde
+toast-save-lost = Le fichier { $file } était endommagé et aucune de ses sauvegardes n'a pu être lue, il repart donc de zéro ; le fichier endommagé a été mis de côté
+toast-save-repaired = Le fichier { $file } a été endommagé sur le disque : il a été mis de côté et enregistré à nouveau
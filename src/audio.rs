@@ -0,0 +1,181 @@
+/// Play sound effects in response to the player's actions
+use crate::{
+    logic::{
+        board::{BlockedEdit, BoardLabels, CellsErased, DigitPlaced, PuzzleSolved},
+        hints::HintGiven,
+    },
+    settings::Settings,
+    CommonLabels,
+};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use self::assets::*;
+
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // ASSETS
+            .init_resource::<SoundEffects>()
+            .add_event::<PlaySound>()
+            // ACTIONS
+            // The puzzle must have been checked, so that solving it plays the completion sound on the same frame
+            .add_system(
+                queue_sounds
+                    .system()
+                    .label(SoundLabels::Queue)
+                    .after(CommonLabels::Action)
+                    .after(BoardLabels::DetectSolved),
+            )
+            .add_system(play_sounds.system().after(SoundLabels::Queue));
+    }
+}
+
+#[derive(SystemLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+enum SoundLabels {
+    Queue,
+}
+
+mod config {
+    pub const PLACEMENT_SOUND: &str = "sounds/placement.wav";
+    pub const ERASE_SOUND: &str = "sounds/erase.wav";
+    pub const INVALID_SOUND: &str = "sounds/invalid.wav";
+    pub const HINT_SOUND: &str = "sounds/hint.wav";
+    pub const COMPLETION_SOUND: &str = "sounds/completion.wav";
+}
+
+/// The categories of sound effects, each of which can be muted on its own
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SoundEffect {
+    /// A digit was placed into a cell
+    Placement,
+    /// Cells were cleared
+    Erase,
+    /// The player tried to change a cell that can't be changed
+    Invalid,
+    /// A hint filled in a cell
+    Hint,
+    /// The puzzle was solved
+    Completion,
+}
+
+impl SoundEffect {
+    /// Every category of sound effect, in the order they are listed in the settings menu
+    pub const ALL: [SoundEffect; 5] = [
+        SoundEffect::Placement,
+        SoundEffect::Erase,
+        SoundEffect::Invalid,
+        SoundEffect::Hint,
+        SoundEffect::Completion,
+    ];
+
+    /// The id of the message naming this category of sound effect
+    pub fn name_id(self) -> &'static str {
+        use SoundEffect::*;
+        match self {
+            Placement => "sound-placement",
+            Erase => "sound-erase",
+            Invalid => "sound-invalid",
+            Hint => "sound-hint",
+            Completion => "sound-completion",
+        }
+    }
+}
+
+/// Event to play a sound effect
+pub struct PlaySound(pub SoundEffect);
+
+mod assets {
+    use super::config::*;
+    use super::SoundEffect;
+    use bevy::prelude::*;
+
+    /// The audio clip for each sound effect
+    pub struct SoundEffects {
+        pub placement: Handle<AudioSource>,
+        pub erase: Handle<AudioSource>,
+        pub invalid: Handle<AudioSource>,
+        pub hint: Handle<AudioSource>,
+        pub completion: Handle<AudioSource>,
+    }
+
+    impl SoundEffects {
+        /// Looks up the clip for the sound effect provided
+        pub fn get(&self, effect: SoundEffect) -> Handle<AudioSource> {
+            use SoundEffect::*;
+            match effect {
+                Placement => self.placement.clone(),
+                Erase => self.erase.clone(),
+                Invalid => self.invalid.clone(),
+                Hint => self.hint.clone(),
+                Completion => self.completion.clone(),
+            }
+        }
+    }
+
+    impl FromWorld for SoundEffects {
+        fn from_world(world: &mut World) -> Self {
+            let asset_server = world
+                .get_resource_mut::<AssetServer>()
+                .expect("ResMut<AssetServer> not found.");
+            SoundEffects {
+                placement: asset_server.load(PLACEMENT_SOUND),
+                erase: asset_server.load(ERASE_SOUND),
+                invalid: asset_server.load(INVALID_SOUND),
+                hint: asset_server.load(HINT_SOUND),
+                completion: asset_server.load(COMPLETION_SOUND),
+            }
+        }
+    }
+}
+
+/// Converts the outcomes of the player's actions into sound effects
+fn queue_sounds(
+    mut placed_reader: EventReader<DigitPlaced>,
+    mut erased_reader: EventReader<CellsErased>,
+    mut blocked_reader: EventReader<BlockedEdit>,
+    mut hint_reader: EventReader<HintGiven>,
+    mut solved_reader: EventReader<PuzzleSolved>,
+    mut event_writer: EventWriter<PlaySound>,
+) {
+    use SoundEffect::*;
+
+    let solved = solved_reader.iter().count() > 0;
+    let hinted = hint_reader.iter().count() > 0;
+    let placed = placed_reader.iter().count() > 0;
+
+    // Solving the puzzle also places a digit, so only the most important of these sounds is played
+    if solved {
+        event_writer.send(PlaySound(Completion));
+    } else if hinted {
+        event_writer.send(PlaySound(Hint));
+    } else if placed {
+        event_writer.send(PlaySound(Placement));
+    }
+
+    if erased_reader.iter().count() > 0 {
+        event_writer.send(PlaySound(Erase));
+    }
+    if blocked_reader.iter().count() > 0 {
+        event_writer.send(PlaySound(Invalid));
+    }
+}
+
+/// Plays each requested sound effect at most once per frame, skipping those that are muted
+fn play_sounds(
+    mut event_reader: EventReader<PlaySound>,
+    sound_effects: Res<SoundEffects>,
+    audio: Res<Audio>,
+    settings: Res<Settings>,
+) {
+    // Actions on several cells at once would otherwise play the same sound on top of itself
+    let effects: HashSet<SoundEffect> = event_reader.iter().map(|event| event.0).collect();
+
+    for effect in effects {
+        if !settings.muted_sounds.is_muted(effect) {
+            audio.play(sound_effects.get(effect));
+        }
+    }
+}
@@ -0,0 +1,308 @@
+/// A sequence of levels that ramp up in difficulty, each unlocked by solving the one before
+///
+/// Levels are generated from fixed seeds, so every player gets the same puzzles
+use crate::graphics::toasts::Toasts;
+use crate::input::{
+    buttons::{PlayLevel, ShowLevelSelect},
+    focus::Focusable,
+    shortcuts::{Shortcut, Shortcuts},
+};
+use crate::localization::Localization;
+use crate::logic::{
+    constraints::ConstraintSet,
+    records::{Medal, PuzzleCompleted},
+    sudoku_generation::{find_seed, GenerationFilters, PuzzleSeed, SeededPuzzle},
+};
+use crate::persistence::{check_integrity, load_json, reload_json, save_json, SaveFile};
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+    utils::HashMap,
+};
+use futures_lite::future;
+use serde::{Deserialize, Serialize};
+
+pub struct CampaignPlugin;
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Campaign>()
+            .init_resource::<LevelSeedSearch>()
+            .add_startup_system(load_json::<Campaign>.system())
+            .add_system(open_level_select.system())
+            .add_system(play_level.system())
+            .add_system(receive_level_seed.system())
+            .add_system(leave_campaign.system())
+            .add_system(award_stars.system())
+            .add_system_to_stage(CoreStage::PreUpdate, reload_json::<Campaign>.system())
+            .add_system(save_json::<Campaign>.system())
+            .add_system(check_integrity::<Campaign>.system());
+    }
+}
+
+/// The most stars a level can award, for solving it within par
+pub const MAX_STARS: u8 = 3;
+
+/// A level of the campaign, which is the first puzzle generated from `first_seed` onwards that passes its filters
+pub struct CampaignLevel {
+    /// The id of the message naming the level
+    pub name: &'static str,
+    first_seed: u64,
+    filters: GenerationFilters,
+}
+
+/// Filters for levels that don't care how the givens are spread out
+const fn level_filters(singles_only: bool, min_guesses: u32) -> GenerationFilters {
+    GenerationFilters {
+        min_givens_per_square: 0,
+        max_givens_per_square: 9,
+        min_givens: 0,
+        singles_only,
+        min_guesses,
+        variants: Vec::new(),
+        size: GridSize::Nine,
+        retries: 100,
+    }
+}
+
+/// The levels of the campaign, from easiest to hardest
+pub const LEVELS: [CampaignLevel; 8] = [
+    CampaignLevel {
+        name: "level-first-steps",
+        first_seed: 1,
+        // Givens in every square leave no part of the board without a foothold
+        filters: GenerationFilters {
+            min_givens_per_square: 3,
+            ..level_filters(true, 0)
+        },
+    },
+    CampaignLevel {
+        name: "level-warming-up",
+        first_seed: 1000,
+        filters: GenerationFilters {
+            min_givens_per_square: 2,
+            ..level_filters(true, 0)
+        },
+    },
+    CampaignLevel {
+        name: "level-sparse-squares",
+        first_seed: 2000,
+        filters: level_filters(true, 0),
+    },
+    CampaignLevel {
+        name: "level-uneven-ground",
+        first_seed: 3000,
+        filters: GenerationFilters {
+            max_givens_per_square: 1,
+            ..level_filters(true, 0)
+        },
+    },
+    CampaignLevel {
+        name: "level-beyond-singles",
+        first_seed: 4000,
+        filters: level_filters(false, 1),
+    },
+    CampaignLevel {
+        name: "level-tough-choices",
+        first_seed: 5000,
+        filters: level_filters(false, 2),
+    },
+    CampaignLevel {
+        name: "level-deep-thought",
+        first_seed: 6000,
+        filters: level_filters(false, 4),
+    },
+    CampaignLevel {
+        name: "level-the-summit",
+        first_seed: 7000,
+        filters: level_filters(false, 8),
+    },
+];
+
+/// Resource storing the stars earned on each level, and the level being played
+#[derive(Serialize, Deserialize, Default)]
+pub struct Campaign {
+    /// The most stars earned on each level, where 0 means the level hasn't been solved
+    stars: Vec<u8>,
+    /// The index of the level being played, and the seed it was generated from
+    ///
+    /// Cleared once the player moves on to a puzzle outside of the campaign
+    #[serde(skip)]
+    pub current: Option<(usize, u64)>,
+    /// The seeds found for each level so far, so that replaying a level doesn't search for its seed again
+    #[serde(skip)]
+    seeds: HashMap<usize, u64>,
+}
+
+/// Campaign progress is stored next to the game, like the puzzle archive
+impl SaveFile for Campaign {
+    const PATH: &'static str = "campaign.json";
+    const DESCRIPTION: &'static str = "campaign progress";
+    const NAME_ID: &'static str = "save-campaign";
+}
+
+/// Resource storing the search for a level's seed underway in the background, along with the level
+///
+/// Searching can run through many puzzles for the harder levels, so the board stays responsive meanwhile
+#[derive(Default)]
+struct LevelSeedSearch(Option<(usize, Task<Option<u64>>)>);
+
+impl Campaign {
+    /// The most stars earned on the level
+    pub fn stars(&self, level: usize) -> u8 {
+        self.stars.get(level).copied().unwrap_or_default()
+    }
+
+    /// The first level is always unlocked, and each other level once the level before it is solved
+    pub fn is_unlocked(&self, level: usize) -> bool {
+        level == 0 || self.stars(level - 1) > 0
+    }
+
+    /// The stars earned across every level
+    pub fn total_stars(&self) -> u32 {
+        self.stars.iter().map(|stars| *stars as u32).sum()
+    }
+
+    /// A short description of the level for the level select screen, such as "3. Sparse squares **-"
+    pub fn label(&self, level: usize, localization: &Localization) -> String {
+        let stars = self.stars(level) as usize;
+        format!(
+            "{}. {} {}{}",
+            level + 1,
+            localization.get(LEVELS[level].name),
+            "*".repeat(stars),
+            "-".repeat(MAX_STARS as usize - stars)
+        )
+    }
+}
+
+/// Solving a level always earns a star, with more for solving it close to par
+fn stars_earned(medal: Option<Medal>) -> u8 {
+    match medal {
+        Some(Medal::Gold) => 3,
+        Some(Medal::Silver) => 2,
+        Some(Medal::Bronze) | None => 1,
+    }
+}
+
+/// Opens the level select screen when M is pressed
+fn open_level_select(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    focusable_query: Query<(), With<Focusable>>,
+    mut event_writer: EventWriter<ShowLevelSelect>,
+) {
+    // Don't open the level select screen on top of another menu
+    if shortcuts.just_pressed(Shortcut::LevelSelect, &keyboard_input)
+        && focusable_query.iter().next().is_none()
+    {
+        event_writer.send(ShowLevelSelect);
+    }
+}
+
+/// Generates the puzzle for the chosen level, if it has been unlocked
+///
+/// Levels whose seed hasn't been found yet this session start searching for it in the background
+fn play_level(
+    mut event_reader: EventReader<PlayLevel>,
+    mut campaign: ResMut<Campaign>,
+    mut search: ResMut<LevelSeedSearch>,
+    mut seeded_writer: EventWriter<SeededPuzzle>,
+    task_pool: Res<AsyncComputeTaskPool>,
+) {
+    for event in event_reader.iter() {
+        let level = event.0;
+        if level >= LEVELS.len() || !campaign.is_unlocked(level) {
+            continue;
+        }
+
+        if let Some(&seed) = campaign.seeds.get(&level) {
+            // A search for another level is no longer wanted
+            search.0 = None;
+            campaign.current = Some((level, seed));
+            seeded_writer.send(SeededPuzzle(seed));
+            continue;
+        }
+
+        let first_seed = LEVELS[level].first_seed;
+        let filters = LEVELS[level].filters.clone();
+        // Seeded puzzles are always classic, so the seed must be found under the classic rules
+        let task = task_pool
+            .spawn(async move { find_seed(first_seed, &filters, &ConstraintSet::classic()) });
+        search.0 = Some((level, task));
+    }
+}
+
+/// Starts the level once the search for its seed has finished, remembering the seed for next time
+fn receive_level_seed(
+    mut search: ResMut<LevelSeedSearch>,
+    mut campaign: ResMut<Campaign>,
+    mut seeded_writer: EventWriter<SeededPuzzle>,
+) {
+    let (level, seed) = match search.0.as_mut() {
+        Some((level, searching)) => match future::block_on(future::poll_once(searching)) {
+            Some(seed) => (*level, seed),
+            None => return,
+        },
+        None => return,
+    };
+
+    search.0 = None;
+    let seed = seed.expect("Classic puzzles can always be generated");
+    campaign.seeds.insert(level, seed);
+    campaign.current = Some((level, seed));
+    seeded_writer.send(SeededPuzzle(seed));
+}
+
+/// Forgets the level being played once a puzzle from elsewhere is started
+fn leave_campaign(puzzle_seed: Res<PuzzleSeed>, mut campaign: ResMut<Campaign>) {
+    if !puzzle_seed.is_changed() {
+        return;
+    }
+
+    if matches!(campaign.current, Some((_, seed)) if puzzle_seed.0 != Some(seed)) {
+        campaign.current = None;
+    }
+}
+
+/// Awards stars for solving the level being played, keeping the most earned on each level
+fn award_stars(
+    mut event_reader: EventReader<PuzzleCompleted>,
+    mut campaign: ResMut<Campaign>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for event in event_reader.iter() {
+        let level = match campaign.current {
+            Some((level, _)) => level,
+            None => continue,
+        };
+
+        if campaign.stars.len() <= level {
+            campaign.stars.resize(level + 1, 0);
+        }
+        let stars = stars_earned(event.medal);
+        let previous = campaign.stars[level];
+        if stars > previous {
+            campaign.stars[level] = stars;
+            if previous == 0 && level + 1 < LEVELS.len() {
+                toasts.push(localization.format(
+                    "toast-level-unlocked",
+                    &[
+                        ("level", (level + 2).into()),
+                        ("name", localization.get(LEVELS[level + 1].name).into()),
+                    ],
+                ));
+            }
+            if stars == MAX_STARS {
+                toasts.push(localization.format(
+                    "toast-all-stars",
+                    &[
+                        ("stars", MAX_STARS.into()),
+                        ("name", localization.get(LEVELS[level].name).into()),
+                    ],
+                ));
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+/// A stripped-down mode for players solving on paper, which keeps the candidates of a typed-in puzzle up to date
+use crate::{
+    graphics::{
+        board::config::{GRID_CENTER_X, GRID_CENTER_Y},
+        buttons::setup::UiBox,
+        hud::setup::Hud,
+        MainCamera,
+    },
+    logic::{
+        board::{
+            marks::{CenterMarks, Marks},
+            Cell, Coordinates, Value,
+        },
+        candidates::compute_candidates,
+        constraints::ConstraintSet,
+    },
+    CommonLabels,
+};
+use bevy::prelude::*;
+
+pub struct CompanionPlugin;
+
+impl Plugin for CompanionPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<CompanionMode>()
+            // Must be known before the first puzzle is chosen
+            .add_startup_system_to_stage(StartupStage::PreStartup, read_companion_flag.system())
+            // Must occur after the rest of the interface has been spawned
+            .add_startup_system_to_stage(StartupStage::PostStartup, strip_interface.system())
+            .add_system(update_candidates.system().after(CommonLabels::Action));
+    }
+}
+
+/// How far the camera zooms in on the board, leaving room for large candidates
+/// and for the clues written in the band around the grid
+const COMPANION_ZOOM: f32 = 0.8;
+
+/// Resource storing whether the game was launched as a companion for solving on paper,
+/// by passing `--companion` as the first command line argument
+///
+/// A puzzle to start from can be passed as the second argument, in any notation `Position` supports
+#[derive(Default)]
+pub struct CompanionMode(pub bool);
+
+/// Checks the command line for the companion mode flag
+fn read_companion_flag(mut companion_mode: ResMut<CompanionMode>) {
+    companion_mode.0 = std::env::args().nth(1).as_deref() == Some("--companion");
+}
+
+/// Removes the buttons and status lines, and zooms in on the board
+fn strip_interface(
+    companion_mode: Res<CompanionMode>,
+    interface_query: Query<Entity, Or<(With<UiBox>, With<Hud>)>>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<MainCamera>>,
+    mut commands: Commands,
+) {
+    if !companion_mode.0 {
+        return;
+    }
+
+    for entity in interface_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        transform.translation.x = GRID_CENTER_X;
+        transform.translation.y = GRID_CENTER_Y;
+        projection.scale = COMPANION_ZOOM;
+    }
+}
+
+/// Keeps the center marks of every unfilled cell limited to its candidates as digits are placed
+///
+/// Cells without center marks are given all of their candidates,
+/// while candidates the player has already eliminated by hand stay eliminated
+fn update_candidates(
+    companion_mode: Res<CompanionMode>,
+    mut query: Query<(&Coordinates, &mut Value), With<Cell>>,
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    constraints: Res<ConstraintSet>,
+) {
+    if !companion_mode.0 || changed_query.iter().next().is_none() {
+        return;
+    }
+
+    let cells: Vec<_> = query.iter().collect();
+    let candidates = compute_candidates(&cells, &constraints);
+
+    for (coordinates, mut value) in query.iter_mut() {
+        let digits = match candidates.get(coordinates) {
+            Some(digits) => digits,
+            None => continue,
+        };
+
+        let new_value = match &*value {
+            Value::Marked(center, corner) if !center.is_empty() => Value::Marked(
+                CenterMarks::from_digits(
+                    center
+                        .digits()
+                        .into_iter()
+                        .filter(|num| digits.contains(num)),
+                ),
+                corner.clone(),
+            ),
+            Value::Marked(_, corner) => Value::Marked(
+                CenterMarks::from_digits(digits.iter().copied()),
+                corner.clone(),
+            ),
+            _ => Value::Marked(
+                CenterMarks::from_digits(digits.iter().copied()),
+                Default::default(),
+            ),
+        }
+        .cleanup();
+
+        // Avoid triggering change detection, which would run this system again
+        if *value != new_value {
+            *value = new_value;
+        }
+    }
+}
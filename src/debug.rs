@@ -0,0 +1,362 @@
+/// Developer tools for inspecting the game while it runs
+use crate::input::{
+    buttons::{
+        CheckPuzzle, ChooseLanguage, CoachPlayer, EnterSeed, FillCandidates, FindMistake, GetHint,
+        LockPuzzle, MinimizePuzzle, NewPuzzle, RemixPuzzle, ResetPuzzle, ShowLevelSelect,
+        ShowSettings, ShowTechniques, SolvePuzzle,
+    },
+    input_mode::InputMode,
+    ScrambleRemaining,
+};
+use bevy::{prelude::*, render::RenderStage};
+use std::time::Instant;
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<profiling::FrameCapture>()
+            .init_resource::<audit::ButtonsSeen>()
+            .add_system(profiling::start_capture.system())
+            .add_system(analysis::analyze_position.system())
+            .add_system(audit::run_audit.system())
+            // Timestamps are recorded in stages of their own, which can't overlap the stages they separate,
+            // unlike systems ordered by label that still run alongside any unlabelled systems
+            .add_stage_before(
+                CoreStage::First,
+                profiling::ProfilingStage::FrameStart,
+                SystemStage::single_threaded().with_system(profiling::mark_frame_start.system()),
+            )
+            .add_stage_before(
+                CoreStage::Update,
+                profiling::ProfilingStage::UpdateStart,
+                SystemStage::single_threaded().with_system(profiling::mark_update_start.system()),
+            )
+            .add_stage_after(
+                CoreStage::Update,
+                profiling::ProfilingStage::UpdateEnd,
+                SystemStage::single_threaded().with_system(profiling::mark_update_end.system()),
+            )
+            .add_stage_before(
+                RenderStage::RenderResource,
+                profiling::ProfilingStage::RenderStart,
+                SystemStage::single_threaded().with_system(profiling::mark_render_start.system()),
+            )
+            .add_stage_after(
+                CoreStage::Last,
+                profiling::ProfilingStage::FrameEnd,
+                SystemStage::single_threaded().with_system(profiling::mark_frame_end.system()),
+            );
+
+        // Every button standing in for a shortcut is watched for, so the audit knows which controls have one
+        app.add_system(audit::record_buttons::<NewPuzzle>.system())
+            .add_system(audit::record_buttons::<ResetPuzzle>.system())
+            .add_system(audit::record_buttons::<SolvePuzzle>.system())
+            .add_system(audit::record_buttons::<RemixPuzzle>.system())
+            .add_system(audit::record_buttons::<GetHint>.system())
+            .add_system(audit::record_buttons::<FindMistake>.system())
+            .add_system(audit::record_buttons::<CheckPuzzle>.system())
+            .add_system(audit::record_buttons::<FillCandidates>.system())
+            .add_system(audit::record_buttons::<ScrambleRemaining>.system())
+            .add_system(audit::record_buttons::<ShowTechniques>.system())
+            .add_system(audit::record_buttons::<CoachPlayer>.system())
+            .add_system(audit::record_buttons::<LockPuzzle>.system())
+            .add_system(audit::record_buttons::<MinimizePuzzle>.system())
+            .add_system(audit::record_buttons::<ShowLevelSelect>.system())
+            .add_system(audit::record_buttons::<ShowSettings>.system())
+            .add_system(audit::record_buttons::<EnterSeed>.system())
+            .add_system(audit::record_buttons::<ChooseLanguage>.system())
+            .add_system(audit::record_buttons::<InputMode>.system());
+
+        // Catch logic errors in development builds as soon as they occur
+        #[cfg(debug_assertions)]
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            invariants::assert_invariants.system(),
+        );
+    }
+}
+
+/// Inspect the current position using the solver
+mod analysis {
+    use crate::input::shortcuts::{Shortcut, Shortcuts};
+    use crate::logic::{
+        board::{Cell, Coordinates, Value},
+        constraints::ConstraintSet,
+        notation::Position,
+    };
+    use bevy::prelude::*;
+
+    /// Logs the current position as a pencilmark string, along with its grade, when F11 is pressed
+    ///
+    /// Center marks are treated as the only candidates of their cells,
+    /// so this reveals whether the player has eliminated a correct candidate
+    pub fn analyze_position(
+        keyboard_input: Res<Input<KeyCode>>,
+        shortcuts: Res<Shortcuts>,
+        query: Query<(&Coordinates, &Value), With<Cell>>,
+        constraints: Res<ConstraintSet>,
+    ) {
+        if shortcuts.just_pressed(Shortcut::AnalyzePosition, &keyboard_input) {
+            let cells: Vec<_> = query.iter().collect();
+            let position = Position::from_cells(constraints.grid(), &cells);
+
+            info!("Position: {}", position.to_pencilmarks());
+            info!(
+                "Solutions: {:?}, grade: {:?}",
+                position.uniqueness(&constraints),
+                position.grade(&constraints)
+            );
+        }
+    }
+}
+
+/// Checks that every control can be reached from the keyboard, from a gamepad and from a button on screen
+///
+/// Keyboard bindings are read from the `Shortcuts` map. Gamepads can only press the buttons of menus,
+/// so buttons are recorded as they are spawned, and the audit covers the menus opened so far this session
+mod audit {
+    use crate::input::{
+        buttons::ShortcutButton,
+        focus::Focusable,
+        shortcuts::{Shortcut, Shortcuts},
+    };
+    use bevy::{prelude::*, utils::HashSet};
+
+    /// Resource storing the shortcuts whose buttons have been spawned so far
+    #[derive(Default)]
+    pub struct ButtonsSeen {
+        /// Shortcuts with a button that can be clicked
+        clicked: HashSet<Shortcut>,
+        /// Shortcuts with a button in a menu, which can be focused and pressed with a gamepad
+        focused: HashSet<Shortcut>,
+    }
+
+    /// Records the shortcuts of the buttons of this type as they are spawned
+    pub fn record_buttons<Marker: ShortcutButton>(
+        query: Query<(&Marker, Option<&Focusable>), (With<Button>, Added<Marker>)>,
+        mut buttons_seen: ResMut<ButtonsSeen>,
+    ) {
+        for (marker, focusable) in query.iter() {
+            buttons_seen.clicked.insert(marker.shortcut());
+            if focusable.is_some() {
+                buttons_seen.focused.insert(marker.shortcut());
+            }
+        }
+    }
+
+    /// Logs every control that can't be reached from the keyboard, a gamepad or a button, when Ctrl + F12 is pressed
+    pub fn run_audit(
+        keyboard_input: Res<Input<KeyCode>>,
+        shortcuts: Res<Shortcuts>,
+        buttons_seen: Res<ButtonsSeen>,
+    ) {
+        if !shortcuts.just_pressed(Shortcut::AccessibilityAudit, &keyboard_input) {
+            return;
+        }
+
+        let all = Shortcut::all();
+        let mut no_keyboard = Vec::new();
+        let mut no_gamepad = Vec::new();
+        let mut no_button = Vec::new();
+        for shortcut in all.iter().copied() {
+            if shortcuts.binding(shortcut).is_none() {
+                no_keyboard.push(shortcut);
+            }
+            if !buttons_seen.focused.contains(&shortcut) {
+                no_gamepad.push(shortcut);
+            }
+            if !buttons_seen.clicked.contains(&shortcut) {
+                no_button.push(shortcut);
+            }
+        }
+
+        info!(
+            "Accessibility audit of {} controls, counting the buttons of the menus opened so far",
+            all.len()
+        );
+        for (reach, unreachable) in [
+            ("keyboard binding", no_keyboard),
+            ("gamepad binding", no_gamepad),
+            ("button", no_button),
+        ]
+        .iter()
+        {
+            if unreachable.is_empty() {
+                info!("Every control has a {}", reach);
+            } else {
+                warn!("No {}: {:?}", reach, unreachable);
+            }
+        }
+    }
+}
+
+/// Validates the state of the board
+#[cfg(debug_assertions)]
+mod invariants {
+    use crate::logic::{
+        board::{Cell, Coordinates, Fixed, Value},
+        constraints::ConstraintSet,
+        invariants::check_invariants,
+        sudoku_generation::CompletePuzzle,
+    };
+    use bevy::prelude::*;
+
+    /// Panics if any of the board's invariants have been broken
+    pub fn assert_invariants(
+        query: Query<(&Coordinates, &Value, &Fixed), With<Cell>>,
+        complete_puzzle: Res<CompletePuzzle>,
+        constraints: Res<ConstraintSet>,
+    ) {
+        let cells: Vec<_> = query.iter().collect();
+
+        if let Err(violation) = check_invariants(&cells, &complete_puzzle.numbers, &constraints) {
+            panic!("Board invariant violated: {:?}", violation);
+        }
+    }
+}
+
+/// Capture timings for a window of frames and export them for viewing as a flamegraph
+///
+/// Our input, logic and graphics systems all run in parallel within the update stage, so they are timed together.
+/// For per-system spans, build with `--features trace`,
+/// which records a chrome trace of the whole session using Bevy's built-in instrumentation
+pub mod profiling {
+    use super::*;
+    use crate::input::shortcuts::{Shortcut, Shortcuts};
+    use std::fmt::Write;
+
+    /// The number of frames recorded by each capture
+    const CAPTURE_LENGTH: usize = 120;
+    /// The file captures are written to, in chrome://tracing format
+    const CAPTURE_PATH: &str = "frame_capture.json";
+
+    /// Stages holding nothing but the system recording when they were reached
+    #[derive(StageLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+    pub enum ProfilingStage {
+        FrameStart,
+        UpdateStart,
+        UpdateEnd,
+        RenderStart,
+        FrameEnd,
+    }
+
+    /// The time at which each part of the frame began, and when the frame ended
+    #[derive(Default, Clone)]
+    pub struct FrameTimings {
+        /// Engine input and events are handled first
+        start: Option<Instant>,
+        /// Every system of the game runs during the update
+        update: Option<Instant>,
+        /// Transforms and UI layout are then updated
+        post_update: Option<Instant>,
+        render: Option<Instant>,
+        end: Option<Instant>,
+    }
+
+    /// Resource storing the frames captured so far
+    #[derive(Default)]
+    pub struct FrameCapture {
+        /// Are we currently recording frames
+        active: bool,
+        current: FrameTimings,
+        frames: Vec<FrameTimings>,
+    }
+
+    impl FrameCapture {
+        /// Converts the captured frames into the chrome://tracing JSON format
+        fn to_chrome_trace(&self) -> String {
+            let start = match self.frames.first().and_then(|frame| frame.start) {
+                Some(start) => start,
+                None => return "[]".to_string(),
+            };
+
+            let mut events = Vec::new();
+            for frame in self.frames.iter() {
+                let spans = [
+                    ("events", frame.start, frame.update),
+                    ("update", frame.update, frame.post_update),
+                    ("layout", frame.post_update, frame.render),
+                    ("render", frame.render, frame.end),
+                    ("frame", frame.start, frame.end),
+                ];
+
+                for (name, begin, end) in spans.iter() {
+                    if let (Some(begin), Some(end)) = (begin, end) {
+                        let mut event = String::new();
+                        write!(
+                            event,
+                            r#"{{"name":"{}","ph":"X","ts":{},"dur":{},"pid":0,"tid":0}}"#,
+                            name,
+                            begin.saturating_duration_since(start).as_micros(),
+                            end.saturating_duration_since(*begin).as_micros()
+                        )
+                        .expect("Writing to a String cannot fail");
+                        events.push(event);
+                    }
+                }
+            }
+
+            format!("[{}]", events.join(","))
+        }
+    }
+
+    /// Begins capturing frames when F12 is pressed
+    pub fn start_capture(
+        keyboard_input: Res<Input<KeyCode>>,
+        shortcuts: Res<Shortcuts>,
+        mut frame_capture: ResMut<FrameCapture>,
+    ) {
+        if shortcuts.just_pressed(Shortcut::CaptureFrames, &keyboard_input) && !frame_capture.active
+        {
+            info!("Capturing {} frames", CAPTURE_LENGTH);
+            *frame_capture = FrameCapture {
+                active: true,
+                ..Default::default()
+            };
+        }
+    }
+
+    pub fn mark_frame_start(mut frame_capture: ResMut<FrameCapture>) {
+        if frame_capture.active {
+            frame_capture.current.start = Some(Instant::now());
+        }
+    }
+
+    pub fn mark_update_start(mut frame_capture: ResMut<FrameCapture>) {
+        if frame_capture.active {
+            frame_capture.current.update = Some(Instant::now());
+        }
+    }
+
+    pub fn mark_update_end(mut frame_capture: ResMut<FrameCapture>) {
+        if frame_capture.active {
+            frame_capture.current.post_update = Some(Instant::now());
+        }
+    }
+
+    pub fn mark_render_start(mut frame_capture: ResMut<FrameCapture>) {
+        if frame_capture.active {
+            frame_capture.current.render = Some(Instant::now());
+        }
+    }
+
+    /// Stores the completed frame, and writes the capture to disk once enough frames are stored
+    pub fn mark_frame_end(mut frame_capture: ResMut<FrameCapture>) {
+        if !frame_capture.active {
+            return;
+        }
+
+        let mut frame = std::mem::take(&mut frame_capture.current);
+        frame.end = Some(Instant::now());
+        frame_capture.frames.push(frame);
+
+        if frame_capture.frames.len() >= CAPTURE_LENGTH {
+            frame_capture.active = false;
+            match std::fs::write(CAPTURE_PATH, frame_capture.to_chrome_trace()) {
+                Ok(()) => info!("Frame capture written to {}", CAPTURE_PATH),
+                Err(error) => warn!("Could not write frame capture: {}", error),
+            }
+        }
+    }
+}
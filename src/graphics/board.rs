@@ -1,10 +1,23 @@
 /// Build and display the Sudoku board
 use crate::{
+    graphics::MainCamera,
     input::Selected,
-    logic::board::{Cell, Coordinates, Fixed, Value},
+    logic::{
+        board::{
+            marks::MAX_CORNER_MARKS, BlockedEdit, Cell, CellColor, Coordinates, Fixed,
+            HouseCompleted, Locked, Provenance, PuzzleSolved, Value,
+        },
+        conflicts::{Conflicting, Flagged, Incorrect, Mistaken},
+        constraints::{ConstraintSet, LineColor, Overlay},
+        grid::{digit_symbol, GridSize},
+        likelihood::Likelihoods,
+    },
+    settings::{DisplayPreset, Settings},
     CommonLabels,
 };
 use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use std::time::Duration;
 
 use self::assets::*;
 use self::config::*;
@@ -19,52 +32,246 @@ impl Plugin for BoardPlugin {
             .init_resource::<FillableFont>()
             .init_resource::<BackgroundColor>()
             .init_resource::<SelectionColor>()
+            .init_resource::<MistakeColor>()
+            .init_resource::<FlashColor>()
+            .init_resource::<FlourishColor>()
+            .init_resource::<CellPalette>()
+            .init_resource::<GridColor>()
+            .init_resource::<OverlayColor>()
+            .init_resource::<OverlayFillColor>()
+            .init_resource::<OverlayLinePalette>()
+            .init_resource::<OverlayShadeColor>()
+            .init_resource::<LockColor>()
             // SETUP
-            // Must occur in an earlier stage to ensure that the cells are initialized
-            // as commands are not processed until the end of the stage
-            .add_startup_system_to_stage(StartupStage::PreStartup, setup::spawn_cells.system())
-            .add_startup_system(setup::spawn_grid.system())
-            .add_startup_system(setup::spawn_cell_numbers.system())
+            // The board is built once the first puzzle is chosen, and rebuilt whenever the size of its grid changes
+            .add_system(setup::rebuild_board.system().after(CommonLabels::Action))
+            .add_system(setup::fit_camera.system().after(CommonLabels::Action))
             // ACTION HANDLING
             .add_system_set(
                 SystemSet::new()
                     .after(CommonLabels::Action)
                     .with_system(actions::color_selected.system())
+                    .with_system(actions::flash_blocked_cells.system())
+                    .with_system(actions::fade_flashes.system())
+                    .with_system(actions::flourish_completed_houses.system())
+                    .with_system(actions::shimmer_solved_board.system())
+                    .with_system(actions::animate_flourishes.system())
                     .with_system(actions::update_cell_numbers.system())
-                    .with_system(actions::style_numbers.system()),
-            );
+                    .with_system(actions::update_corner_marks.system())
+                    .with_system(actions::style_numbers.system())
+                    .with_system(actions::resize_gridlines.system())
+                    .with_system(actions::size_numbers.system())
+                    .with_system(actions::size_corner_marks.system())
+                    .with_system(actions::paint_cells.system())
+                    .with_system(actions::underline_locked.system())
+                    .with_system(actions::show_likelihoods.system())
+                    .with_system(actions::draw_overlays.system())
+                    .with_system(actions::draw_region_borders.system()),
+            )
+            // Must occur in a later stage than conflict detection and auto-check,
+            // as the markers they add and remove are not applied until the end of the stage
+            .add_system_to_stage(CoreStage::PostUpdate, actions::color_conflicts.system());
     }
 }
 
-mod config {
+pub mod config {
     use super::*;
 
     // Colors
     pub const SELECTION_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
+    pub const MISTAKE_COLOR: Color = Color::rgb(1.0, 0.7, 0.7);
+    /// The color cells flash when the player tries to change a digit that can't be changed
+    pub const FLASH_COLOR: Color = Color::rgb(0.6, 0.6, 0.6);
+    pub const FLASH_DURATION: Duration = Duration::from_millis(200);
+    /// The color that sweeps across a row, column or square when it is completed
+    pub const FLOURISH_COLOR: Color = Color::rgb(0.75, 0.9, 0.75);
+    /// How long each cell stays highlighted during the sweep
+    pub const FLOURISH_DURATION: Duration = Duration::from_millis(250);
+    /// The delay between one cell of the house lighting up and the next
+    pub const FLOURISH_STEP: Duration = Duration::from_millis(40);
 
     pub const GRID_COLOR: Color = Color::rgb(0.1, 0.1, 0.1);
+    /// The color of digits given by the puzzle
     pub const NUMBER_COLOR: Color = Color::BLACK;
+    /// The color of digits and marks entered by the player
+    pub const FILLABLE_NUMBER_COLOR: Color = Color::rgb(0.1, 0.3, 0.8);
+    /// The colors of digits filled in by hints and the solver, when provenance is shown
+    pub const HINT_NUMBER_COLOR: Color = Color::rgb(0.55, 0.2, 0.7);
+    pub const SOLVER_NUMBER_COLOR: Color = Color::rgb(0.45, 0.45, 0.45);
+    pub const LOCK_COLOR: Color = Color::rgb(0.2, 0.35, 0.8);
+    pub const CONFLICT_COLOR: Color = Color::rgb(0.85, 0.1, 0.1);
+    /// The color of digits found to be wrong by auto-check
+    pub const INCORRECT_COLOR: Color = Color::rgb(0.9, 0.5, 0.0);
+    /// The colors players can paint cells with
+    pub const CELL_PALETTE: [Color; 9] = [
+        Color::rgb(1.0, 0.6, 0.6),
+        Color::rgb(1.0, 0.8, 0.55),
+        Color::rgb(1.0, 1.0, 0.6),
+        Color::rgb(0.65, 0.95, 0.65),
+        Color::rgb(0.6, 0.95, 0.95),
+        Color::rgb(0.6, 0.75, 1.0),
+        Color::rgb(0.8, 0.65, 1.0),
+        Color::rgb(1.0, 0.65, 0.9),
+        Color::rgb(0.75, 0.75, 0.75),
+    ];
 
     // Fonts
     pub const FIXED_NUM_FONT: &str = "fonts/Ubuntu-Bold.ttf";
     pub const FILLABLE_NUM_FONT: &str = "fonts/Ubuntu-Light.ttf";
 
+    /// The color of the digits in the likelihood view, which fade as they become less likely
+    pub const LIKELIHOOD_COLOR: Color = Color::rgb(0.1, 0.3, 0.8);
+    /// The opacity of the least likely digits that are still possible, so they remain legible
+    pub const LIKELIHOOD_MIN_ALPHA: f32 = 0.15;
+
+    /// The color of the outlines and labels drawn by the constraints of variant puzzles
+    pub const OVERLAY_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+    /// The color of the lines and bulbs drawn beneath the digits, such as thermometers
+    pub const OVERLAY_FILL_COLOR: Color = Color::rgb(0.8, 0.8, 0.8);
+    /// The colors of green and purple lines, such as German whispers and renban lines
+    pub const OVERLAY_GREEN_COLOR: Color = Color::rgb(0.55, 0.85, 0.55);
+    pub const OVERLAY_PURPLE_COLOR: Color = Color::rgb(0.85, 0.6, 0.95);
+    /// The color of the shading drawn over cells, such as the windows of windoku,
+    /// which lets the selection and the cell's own color show through
+    pub const OVERLAY_SHADE_COLOR: Color = Color::rgba(0.5, 0.5, 0.5, 0.25);
+
     // Sizes
     pub const CELL_SIZE: f32 = 50.0;
-    pub const GRID_SIZE: f32 = 9.0 * CELL_SIZE;
-    pub const MINOR_LINE_THICKNESS: f32 = 2.0;
-    pub const MAJOR_LINE_THICKNESS: f32 = 4.0;
+    /// The distance between an outline and the edges of the cells it surrounds
+    pub const OVERLAY_INSET: f32 = 0.08 * CELL_SIZE;
+    pub const OVERLAY_THICKNESS: f32 = 1.5;
+    /// The length of each dash of a dashed outline, and of the gaps between them
+    pub const OVERLAY_DASH: f32 = 0.1 * CELL_SIZE;
+    pub const OVERLAY_LABEL_FONT_SIZE: f32 = 0.25 * CELL_SIZE;
+    pub const OVERLAY_LINE_WIDTH: f32 = 0.3 * CELL_SIZE;
+    pub const OVERLAY_BULB_SIZE: f32 = 0.75 * CELL_SIZE;
+    pub const OVERLAY_DOT_SIZE: f32 = 0.25 * CELL_SIZE;
+    /// The width of the rim of an empty bulb, such as the ends of a between line
+    pub const OVERLAY_RING_THICKNESS: f32 = 0.06 * CELL_SIZE;
+    /// The circles on the corners between cells, such as quadruples, fit up to four digits in two rows
+    pub const OVERLAY_CORNER_SIZE: f32 = 0.45 * CELL_SIZE;
+    pub const OVERLAY_CORNER_FONT_SIZE: f32 = 0.2 * CELL_SIZE;
+    /// The length of the arrow pointing from a clue outside the grid into the cells it applies to
+    pub const OUTER_CLUE_ARROW: f32 = 0.3 * CELL_SIZE;
+
+    /// The sizes used to draw the board, controlled by the `DisplayPreset` setting
+    pub struct DisplayMetrics {
+        pub minor_line_thickness: f32,
+        pub major_line_thickness: f32,
+        /// The space between the edge of the cell and its digit
+        pub cell_padding: f32,
+        /// The size of the marks, relative to the size of the digits
+        pub mark_scale: f32,
+    }
+
+    impl DisplayMetrics {
+        pub fn new(preset: DisplayPreset) -> Self {
+            use DisplayPreset::*;
+            match preset {
+                Compact => DisplayMetrics {
+                    minor_line_thickness: 1.0,
+                    major_line_thickness: 3.0,
+                    cell_padding: 3.0,
+                    mark_scale: 0.35,
+                },
+                Comfortable => DisplayMetrics {
+                    minor_line_thickness: 2.0,
+                    major_line_thickness: 4.0,
+                    cell_padding: 5.0,
+                    mark_scale: 0.4,
+                },
+                Broadcast => DisplayMetrics {
+                    minor_line_thickness: 3.0,
+                    major_line_thickness: 6.0,
+                    cell_padding: 7.0,
+                    mark_scale: 0.5,
+                },
+            }
+        }
+
+        /// The font size of the digits filled into cells
+        pub fn number_font_size(&self) -> f32 {
+            CELL_SIZE - 2.0 * self.cell_padding
+        }
+
+        /// The font size of the marks written into the center of cells
+        pub fn mark_font_size(&self) -> f32 {
+            self.mark_scale * self.number_font_size()
+        }
+
+        /// The font size of a cell's center marks, which shrink once there are too many to fit
+        pub fn center_mark_font_size(&self, n_marks: usize) -> f32 {
+            if n_marks < CROWDED_CENTER_MARKS {
+                return self.mark_font_size();
+            }
+
+            let available_width = CELL_SIZE - 2.0 * self.cell_padding;
+            let fitted_size = available_width / (DIGIT_ASPECT_RATIO * n_marks as f32);
+            fitted_size.min(self.mark_font_size())
+        }
+
+        /// The font size of the marks written around the edges of cells
+        ///
+        /// These are smaller than center marks, as up to eight must share a cell
+        pub fn corner_mark_font_size(&self) -> f32 {
+            CORNER_MARK_SCALE * self.mark_font_size()
+        }
+    }
 
     // Positions
     // Defines the center lines of the grid in absolute coordinates
     // (0, 0) is in the center of the screen in Bevy
+    // A band a cell wide is left clear around the grid, for clues written outside it such as little killer sums
     pub const GRID_CENTER_X: f32 = -300.0;
-    pub const GRID_LEFT_EDGE: f32 = GRID_CENTER_X - 0.5 * GRID_SIZE;
     pub const GRID_CENTER_Y: f32 = 0.0;
-    pub const GRID_BOT_EDGE: f32 = GRID_CENTER_Y - 0.5 * GRID_SIZE;
+
+    /// The width of the grid, before the camera zooms to fit it on screen
+    pub fn grid_size(grid: GridSize) -> f32 {
+        grid.width() as f32 * CELL_SIZE
+    }
+
+    pub fn grid_left_edge(grid: GridSize) -> f32 {
+        GRID_CENTER_X - 0.5 * grid_size(grid)
+    }
+
+    pub fn grid_bot_edge(grid: GridSize) -> f32 {
+        GRID_CENTER_Y - 0.5 * grid_size(grid)
+    }
+
+    /// How far the camera zooms out, so that every size of grid fills the space of the classic 9x9 grid
+    pub fn grid_zoom(grid: GridSize) -> f32 {
+        grid.width() as f32 / 9.0
+    }
+
+    /// The space between the edge of a cell and its paint, leaving room to show the selection
+    pub const PAINT_INSET: f32 = 0.1 * CELL_SIZE;
+    /// The size and position of the line drawn beneath locked digits
+    pub const UNDERLINE_WIDTH: f32 = 0.4 * CELL_SIZE;
+    pub const UNDERLINE_THICKNESS: f32 = 2.0;
+    pub const UNDERLINE_OFFSET_Y: f32 = -0.3 * CELL_SIZE;
 
     pub const NUM_OFFSET_X: f32 = 0.0 * CELL_SIZE;
     pub const NUM_OFFSET_Y: f32 = 0.03 * CELL_SIZE;
+
+    /// Cells with at least this many center marks have them shrunk to fit
+    pub const CROWDED_CENTER_MARKS: usize = 5;
+    /// The width of a digit relative to its font size
+    pub const DIGIT_ASPECT_RATIO: f32 = 0.55;
+    /// The size of corner marks, relative to the size of center marks
+    pub const CORNER_MARK_SCALE: f32 = 0.8;
+    /// Where each corner mark is drawn relative to the center of its cell, in the order they are filled
+    ///
+    /// The corners are used first, followed by the middle of each edge
+    pub const CORNER_MARK_OFFSETS: [(f32, f32); MAX_CORNER_MARKS] = [
+        (-0.32 * CELL_SIZE, 0.32 * CELL_SIZE),
+        (0.32 * CELL_SIZE, 0.32 * CELL_SIZE),
+        (-0.32 * CELL_SIZE, -0.32 * CELL_SIZE),
+        (0.32 * CELL_SIZE, -0.32 * CELL_SIZE),
+        (0.0, 0.32 * CELL_SIZE),
+        (0.0, -0.32 * CELL_SIZE),
+        (-0.32 * CELL_SIZE, 0.0),
+        (0.32 * CELL_SIZE, 0.0),
+    ];
 }
 
 // QUALITY: reduce asset loading code duplication dramatically
@@ -77,6 +284,8 @@ pub mod assets {
     pub struct BackgroundColor(pub Handle<ColorMaterial>);
     /// The color of cells when selected
     pub struct SelectionColor(pub Handle<ColorMaterial>);
+    /// The color of cells revealed to contain the wrong digit
+    pub struct MistakeColor(pub Handle<ColorMaterial>);
 
     impl FromWorld for BackgroundColor {
         fn from_world(world: &mut World) -> Self {
@@ -96,6 +305,161 @@ pub mod assets {
         }
     }
 
+    /// The color of cells that can't be changed, briefly shown when the player tries to
+    pub struct FlashColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for FlashColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            FlashColor(materials.add(FLASH_COLOR.into()))
+        }
+    }
+
+    /// The color swept across houses as they are completed
+    pub struct FlourishColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for FlourishColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            FlourishColor(materials.add(FLOURISH_COLOR.into()))
+        }
+    }
+
+    impl FromWorld for MistakeColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            MistakeColor(materials.add(MISTAKE_COLOR.into()))
+        }
+    }
+
+    /// The colors players can paint cells with, followed by the transparent color of unpainted cells
+    pub struct CellPalette {
+        pub colors: Vec<Handle<ColorMaterial>>,
+        pub none: Handle<ColorMaterial>,
+    }
+
+    impl CellPalette {
+        /// Looks up the material for the cell color provided
+        pub fn get(&self, cell_color: &CellColor) -> Handle<ColorMaterial> {
+            match cell_color.0 {
+                Some(i) => self.colors[(i - 1) as usize].clone(),
+                None => self.none.clone(),
+            }
+        }
+    }
+
+    impl FromWorld for CellPalette {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            CellPalette {
+                colors: CELL_PALETTE
+                    .iter()
+                    .map(|color| materials.add((*color).into()))
+                    .collect(),
+                none: materials.add(Color::NONE.into()),
+            }
+        }
+    }
+
+    /// The color of the lines of the grid, and the borders of its regions
+    pub struct GridColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for GridColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            GridColor(materials.add(GRID_COLOR.into()))
+        }
+    }
+
+    /// The color of the markings drawn for a puzzle's constraints
+    pub struct OverlayColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for OverlayColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            OverlayColor(materials.add(OVERLAY_COLOR.into()))
+        }
+    }
+
+    /// The color of the lines and bulbs drawn for a puzzle's constraints
+    pub struct OverlayFillColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for OverlayFillColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            OverlayFillColor(materials.add(OVERLAY_FILL_COLOR.into()))
+        }
+    }
+
+    /// The colors of the lines drawn for a puzzle's constraints
+    pub struct OverlayLinePalette {
+        pub gray: Handle<ColorMaterial>,
+        pub green: Handle<ColorMaterial>,
+        pub purple: Handle<ColorMaterial>,
+    }
+
+    impl OverlayLinePalette {
+        /// Looks up the material for the line color provided
+        pub fn get(&self, color: LineColor) -> Handle<ColorMaterial> {
+            match color {
+                LineColor::Gray => self.gray.clone(),
+                LineColor::Green => self.green.clone(),
+                LineColor::Purple => self.purple.clone(),
+            }
+        }
+    }
+
+    impl FromWorld for OverlayLinePalette {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            OverlayLinePalette {
+                gray: materials.add(OVERLAY_FILL_COLOR.into()),
+                green: materials.add(OVERLAY_GREEN_COLOR.into()),
+                purple: materials.add(OVERLAY_PURPLE_COLOR.into()),
+            }
+        }
+    }
+
+    /// The color of the shading drawn for a puzzle's constraints
+    pub struct OverlayShadeColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for OverlayShadeColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            OverlayShadeColor(materials.add(OVERLAY_SHADE_COLOR.into()))
+        }
+    }
+
+    /// The color of the line drawn beneath digits the player has locked in
+    pub struct LockColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for LockColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            LockColor(materials.add(LOCK_COLOR.into()))
+        }
+    }
+
     // Fonts used in our game
     pub struct FixedFont(pub Handle<Font>);
 
@@ -123,56 +487,207 @@ pub mod assets {
 mod setup {
     use super::*;
 
-    pub fn spawn_grid(mut commands: Commands, mut materials: ResMut<Assets<ColorMaterial>>) {
-        let grid_handle = materials.add(GRID_COLOR.into());
+    /// Despawns the cells and grid lines of the board, along with everything drawn in the cells,
+    /// and spawns them again whenever the puzzle is played on a grid of a different size
+    ///
+    /// Each cell is spawned together with the entities displaying it,
+    /// so that the relations between them are in place by the time the puzzle is filled in
+    pub fn rebuild_board(
+        constraints: Res<ConstraintSet>,
+        mut built: Local<Option<GridSize>>,
+        cell_query: Query<
+            (
+                Entity,
+                &Relation<DisplayedBy>,
+                &Relation<CornerMarkedBy>,
+                &Relation<PaintedBy>,
+                &Relation<UnderlinedBy>,
+                &Relation<LikelihoodShownBy>,
+            ),
+            With<Cell>,
+        >,
+        gridline_query: Query<Entity, With<GridLine>>,
+        fixed_font: Res<FixedFont>,
+        fillable_font: Res<FillableFont>,
+        grid_color: Res<GridColor>,
+        palette: Res<CellPalette>,
+        lock_color: Res<LockColor>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        let grid = constraints.grid();
+        if *built == Some(grid) {
+            return;
+        }
+        *built = Some(grid);
 
-        for row in 0..=9 {
-            commands.spawn_bundle(new_gridline(
-                Orientation::Horizontal,
-                row,
-                grid_handle.clone(),
-            ));
+        for (
+            cell_entity,
+            displayed_by,
+            corner_marked_by,
+            painted_by,
+            underlined_by,
+            likelihood_shown_by,
+        ) in cell_query.iter()
+        {
+            let shown_by = displayed_by
+                .into_iter()
+                .map(|(entity, _)| entity)
+                .chain(corner_marked_by.into_iter().map(|(entity, _)| entity))
+                .chain(painted_by.into_iter().map(|(entity, _)| entity))
+                .chain(underlined_by.into_iter().map(|(entity, _)| entity))
+                .chain(likelihood_shown_by.into_iter().map(|(entity, _)| entity));
+            for entity in shown_by {
+                commands.entity(entity).despawn();
+            }
+            commands.entity(cell_entity).despawn();
+        }
+        for entity in gridline_query.iter() {
+            commands.entity(entity).despawn();
         }
 
-        for column in 0..=9 {
-            commands.spawn_bundle(new_gridline(
-                Orientation::Vertical,
-                column,
-                grid_handle.clone(),
-            ));
+        let metrics = DisplayMetrics::new(settings.display_preset);
+        spawn_grid(&mut commands, grid, grid_color.0.clone(), &metrics);
+        for coordinates in grid.cells() {
+            let cell_bundle = CellBundle::new(coordinates.row, coordinates.column, grid);
+            let cell_transform = cell_bundle.cell_fill.transform;
+            let cell_entity = commands.spawn_bundle(cell_bundle).id();
+
+            spawn_cell_number(
+                &mut commands,
+                cell_entity,
+                &cell_transform,
+                fixed_font.0.clone(),
+                &metrics,
+            );
+            spawn_corner_marks(
+                &mut commands,
+                cell_entity,
+                &cell_transform,
+                fillable_font.0.clone(),
+                &metrics,
+            );
+            spawn_cell_paint(
+                &mut commands,
+                cell_entity,
+                &cell_transform,
+                palette.none.clone(),
+            );
+            spawn_cell_underline(
+                &mut commands,
+                cell_entity,
+                &cell_transform,
+                lock_color.0.clone(),
+            );
+            spawn_likelihood_digits(
+                &mut commands,
+                cell_entity,
+                &cell_transform,
+                grid,
+                fillable_font.0.clone(),
+                &metrics,
+            );
         }
     }
 
-    enum Orientation {
+    /// Zooms the camera so that the board fills the same space whatever the size of its grid,
+    /// keeping the center of the board where it was on screen
+    pub fn fit_camera(
+        constraints: Res<ConstraintSet>,
+        mut camera_query: Query<&mut Transform, With<MainCamera>>,
+    ) {
+        if !constraints.is_changed() {
+            return;
+        }
+
+        let zoom = grid_zoom(constraints.grid());
+        let center = Vec2::new(GRID_CENTER_X, GRID_CENTER_Y);
+        for mut transform in camera_query.iter_mut() {
+            let on_screen = (center - transform.translation.truncate()) / transform.scale.x;
+            let translation = center - zoom * on_screen;
+            transform.translation.x = translation.x;
+            transform.translation.y = translation.y;
+            transform.scale = Vec3::new(zoom, zoom, 1.0);
+        }
+    }
+
+    /// Spawns the lines of each grid making up the board, which overlap where the grids of a samurai board do
+    fn spawn_grid(
+        commands: &mut Commands,
+        grid: GridSize,
+        grid_handle: Handle<ColorMaterial>,
+        metrics: &DisplayMetrics,
+    ) {
+        for subgrid in grid.subgrids() {
+            for orientation in [Orientation::Horizontal, Orientation::Vertical].iter() {
+                for i in 0..=grid.digits() {
+                    let gridline = GridLine {
+                        orientation: *orientation,
+                        i,
+                        grid,
+                        subgrid,
+                    };
+                    commands
+                        .spawn_bundle(new_gridline(&gridline, grid_handle.clone(), metrics))
+                        .insert(gridline);
+                }
+            }
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub enum Orientation {
         Horizontal,
         Vertical,
     }
 
-    fn new_gridline(
-        orientation: Orientation,
-        i: u8,
-        grid_handle: Handle<ColorMaterial>,
-    ) -> SpriteBundle {
-        // The grid lines that define the boxes need to be thicker
-        let thickness = if (i % 3) == 0 {
-            MAJOR_LINE_THICKNESS
+    /// Component for the lines of the grid, used to resize them when the display preset changes
+    pub struct GridLine {
+        pub orientation: Orientation,
+        /// Lines are counted from 0 up to the width of their grid, from the bottom or left edge of that grid
+        pub i: u8,
+        pub grid: GridSize,
+        /// The top left corner of the grid the line belongs to, among those making up the board
+        pub subgrid: (u8, u8),
+    }
+
+    /// Computes the size of the grid line
+    pub fn gridline_size(gridline: &GridLine, metrics: &DisplayMetrics) -> Vec2 {
+        // The edges of each grid are thick, while the borders between regions are drawn from the puzzle's regions
+        let thickness = if gridline.i == 0 || gridline.i == gridline.grid.digits() {
+            metrics.major_line_thickness
         } else {
-            MINOR_LINE_THICKNESS
+            metrics.minor_line_thickness
         };
+        // Lines keep their thickness on screen however far the camera zooms to fit the grid
+        let thickness = thickness * grid_zoom(gridline.grid);
 
-        let length = GRID_SIZE + thickness;
+        let length = gridline.grid.digits() as f32 * CELL_SIZE + thickness;
 
-        let size = match orientation {
+        match gridline.orientation {
             Orientation::Horizontal => Vec2::new(length, thickness),
             Orientation::Vertical => Vec2::new(thickness, length),
-        };
+        }
+    }
+
+    fn new_gridline(
+        gridline: &GridLine,
+        grid_handle: Handle<ColorMaterial>,
+        metrics: &DisplayMetrics,
+    ) -> SpriteBundle {
+        let size = gridline_size(gridline, metrics);
 
         // Each objects' position is defined by its center
-        let offset = i as f32 * CELL_SIZE;
+        // The grid's corner is offset along the same axes as the rows and columns of the cells are
+        let (top, left) = gridline.subgrid;
+        let left_edge = grid_left_edge(gridline.grid) + top as f32 * CELL_SIZE;
+        let bot_edge = grid_bot_edge(gridline.grid) + left as f32 * CELL_SIZE;
+        let offset = gridline.i as f32 * CELL_SIZE;
+        let half_size = 0.5 * gridline.grid.digits() as f32 * CELL_SIZE;
 
-        let (x, y) = match orientation {
-            Orientation::Horizontal => (GRID_LEFT_EDGE + 0.5 * GRID_SIZE, GRID_BOT_EDGE + offset),
-            Orientation::Vertical => (GRID_LEFT_EDGE + offset, GRID_BOT_EDGE + 0.5 * GRID_SIZE),
+        let (x, y) = match gridline.orientation {
+            Orientation::Horizontal => (left_edge + half_size, bot_edge + offset),
+            Orientation::Vertical => (left_edge + offset, bot_edge + half_size),
         };
 
         SpriteBundle {
@@ -184,39 +699,33 @@ mod setup {
         }
     }
 
-    pub fn spawn_cells(mut commands: Commands) {
-        for row in 1..=9 {
-            for column in 1..=9 {
-                commands.spawn_bundle(CellBundle::new(row, column));
-            }
-        }
-    }
-
     #[derive(Bundle)]
     struct CellBundle {
         cell: Cell,
         coordinates: Coordinates,
         value: Value,
         fixed: Fixed,
+        locked: Locked,
+        cell_color: CellColor,
+        provenance: Provenance,
         #[bundle]
         cell_fill: SpriteBundle,
     }
 
     impl CellBundle {
-        fn new(row: u8, column: u8) -> Self {
-            let x = GRID_LEFT_EDGE + CELL_SIZE * row as f32 - 0.5 * CELL_SIZE;
-            let y = GRID_BOT_EDGE + CELL_SIZE * column as f32 - 0.5 * CELL_SIZE;
+        fn new(row: u8, column: u8, grid: GridSize) -> Self {
+            let x = grid_left_edge(grid) + CELL_SIZE * row as f32 - 0.5 * CELL_SIZE;
+            let y = grid_bot_edge(grid) + CELL_SIZE * column as f32 - 0.5 * CELL_SIZE;
 
             CellBundle {
                 cell: Cell,
-                coordinates: Coordinates {
-                    row,
-                    column,
-                    square: Coordinates::compute_square(row, column),
-                },
+                coordinates: Coordinates { row, column },
                 // No digits are filled in to begin with
                 value: Value::Empty,
                 fixed: Fixed(false),
+                locked: Locked(false),
+                cell_color: CellColor::default(),
+                provenance: Provenance::default(),
                 cell_fill: SpriteBundle {
                     // The material for this sprite begins with the same material as our background
                     sprite: Sprite::new(Vec2::new(CELL_SIZE, CELL_SIZE)),
@@ -235,54 +744,240 @@ mod setup {
     // is displayed by the target entity (the Text2d entity in the same location)
     pub struct DisplayedBy;
 
-    /// Adds a text number associated with each cell to display its value
-    pub fn spawn_cell_numbers(
-        query: Query<(Entity, &Transform), With<Cell>>,
-        mut commands: Commands,
-        font_res: Res<FixedFont>,
+    /// Adds a text number associated with the cell to display its value
+    fn spawn_cell_number(
+        commands: &mut Commands,
+        cell_entity: Entity,
+        cell_transform: &Transform,
+        font: Handle<Font>,
+        metrics: &DisplayMetrics,
     ) {
         const TEXT_ALIGNMENT: TextAlignment = TextAlignment {
             vertical: VerticalAlign::Center,
             horizontal: HorizontalAlign::Center,
         };
 
-        for (cell_entity, cell_transform) in query.iter() {
-            let mut number_transform = cell_transform.clone();
+        let mut number_transform = cell_transform.clone();
 
-            // Tweaks for aesthetic perfection
-            number_transform.translation.x += NUM_OFFSET_X;
-            number_transform.translation.y += NUM_OFFSET_Y;
+        // Tweaks for aesthetic perfection
+        number_transform.translation.x += NUM_OFFSET_X;
+        number_transform.translation.y += NUM_OFFSET_Y;
 
-            // These numbers must be displayed on top of the cells they are in
-            number_transform.translation.z += 1.0;
+        // These numbers must be displayed on top of the cells they are in
+        number_transform.translation.z += 1.0;
 
-            let text_style = TextStyle {
-                font: font_res.0.clone(),
-                font_size: 0.8 * CELL_SIZE,
-                color: NUMBER_COLOR,
-            };
+        let text_style = TextStyle {
+            font,
+            font_size: metrics.number_font_size(),
+            color: NUMBER_COLOR,
+        };
+
+        let text_entity = commands
+            .spawn_bundle(Text2dBundle {
+                // This value begins empty, but then is later set in update_cell_numbers system
+                // to match the cell's `value` field
+                text: Text::with_section("", text_style, TEXT_ALIGNMENT),
+                transform: number_transform,
+                ..Default::default()
+            })
+            .insert(CellNumber)
+            .id();
+
+        commands
+            .entity(cell_entity)
+            .insert_relation(DisplayedBy, text_entity);
+    }
+
+    /// Component for the text displaying a cell's corner marks,
+    /// storing which of the `CORNER_MARK_OFFSETS` it is drawn at
+    pub struct CornerMarkSlot(pub usize);
+
+    // Marker relation to designate that the corner marks of the source entity (the Cell entity)
+    // are displayed by the target entities (the Text2d entities around its edges)
+    pub struct CornerMarkedBy;
+
+    /// Adds a text slot for each possible corner mark around the edges of the cell
+    fn spawn_corner_marks(
+        commands: &mut Commands,
+        cell_entity: Entity,
+        cell_transform: &Transform,
+        font: Handle<Font>,
+        metrics: &DisplayMetrics,
+    ) {
+        const TEXT_ALIGNMENT: TextAlignment = TextAlignment {
+            vertical: VerticalAlign::Center,
+            horizontal: HorizontalAlign::Center,
+        };
 
-            let text_entity = commands
+        let text_style = TextStyle {
+            font,
+            font_size: metrics.corner_mark_font_size(),
+            color: FILLABLE_NUMBER_COLOR,
+        };
+
+        for (slot, (offset_x, offset_y)) in CORNER_MARK_OFFSETS.iter().enumerate() {
+            let mut mark_transform = cell_transform.clone();
+            mark_transform.translation.x += offset_x;
+            mark_transform.translation.y += offset_y;
+            // Marks must be displayed on top of the cells they are in
+            mark_transform.translation.z += 1.0;
+
+            let mark_entity = commands
                 .spawn_bundle(Text2dBundle {
-                    // This value begins empty, but then is later set in update_cell_numbers system
-                    // to match the cell's `value` field
+                    // Set in the update_corner_marks system
                     text: Text::with_section("", text_style.clone(), TEXT_ALIGNMENT),
-                    transform: number_transform,
+                    transform: mark_transform,
                     ..Default::default()
                 })
-                .insert(CellNumber)
+                .insert(CornerMarkSlot(slot))
                 .id();
 
             commands
                 .entity(cell_entity)
-                .insert_relation(DisplayedBy, text_entity);
+                .insert_relation(CornerMarkedBy, mark_entity);
+        }
+    }
+
+    // Marker relation to designate that the CellColor on the source entity (the Cell entity)
+    // is displayed by the target entity (the paint sprite in the same location)
+    pub struct PaintedBy;
+
+    /// Adds a sprite associated with the cell to display its color, beneath its number
+    fn spawn_cell_paint(
+        commands: &mut Commands,
+        cell_entity: Entity,
+        cell_transform: &Transform,
+        material: Handle<ColorMaterial>,
+    ) {
+        let mut paint_transform = cell_transform.clone();
+        // Paint covers the cell, but not its number
+        paint_transform.translation.z += 0.5;
+
+        let paint_size = CELL_SIZE - 2.0 * PAINT_INSET;
+
+        let paint_entity = commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite::new(Vec2::new(paint_size, paint_size)),
+                transform: paint_transform,
+                material,
+                ..Default::default()
+            })
+            .id();
+
+        commands
+            .entity(cell_entity)
+            .insert_relation(PaintedBy, paint_entity);
+    }
+
+    // Marker relation to designate that the Locked status of the source entity (the Cell entity)
+    // is displayed by the target entity (the underline sprite beneath its number)
+    pub struct UnderlinedBy;
+
+    /// Adds a hidden line beneath the cell's number, shown once the player locks the digit in
+    fn spawn_cell_underline(
+        commands: &mut Commands,
+        cell_entity: Entity,
+        cell_transform: &Transform,
+        material: Handle<ColorMaterial>,
+    ) {
+        let mut underline_transform = cell_transform.clone();
+        underline_transform.translation.y += UNDERLINE_OFFSET_Y;
+        // Drawn alongside the number, above any paint
+        underline_transform.translation.z += 1.0;
+
+        let underline_entity = commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite::new(Vec2::new(UNDERLINE_WIDTH, UNDERLINE_THICKNESS)),
+                transform: underline_transform,
+                material,
+                visible: Visible {
+                    is_visible: false,
+                    is_transparent: false,
+                },
+                ..Default::default()
+            })
+            .id();
+
+        commands
+            .entity(cell_entity)
+            .insert_relation(UnderlinedBy, underline_entity);
+    }
+
+    /// Component for the text showing how likely a digit is to belong in a cell
+    pub struct LikelihoodDigit(pub u8);
+
+    // Marker relation to designate that the likelihoods of the source entity (the Cell entity)
+    // are displayed by the target entities (the Text2d entities laid out in a small grid inside it)
+    pub struct LikelihoodShownBy;
+
+    /// Adds a hidden digit for each of the possible values of the cell,
+    /// shown only in the likelihood view
+    fn spawn_likelihood_digits(
+        commands: &mut Commands,
+        cell_entity: Entity,
+        cell_transform: &Transform,
+        grid: GridSize,
+        font: Handle<Font>,
+        metrics: &DisplayMetrics,
+    ) {
+        const TEXT_ALIGNMENT: TextAlignment = TextAlignment {
+            vertical: VerticalAlign::Center,
+            horizontal: HorizontalAlign::Center,
+        };
+
+        // Digits are laid out like a phone keypad, with 1 in the top left,
+        // in rows as long as the smallest square that fits them all
+        let columns = (grid.digits() as f32).sqrt().ceil();
+        let rows = (grid.digits() as f32 / columns).ceil();
+
+        // Rows of more than three digits are squeezed into the cell with a smaller font
+        let text_style = TextStyle {
+            font,
+            font_size: metrics.mark_font_size() * 3.0 / columns.max(3.0),
+            color: LIKELIHOOD_COLOR,
+        };
+        for num in 1..=grid.digits() {
+            let i = (num - 1) as f32;
+            let mut digit_transform = cell_transform.clone();
+            digit_transform.translation.x +=
+                (i % columns - 0.5 * (columns - 1.0)) * CELL_SIZE / columns;
+            digit_transform.translation.y +=
+                (0.5 * (rows - 1.0) - (i / columns).floor()) * CELL_SIZE / columns;
+            // Digits must be displayed on top of the cells they are in
+            digit_transform.translation.z += 1.0;
+
+            let digit_entity = commands
+                .spawn_bundle(Text2dBundle {
+                    text: Text::with_section(
+                        digit_symbol(num).to_string(),
+                        text_style.clone(),
+                        TEXT_ALIGNMENT,
+                    ),
+                    transform: digit_transform,
+                    // Shown by the show_likelihoods system
+                    visible: Visible {
+                        is_visible: false,
+                        is_transparent: true,
+                    },
+                    ..Default::default()
+                })
+                .insert(LikelihoodDigit(num))
+                .id();
+
+            commands
+                .entity(cell_entity)
+                .insert_relation(LikelihoodShownBy, digit_entity);
         }
     }
 }
 
 mod actions {
-    use super::setup::DisplayedBy;
+    use super::setup::{
+        gridline_size, CornerMarkSlot, CornerMarkedBy, DisplayedBy, GridLine, LikelihoodDigit,
+        LikelihoodShownBy, PaintedBy, UnderlinedBy,
+    };
     use super::*;
+    use crate::logic::board::marks::Marks;
 
     /// Changes the cell displays to match their values
     pub fn update_cell_numbers(
@@ -297,36 +992,287 @@ mod actions {
                     .expect("No corresponding entity found!");
 
                 // There is only one section in our text
+                // Corner marks are displayed separately, by update_corner_marks
                 text.sections[0].value = match cell_value.clone() {
-                    Filled(n) => n.to_string(),
-                    // TODO: properly display markings
-                    Marked(center, corner) => {
-                        format!("Center: {}", center.to_string())
-                            + "|"
-                            + &format!("Corner: {}", corner.to_string())
-                    }
+                    Filled(n) => digit_symbol(n).to_string(),
+                    Marked(center, _) => center.to_string(),
                     Empty => "".to_string(),
                 }
             }
         }
     }
 
+    /// Fills the corner mark slots of each cell with its corner marks, in ascending order
+    pub fn update_corner_marks(
+        cell_query: Query<(&Value, &Relation<CornerMarkedBy>), (With<Cell>, Changed<Value>)>,
+        mut mark_query: Query<(&CornerMarkSlot, &mut Text)>,
+    ) {
+        for (cell_value, corner_marked_by) in cell_query.iter() {
+            let digits = match cell_value {
+                Value::Marked(_, corner) => corner.digits(),
+                _ => Vec::new(),
+            };
+
+            for (mark_entity, _) in corner_marked_by {
+                let (slot, mut text) = mark_query
+                    .get_mut(mark_entity)
+                    .expect("Corresponding corner mark entity not found.");
+                text.sections[0].value = match digits.get(slot.0) {
+                    Some(num) => digit_symbol(*num).to_string(),
+                    None => "".to_string(),
+                };
+            }
+        }
+    }
+
     /// Set the background color of selected cells
+    ///
+    /// Revealed mistakes and cells flagged by checking the puzzle are highlighted even when selected,
+    /// cells flash when the player tries to change them but can't,
+    /// and completed houses are swept with a brief highlight
     pub fn color_selected(
-        mut query: Query<(Option<&Selected>, &mut Handle<ColorMaterial>), With<Cell>>,
+        mut query: Query<
+            (
+                Option<&Selected>,
+                Option<&Mistaken>,
+                Option<&Flagged>,
+                Option<&Flashing>,
+                Option<&Flourish>,
+                &mut Handle<ColorMaterial>,
+            ),
+            With<Cell>,
+        >,
         background_color: Res<BackgroundColor>,
         selection_color: Res<SelectionColor>,
+        mistake_color: Res<MistakeColor>,
+        flash_color: Res<FlashColor>,
+        flourish_color: Res<FlourishColor>,
     ) {
         // QUALITY: use Added and Removed queries to avoid excessive spinning
         // once https://github.com/bevyengine/bevy/issues/2148 is fixed
-        for (maybe_selected, mut material_handle) in query.iter_mut() {
-            match maybe_selected {
-                Some(_) => *material_handle = selection_color.0.clone(),
-                None => *material_handle = background_color.0.clone(),
+        for (
+            maybe_selected,
+            maybe_mistaken,
+            maybe_flagged,
+            maybe_flashing,
+            maybe_flourish,
+            mut material_handle,
+        ) in query.iter_mut()
+        {
+            let is_wrong = maybe_mistaken.is_some() || maybe_flagged.is_some();
+            let is_lit = maybe_flourish.map_or(false, Flourish::is_lit);
+            *material_handle = match (maybe_flashing, is_lit, maybe_selected, is_wrong) {
+                (Some(_), _, _, _) => flash_color.0.clone(),
+                (None, true, _, _) => flourish_color.0.clone(),
+                (None, false, _, true) => mistake_color.0.clone(),
+                (None, false, Some(_), false) => selection_color.0.clone(),
+                (None, false, None, false) => background_color.0.clone(),
+            }
+        }
+    }
+
+    /// Component for cells taking part in the sweep across a completed house,
+    /// removed once the cell's turn in the sweep is over
+    pub struct Flourish {
+        /// How long until this cell lights up, based on its place in the house
+        pub delay: Duration,
+        /// The time since the house was completed
+        pub elapsed: Duration,
+    }
+
+    impl Flourish {
+        /// Is this cell currently highlighted
+        pub fn is_lit(&self) -> bool {
+            self.elapsed >= self.delay && !self.is_over()
+        }
+
+        /// Has this cell finished its part of the sweep
+        pub fn is_over(&self) -> bool {
+            self.elapsed >= self.delay + FLOURISH_DURATION
+        }
+    }
+
+    /// Starts a sweep across each completed house, one cell after another in reading order
+    ///
+    /// No sweep is shown when animations are turned off
+    pub fn flourish_completed_houses(
+        mut event_reader: EventReader<HouseCompleted>,
+        query: Query<(Entity, &Coordinates), With<Cell>>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        for event in event_reader.iter() {
+            if !settings.animated() {
+                continue;
+            }
+
+            for (entity, coordinates) in query.iter() {
+                if let Some(i) = event.cells.iter().position(|cell| cell == coordinates) {
+                    commands.entity(entity).insert(Flourish {
+                        delay: FLOURISH_STEP * i as u32,
+                        elapsed: Duration::ZERO,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Sweeps a shimmer diagonally across the whole board once the puzzle is solved
+    ///
+    /// No shimmer is shown when animations are turned off
+    pub fn shimmer_solved_board(
+        mut event_reader: EventReader<PuzzleSolved>,
+        query: Query<(Entity, &Coordinates), With<Cell>>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        for _ in event_reader.iter() {
+            if !settings.animated() {
+                continue;
+            }
+
+            for (entity, coordinates) in query.iter() {
+                // Starts from the top left corner, reaching the bottom right one last
+                let diagonal = (coordinates.row + coordinates.column - 2) as u32;
+                commands.entity(entity).insert(Flourish {
+                    delay: FLOURISH_STEP * diagonal,
+                    elapsed: Duration::ZERO,
+                });
+            }
+        }
+    }
+
+    /// Advances the sweeps across completed houses and solved boards,
+    /// ending any that are underway once animations are turned off
+    pub fn animate_flourishes(
+        mut query: Query<(Entity, &mut Flourish)>,
+        time: Res<Time>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        for (entity, mut flourish) in query.iter_mut() {
+            flourish.elapsed += time.delta();
+            if flourish.is_over() || !settings.animated() {
+                commands.entity(entity).remove::<Flourish>();
             }
         }
     }
-    /// Sets the style of the numbers based on whether or not they're fixed
+
+    /// Component for cells that are briefly flashing, removed once its timer finishes
+    pub struct Flashing(pub Timer);
+
+    /// Flashes cells the player tried to change, so they can see why nothing happened
+    ///
+    /// No flash is shown when animations are turned off
+    pub fn flash_blocked_cells(
+        mut event_reader: EventReader<BlockedEdit>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        for event in event_reader.iter() {
+            if !settings.animated() {
+                continue;
+            }
+
+            commands
+                .entity(event.cell)
+                .insert(Flashing(Timer::new(FLASH_DURATION, false)));
+        }
+    }
+
+    /// Stops flashing cells once their flash is over, or at once when animations are turned off
+    pub fn fade_flashes(
+        mut query: Query<(Entity, &mut Flashing)>,
+        time: Res<Time>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        for (entity, mut flashing) in query.iter_mut() {
+            if flashing.0.tick(time.delta()).finished() || !settings.animated() {
+                commands.entity(entity).remove::<Flashing>();
+            }
+        }
+    }
+
+    /// Colors digits that break one of the puzzle's constraints,
+    /// followed by digits that auto-check has found to be wrong
+    ///
+    /// Other digits are colored by whether they were given and where they came from.
+    /// Only cells whose digit, origin or markers changed are recolored, unless the settings changed
+    pub fn color_conflicts(
+        changed_query: Query<
+            Entity,
+            (
+                With<Cell>,
+                Or<(
+                    Changed<Value>,
+                    Changed<Fixed>,
+                    Changed<Provenance>,
+                    Added<Conflicting>,
+                    Added<Incorrect>,
+                )>,
+            ),
+        >,
+        removed_conflicting: RemovedComponents<Conflicting>,
+        removed_incorrect: RemovedComponents<Incorrect>,
+        cell_query: Query<
+            (
+                Entity,
+                Option<&Conflicting>,
+                Option<&Incorrect>,
+                &Fixed,
+                &Provenance,
+                &Relation<DisplayedBy>,
+            ),
+            With<Cell>,
+        >,
+        mut text_query: Query<&mut Text>,
+        settings: Res<Settings>,
+    ) {
+        let recolored: HashSet<Entity> = if settings.is_changed() {
+            cell_query.iter().map(|(entity, ..)| entity).collect()
+        } else {
+            changed_query
+                .iter()
+                .chain(removed_conflicting.iter())
+                .chain(removed_incorrect.iter())
+                .collect()
+        };
+
+        for entity in recolored {
+            let (_, maybe_conflicting, maybe_incorrect, is_fixed, provenance, displayed_by) =
+                match cell_query.get(entity) {
+                    Ok(cell) => cell,
+                    Err(_) => continue,
+                };
+            for (text_entity, _) in displayed_by {
+                let mut text = text_query
+                    .get_mut(text_entity)
+                    .expect("Corresponding text entity not found.");
+                text.sections[0].style.color = match (maybe_conflicting, maybe_incorrect) {
+                    (Some(_), _) => CONFLICT_COLOR,
+                    (None, Some(_)) => INCORRECT_COLOR,
+                    (None, None) => number_color(is_fixed, provenance, &*settings),
+                }
+            }
+        }
+    }
+
+    /// The color of a cell's number when it isn't highlighted
+    ///
+    /// Digits filled in by hints or the solver are tinted when the player asks to see where digits came from
+    fn number_color(is_fixed: &Fixed, provenance: &Provenance, settings: &Settings) -> Color {
+        match (is_fixed.0, provenance) {
+            (true, _) => NUMBER_COLOR,
+            (false, Provenance::Hint) if settings.show_provenance => HINT_NUMBER_COLOR,
+            (false, Provenance::Solver) if settings.show_provenance => SOLVER_NUMBER_COLOR,
+            (false, _) => FILLABLE_NUMBER_COLOR,
+        }
+    }
+
+    /// Sets the font of the numbers based on whether or not they're fixed
+    ///
+    /// Their color is set by `color_conflicts`
     pub fn style_numbers(
         cell_query: Query<(&Fixed, &Relation<DisplayedBy>), Changed<Fixed>>,
         mut text_query: Query<&mut Text>,
@@ -341,8 +1287,699 @@ mod actions {
                 text.sections[0].style.font = match is_fixed.0 {
                     true => fixed_font_res.0.clone(),
                     false => fillable_font_res.0.clone(),
+                };
+            }
+        }
+    }
+
+    /// Resizes the grid lines to match the display preset
+    pub fn resize_gridlines(settings: Res<Settings>, mut query: Query<(&GridLine, &mut Sprite)>) {
+        if settings.is_changed() {
+            let metrics = DisplayMetrics::new(settings.display_preset);
+            for (gridline, mut sprite) in query.iter_mut() {
+                sprite.size = gridline_size(gridline, &metrics);
+            }
+        }
+    }
+
+    /// Sets the font size of the cell numbers based on the display preset, whether they are marks
+    /// and how many marks there are
+    pub fn size_numbers(
+        settings: Res<Settings>,
+        changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+        cell_query: Query<(&Value, &Relation<DisplayedBy>), With<Cell>>,
+        mut text_query: Query<&mut Text>,
+    ) {
+        if !settings.is_changed() && changed_query.iter().next().is_none() {
+            return;
+        }
+
+        let metrics = DisplayMetrics::new(settings.display_preset);
+        for (value, displayed_by) in cell_query.iter() {
+            for (text_entity, _) in displayed_by {
+                let mut text = text_query
+                    .get_mut(text_entity)
+                    .expect("Corresponding text entity not found.");
+                text.sections[0].style.font_size = match value {
+                    Value::Marked(center, _) => {
+                        metrics.center_mark_font_size(center.digits().len())
+                    }
+                    _ => metrics.number_font_size(),
+                };
+            }
+        }
+    }
+
+    /// Sets the font size of the corner marks based on the display preset
+    pub fn size_corner_marks(
+        settings: Res<Settings>,
+        mut mark_query: Query<&mut Text, With<CornerMarkSlot>>,
+    ) {
+        if settings.is_changed() {
+            let metrics = DisplayMetrics::new(settings.display_preset);
+            for mut text in mark_query.iter_mut() {
+                text.sections[0].style.font_size = metrics.corner_mark_font_size();
+            }
+        }
+    }
+
+    /// Changes the color of the paint sprites to match the cell colors
+    pub fn paint_cells(
+        cell_query: Query<(&CellColor, &Relation<PaintedBy>), Changed<CellColor>>,
+        mut paint_query: Query<&mut Handle<ColorMaterial>>,
+        palette: Res<CellPalette>,
+    ) {
+        for (cell_color, painted_by) in cell_query.iter() {
+            for (paint_entity, _) in painted_by {
+                let mut material = paint_query
+                    .get_mut(paint_entity)
+                    .expect("Corresponding paint entity not found.");
+                *material = palette.get(cell_color);
+            }
+        }
+    }
+
+    /// Shows the underline beneath digits the player has locked in
+    pub fn underline_locked(
+        cell_query: Query<(&Locked, &Relation<UnderlinedBy>), Changed<Locked>>,
+        mut underline_query: Query<&mut Visible>,
+    ) {
+        for (is_locked, underlined_by) in cell_query.iter() {
+            for (underline_entity, _) in underlined_by {
+                let mut visible = underline_query
+                    .get_mut(underline_entity)
+                    .expect("Corresponding underline entity not found.");
+                visible.is_visible = is_locked.0;
+            }
+        }
+    }
+
+    /// Replaces the marks of unfilled cells with every digit that could go there in the likelihood view,
+    /// fading each digit by how rarely it appears in the completions sampled
+    pub fn show_likelihoods(
+        likelihoods: Res<Likelihoods>,
+        changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+        cell_query: Query<
+            (
+                &Coordinates,
+                &Value,
+                &Relation<DisplayedBy>,
+                &Relation<CornerMarkedBy>,
+                &Relation<LikelihoodShownBy>,
+            ),
+            With<Cell>,
+        >,
+        mut visible_query: Query<&mut Visible>,
+        mut digit_query: Query<(&LikelihoodDigit, &mut Text)>,
+    ) {
+        if !likelihoods.is_changed() && changed_query.iter().next().is_none() {
+            return;
+        }
+
+        for (coordinates, value, displayed_by, corner_marked_by, likelihood_shown_by) in
+            cell_query.iter()
+        {
+            let show_overlay = likelihoods.enabled
+                && !likelihoods.contradiction
+                && !matches!(value, Value::Filled(_));
+
+            // The cell's own marks would be drawn underneath the likelihoods
+            let mark_entities = displayed_by
+                .into_iter()
+                .map(|(entity, _)| entity)
+                .chain(corner_marked_by.into_iter().map(|(entity, _)| entity));
+            for mark_entity in mark_entities {
+                let mut visible = visible_query
+                    .get_mut(mark_entity)
+                    .expect("Corresponding mark entity not found.");
+                if visible.is_visible == show_overlay {
+                    visible.is_visible = !show_overlay;
                 }
             }
+
+            for (digit_entity, _) in likelihood_shown_by {
+                let (digit, mut text) = digit_query
+                    .get_mut(digit_entity)
+                    .expect("Corresponding likelihood digit entity not found.");
+                let frequency = likelihoods.frequency(coordinates, digit.0);
+                text.sections[0]
+                    .style
+                    .color
+                    .set_a(LIKELIHOOD_MIN_ALPHA + (1.0 - LIKELIHOOD_MIN_ALPHA) * frequency);
+
+                let mut visible = visible_query
+                    .get_mut(digit_entity)
+                    .expect("Corresponding likelihood digit entity not found.");
+                visible.is_visible = show_overlay && frequency > 0.0;
+            }
         }
     }
+
+    /// Marker component for the outlines, labels, lines, bulbs, dots, arrows, circles and shading drawn for the puzzle's constraints
+    pub struct ConstraintOverlay;
+
+    /// Redraws the markings of the puzzle's constraints whenever they change,
+    /// or the board is rebuilt for a grid of a different size
+    pub fn draw_overlays(
+        constraints: Res<ConstraintSet>,
+        cell_query: Query<(&Coordinates, &Transform), With<Cell>>,
+        added_query: Query<(), Added<Cell>>,
+        overlay_query: Query<Entity, With<ConstraintOverlay>>,
+        overlay_color: Res<OverlayColor>,
+        fill_color: Res<OverlayFillColor>,
+        line_palette: Res<OverlayLinePalette>,
+        shade_color: Res<OverlayShadeColor>,
+        background_color: Res<BackgroundColor>,
+        font: Res<FixedFont>,
+        mut commands: Commands,
+    ) {
+        if !constraints.is_changed() && added_query.iter().next().is_none() {
+            return;
+        }
+        for entity in overlay_query.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        // Outlines are traced around the cells as they are laid out on screen
+        let grid = constraints.grid();
+        let centers: HashMap<usize, Vec3> = cell_query
+            .iter()
+            .filter(|(coordinates, _)| grid.contains(coordinates))
+            .map(|(coordinates, transform)| (grid.index(coordinates), transform.translation))
+            .collect();
+        let grid_position = |center: &Vec3| {
+            (
+                (center.x / CELL_SIZE).round() as i32,
+                (center.y / CELL_SIZE).round() as i32,
+            )
+        };
+
+        for overlay in constraints.overlays() {
+            match overlay {
+                Overlay::Outline {
+                    cells,
+                    label,
+                    dashed,
+                } => {
+                    let outlined: Vec<&Vec3> =
+                        cells.iter().filter_map(|i| centers.get(i)).collect();
+                    let inside: HashSet<(i32, i32)> = outlined
+                        .iter()
+                        .map(|center| grid_position(*center))
+                        .collect();
+
+                    for center in outlined.iter() {
+                        let (x, y) = grid_position(*center);
+                        let segments = outline_segments(
+                            !inside.contains(&(x - 1, y)),
+                            !inside.contains(&(x + 1, y)),
+                            !inside.contains(&(x, y - 1)),
+                            !inside.contains(&(x, y + 1)),
+                        );
+                        let segments = if dashed {
+                            segments.into_iter().flat_map(dashes).collect()
+                        } else {
+                            segments
+                        };
+                        for (offset, size) in segments {
+                            commands
+                                .spawn_bundle(SpriteBundle {
+                                    sprite: Sprite::new(size),
+                                    // Drawn above any paint, but beneath the digits
+                                    transform: Transform::from_translation(
+                                        **center + offset.extend(0.75),
+                                    ),
+                                    material: overlay_color.0.clone(),
+                                    ..Default::default()
+                                })
+                                .insert(ConstraintOverlay);
+                        }
+                    }
+
+                    // Labels sit in the top left corner of the outline
+                    let corner = outlined.iter().max_by(|a, b| {
+                        (a.y, -a.x)
+                            .partial_cmp(&(b.y, -b.x))
+                            .expect("Cell positions are never NaN")
+                    });
+                    if let (Some(label), Some(corner)) = (label, corner) {
+                        let offset = Vec2::new(
+                            -0.5 * CELL_SIZE + 2.0 * OVERLAY_INSET,
+                            0.5 * CELL_SIZE - 1.5 * OVERLAY_INSET,
+                        );
+                        commands
+                            .spawn_bundle(Text2dBundle {
+                                text: Text::with_section(
+                                    label,
+                                    TextStyle {
+                                        font: font.0.clone(),
+                                        font_size: OVERLAY_LABEL_FONT_SIZE,
+                                        color: OVERLAY_COLOR,
+                                    },
+                                    TextAlignment {
+                                        vertical: VerticalAlign::Top,
+                                        horizontal: HorizontalAlign::Left,
+                                    },
+                                ),
+                                transform: Transform::from_translation(
+                                    **corner + offset.extend(1.0),
+                                ),
+                                ..Default::default()
+                            })
+                            .insert(ConstraintOverlay);
+                    }
+                }
+                Overlay::Line { cells, color } => {
+                    let points: Vec<Vec3> = cells
+                        .iter()
+                        .filter_map(|i| centers.get(i))
+                        .map(|center| Vec3::new(center.x, center.y, 0.6))
+                        .collect();
+
+                    for pair in points.windows(2) {
+                        let direction = pair[1] - pair[0];
+                        spawn_overlay_sprite(
+                            &mut commands,
+                            line_palette.get(color),
+                            0.5 * (pair[0] + pair[1]),
+                            Vec2::new(direction.length(), OVERLAY_LINE_WIDTH),
+                            direction.y.atan2(direction.x),
+                        );
+                    }
+                    // Filling in each turn, so that bends don't leave a notch
+                    for point in points.iter() {
+                        spawn_overlay_sprite(
+                            &mut commands,
+                            line_palette.get(color),
+                            *point,
+                            Vec2::splat(OVERLAY_LINE_WIDTH),
+                            0.0,
+                        );
+                    }
+                }
+                Overlay::ThinLine { cells } => {
+                    let points: Vec<Vec3> = cells
+                        .iter()
+                        .filter_map(|i| centers.get(i))
+                        .map(|center| Vec3::new(center.x, center.y, 0.6))
+                        .collect();
+                    let (first, last) = match (points.first(), points.last()) {
+                        (Some(first), Some(last)) if points.len() > 1 => (*first, *last),
+                        _ => continue,
+                    };
+                    // Reaching half a step past the end cells, such as out to the corners of the grid
+                    let start = first - 0.5 * (points[1] - first);
+                    let end = last + 0.5 * (last - points[points.len() - 2]);
+                    let ends: Vec<Vec3> = std::iter::once(start)
+                        .chain(points.iter().copied())
+                        .chain(std::iter::once(end))
+                        .collect();
+                    for pair in ends.windows(2) {
+                        let direction = pair[1] - pair[0];
+                        spawn_overlay_sprite(
+                            &mut commands,
+                            overlay_color.0.clone(),
+                            0.5 * (pair[0] + pair[1]),
+                            Vec2::new(direction.length(), OVERLAY_THICKNESS),
+                            direction.y.atan2(direction.x),
+                        );
+                    }
+                }
+                Overlay::Bulb { cell, filled } => {
+                    if let Some(center) = centers.get(&cell) {
+                        let center = Vec3::new(center.x, center.y, 0.6);
+                        spawn_overlay_disc(
+                            &mut commands,
+                            fill_color.0.clone(),
+                            center,
+                            OVERLAY_BULB_SIZE,
+                        );
+                        // Empty circles also cover the end of the line running into them
+                        if !filled {
+                            spawn_overlay_disc(
+                                &mut commands,
+                                background_color.0.clone(),
+                                center + Vec3::new(0.0, 0.0, 0.01),
+                                OVERLAY_BULB_SIZE - 2.0 * OVERLAY_RING_THICKNESS,
+                            );
+                        }
+                    }
+                }
+                Overlay::Dot { cells, filled } => {
+                    if let (Some(first), Some(second)) =
+                        (centers.get(&cells.0), centers.get(&cells.1))
+                    {
+                        // Dots sit on top of the grid line between the cells
+                        let center = 0.5 * (*first + *second) + Vec3::new(0.0, 0.0, 0.1);
+                        spawn_overlay_disc(
+                            &mut commands,
+                            overlay_color.0.clone(),
+                            center,
+                            OVERLAY_DOT_SIZE,
+                        );
+                        if !filled {
+                            spawn_overlay_disc(
+                                &mut commands,
+                                background_color.0.clone(),
+                                center + Vec3::new(0.0, 0.0, 0.01),
+                                OVERLAY_DOT_SIZE - 2.0 * OVERLAY_THICKNESS,
+                            );
+                        }
+                    }
+                }
+                Overlay::Shade { cells } => {
+                    for center in cells.iter().filter_map(|i| centers.get(i)) {
+                        // Above the cell, but beneath its paint and the grid lines
+                        spawn_overlay_sprite(
+                            &mut commands,
+                            shade_color.0.clone(),
+                            Vec3::new(center.x, center.y, 0.25),
+                            Vec2::splat(CELL_SIZE),
+                            0.0,
+                        );
+                    }
+                }
+                Overlay::OuterClue { cells, step, label } => {
+                    // The clue sits one step back from the first cell, in the band around the grid,
+                    // found from how the cells are laid out on screen
+                    let first = cells.first().and_then(|i| centers.get(i));
+                    let (first, origin, across, down) = match (
+                        first,
+                        centers.get(&0),
+                        centers.get(&1),
+                        centers.get(&(grid.width() as usize)),
+                    ) {
+                        (Some(first), Some(origin), Some(across), Some(down)) => {
+                            (*first, *origin, *across, *down)
+                        }
+                        _ => continue,
+                    };
+                    let direction = (step.0 as f32 * (down - origin)
+                        + step.1 as f32 * (across - origin))
+                        .truncate();
+                    let clue = first.truncate() - direction;
+
+                    commands
+                        .spawn_bundle(Text2dBundle {
+                            text: Text::with_section(
+                                label,
+                                TextStyle {
+                                    font: font.0.clone(),
+                                    font_size: OVERLAY_LABEL_FONT_SIZE,
+                                    color: OVERLAY_COLOR,
+                                },
+                                TextAlignment {
+                                    vertical: VerticalAlign::Center,
+                                    horizontal: HorizontalAlign::Center,
+                                },
+                            ),
+                            transform: Transform::from_translation(clue.extend(1.0)),
+                            ..Default::default()
+                        })
+                        .insert(ConstraintOverlay);
+
+                    // The arrow ends at the corner of the grid between the clue and the first cell
+                    let along = direction.normalize();
+                    let tip = clue + 0.5 * direction;
+                    let tail = tip - along * OUTER_CLUE_ARROW;
+                    let angle = along.y.atan2(along.x);
+                    spawn_overlay_sprite(
+                        &mut commands,
+                        overlay_color.0.clone(),
+                        (0.5 * (tip + tail)).extend(1.0),
+                        Vec2::new(OUTER_CLUE_ARROW, OVERLAY_THICKNESS),
+                        angle,
+                    );
+                    for &side in [-1.0f32, 1.0].iter() {
+                        let barb = angle + side * 0.75 * std::f32::consts::PI;
+                        spawn_overlay_sprite(
+                            &mut commands,
+                            overlay_color.0.clone(),
+                            (tip + 0.25 * OUTER_CLUE_ARROW * Vec2::new(barb.cos(), barb.sin()))
+                                .extend(1.0),
+                            Vec2::new(0.5 * OUTER_CLUE_ARROW, OVERLAY_THICKNESS),
+                            barb,
+                        );
+                    }
+                }
+                Overlay::EdgeLabel { cells, label } => {
+                    if let (Some(first), Some(second)) =
+                        (centers.get(&cells.0), centers.get(&cells.1))
+                    {
+                        // Labels cover the grid line between the cells, so that it doesn't strike through them
+                        let center = 0.5 * (*first + *second) + Vec3::new(0.0, 0.0, 0.1);
+                        spawn_overlay_disc(
+                            &mut commands,
+                            background_color.0.clone(),
+                            center,
+                            OVERLAY_DOT_SIZE,
+                        );
+                        commands
+                            .spawn_bundle(Text2dBundle {
+                                text: Text::with_section(
+                                    label,
+                                    TextStyle {
+                                        font: font.0.clone(),
+                                        font_size: OVERLAY_LABEL_FONT_SIZE,
+                                        color: OVERLAY_COLOR,
+                                    },
+                                    TextAlignment {
+                                        vertical: VerticalAlign::Center,
+                                        horizontal: HorizontalAlign::Center,
+                                    },
+                                ),
+                                transform: Transform::from_translation(
+                                    center + Vec3::new(0.0, 0.0, 0.01),
+                                ),
+                                ..Default::default()
+                            })
+                            .insert(ConstraintOverlay);
+                    }
+                }
+                Overlay::CornerLabel { cells, label } => {
+                    let corners: Vec<&Vec3> = cells.iter().filter_map(|i| centers.get(i)).collect();
+                    if corners.is_empty() {
+                        continue;
+                    }
+                    // The circle covers the corner where the grid lines between the cells cross
+                    let center = corners
+                        .iter()
+                        .fold(Vec3::ZERO, |sum, &&corner| sum + corner)
+                        / corners.len() as f32
+                        + Vec3::new(0.0, 0.0, 0.1);
+                    spawn_overlay_disc(
+                        &mut commands,
+                        overlay_color.0.clone(),
+                        center,
+                        OVERLAY_CORNER_SIZE,
+                    );
+                    spawn_overlay_disc(
+                        &mut commands,
+                        background_color.0.clone(),
+                        center + Vec3::new(0.0, 0.0, 0.01),
+                        OVERLAY_CORNER_SIZE - 2.0 * OVERLAY_THICKNESS,
+                    );
+
+                    // Labels are split into rows of two, so that four digits fit inside the circle
+                    let characters: Vec<char> = label.chars().collect();
+                    let label = characters
+                        .chunks(2)
+                        .map(|row| row.iter().collect::<String>())
+                        .collect::<Vec<String>>()
+                        .join("\n");
+                    commands
+                        .spawn_bundle(Text2dBundle {
+                            text: Text::with_section(
+                                label,
+                                TextStyle {
+                                    font: font.0.clone(),
+                                    font_size: OVERLAY_CORNER_FONT_SIZE,
+                                    color: OVERLAY_COLOR,
+                                },
+                                TextAlignment {
+                                    vertical: VerticalAlign::Center,
+                                    horizontal: HorizontalAlign::Center,
+                                },
+                            ),
+                            transform: Transform::from_translation(
+                                center + Vec3::new(0.0, 0.0, 0.02),
+                            ),
+                            ..Default::default()
+                        })
+                        .insert(ConstraintOverlay);
+                }
+            }
+        }
+    }
+
+    /// Marker component for the thick lines drawn between cells in different regions
+    pub struct RegionBorder;
+
+    /// Redraws the borders between the regions whenever they, the display preset or the cells of the board change
+    ///
+    /// Each border runs along the edge between two neighbouring cells in different regions,
+    /// reaching a little past either end so that the borders meet at the corners
+    pub fn draw_region_borders(
+        constraints: Res<ConstraintSet>,
+        settings: Res<Settings>,
+        cell_query: Query<(&Coordinates, &Transform), With<Cell>>,
+        added_query: Query<(), Added<Cell>>,
+        border_query: Query<Entity, With<RegionBorder>>,
+        grid_color: Res<GridColor>,
+        mut commands: Commands,
+    ) {
+        if !constraints.is_changed()
+            && !settings.is_changed()
+            && added_query.iter().next().is_none()
+        {
+            return;
+        }
+        for entity in border_query.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        let grid = constraints.grid();
+        let centers: HashMap<usize, Vec3> = cell_query
+            .iter()
+            .filter(|(coordinates, _)| grid.contains(coordinates))
+            .map(|(coordinates, transform)| (grid.index(coordinates), transform.translation))
+            .collect();
+        // Borders keep their thickness on screen however far the camera zooms to fit the grid
+        let thickness =
+            DisplayMetrics::new(settings.display_preset).major_line_thickness * grid_zoom(grid);
+        let regions = constraints.regions();
+
+        for (i, j) in grid
+            .neighbours()
+            .into_iter()
+            .filter(|&(i, j)| regions.region(i) != regions.region(j))
+        {
+            if let (Some(first), Some(second)) = (centers.get(&i), centers.get(&j)) {
+                let midpoint = 0.5 * (*first + *second);
+                let across = (*second - *first).truncate().abs();
+                let size = if across.x > across.y {
+                    Vec2::new(thickness, CELL_SIZE + thickness)
+                } else {
+                    Vec2::new(CELL_SIZE + thickness, thickness)
+                };
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite::new(size),
+                        // Level with the grid lines, covering the cells either side
+                        transform: Transform::from_xyz(midpoint.x, midpoint.y, 1.0),
+                        material: grid_color.0.clone(),
+                        ..Default::default()
+                    })
+                    .insert(RegionBorder);
+            }
+        }
+    }
+
+    /// Spawns a near circle drawn for the puzzle's constraints, `size` across
+    ///
+    /// Squares turned a third of the way to each other's corners make a twelve-sided shape, without needing a texture
+    fn spawn_overlay_disc(
+        commands: &mut Commands,
+        material: Handle<ColorMaterial>,
+        translation: Vec3,
+        size: f32,
+    ) {
+        for k in 0..3 {
+            spawn_overlay_sprite(
+                commands,
+                material.clone(),
+                translation,
+                Vec2::splat(size),
+                k as f32 * std::f32::consts::FRAC_PI_6,
+            );
+        }
+    }
+
+    /// Spawns a rectangle drawn for the puzzle's constraints, turned counterclockwise by `angle` radians
+    fn spawn_overlay_sprite(
+        commands: &mut Commands,
+        material: Handle<ColorMaterial>,
+        translation: Vec3,
+        size: Vec2,
+        angle: f32,
+    ) {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite::new(size),
+                transform: Transform::from_translation(translation)
+                    .with_rotation(Quat::from_rotation_z(angle)),
+                material,
+                ..Default::default()
+            })
+            .insert(ConstraintOverlay);
+    }
+
+    /// The offset from the cell's center and the size of each side of a cell that lies on the edge of an outline,
+    /// given which of its left, right, bottom and top sides do
+    ///
+    /// Sides are inset into the cell, stopping where they meet another side of the outline
+    /// and otherwise running on to meet the side drawn in the neighbouring cell
+    fn outline_segments(left: bool, right: bool, bottom: bool, top: bool) -> Vec<(Vec2, Vec2)> {
+        let half = 0.5 * CELL_SIZE;
+        let edge = half - OVERLAY_INSET;
+        let (low_y, high_y) = (
+            if bottom { -edge } else { -half },
+            if top { edge } else { half },
+        );
+        let (low_x, high_x) = (
+            if left { -edge } else { -half },
+            if right { edge } else { half },
+        );
+
+        let vertical = |x: f32| {
+            (
+                Vec2::new(x, 0.5 * (low_y + high_y)),
+                Vec2::new(OVERLAY_THICKNESS, high_y - low_y),
+            )
+        };
+        let horizontal = |y: f32| {
+            (
+                Vec2::new(0.5 * (low_x + high_x), y),
+                Vec2::new(high_x - low_x, OVERLAY_THICKNESS),
+            )
+        };
+
+        let mut segments = Vec::new();
+        if left {
+            segments.push(vertical(-edge));
+        }
+        if right {
+            segments.push(vertical(edge));
+        }
+        if bottom {
+            segments.push(horizontal(-edge));
+        }
+        if top {
+            segments.push(horizontal(edge));
+        }
+        segments
+    }
+
+    /// Breaks a segment of an outline into evenly spaced dashes
+    fn dashes((offset, size): (Vec2, Vec2)) -> Vec<(Vec2, Vec2)> {
+        let (length, along) = if size.x > size.y {
+            (size.x, Vec2::X)
+        } else {
+            (size.y, Vec2::Y)
+        };
+        let n_dashes = ((length + OVERLAY_DASH) / (2.0 * OVERLAY_DASH)).round() as usize;
+        // Segments too short to break up are left whole
+        if n_dashes < 2 {
+            return vec![(offset, size)];
+        }
+
+        let dash_size = along * OVERLAY_DASH + (Vec2::ONE - along) * OVERLAY_THICKNESS;
+        // The gaps are spread evenly between the dashes, which start and end the segment
+        let spacing = (length - OVERLAY_DASH) / (n_dashes - 1) as f32;
+        let start = offset - along * 0.5 * (length - OVERLAY_DASH);
+
+        (0..n_dashes)
+            .map(|k| (start + along * spacing * k as f32, dash_size))
+            .collect()
+    }
 }
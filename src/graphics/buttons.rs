@@ -1,8 +1,23 @@
 /// Build and display the UI buttons
-use super::board::assets::FixedFont;
-use crate::input::buttons::{NewPuzzle, ResetPuzzle, SolvePuzzle};
+use super::board::assets::{CellPalette, FixedFont};
+use super::tooltips::Tooltip;
+use crate::input::buttons::{
+    CheckPuzzle, ColorSwatch, FillCandidates, FindMistake, GetHint, NewPuzzle, RemixPuzzle,
+    ResetPuzzle, ShowSettings, SolvePuzzle,
+};
+use crate::localization::{Localization, LocalizedText};
+use crate::logic::{
+    board::{Cell, CompletedDigits, Value},
+    constraints::ConstraintSet,
+    grid::{digit_symbol, GridSize},
+    hints::HintCooldown,
+};
+use crate::settings::Settings;
 use crate::{
-    input::{input_mode::InputMode, CellInput},
+    input::{
+        input_mode::{ArmedDigit, InputMode},
+        CellInput, ScrambleRemaining,
+    },
     CommonLabels,
 };
 use bevy::{ecs::component::Component, prelude::*};
@@ -21,9 +36,19 @@ impl Plugin for BoardButtonsPlugin {
             .init_resource::<ButtonMaterials<NewPuzzle>>()
             .init_resource::<ButtonMaterials<ResetPuzzle>>()
             .init_resource::<ButtonMaterials<SolvePuzzle>>()
+            .init_resource::<ButtonMaterials<RemixPuzzle>>()
+            .init_resource::<ButtonMaterials<GetHint>>()
+            .init_resource::<ButtonMaterials<FindMistake>>()
+            .init_resource::<ButtonMaterials<CheckPuzzle>>()
+            .init_resource::<ButtonMaterials<FillCandidates>>()
+            .init_resource::<ButtonMaterials<ShowSettings>>()
+            .init_resource::<ButtonMaterials<ScrambleRemaining>>()
             .init_resource::<ButtonMaterials<InputMode>>()
             .init_resource::<ButtonMaterials<CellInput>>()
+            .init_resource::<ButtonMaterials<ColorSwatch>>()
             .init_resource::<NoneColor>()
+            .init_resource::<ButtonIcons>()
+            .init_resource::<DisabledMaterial>()
             // SETUP
             // Must be complete before we can spawn buttons
             .add_startup_system_to_stage(
@@ -42,6 +67,26 @@ impl Plugin for BoardButtonsPlugin {
                 actions::show_selected_input_mode
                     .system()
                     .after(CommonLabels::Action),
+            )
+            .add_system(
+                actions::rebuild_number_pad
+                    .system()
+                    .after(CommonLabels::Action),
+            )
+            .add_system(
+                actions::style_number_buttons
+                    .system()
+                    .after(CommonLabels::Action),
+            )
+            .add_system(
+                actions::show_hint_cooldown
+                    .system()
+                    .after(CommonLabels::Action),
+            )
+            .add_system(
+                actions::show_remaining_digits
+                    .system()
+                    .after(CommonLabels::Action),
             );
     }
 }
@@ -51,12 +96,28 @@ mod config {
     pub const UI_FRACTION: f32 = 50.0;
     /// The side length of the UI buttons
     pub const BUTTON_LENGTH: f32 = 64.0;
-    /// The side length of the numpad-like input buttons
+    /// The side length of the numpad-like input buttons, when there are at most three in a row
     pub const NUM_BUTTON_LENGTH: f32 = 64.0;
+    /// The side length of the buttons used to paint cells
+    pub const SWATCH_BUTTON_LENGTH: f32 = 32.0;
+    /// The size of text labels on the UI buttons
+    pub const LABEL_FONT_SIZE: f32 = 0.3 * BUTTON_LENGTH;
+    /// The side length of the icons on the game control and input mode buttons
+    pub const ICON_LENGTH: f32 = 0.5 * BUTTON_LENGTH;
+    /// The size of the text labels under each icon
+    pub const ICON_LABEL_FONT_SIZE: f32 = 0.2 * BUTTON_LENGTH;
+
+    pub const NEW_ICON: &str = "icons/new.png";
+    pub const RESET_ICON: &str = "icons/reset.png";
+    pub const SOLVE_ICON: &str = "icons/solve.png";
+    pub const FILL_ICON: &str = "icons/fill.png";
+    pub const CENTER_MARK_ICON: &str = "icons/center_mark.png";
+    pub const CORNER_MARK_ICON: &str = "icons/corner_mark.png";
+    pub const COLOR_ICON: &str = "icons/color.png";
 }
 
 // QUALITY: reduce asset loading code duplication dramatically
-mod assets {
+pub mod assets {
     use super::*;
     /// The null, transparent color
     pub struct NoneColor(pub Handle<ColorMaterial>);
@@ -70,6 +131,78 @@ mod assets {
         }
     }
 
+    /// Resource for the icons drawn on the game control and input mode buttons
+    pub struct ButtonIcons {
+        pub new: Handle<ColorMaterial>,
+        pub reset: Handle<ColorMaterial>,
+        pub solve: Handle<ColorMaterial>,
+        pub fill: Handle<ColorMaterial>,
+        pub center_mark: Handle<ColorMaterial>,
+        pub corner_mark: Handle<ColorMaterial>,
+        pub color: Handle<ColorMaterial>,
+    }
+
+    impl ButtonIcons {
+        /// Looks up the icon for the button that switches to this input mode
+        pub fn input_mode(&self, input_mode: InputMode) -> Handle<ColorMaterial> {
+            match input_mode {
+                InputMode::Fill => self.fill.clone(),
+                InputMode::CenterMark => self.center_mark.clone(),
+                InputMode::CornerMark => self.corner_mark.clone(),
+                InputMode::Color => self.color.clone(),
+            }
+        }
+    }
+
+    impl FromWorld for ButtonIcons {
+        fn from_world(world: &mut World) -> Self {
+            let asset_server = world
+                .get_resource::<AssetServer>()
+                .expect("Res<AssetServer> not found.");
+            let textures: Vec<Handle<Texture>> = [
+                NEW_ICON,
+                RESET_ICON,
+                SOLVE_ICON,
+                FILL_ICON,
+                CENTER_MARK_ICON,
+                CORNER_MARK_ICON,
+                COLOR_ICON,
+            ]
+            .iter()
+            .map(|path| asset_server.load(*path))
+            .collect();
+
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            let mut icons = textures
+                .into_iter()
+                .map(|texture| materials.add(texture.into()));
+            let mut next_icon = || icons.next().expect("Icon not loaded.");
+            ButtonIcons {
+                new: next_icon(),
+                reset: next_icon(),
+                solve: next_icon(),
+                fill: next_icon(),
+                center_mark: next_icon(),
+                corner_mark: next_icon(),
+                color: next_icon(),
+            }
+        }
+    }
+
+    /// Resource for the material of buttons that can't currently be used
+    pub struct DisabledMaterial(pub Handle<ColorMaterial>);
+
+    impl FromWorld for DisabledMaterial {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            DisabledMaterial(materials.add(Color::rgb(0.85, 0.85, 0.85).into()))
+        }
+    }
+
     /// Resource that contains the raw materials for each button type
     /// corresponding to the Marker type marker component
     pub struct ButtonMaterials<Marker: Component> {
@@ -128,6 +261,104 @@ mod assets {
         }
     }
 
+    impl FromWorld for ButtonMaterials<RemixPuzzle> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(1.0, 0.65, 0.15).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<GetHint> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.85, 0.15, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<FindMistake> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(1.0, 0.5, 0.5).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<CheckPuzzle> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(1.0, 0.75, 0.4).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<FillCandidates> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.5, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<ShowSettings> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.7, 0.7, 0.7).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<ScrambleRemaining> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.9, 0.6).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
     impl FromWorld for ButtonMaterials<InputMode> {
         fn from_world(world: &mut World) -> Self {
             let mut materials = world
@@ -142,6 +373,21 @@ mod assets {
         }
     }
 
+    impl FromWorld for ButtonMaterials<ColorSwatch> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                // Each swatch is shown in its own color from the palette instead
+                normal: materials.add(Color::NONE.into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
     impl FromWorld for ButtonMaterials<CellInput> {
         fn from_world(world: &mut World) -> Self {
             let mut materials = world
@@ -157,7 +403,7 @@ mod assets {
     }
 }
 
-mod setup {
+pub mod setup {
     use super::*;
     #[derive(Bundle)]
     struct BoardButtonBundle<Marker: Component> {
@@ -193,6 +439,8 @@ mod setup {
                         size,
                         // Padding between buttons
                         margin: Rect::all(Val::Px(5.0)),
+                        // Icons sit above their labels
+                        flex_direction: FlexDirection::ColumnReverse,
                         // Horizontally center child text
                         justify_content: JustifyContent::Center,
                         // Vertically center child text
@@ -213,6 +461,114 @@ mod setup {
     pub struct SudokuBox;
     /// Marker component for layout box of UI elements
     pub struct UiBox;
+    /// Marker component for the text on the hint button
+    pub struct HintLabel;
+    /// Component for the text showing how many of a digit are left to place
+    pub struct RemainingLabel(pub u8);
+    /// Component for the layout rows holding the number buttons, counted from the bottom like a numeric keypad
+    pub struct NumberRow(pub usize);
+
+    /// The number of layout rows set aside for the number buttons, enough for the 16 digits of the largest grid
+    pub const N_NUMBER_ROWS: usize = 4;
+
+    /// Spawns a square button of this side length entering a digit,
+    /// with the count of that digit left to place tucked under it
+    pub fn spawn_number_button(
+        commands: &mut Commands,
+        num: u8,
+        length: f32,
+        number_materials: &ButtonMaterials<CellInput>,
+        font: Handle<Font>,
+    ) -> Entity {
+        const TEXT_ALIGNMENT: TextAlignment = TextAlignment {
+            vertical: VerticalAlign::Center,
+            horizontal: HorizontalAlign::Center,
+        };
+
+        let text_style = TextStyle {
+            font: font.clone(),
+            font_size: 0.8 * length,
+            color: Color::BLACK,
+        };
+        let num_button_size = Size::new(Val::Px(length), Val::Px(length));
+
+        commands
+            .spawn_bundle(BoardButtonBundle::<CellInput>::new_with_data(
+                num_button_size,
+                number_materials,
+                CellInput { num },
+            ))
+            .insert(Tooltip::with_digit("tooltip-enter-digit", num))
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        digit_symbol(num).to_string(),
+                        text_style,
+                        TEXT_ALIGNMENT,
+                    ),
+                    ..Default::default()
+                });
+
+                parent
+                    .spawn_bundle(TextBundle {
+                        style: Style {
+                            // Tucked under the number, rather than laid out beside it
+                            position_type: PositionType::Absolute,
+                            position: Rect {
+                                bottom: Val::Px(2.0),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        // This label is set in the show_remaining_digits system
+                        text: Text::with_section(
+                            "",
+                            TextStyle {
+                                font,
+                                // The count of digits remaining is much smaller than the digit above it
+                                font_size: 0.2 * length,
+                                color: Color::BLACK,
+                            },
+                            TEXT_ALIGNMENT,
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(RemainingLabel(num));
+            })
+            .id()
+    }
+
+    /// Spawns an icon with a short label under it, as the contents of a button
+    fn spawn_icon_and_label(
+        parent: &mut ChildBuilder,
+        icon: Handle<ColorMaterial>,
+        label: &'static str,
+        font: Handle<Font>,
+    ) {
+        parent.spawn_bundle(ImageBundle {
+            style: Style {
+                size: Size::new(Val::Px(ICON_LENGTH), Val::Px(ICON_LENGTH)),
+                ..Default::default()
+            },
+            material: icon,
+            ..Default::default()
+        });
+        parent
+            .spawn_bundle(TextBundle {
+                // This label is translated in the localize_text system
+                text: Text::with_section(
+                    "",
+                    TextStyle {
+                        font,
+                        font_size: ICON_LABEL_FONT_SIZE,
+                        color: Color::BLACK,
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            })
+            .insert(LocalizedText(label));
+    }
 
     /// Spawns layout-only nodes for storing the game's user interface
     pub fn spawn_layout_boxes(mut commands: Commands, none_color: Res<NoneColor>) {
@@ -268,16 +624,26 @@ mod setup {
         new_button_materials: Res<ButtonMaterials<NewPuzzle>>,
         reset_button_materials: Res<ButtonMaterials<ResetPuzzle>>,
         solve_button_materials: Res<ButtonMaterials<SolvePuzzle>>,
-        number_materials: Res<ButtonMaterials<CellInput>>,
+        remix_button_materials: Res<ButtonMaterials<RemixPuzzle>>,
+        hint_button_materials: Res<ButtonMaterials<GetHint>>,
+        find_mistake_button_materials: Res<ButtonMaterials<FindMistake>>,
+        check_button_materials: Res<ButtonMaterials<CheckPuzzle>>,
+        fill_candidates_button_materials: Res<ButtonMaterials<FillCandidates>>,
+        settings_button_materials: Res<ButtonMaterials<ShowSettings>>,
+        scramble_button_materials: Res<ButtonMaterials<ScrambleRemaining>>,
         // TODO: split into three? Or maybe group into two resources total?
         input_mode_button_materials: Res<ButtonMaterials<InputMode>>,
+        swatch_materials: Res<ButtonMaterials<ColorSwatch>>,
+        palette: Res<CellPalette>,
+        icons: Res<ButtonIcons>,
         font: Res<FixedFont>,
     ) {
         let button_size = Size::new(Val::Px(BUTTON_LENGTH), Val::Px(BUTTON_LENGTH));
-        let num_button_size = Size::new(Val::Px(NUM_BUTTON_LENGTH), Val::Px(NUM_BUTTON_LENGTH));
+        let swatch_button_size =
+            Size::new(Val::Px(SWATCH_BUTTON_LENGTH), Val::Px(SWATCH_BUTTON_LENGTH));
 
         // Layout nodes
-        const N_ROWS: usize = 5;
+        const N_ROWS: usize = N_NUMBER_ROWS + 5;
         let mut layout_nodes = [Entity::new(0); N_ROWS];
         for i in 0..N_ROWS {
             layout_nodes[i] = commands
@@ -293,41 +659,6 @@ mod setup {
                 .id();
         }
 
-        // Number input buttons
-        let mut number_buttons = [Entity::new(0); 9];
-        for i in 0..9 {
-            let num = i + 1;
-
-            const TEXT_ALIGNMENT: TextAlignment = TextAlignment {
-                vertical: VerticalAlign::Center,
-                horizontal: HorizontalAlign::Center,
-            };
-
-            let text_style = TextStyle {
-                font: font.0.clone(),
-                font_size: 0.8 * NUM_BUTTON_LENGTH,
-                color: Color::BLACK,
-            };
-
-            number_buttons[i] = commands
-                .spawn_bundle(BoardButtonBundle::<CellInput>::new_with_data(
-                    num_button_size,
-                    &*number_materials,
-                    CellInput { num: num as u8 },
-                ))
-                .with_children(|parent| {
-                    parent.spawn_bundle(TextBundle {
-                        text: Text::with_section(
-                            num.to_string(),
-                            text_style.clone(),
-                            TEXT_ALIGNMENT,
-                        ),
-                        ..Default::default()
-                    });
-                })
-                .id();
-        }
-
         // Input mode buttons
         let fill_button = commands
             .spawn_bundle(BoardButtonBundle::<InputMode>::new_with_data(
@@ -335,6 +666,15 @@ mod setup {
                 &*input_mode_button_materials,
                 InputMode::Fill,
             ))
+            .with_children(|parent| {
+                spawn_icon_and_label(
+                    parent,
+                    icons.input_mode(InputMode::Fill),
+                    "label-fill",
+                    font.0.clone(),
+                )
+            })
+            .insert(Tooltip::new("tooltip-fill"))
             .id();
 
         let center_mark_button = commands
@@ -343,6 +683,15 @@ mod setup {
                 &*input_mode_button_materials,
                 InputMode::CenterMark,
             ))
+            .with_children(|parent| {
+                spawn_icon_and_label(
+                    parent,
+                    icons.input_mode(InputMode::CenterMark),
+                    "label-center",
+                    font.0.clone(),
+                )
+            })
+            .insert(Tooltip::new("tooltip-center-mark"))
             .id();
 
         let corner_mark_button = commands
@@ -351,14 +700,65 @@ mod setup {
                 &*input_mode_button_materials,
                 InputMode::CornerMark,
             ))
+            .with_children(|parent| {
+                spawn_icon_and_label(
+                    parent,
+                    icons.input_mode(InputMode::CornerMark),
+                    "label-corner",
+                    font.0.clone(),
+                )
+            })
+            .insert(Tooltip::new("tooltip-corner-mark"))
+            .id();
+
+        let color_button = commands
+            .spawn_bundle(BoardButtonBundle::<InputMode>::new_with_data(
+                button_size,
+                &*input_mode_button_materials,
+                InputMode::Color,
+            ))
+            .with_children(|parent| {
+                spawn_icon_and_label(
+                    parent,
+                    icons.input_mode(InputMode::Color),
+                    "label-color",
+                    font.0.clone(),
+                )
+            })
+            .insert(Tooltip::new("tooltip-color"))
             .id();
 
+        // Color swatch buttons
+        let mut swatch_buttons = [Entity::new(0); 9];
+        for i in 0..9 {
+            // Each swatch displays the color it paints
+            let materials = ButtonMaterials {
+                normal: palette.colors[i].clone(),
+                hovered: swatch_materials.hovered.clone(),
+                pressed: swatch_materials.pressed.clone(),
+                _marker: PhantomData,
+            };
+
+            swatch_buttons[i] = commands
+                .spawn_bundle(BoardButtonBundle::<ColorSwatch>::new_with_data(
+                    swatch_button_size,
+                    &materials,
+                    ColorSwatch(i as u8 + 1),
+                ))
+                .insert(Tooltip::with_digit("tooltip-paint-color", i as u8 + 1))
+                .id();
+        }
+
         // Game control buttons
         let new_game_button = commands
             .spawn_bundle(BoardButtonBundle::<NewPuzzle>::new(
                 button_size,
                 &*new_button_materials,
             ))
+            .with_children(|parent| {
+                spawn_icon_and_label(parent, icons.new.clone(), "label-new", font.0.clone())
+            })
+            .insert(Tooltip::new("tooltip-new"))
             .id();
 
         let reset_game_button = commands
@@ -366,6 +766,10 @@ mod setup {
                 button_size,
                 &*reset_button_materials,
             ))
+            .with_children(|parent| {
+                spawn_icon_and_label(parent, icons.reset.clone(), "label-reset", font.0.clone())
+            })
+            .insert(Tooltip::new("tooltip-reset"))
             .id();
 
         let solve_game_button = commands
@@ -373,44 +777,216 @@ mod setup {
                 button_size,
                 &*solve_button_materials,
             ))
+            .with_children(|parent| {
+                spawn_icon_and_label(parent, icons.solve.clone(), "label-solve", font.0.clone())
+            })
+            .insert(Tooltip::new("tooltip-solve"))
+            .id();
+
+        let remix_game_button = commands
+            .spawn_bundle(BoardButtonBundle::<RemixPuzzle>::new(
+                button_size,
+                &*remix_button_materials,
+            ))
+            .insert(Tooltip::new("tooltip-remix"))
+            .id();
+
+        let hint_button = commands
+            .spawn_bundle(BoardButtonBundle::<GetHint>::new(
+                button_size,
+                &*hint_button_materials,
+            ))
+            .insert(Tooltip::new("tooltip-hint"))
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        // This label is set in the show_hint_cooldown system
+                        text: Text::with_section(
+                            "",
+                            TextStyle {
+                                font: font.0.clone(),
+                                font_size: LABEL_FONT_SIZE,
+                                color: Color::BLACK,
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(HintLabel);
+            })
+            .id();
+
+        let find_mistake_button = commands
+            .spawn_bundle(BoardButtonBundle::<FindMistake>::new(
+                button_size,
+                &*find_mistake_button_materials,
+            ))
+            .insert(Tooltip::new("tooltip-find-mistake"))
+            .id();
+
+        let check_puzzle_button = commands
+            .spawn_bundle(BoardButtonBundle::<CheckPuzzle>::new(
+                button_size,
+                &*check_button_materials,
+            ))
+            .insert(Tooltip::new("tooltip-check"))
+            .id();
+
+        let fill_candidates_button = commands
+            .spawn_bundle(BoardButtonBundle::<FillCandidates>::new(
+                button_size,
+                &*fill_candidates_button_materials,
+            ))
+            .insert(Tooltip::new("tooltip-fill-candidates"))
+            .id();
+
+        let scramble_button = commands
+            .spawn_bundle(BoardButtonBundle::<ScrambleRemaining>::new(
+                button_size,
+                &*scramble_button_materials,
+            ))
+            .insert(Tooltip::new("tooltip-scramble"))
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        // This label is translated in the localize_text system
+                        text: Text::with_section(
+                            "",
+                            TextStyle {
+                                font: font.0.clone(),
+                                font_size: ICON_LABEL_FONT_SIZE,
+                                color: Color::BLACK,
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(LocalizedText("label-scramble"));
+            })
+            .id();
+
+        let settings_button = commands
+            .spawn_bundle(BoardButtonBundle::<ShowSettings>::new(
+                button_size,
+                &*settings_button_materials,
+            ))
+            .insert(Tooltip::new("tooltip-settings"))
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        // This label is translated in the localize_text system
+                        text: Text::with_section(
+                            "",
+                            TextStyle {
+                                font: font.0.clone(),
+                                font_size: ICON_LABEL_FONT_SIZE,
+                                color: Color::BLACK,
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    })
+                    .insert(LocalizedText("label-settings"));
+            })
             .id();
 
         // Building our hierarchy, from bottom to top
         let ui_root_entity = ui_root_query.single().expect("No UI root entity found.");
         commands.entity(ui_root_entity).push_children(&layout_nodes);
 
-        // Number buttons
+        // Number buttons are added to their rows by the rebuild_number_pad system,
+        // once the size of the grid is known
+        for (i, row) in layout_nodes[0..N_NUMBER_ROWS].iter().enumerate() {
+            commands.entity(*row).insert(NumberRow(i));
+        }
+
+        // Row 1 buttons
         commands
-            .entity(layout_nodes[0])
-            .push_children(&number_buttons[0..3]);
+            .entity(layout_nodes[N_NUMBER_ROWS])
+            .push_children(&[
+                fill_button,
+                center_mark_button,
+                corner_mark_button,
+                color_button,
+            ]);
 
+        // Row 2 buttons
         commands
-            .entity(layout_nodes[1])
-            .push_children(&number_buttons[3..6]);
+            .entity(layout_nodes[N_NUMBER_ROWS + 1])
+            .push_children(&[
+                new_game_button,
+                reset_game_button,
+                solve_game_button,
+                remix_game_button,
+            ]);
 
+        // Row 3 buttons
         commands
-            .entity(layout_nodes[2])
-            .push_children(&number_buttons[6..9]);
+            .entity(layout_nodes[N_NUMBER_ROWS + 2])
+            .push_children(&[
+                hint_button,
+                find_mistake_button,
+                check_puzzle_button,
+                fill_candidates_button,
+            ]);
 
-        // Row 1 buttons
-        commands.entity(layout_nodes[3]).push_children(&[
-            fill_button,
-            center_mark_button,
-            corner_mark_button,
-        ]);
+        // Row 4 buttons
+        commands
+            .entity(layout_nodes[N_NUMBER_ROWS + 3])
+            .push_children(&swatch_buttons);
 
-        // Row 2 buttons
-        commands.entity(layout_nodes[4]).push_children(&[
-            new_game_button,
-            reset_game_button,
-            solve_game_button,
-        ]);
+        // Row 5 buttons
+        commands
+            .entity(layout_nodes[N_NUMBER_ROWS + 4])
+            .push_children(&[scramble_button, settings_button]);
     }
 }
 
 mod actions {
+    use super::setup::{spawn_number_button, HintLabel, NumberRow, RemainingLabel};
     use super::*;
 
+    /// Respawns the number buttons whenever the puzzle is played on a grid of a different size,
+    /// with a button for each of its digits in rows as long as the smallest square that fits them all.
+    /// Buttons shrink to fit more than three in a row, so the pad takes up the same room on larger grids
+    pub fn rebuild_number_pad(
+        constraints: Res<ConstraintSet>,
+        mut built: Local<Option<GridSize>>,
+        row_query: Query<(Entity, &NumberRow)>,
+        button_query: Query<Entity, With<CellInput>>,
+        number_materials: Res<ButtonMaterials<CellInput>>,
+        font: Res<FixedFont>,
+        mut commands: Commands,
+    ) {
+        let grid = constraints.grid();
+        if *built == Some(grid) {
+            return;
+        }
+        *built = Some(grid);
+
+        for entity in button_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let mut rows: Vec<(Entity, &NumberRow)> = row_query.iter().collect();
+        rows.sort_by_key(|(_, row)| row.0);
+        let columns = (grid.digits() as f32).sqrt().ceil() as usize;
+        let length = NUM_BUTTON_LENGTH * (3.0 / columns as f32).min(1.0);
+        for (i, num) in (1..=grid.digits()).enumerate() {
+            // The rows are missing when the interface has been stripped away in companion mode
+            if let Some((row, _)) = rows.get(i / columns) {
+                let button = spawn_number_button(
+                    &mut commands,
+                    num,
+                    length,
+                    &*number_materials,
+                    font.0.clone(),
+                );
+                commands.entity(*row).push_children(&[button]);
+            }
+        }
+    }
+
     /// Marker component for entities whose materials should not respond
     pub struct FixedMaterial;
 
@@ -464,4 +1040,93 @@ mod actions {
             }
         }
     }
+
+    /// Permanently displays the armed digit as pressed when using digit-first input,
+    /// and greys out digits that have been completed
+    pub fn style_number_buttons(
+        mut button_query: Query<(
+            Entity,
+            &CellInput,
+            &mut Handle<ColorMaterial>,
+            &PressedMaterial,
+            &NormalMaterial,
+        )>,
+        added_query: Query<(), Added<CellInput>>,
+        armed_digit: Res<ArmedDigit>,
+        completed_digits: Res<CompletedDigits>,
+        settings: Res<Settings>,
+        disabled_material: Res<DisabledMaterial>,
+        mut commands: Commands,
+    ) {
+        if armed_digit.is_changed()
+            || completed_digits.is_changed()
+            || settings.is_changed()
+            || added_query.iter().next().is_some()
+        {
+            for (entity, cell_input, mut material, pressed_material, normal_material) in
+                button_query.iter_mut()
+            {
+                let disabled = settings.disable_completed_digits
+                    && completed_digits.0.contains(&cell_input.num);
+
+                if disabled {
+                    *material = disabled_material.0.clone();
+                    commands.entity(entity).insert(FixedMaterial);
+                } else if armed_digit.armed() == Some(cell_input.num) {
+                    *material = pressed_material.0.clone();
+                    commands.entity(entity).insert(FixedMaterial);
+                } else {
+                    *material = normal_material.0.clone();
+                    commands.entity(entity).remove::<FixedMaterial>();
+                }
+            }
+        }
+    }
+
+    /// Shows the time remaining until another hint can be used on the hint button
+    pub fn show_hint_cooldown(
+        hint_cooldown: Res<HintCooldown>,
+        localization: Res<Localization>,
+        mut query: Query<&mut Text, With<HintLabel>>,
+    ) {
+        if hint_cooldown.is_changed() || localization.is_changed() {
+            for mut text in query.iter_mut() {
+                let seconds_remaining = hint_cooldown.0.as_secs_f32().ceil();
+                text.sections[0].value = if seconds_remaining > 0.0 {
+                    localization.format(
+                        "hint-cooldown",
+                        &[("seconds", (seconds_remaining as u32).into())],
+                    )
+                } else {
+                    localization.get("label-hint")
+                };
+            }
+        }
+    }
+
+    /// Shows how many more of each digit must be placed on the number buttons
+    pub fn show_remaining_digits(
+        cell_query: Query<&Value, With<Cell>>,
+        changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+        added_query: Query<(), Added<RemainingLabel>>,
+        constraints: Res<ConstraintSet>,
+        mut label_query: Query<(&RemainingLabel, &mut Text)>,
+    ) {
+        if changed_query.iter().next().is_none() && added_query.iter().next().is_none() {
+            return;
+        }
+
+        for (remaining_label, mut text) in label_query.iter_mut() {
+            let placed = cell_query
+                .iter()
+                .filter(|value| **value == Value::Filled(remaining_label.0))
+                .count();
+            // Placing a digit too many times shouldn't show a negative count
+            text.sections[0].value = constraints
+                .grid()
+                .digit_count()
+                .saturating_sub(placed)
+                .to_string();
+        }
+    }
 }
@@ -0,0 +1,154 @@
+/// Celebrate the player solving the puzzle
+use super::buttons::setup::SudokuBox;
+use crate::{logic::board::PuzzleSolved, settings::Settings, CommonLabels};
+use bevy::prelude::*;
+use rand::Rng;
+
+use self::assets::*;
+use self::config::*;
+
+pub struct CelebrationPlugin;
+
+impl Plugin for CelebrationPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // ASSETS
+            .init_resource::<ConfettiMaterials>()
+            // ACTIONS
+            .add_system_set(
+                SystemSet::new()
+                    .after(CommonLabels::Action)
+                    .with_system(actions::launch_confetti.system())
+                    .with_system(actions::animate_confetti.system()),
+            );
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+    use std::time::Duration;
+
+    pub const CONFETTI_COLORS: [Color; 5] = [
+        Color::rgb(0.95, 0.3, 0.3),
+        Color::rgb(0.95, 0.75, 0.2),
+        Color::rgb(0.3, 0.8, 0.4),
+        Color::rgb(0.3, 0.55, 0.95),
+        Color::rgb(0.7, 0.4, 0.9),
+    ];
+    pub const CONFETTI_COUNT: usize = 80;
+    /// The width and height of each piece of confetti, in pixels
+    pub const CONFETTI_SIZE: f32 = 8.0;
+    /// How long each piece of confetti lasts before disappearing
+    pub const CONFETTI_LIFETIME: Duration = Duration::from_secs(2);
+    /// The range of launch speeds, in pixels per second
+    pub const LAUNCH_SPEED: (f32, f32) = (400.0, 800.0);
+    /// The widest angle from vertical that confetti is launched at, in radians
+    pub const LAUNCH_SPREAD: f32 = 0.5;
+    /// The downwards acceleration of confetti, in pixels per second squared
+    pub const GRAVITY: f32 = 600.0;
+}
+
+mod assets {
+    use super::*;
+
+    /// The colors confetti is chosen from
+    pub struct ConfettiMaterials(pub Vec<Handle<ColorMaterial>>);
+
+    impl FromWorld for ConfettiMaterials {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ConfettiMaterials(
+                CONFETTI_COLORS
+                    .iter()
+                    .map(|color| materials.add((*color).into()))
+                    .collect(),
+            )
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Component for a piece of confetti, measured from the bottom left corner of the Sudoku box
+    pub struct Confetti {
+        pub position: Vec2,
+        pub velocity: Vec2,
+        pub lifetime: Timer,
+    }
+
+    /// Bursts confetti upwards from the bottom of the Sudoku box when the puzzle is solved
+    ///
+    /// Skipped when animations are turned off, in low-spec mode or when motion is reduced
+    pub fn launch_confetti(
+        mut event_reader: EventReader<PuzzleSolved>,
+        box_query: Query<(Entity, &Node), With<SudokuBox>>,
+        materials: Res<ConfettiMaterials>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        for _ in event_reader.iter() {
+            if !settings.animated() {
+                continue;
+            }
+
+            let mut rng = rand::thread_rng();
+            for (box_entity, node) in box_query.iter() {
+                commands.entity(box_entity).with_children(|parent| {
+                    for _ in 0..CONFETTI_COUNT {
+                        let angle = rng.gen_range(-LAUNCH_SPREAD..LAUNCH_SPREAD);
+                        let speed = rng.gen_range(LAUNCH_SPEED.0..LAUNCH_SPEED.1);
+                        let material = materials.0[rng.gen_range(0..materials.0.len())].clone();
+
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    // Confetti flies over the board, rather than being laid out
+                                    position_type: PositionType::Absolute,
+                                    size: Size::new(Val::Px(CONFETTI_SIZE), Val::Px(CONFETTI_SIZE)),
+                                    ..Default::default()
+                                },
+                                material,
+                                ..Default::default()
+                            })
+                            .insert(Confetti {
+                                position: Vec2::new(0.5 * node.size.x, 0.0),
+                                velocity: speed * Vec2::new(angle.sin(), angle.cos()),
+                                lifetime: Timer::new(CONFETTI_LIFETIME, false),
+                            });
+                    }
+                });
+            }
+        }
+    }
+
+    /// Moves confetti under gravity, removing each piece once its time is up
+    ///
+    /// Confetti still in the air when animations are turned off is cleared at once
+    pub fn animate_confetti(
+        mut query: Query<(Entity, &mut Confetti, &mut Style)>,
+        time: Res<Time>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        let delta = time.delta_seconds();
+        for (entity, mut confetti, mut style) in query.iter_mut() {
+            if confetti.lifetime.tick(time.delta()).finished() || !settings.animated() {
+                commands.entity(entity).despawn_recursive();
+                continue;
+            }
+
+            confetti.velocity.y -= GRAVITY * delta;
+            let velocity = confetti.velocity;
+            confetti.position += velocity * delta;
+
+            style.position = Rect {
+                left: Val::Px(confetti.position.x),
+                bottom: Val::Px(confetti.position.y),
+                ..Default::default()
+            };
+        }
+    }
+}
@@ -0,0 +1,1946 @@
+/// Build and display modal dialogs
+use super::board::assets::FixedFont;
+use super::buttons::assets::{ButtonMaterials, HoveredMaterial, NormalMaterial, PressedMaterial};
+use crate::{
+    audio::SoundEffect,
+    campaign::{Campaign, LEVELS, MAX_STARS},
+    input::{
+        buttons::{
+            AddProfile, BookmarkPuzzle, BrowseCollection, CancelDialog, ChooseLanguage,
+            ChooseProfile, CreateProfile, CycleArchiveFilter, CycleGridSize, CycleScrambleSize,
+            EnterSeed, NewPuzzle, PlayCollectionPuzzle, PlayLevel, PlaySeed, QuitGame, RemixPuzzle,
+            ResetPuzzle, ShowLevelSelect, ShowProfiles, ShowSettings, ShowStats, ShowTechniques,
+            StarPuzzle, ToggleSound, TrainTechnique,
+        },
+        focus::{DefaultFocus, Focus, Focusable},
+    },
+    localization::{Language, Localization},
+    logic::{
+        board::{Cell, Fixed, Provenance, Value},
+        collections::{Collection, PAGE_SIZE},
+        mistakes::{GameOver, MISTAKE_LIMIT},
+        records::{BestTimes, Difficulty, PuzzleCompleted},
+        stats::Stats,
+        sudoku_generation::{
+            DiscardConfirmation, DiscardingAction, GenerationFilters, ScrambleConfig, SeedEntry,
+            SeededPuzzle,
+        },
+        techniques::Technique,
+        timer::format_duration,
+    },
+    profiles::{ProfileNameEntry, Profiles, AVATAR_COLORS},
+    settings::Settings,
+    CommonLabels,
+};
+use bevy::{ecs::component::Component, prelude::*, ui::FocusPolicy};
+use std::{marker::PhantomData, time::Duration};
+
+pub use self::actions::{CollectionBrowser, ProfileNameDialog, SeedEntryDialog};
+use self::assets::*;
+use self::config::*;
+
+pub struct DialogPlugin;
+
+impl Plugin for DialogPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // ASSETS
+            .init_resource::<ButtonMaterials<BookmarkPuzzle>>()
+            .init_resource::<ButtonMaterials<CancelDialog>>()
+            .init_resource::<ButtonMaterials<BrowseCollection>>()
+            .init_resource::<ButtonMaterials<PlayCollectionPuzzle>>()
+            .init_resource::<ButtonMaterials<CycleArchiveFilter>>()
+            .init_resource::<ButtonMaterials<StarPuzzle>>()
+            .init_resource::<ButtonMaterials<ShowLevelSelect>>()
+            .init_resource::<ButtonMaterials<PlayLevel>>()
+            .init_resource::<ButtonMaterials<TrainTechnique>>()
+            .init_resource::<ButtonMaterials<QuitGame>>()
+            .init_resource::<ButtonMaterials<ChooseLanguage>>()
+            .init_resource::<ButtonMaterials<ToggleSound>>()
+            .init_resource::<ButtonMaterials<CycleScrambleSize>>()
+            .init_resource::<ButtonMaterials<CycleGridSize>>()
+            .init_resource::<ButtonMaterials<ShowStats>>()
+            .init_resource::<ButtonMaterials<EnterSeed>>()
+            .init_resource::<ButtonMaterials<PlaySeed>>()
+            .init_resource::<ButtonMaterials<ShowProfiles>>()
+            .init_resource::<ButtonMaterials<ChooseProfile>>()
+            .init_resource::<ButtonMaterials<AddProfile>>()
+            .init_resource::<ButtonMaterials<CreateProfile>>()
+            .init_resource::<AvatarMaterials>()
+            .init_resource::<DialogColors>()
+            // ACTIONS
+            .add_system_set(
+                SystemSet::new()
+                    .after(CommonLabels::Action)
+                    .with_system(actions::spawn_confirmation_dialog.system())
+                    .with_system(actions::spawn_game_over_dialog.system())
+                    .with_system(actions::spawn_victory_dialog.system())
+                    .with_system(actions::spawn_collection_browser.system())
+                    .with_system(actions::spawn_level_select.system())
+                    .with_system(actions::spawn_technique_select.system())
+                    .with_system(actions::spawn_settings_menu.system())
+                    .with_system(actions::spawn_stats_screen.system())
+                    .with_system(actions::spawn_seed_entry.system())
+                    .with_system(actions::spawn_profile_select.system())
+                    .with_system(actions::spawn_profile_name_entry.system())
+                    .with_system(actions::close_dialogs.system())
+                    .with_system(actions::show_focus.system()),
+            );
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+    use std::time::Duration;
+
+    pub const OVERLAY_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.5);
+    pub const PANEL_COLOR: Color = Color::rgb(0.95, 0.95, 0.95);
+    pub const TEXT_COLOR: Color = Color::BLACK;
+    /// The bar under the button focused by the keyboard or gamepad
+    pub const FOCUS_COLOR: Color = Color::rgb(0.1, 0.3, 0.8);
+    pub const FOCUS_BAR_HEIGHT: f32 = 4.0;
+
+    pub const FONT_SIZE: f32 = 24.0;
+    /// The size of the buttons used to answer the dialog
+    pub const BUTTON_WIDTH: f32 = 160.0;
+    pub const BUTTON_HEIGHT: f32 = 48.0;
+    /// The square of color shown next to each profile's name
+    pub const AVATAR_SIZE: f32 = 32.0;
+
+    /// How long the celebration plays before the victory dialog is shown
+    pub const VICTORY_DELAY: Duration = Duration::from_millis(1500);
+}
+
+// QUALITY: reduce asset loading code duplication dramatically
+mod assets {
+    use super::*;
+    /// The colors dialogs are drawn with
+    pub struct DialogColors {
+        /// The translucent color that covers the rest of the game while a dialog is open
+        pub overlay: Handle<ColorMaterial>,
+        /// The color of the dialog's background
+        pub panel: Handle<ColorMaterial>,
+        /// The color of the bar marking the focused button
+        pub focus: Handle<ColorMaterial>,
+    }
+
+    impl FromWorld for DialogColors {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            DialogColors {
+                overlay: materials.add(OVERLAY_COLOR.into()),
+                panel: materials.add(PANEL_COLOR.into()),
+                focus: materials.add(FOCUS_COLOR.into()),
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<BookmarkPuzzle> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(1.0, 0.85, 0.15).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<BrowseCollection> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.8, 0.8, 0.8).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<CycleArchiveFilter> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.8, 0.8, 0.8).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<StarPuzzle> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.9, 0.8, 0.4).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<PlayCollectionPuzzle> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<ShowLevelSelect> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<PlayLevel> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<TrainTechnique> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<ChooseLanguage> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.7, 0.85, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<ToggleSound> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.85, 0.75, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<CycleScrambleSize> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.9, 0.6).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<CycleGridSize> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.9, 0.6).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<ShowStats> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<EnterSeed> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<PlaySeed> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.9, 0.6).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<QuitGame> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.9, 0.5, 0.5).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<CancelDialog> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.8, 0.8, 0.8).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+    impl FromWorld for ButtonMaterials<ShowProfiles> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<ChooseProfile> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.8, 0.8, 0.8).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<AddProfile> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.9, 0.6).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<CreateProfile> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.9, 0.6).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    /// The color of each profile's avatar, in the order of `AVATAR_COLORS`
+    pub struct AvatarMaterials(pub Vec<Handle<ColorMaterial>>);
+
+    impl FromWorld for AvatarMaterials {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            AvatarMaterials(
+                AVATAR_COLORS
+                    .iter()
+                    .map(|color| materials.add((*color).into()))
+                    .collect(),
+            )
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Marker component for the root node of a dialog
+    pub struct Dialog;
+
+    /// Marker component for dialogs listing the puzzles in a collection
+    pub struct CollectionBrowser;
+    /// Marker component for the settings menu
+    pub struct SettingsMenu;
+    /// Marker component for the dialog asking for the seed of a puzzle to play
+    pub struct SeedEntryDialog;
+    /// Marker component for the dialog asking for the name of a new profile
+    pub struct ProfileNameDialog;
+    /// Marker component for the bar under the focused button
+    pub struct FocusBar;
+    /// Asks the player to confirm before discarding their progress for a new puzzle, a reset, a remix or to quit
+    pub fn spawn_confirmation_dialog(
+        mut event_reader: EventReader<DiscardConfirmation>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        new_button_materials: Res<ButtonMaterials<NewPuzzle>>,
+        reset_button_materials: Res<ButtonMaterials<ResetPuzzle>>,
+        remix_button_materials: Res<ButtonMaterials<RemixPuzzle>>,
+        quit_button_materials: Res<ButtonMaterials<QuitGame>>,
+        bookmark_button_materials: Res<ButtonMaterials<BookmarkPuzzle>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        for event in event_reader.iter() {
+            // Only one dialog should be open at once
+            if dialog_query.iter().next().is_some() {
+                continue;
+            }
+
+            let text_style = TextStyle {
+                font: font.0.clone(),
+                font_size: FONT_SIZE,
+                color: TEXT_COLOR,
+            };
+
+            let question = match event.action {
+                DiscardingAction::NewPuzzle => "confirm-new-puzzle",
+                DiscardingAction::ResetPuzzle => "confirm-reset",
+                DiscardingAction::RemixPuzzle => "confirm-remix",
+                DiscardingAction::QuitGame => "confirm-quit",
+            };
+            let percent = (event.completion * 100.0).round() as u32;
+            let progress = localization.format(
+                "confirm-progress",
+                &[
+                    ("percent", percent.into()),
+                    ("time", event.puzzle_timer.to_string().into()),
+                ],
+            );
+            let message = format!("{}\n{}", localization.get(question), progress);
+
+            spawn_dialog(
+                &mut commands,
+                &*dialog_colors,
+                &*settings,
+                message,
+                text_style.clone(),
+                |parent| {
+                    match event.action {
+                        DiscardingAction::NewPuzzle => {
+                            spawn_dialog_button(
+                                parent,
+                                NewPuzzle { confirmed: true },
+                                &*new_button_materials,
+                                &localization.get("dialog-discard"),
+                                text_style.clone(),
+                                false,
+                            );
+                            // Bookmarking keeps the progress to come back to, then starts the new puzzle
+                            spawn_dialog_button(
+                                parent,
+                                BookmarkPuzzle,
+                                &*bookmark_button_materials,
+                                &localization.get("dialog-bookmark"),
+                                text_style.clone(),
+                                false,
+                            );
+                        }
+                        DiscardingAction::ResetPuzzle => spawn_dialog_button(
+                            parent,
+                            ResetPuzzle { confirmed: true },
+                            &*reset_button_materials,
+                            &localization.get("dialog-reset"),
+                            text_style.clone(),
+                            false,
+                        ),
+                        DiscardingAction::RemixPuzzle => spawn_dialog_button(
+                            parent,
+                            RemixPuzzle { confirmed: true },
+                            &*remix_button_materials,
+                            &localization.get("dialog-remix"),
+                            text_style.clone(),
+                            false,
+                        ),
+                        DiscardingAction::QuitGame => spawn_dialog_button(
+                            parent,
+                            QuitGame { confirmed: true },
+                            &*quit_button_materials,
+                            &localization.get("dialog-quit"),
+                            text_style.clone(),
+                            false,
+                        ),
+                    }
+                    spawn_dialog_button(
+                        parent,
+                        CancelDialog,
+                        &*cancel_button_materials,
+                        &localization.get("dialog-cancel"),
+                        text_style.clone(),
+                        true,
+                    );
+                },
+            );
+        }
+    }
+
+    /// Tells the player that they have run out of mistakes, offering to retry the puzzle or start another
+    pub fn spawn_game_over_dialog(
+        mut event_reader: EventReader<GameOver>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        new_button_materials: Res<ButtonMaterials<NewPuzzle>>,
+        reset_button_materials: Res<ButtonMaterials<ResetPuzzle>>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        for _ in event_reader.iter() {
+            // Only one dialog should be open at once
+            if dialog_query.iter().next().is_some() {
+                continue;
+            }
+
+            let text_style = TextStyle {
+                font: font.0.clone(),
+                font_size: FONT_SIZE,
+                color: TEXT_COLOR,
+            };
+
+            let message = localization.format("game-over", &[("mistakes", MISTAKE_LIMIT.into())]);
+
+            spawn_dialog(
+                &mut commands,
+                &*dialog_colors,
+                &*settings,
+                message,
+                text_style.clone(),
+                |parent| {
+                    spawn_dialog_button(
+                        parent,
+                        NewPuzzle { confirmed: true },
+                        &*new_button_materials,
+                        &localization.get("dialog-new-puzzle"),
+                        text_style.clone(),
+                        false,
+                    );
+                    spawn_dialog_button(
+                        parent,
+                        ResetPuzzle { confirmed: true },
+                        &*reset_button_materials,
+                        &localization.get("dialog-try-again"),
+                        text_style.clone(),
+                        true,
+                    );
+                },
+            );
+        }
+    }
+
+    /// Congratulates the player once the celebration of their solve is over,
+    /// showing who placed the digits, their best times and whether this puzzle beat one
+    ///
+    /// With motion reduced there is no celebration to wait for, so the dialog is shown straight away
+    pub fn spawn_victory_dialog(
+        mut event_reader: EventReader<PuzzleCompleted>,
+        mut pending: Local<Option<(Timer, String)>>,
+        time: Res<Time>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        new_button_materials: Res<ButtonMaterials<NewPuzzle>>,
+        star_button_materials: Res<ButtonMaterials<StarPuzzle>>,
+        levels_button_materials: Res<ButtonMaterials<ShowLevelSelect>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        best_times: Res<BestTimes>,
+        campaign: Res<Campaign>,
+        cell_query: Query<(&Value, &Fixed, &Provenance), With<Cell>>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        for event in event_reader.iter() {
+            let time = event.puzzle_timer.to_string();
+            let mut lines = vec![localization.format("victory-solved", &[("time", time.into())])];
+            if event.puzzle_timer.hints_used > 0 {
+                lines.push(localization.format(
+                    "victory-penalty",
+                    &[
+                        (
+                            "penalty",
+                            format_duration(event.puzzle_timer.penalty).into(),
+                        ),
+                        ("hints", event.puzzle_timer.hints_used.into()),
+                    ],
+                ));
+            }
+            if let Some(par) = event.par {
+                let par = format_duration(par);
+                lines.push(match event.medal {
+                    Some(medal) => localization.format(
+                        "victory-par-medal",
+                        &[
+                            ("par", par.into()),
+                            ("medal", localization.get(medal.name_id()).into()),
+                        ],
+                    ),
+                    None => localization.format("victory-par", &[("par", par.into())]),
+                });
+            }
+
+            let placed_by = |source: Provenance| {
+                cell_query
+                    .iter()
+                    .filter(|(value, is_fixed, provenance)| {
+                        !is_fixed.0 && matches!(value, Value::Filled(_)) && **provenance == source
+                    })
+                    .count()
+            };
+            let mut placed = vec![localization.format(
+                "victory-placed-by-player",
+                &[("count", placed_by(Provenance::Player).into())],
+            )];
+            for (source, id) in [
+                (Provenance::Hint, "victory-placed-by-hints"),
+                (Provenance::Solver, "victory-placed-by-solver"),
+            ]
+            .iter()
+            {
+                let count = placed_by(*source);
+                if count > 0 {
+                    placed.push(localization.format(id, &[("count", count.into())]));
+                }
+            }
+            lines.push(placed.join(", "));
+            if event.new_best {
+                lines.push(localization.format(
+                    "victory-new-best",
+                    &[(
+                        "difficulty",
+                        localization.get(event.difficulty.name_id()).into(),
+                    )],
+                ));
+            }
+
+            lines.push(String::new());
+            lines.push(localization.get("victory-best-times"));
+            for difficulty in [Difficulty::Easy, Difficulty::Hard].iter() {
+                if let Some(best) = best_times.difficulties.get(difficulty) {
+                    lines.push(localization.format(
+                        "victory-best-time",
+                        &[
+                            ("difficulty", localization.get(difficulty.name_id()).into()),
+                            ("time", format_duration(*best).into()),
+                        ],
+                    ));
+                }
+            }
+            let message = lines.join("\n");
+
+            // There's no celebration to wait for when animations are turned off
+            let delay = if !settings.animated() {
+                Duration::ZERO
+            } else {
+                VICTORY_DELAY
+            };
+            *pending = Some((Timer::new(delay, false), message));
+        }
+
+        let ready = match pending.as_mut() {
+            Some((timer, _)) => timer.tick(time.delta()).finished(),
+            None => false,
+        };
+        if !ready {
+            return;
+        }
+        let (_, message) = pending.take().expect("Victory dialog is pending");
+
+        // Only one dialog should be open at once
+        if dialog_query.iter().next().is_some() {
+            return;
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+        // Campaign levels lead back to the level select screen, to move on to the next level
+        let in_campaign = campaign.current.is_some();
+
+        spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            message,
+            text_style.clone(),
+            |parent| {
+                if in_campaign {
+                    spawn_dialog_button(
+                        parent,
+                        ShowLevelSelect,
+                        &*levels_button_materials,
+                        &localization.get("dialog-levels"),
+                        text_style.clone(),
+                        true,
+                    );
+                }
+                spawn_dialog_button(
+                    parent,
+                    NewPuzzle { confirmed: true },
+                    &*new_button_materials,
+                    &localization.get("dialog-new-puzzle"),
+                    text_style.clone(),
+                    !in_campaign,
+                );
+                spawn_dialog_button(
+                    parent,
+                    StarPuzzle(None),
+                    &*star_button_materials,
+                    &localization.get("dialog-play-later"),
+                    text_style.clone(),
+                    false,
+                );
+                spawn_dialog_button(
+                    parent,
+                    CancelDialog,
+                    &*cancel_button_materials,
+                    &localization.get("dialog-close"),
+                    text_style.clone(),
+                    false,
+                );
+            },
+        );
+    }
+
+    /// Lists a page of the puzzles in the collection or archive with their difficulty,
+    /// replacing the page shown before
+    pub fn spawn_collection_browser(
+        mut event_reader: EventReader<BrowseCollection>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        browser_query: Query<Entity, With<CollectionBrowser>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        browse_button_materials: Res<ButtonMaterials<BrowseCollection>>,
+        play_button_materials: Res<ButtonMaterials<PlayCollectionPuzzle>>,
+        filter_button_materials: Res<ButtonMaterials<CycleArchiveFilter>>,
+        star_button_materials: Res<ButtonMaterials<StarPuzzle>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        collection: Res<Collection>,
+        best_times: Res<BestTimes>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        // Only the last page asked for is shown
+        let page = match event_reader.iter().last() {
+            Some(event) => event.page.min(collection.pages().saturating_sub(1)),
+            None => return,
+        };
+        // The archive is still listed when nothing passes its filter, so the filter can be changed
+        if collection.puzzles.is_empty() && collection.archive_filter.is_none() {
+            return;
+        }
+
+        // Other dialogs take priority over browsing
+        let browsing = browser_query.iter().next().is_some();
+        if dialog_query.iter().next().is_some() && !browsing {
+            return;
+        }
+        for entity in browser_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        let title = match collection.archive_filter {
+            Some(filter) => {
+                let search = if collection.archive_search.is_empty() {
+                    localization.get("archive-search-hint")
+                } else {
+                    localization.format(
+                        "archive-search",
+                        &[("search", collection.archive_search.clone().into())],
+                    )
+                };
+                format!(
+                    "{}\n{}",
+                    localization.format(
+                        "archive-title",
+                        &[("filter", localization.get(filter.name_id()).into())],
+                    ),
+                    search
+                )
+            }
+            None => collection.name.clone(),
+        };
+        let progress = localization.format(
+            "collection-progress",
+            &[
+                ("page", (page + 1).into()),
+                ("pages", collection.pages().into()),
+                ("solved", collection.completed.len().into()),
+                ("puzzles", collection.puzzles.len().into()),
+            ],
+        );
+        let message = format!("{}\n{}", title, progress);
+
+        let shown = page * PAGE_SIZE..collection.puzzles.len().min((page + 1) * PAGE_SIZE);
+        // Focus starts on the first unsolved puzzle of the page
+        let focused = shown
+            .clone()
+            .find(|i| !collection.completed.contains(i))
+            .unwrap_or(shown.start);
+
+        let column = Style {
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        };
+
+        let browser = spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            message,
+            text_style.clone(),
+            |parent| {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: column.clone(),
+                        material: dialog_colors.panel.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for i in shown {
+                            // Archived puzzles can be starred next to where they are listed
+                            parent
+                                .spawn_bundle(NodeBundle {
+                                    material: dialog_colors.panel.clone(),
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    spawn_dialog_button(
+                                        parent,
+                                        PlayCollectionPuzzle(i),
+                                        &*play_button_materials,
+                                        &collection.label(i, &*best_times, &*localization),
+                                        text_style.clone(),
+                                        i == focused,
+                                    );
+                                    if collection.archive_filter.is_some() {
+                                        let label =
+                                            localization.get(if collection.puzzles[i].starred {
+                                                "dialog-unstar"
+                                            } else {
+                                                "dialog-star"
+                                            });
+                                        spawn_dialog_button(
+                                            parent,
+                                            StarPuzzle(Some(i)),
+                                            &*star_button_materials,
+                                            &label,
+                                            text_style.clone(),
+                                            false,
+                                        );
+                                    }
+                                });
+                        }
+                    });
+
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: column.clone(),
+                        material: dialog_colors.panel.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        if let Some(filter) = collection.archive_filter {
+                            spawn_dialog_button(
+                                parent,
+                                CycleArchiveFilter,
+                                &*filter_button_materials,
+                                &localization.format(
+                                    "archive-show",
+                                    &[("filter", localization.get(filter.name_id()).into())],
+                                ),
+                                text_style.clone(),
+                                collection.puzzles.is_empty(),
+                            );
+                        }
+                        if page > 0 {
+                            spawn_dialog_button(
+                                parent,
+                                BrowseCollection { page: page - 1 },
+                                &*browse_button_materials,
+                                &localization.get("dialog-previous"),
+                                text_style.clone(),
+                                false,
+                            );
+                        }
+                        if page + 1 < collection.pages() {
+                            spawn_dialog_button(
+                                parent,
+                                BrowseCollection { page: page + 1 },
+                                &*browse_button_materials,
+                                &localization.get("dialog-next"),
+                                text_style.clone(),
+                                false,
+                            );
+                        }
+                        spawn_dialog_button(
+                            parent,
+                            CancelDialog,
+                            &*cancel_button_materials,
+                            &localization.get("dialog-close"),
+                            text_style.clone(),
+                            false,
+                        );
+                    });
+            },
+        );
+        commands.entity(browser).insert(CollectionBrowser);
+    }
+
+    /// Shows the levels of the campaign that have been unlocked, with the stars earned on each,
+    /// replacing any dialog that was open
+    pub fn spawn_level_select(
+        mut event_reader: EventReader<ShowLevelSelect>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        level_button_materials: Res<ButtonMaterials<PlayLevel>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        campaign: Res<Campaign>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        if event_reader.iter().count() == 0 {
+            return;
+        }
+        // The level select screen is only opened from the keyboard with no other menu open,
+        // or from the victory dialog, which it replaces
+        for entity in dialog_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        let unlocked: Vec<usize> = (0..LEVELS.len())
+            .filter(|level| campaign.is_unlocked(*level))
+            .collect();
+        let mut message = localization.format(
+            "campaign-progress",
+            &[
+                ("stars", campaign.total_stars().into()),
+                ("total", (LEVELS.len() * MAX_STARS as usize).into()),
+            ],
+        );
+        if unlocked.len() < LEVELS.len() {
+            message.push('\n');
+            message.push_str(&localization.get("campaign-locked"));
+        }
+        // Focus starts on the newest level unlocked
+        let focused = unlocked.last().copied();
+
+        let column = Style {
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        };
+
+        spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            message,
+            text_style.clone(),
+            |parent| {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: column.clone(),
+                        material: dialog_colors.panel.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for level in unlocked {
+                            spawn_dialog_button(
+                                parent,
+                                PlayLevel(level),
+                                &*level_button_materials,
+                                &campaign.label(level, &*localization),
+                                text_style.clone(),
+                                Some(level) == focused,
+                            );
+                        }
+                    });
+
+                spawn_dialog_button(
+                    parent,
+                    CancelDialog,
+                    &*cancel_button_materials,
+                    &localization.get("dialog-close"),
+                    text_style.clone(),
+                    false,
+                );
+            },
+        );
+    }
+
+    /// Lists the techniques that can be practised with the technique trainer
+    pub fn spawn_technique_select(
+        mut event_reader: EventReader<ShowTechniques>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        technique_button_materials: Res<ButtonMaterials<TrainTechnique>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        if event_reader.iter().count() == 0 {
+            return;
+        }
+        // Only one dialog should be open at once
+        if dialog_query.iter().next().is_some() {
+            return;
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        let column = Style {
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        };
+
+        spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            localization.get("trainer-select"),
+            text_style.clone(),
+            |parent| {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: column.clone(),
+                        material: dialog_colors.panel.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for (i, technique) in Technique::ALL.iter().enumerate() {
+                            spawn_dialog_button(
+                                parent,
+                                TrainTechnique(i),
+                                &*technique_button_materials,
+                                &localization.get(technique.name_id()),
+                                text_style.clone(),
+                                i == 0,
+                            );
+                        }
+                    });
+
+                spawn_dialog_button(
+                    parent,
+                    CancelDialog,
+                    &*cancel_button_materials,
+                    &localization.get("dialog-close"),
+                    text_style.clone(),
+                    false,
+                );
+            },
+        );
+    }
+
+    /// Opens the settings menu, and rebuilds it whenever a setting is changed from it
+    pub fn spawn_settings_menu(
+        mut event_reader: EventReader<ShowSettings>,
+        mut sound_events: EventReader<ToggleSound>,
+        mut scramble_events: EventReader<CycleScrambleSize>,
+        mut grid_size_events: EventReader<CycleGridSize>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        menu_query: Query<Entity, With<SettingsMenu>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        language_button_materials: Res<ButtonMaterials<ChooseLanguage>>,
+        sound_button_materials: Res<ButtonMaterials<ToggleSound>>,
+        scramble_button_materials: Res<ButtonMaterials<CycleScrambleSize>>,
+        grid_size_button_materials: Res<ButtonMaterials<CycleGridSize>>,
+        stats_button_materials: Res<ButtonMaterials<ShowStats>>,
+        seed_button_materials: Res<ButtonMaterials<EnterSeed>>,
+        profiles_button_materials: Res<ButtonMaterials<ShowProfiles>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        scramble_config: Res<ScrambleConfig>,
+        generation_filters: Res<GenerationFilters>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        let open = menu_query.iter().next().is_some();
+        let opened = event_reader.iter().count() > 0;
+        // Focus stays on the setting that was just changed
+        let toggled = sound_events.iter().last().map(|event| event.0);
+        let resized = scramble_events.iter().count() > 0;
+        let regridded = grid_size_events.iter().count() > 0;
+        let changed = toggled.is_some() || resized || regridded;
+        // The text is only translated once the localization has caught up with the language chosen
+        let retranslated = open && localization.is_changed();
+        if !opened && !retranslated && !(open && changed) {
+            return;
+        }
+        // Other dialogs take priority over the settings
+        if dialog_query.iter().next().is_some() && !open {
+            return;
+        }
+        for entity in menu_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        let column = Style {
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        };
+        let row = Style {
+            align_items: AlignItems::Center,
+            ..Default::default()
+        };
+
+        let menu = spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            localization.get("settings-title"),
+            text_style.clone(),
+            |parent| {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: column.clone(),
+                        material: dialog_colors.panel.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: row.clone(),
+                                material: dialog_colors.panel.clone(),
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn_bundle(TextBundle {
+                                    text: Text::with_section(
+                                        localization.get("settings-language"),
+                                        text_style.clone(),
+                                        Default::default(),
+                                    ),
+                                    ..Default::default()
+                                });
+                                // Each language is named in itself, and focus starts on the one being shown
+                                for language in Language::ALL.iter() {
+                                    spawn_dialog_button(
+                                        parent,
+                                        ChooseLanguage(*language),
+                                        &*language_button_materials,
+                                        language.name(),
+                                        text_style.clone(),
+                                        !changed && *language == settings.language,
+                                    );
+                                }
+                            });
+
+                        for effect in SoundEffect::ALL.iter() {
+                            let state = if settings.muted_sounds.is_muted(*effect) {
+                                "settings-off"
+                            } else {
+                                "settings-on"
+                            };
+                            parent
+                                .spawn_bundle(NodeBundle {
+                                    style: row.clone(),
+                                    material: dialog_colors.panel.clone(),
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(TextBundle {
+                                        text: Text::with_section(
+                                            localization.get(effect.name_id()),
+                                            text_style.clone(),
+                                            Default::default(),
+                                        ),
+                                        ..Default::default()
+                                    });
+                                    spawn_dialog_button(
+                                        parent,
+                                        ToggleSound(*effect),
+                                        &*sound_button_materials,
+                                        &localization.get(state),
+                                        text_style.clone(),
+                                        toggled == Some(*effect),
+                                    );
+                                });
+                        }
+
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: row.clone(),
+                                material: dialog_colors.panel.clone(),
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn_bundle(TextBundle {
+                                    text: Text::with_section(
+                                        localization.get("settings-scramble"),
+                                        text_style.clone(),
+                                        Default::default(),
+                                    ),
+                                    ..Default::default()
+                                });
+                                spawn_dialog_button(
+                                    parent,
+                                    CycleScrambleSize,
+                                    &*scramble_button_materials,
+                                    &scramble_config.removed.to_string(),
+                                    text_style.clone(),
+                                    resized,
+                                );
+                            });
+
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: row.clone(),
+                                material: dialog_colors.panel.clone(),
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn_bundle(TextBundle {
+                                    text: Text::with_section(
+                                        localization.get("settings-grid-size"),
+                                        text_style.clone(),
+                                        Default::default(),
+                                    ),
+                                    ..Default::default()
+                                });
+                                spawn_dialog_button(
+                                    parent,
+                                    CycleGridSize,
+                                    &*grid_size_button_materials,
+                                    &localization.get(generation_filters.size.name_id()),
+                                    text_style.clone(),
+                                    regridded,
+                                );
+                            });
+                    });
+
+                spawn_dialog_button(
+                    parent,
+                    ShowStats,
+                    &*stats_button_materials,
+                    &localization.get("stats-title"),
+                    text_style.clone(),
+                    false,
+                );
+                spawn_dialog_button(
+                    parent,
+                    EnterSeed,
+                    &*seed_button_materials,
+                    &localization.get("seed-entry-button"),
+                    text_style.clone(),
+                    false,
+                );
+                spawn_dialog_button(
+                    parent,
+                    ShowProfiles,
+                    &*profiles_button_materials,
+                    &localization.get("profiles-button"),
+                    text_style.clone(),
+                    false,
+                );
+                spawn_dialog_button(
+                    parent,
+                    CancelDialog,
+                    &*cancel_button_materials,
+                    &localization.get("dialog-close"),
+                    text_style.clone(),
+                    false,
+                );
+            },
+        );
+        commands.entity(menu).insert(SettingsMenu);
+    }
+
+    /// Shows the player's statistics for each difficulty they have solved a puzzle at,
+    /// replacing the settings menu it is opened from
+    pub fn spawn_stats_screen(
+        mut event_reader: EventReader<ShowStats>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        stats: Res<Stats>,
+        best_times: Res<BestTimes>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        if event_reader.iter().count() == 0 {
+            return;
+        }
+        for entity in dialog_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        let mut lines = vec![localization.get("stats-title")];
+        for difficulty in [Difficulty::Easy, Difficulty::Hard].iter() {
+            let stats = match stats.difficulties.get(difficulty) {
+                Some(stats) if stats.completed > 0 => stats,
+                _ => continue,
+            };
+            // Best times are kept separately, and may have been recorded before statistics were
+            let best = best_times
+                .difficulties
+                .get(difficulty)
+                .map_or_else(|| "--:--".to_string(), |best| format_duration(*best));
+            let average = stats
+                .average_time()
+                .map(format_duration)
+                .unwrap_or_default();
+
+            lines.push(String::new());
+            lines.push(localization.format(
+                "stats-solved",
+                &[
+                    ("difficulty", localization.get(difficulty.name_id()).into()),
+                    ("count", stats.completed.into()),
+                    ("best", best.into()),
+                    ("average", average.into()),
+                ],
+            ));
+            lines.push(localization.format(
+                "stats-help",
+                &[
+                    ("hints", stats.hints_used.into()),
+                    ("penalty", format_duration(stats.hint_penalties).into()),
+                    ("mistakes", stats.mistakes.into()),
+                ],
+            ));
+            lines.push(localization.format(
+                "stats-streak",
+                &[
+                    ("streak", stats.streak.into()),
+                    ("best", stats.best_streak.into()),
+                ],
+            ));
+        }
+        if lines.len() == 1 {
+            lines.push(localization.get("stats-empty"));
+        }
+
+        spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            lines.join("\n"),
+            text_style.clone(),
+            |parent| {
+                spawn_dialog_button(
+                    parent,
+                    CancelDialog,
+                    &*cancel_button_materials,
+                    &localization.get("dialog-close"),
+                    text_style.clone(),
+                    true,
+                );
+            },
+        );
+    }
+
+    /// Asks for the seed of a puzzle to play, replacing any other dialog,
+    /// and shows the digits typed so far as they change
+    pub fn spawn_seed_entry(
+        mut event_reader: EventReader<EnterSeed>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        entry_query: Query<Entity, With<SeedEntryDialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        play_button_materials: Res<ButtonMaterials<PlaySeed>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        seed_entry: Res<SeedEntry>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        let opened = event_reader.iter().count() > 0;
+        let open = entry_query.iter().next().is_some();
+        if !opened && !(open && seed_entry.is_changed()) {
+            return;
+        }
+        // Opening the dialog replaces any other, while typing only rebuilds the dialog itself
+        if opened {
+            for entity in dialog_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        } else {
+            for entity in entry_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        // The entry is cleared as the dialog opens, so it starts out empty
+        let seed = if opened {
+            String::new()
+        } else {
+            seed_entry.0.clone()
+        };
+        let message = format!(
+            "{}\n{}",
+            localization.get("seed-entry-title"),
+            localization.format("seed-entry", &[("seed", seed.into())])
+        );
+
+        let dialog = spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            message,
+            text_style.clone(),
+            |parent| {
+                spawn_dialog_button(
+                    parent,
+                    PlaySeed,
+                    &*play_button_materials,
+                    &localization.get("dialog-play"),
+                    text_style.clone(),
+                    true,
+                );
+                spawn_dialog_button(
+                    parent,
+                    CancelDialog,
+                    &*cancel_button_materials,
+                    &localization.get("dialog-cancel"),
+                    text_style.clone(),
+                    false,
+                );
+            },
+        );
+        commands.entity(dialog).insert(SeedEntryDialog);
+    }
+
+    /// Lists the players' profiles, each next to its avatar, replacing any other dialog
+    pub fn spawn_profile_select(
+        mut event_reader: EventReader<ShowProfiles>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        avatar_materials: Res<AvatarMaterials>,
+        profile_button_materials: Res<ButtonMaterials<ChooseProfile>>,
+        add_button_materials: Res<ButtonMaterials<AddProfile>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        profiles: Res<Profiles>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        if event_reader.iter().count() == 0 {
+            return;
+        }
+        for entity in dialog_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        let current = profiles
+            .current()
+            .display_name(profiles.current, &*localization);
+        let message = format!(
+            "{}\n{}",
+            localization.get("profiles-title"),
+            localization.format("profiles-current", &[("name", current.into())])
+        );
+
+        let column = Style {
+            flex_direction: FlexDirection::ColumnReverse,
+            ..Default::default()
+        };
+        let row = Style {
+            align_items: AlignItems::Center,
+            ..Default::default()
+        };
+
+        spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            message,
+            text_style.clone(),
+            |parent| {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: column.clone(),
+                        material: dialog_colors.panel.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for (index, profile) in profiles.profiles.iter().enumerate() {
+                            parent
+                                .spawn_bundle(NodeBundle {
+                                    style: row.clone(),
+                                    material: dialog_colors.panel.clone(),
+                                    ..Default::default()
+                                })
+                                .with_children(|parent| {
+                                    parent.spawn_bundle(NodeBundle {
+                                        style: Style {
+                                            size: Size::new(
+                                                Val::Px(AVATAR_SIZE),
+                                                Val::Px(AVATAR_SIZE),
+                                            ),
+                                            ..Default::default()
+                                        },
+                                        material: avatar_materials.0
+                                            [profile.color % avatar_materials.0.len()]
+                                        .clone(),
+                                        ..Default::default()
+                                    });
+                                    spawn_dialog_button(
+                                        parent,
+                                        ChooseProfile(index),
+                                        &*profile_button_materials,
+                                        &profile.display_name(index, &*localization),
+                                        text_style.clone(),
+                                        index == profiles.current,
+                                    );
+                                });
+                        }
+                    });
+
+                spawn_dialog_button(
+                    parent,
+                    AddProfile,
+                    &*add_button_materials,
+                    &localization.get("profiles-new"),
+                    text_style.clone(),
+                    false,
+                );
+                spawn_dialog_button(
+                    parent,
+                    CancelDialog,
+                    &*cancel_button_materials,
+                    &localization.get("dialog-close"),
+                    text_style.clone(),
+                    false,
+                );
+            },
+        );
+    }
+
+    /// Asks for the name of a new profile, replacing the profile list,
+    /// and shows the letters typed so far as they change
+    pub fn spawn_profile_name_entry(
+        mut event_reader: EventReader<AddProfile>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        entry_query: Query<Entity, With<ProfileNameDialog>>,
+        mut commands: Commands,
+        dialog_colors: Res<DialogColors>,
+        create_button_materials: Res<ButtonMaterials<CreateProfile>>,
+        cancel_button_materials: Res<ButtonMaterials<CancelDialog>>,
+        name_entry: Res<ProfileNameEntry>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+        localization: Res<Localization>,
+    ) {
+        let opened = event_reader.iter().count() > 0;
+        let open = entry_query.iter().next().is_some();
+        if !opened && !(open && name_entry.is_changed()) {
+            return;
+        }
+        // Opening the dialog replaces any other, while typing only rebuilds the dialog itself
+        if opened {
+            for entity in dialog_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        } else {
+            for entity in entry_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        // The entry is cleared as the dialog opens, so it starts out empty
+        let name = if opened {
+            String::new()
+        } else {
+            name_entry.0.clone()
+        };
+        let message = format!(
+            "{}\n{}",
+            localization.get("profile-name-entry-title"),
+            localization.format("profile-name-entry", &[("name", name.into())])
+        );
+
+        let dialog = spawn_dialog(
+            &mut commands,
+            &*dialog_colors,
+            &*settings,
+            message,
+            text_style.clone(),
+            |parent| {
+                spawn_dialog_button(
+                    parent,
+                    CreateProfile,
+                    &*create_button_materials,
+                    &localization.get("dialog-create"),
+                    text_style.clone(),
+                    true,
+                );
+                spawn_dialog_button(
+                    parent,
+                    CancelDialog,
+                    &*cancel_button_materials,
+                    &localization.get("dialog-cancel"),
+                    text_style.clone(),
+                    false,
+                );
+            },
+        );
+        commands.entity(dialog).insert(ProfileNameDialog);
+    }
+
+    /// Spawns a dialog showing the message provided above a row of buttons, covering the rest of the game
+    ///
+    /// Returns the root entity of the dialog
+    fn spawn_dialog(
+        commands: &mut Commands,
+        dialog_colors: &DialogColors,
+        settings: &Settings,
+        message: String,
+        text_style: TextStyle,
+        spawn_buttons: impl FnOnce(&mut ChildBuilder),
+    ) -> Entity {
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    // Cover the rest of the game rather than being laid out next to it
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: dialog_colors.overlay.clone(),
+                // The translucent overlay is costly to blend on weak machines
+                visible: Visible {
+                    is_visible: !settings.low_spec,
+                    is_transparent: true,
+                },
+                ..Default::default()
+            })
+            .insert(Dialog)
+            // Stops the mouse from reaching the buttons beneath the overlay
+            .insert(FocusPolicy::Block)
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            // Message on top, answers below
+                            flex_direction: FlexDirection::ColumnReverse,
+                            align_items: AlignItems::Center,
+                            padding: Rect::all(Val::Px(20.0)),
+                            ..Default::default()
+                        },
+                        material: dialog_colors.panel.clone(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::with_section(message, text_style, Default::default()),
+                            ..Default::default()
+                        });
+
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                material: dialog_colors.panel.clone(),
+                                ..Default::default()
+                            })
+                            .with_children(spawn_buttons);
+                    });
+            })
+            .id()
+    }
+
+    /// Spawns a labelled button that sends an event of the Marker type when pressed
+    fn spawn_dialog_button<Marker: Component>(
+        parent: &mut ChildBuilder,
+        marker: Marker,
+        materials: &ButtonMaterials<Marker>,
+        label: &str,
+        text_style: TextStyle,
+        default_focus: bool,
+    ) {
+        let mut button = parent.spawn_bundle(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(BUTTON_WIDTH), Val::Px(BUTTON_HEIGHT)),
+                // Padding between buttons
+                margin: Rect::all(Val::Px(5.0)),
+                // Horizontally center child text
+                justify_content: JustifyContent::Center,
+                // Vertically center child text
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            material: materials.normal.clone(),
+            ..Default::default()
+        });
+        button
+            .insert(marker)
+            .insert(NormalMaterial(materials.normal.clone()))
+            .insert(HoveredMaterial(materials.hovered.clone()))
+            .insert(PressedMaterial(materials.pressed.clone()))
+            .insert(Focusable);
+
+        if default_focus {
+            button.insert(DefaultFocus);
+        }
+
+        button.with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(label, text_style, Default::default()),
+                ..Default::default()
+            });
+        });
+    }
+
+    /// Marks the focused button with a bar along its bottom edge, and displays it as hovered,
+    /// so keyboard and gamepad players can see it
+    pub fn show_focus(
+        mut button_query: Query<
+            (
+                Entity,
+                &Interaction,
+                &mut Handle<ColorMaterial>,
+                &NormalMaterial,
+                &HoveredMaterial,
+            ),
+            With<Focusable>,
+        >,
+        bar_query: Query<(Entity, &Parent), With<FocusBar>>,
+        focus: Res<Focus>,
+        dialog_colors: Res<DialogColors>,
+        mut commands: Commands,
+    ) {
+        // The bar stays visible while the mouse is over another button, so keyboard players don't lose their place
+        let marked = bar_query.iter().next().map(|(_, parent)| parent.0);
+        if marked != focus.current {
+            for (entity, _) in bar_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            if let Some(entity) = focus
+                .current
+                .filter(|entity| button_query.get(*entity).is_ok())
+            {
+                commands.entity(entity).with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                // Laid over the bottom edge of the button, rather than beside its label
+                                position_type: PositionType::Absolute,
+                                position: Rect {
+                                    bottom: Val::Px(0.0),
+                                    left: Val::Px(0.0),
+                                    ..Default::default()
+                                },
+                                size: Size::new(Val::Percent(100.0), Val::Px(FOCUS_BAR_HEIGHT)),
+                                ..Default::default()
+                            },
+                            material: dialog_colors.focus.clone(),
+                            ..Default::default()
+                        })
+                        .insert(FocusBar);
+                });
+            }
+        }
+
+        for (entity, interaction, mut material, normal_material, hovered_material) in
+            button_query.iter_mut()
+        {
+            // Let the mouse take precedence
+            if *interaction != Interaction::None {
+                continue;
+            }
+
+            let focused_material = if focus.current == Some(entity) {
+                &hovered_material.0
+            } else {
+                &normal_material.0
+            };
+
+            if *material != *focused_material {
+                *material = focused_material.clone();
+            }
+        }
+    }
+
+    /// Closes open dialogs once they have been answered
+    pub fn close_dialogs(
+        mut new_puzzle_events: EventReader<NewPuzzle>,
+        mut bookmark_events: EventReader<BookmarkPuzzle>,
+        mut cancel_events: EventReader<CancelDialog>,
+        mut reset_events: EventReader<ResetPuzzle>,
+        mut remix_events: EventReader<RemixPuzzle>,
+        mut play_events: EventReader<PlayCollectionPuzzle>,
+        mut level_events: EventReader<PlayLevel>,
+        mut technique_events: EventReader<TrainTechnique>,
+        mut seed_events: EventReader<SeededPuzzle>,
+        mut profile_events: EventReader<ChooseProfile>,
+        mut create_profile_events: EventReader<CreateProfile>,
+        dialog_query: Query<Entity, With<Dialog>>,
+        mut commands: Commands,
+    ) {
+        // Every reader must be drained, so that stale events don't close future dialogs
+        // Only confirmed requests for a new puzzle, a reset or a remix answer the dialog
+        let confirmed = new_puzzle_events.iter().any(|event| event.confirmed);
+        let bookmarked = bookmark_events.iter().count() > 0;
+        let cancelled = cancel_events.iter().count() > 0;
+        let reset = reset_events.iter().any(|event| event.confirmed);
+        let remixed = remix_events.iter().any(|event| event.confirmed);
+        let played = play_events.iter().count() > 0
+            || level_events.iter().count() > 0
+            || technique_events.iter().count() > 0
+            || seed_events.iter().count() > 0;
+        let switched =
+            profile_events.iter().count() > 0 || create_profile_events.iter().count() > 0;
+
+        if confirmed || bookmarked || cancelled || reset || remixed || played || switched {
+            for entity in dialog_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
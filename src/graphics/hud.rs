@@ -0,0 +1,252 @@
+/// Build and display the status lines describing the player's progress
+use super::board::assets::FixedFont;
+use super::buttons::assets::NoneColor;
+use crate::{
+    localization::Localization,
+    logic::{
+        board::{completion, Cell, Fixed, Value},
+        mistakes::{Mistakes, MISTAKE_LIMIT},
+        records::ParTime,
+        sudoku_generation::{PuzzleGeneration, PuzzleSeed},
+        timer::format_duration,
+    },
+    settings::Settings,
+    CommonLabels,
+};
+use bevy::prelude::*;
+
+use self::config::*;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // SETUP
+            .add_startup_system(setup::spawn_hud.system())
+            // ACTIONS
+            .add_system_set(
+                SystemSet::new()
+                    .after(CommonLabels::Action)
+                    .with_system(actions::show_remaining_cells.system())
+                    .with_system(actions::show_mistakes.system())
+                    .with_system(actions::show_seed.system())
+                    .with_system(actions::show_par_time.system())
+                    .with_system(actions::show_generating.system()),
+            );
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+
+    pub const TEXT_COLOR: Color = Color::BLACK;
+    pub const FONT_SIZE: f32 = 20.0;
+    /// The distance between the status lines and the top left corner of the window
+    pub const MARGIN: f32 = 10.0;
+}
+
+pub mod setup {
+    use super::*;
+
+    /// Marker component for the node holding the status lines
+    pub struct Hud;
+    /// Marker component for the status line counting the cells left to fill
+    pub struct RemainingCellsLabel;
+    /// Marker component for the status line counting the player's mistakes
+    pub struct MistakesLabel;
+    /// Marker component for the status line showing the seed of the current puzzle
+    pub struct SeedLabel;
+    /// Marker component for the status line showing the par time of the current puzzle
+    pub struct ParTimeLabel;
+    /// Marker component for the status line shown while a new puzzle is being made
+    pub struct GeneratingLabel;
+
+    /// Spawns the status lines in the top left corner of the window
+    pub fn spawn_hud(mut commands: Commands, font: Res<FixedFont>, none_color: Res<NoneColor>) {
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    // Placed over the layout, rather than taking space from the board or buttons
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        top: Val::Px(MARGIN),
+                        left: Val::Px(MARGIN),
+                        ..Default::default()
+                    },
+                    // One status line under another
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..Default::default()
+                },
+                material: none_color.0.clone(),
+                ..Default::default()
+            })
+            .insert(Hud)
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        // This label is set in the show_remaining_cells system
+                        text: Text::with_section("", text_style.clone(), Default::default()),
+                        ..Default::default()
+                    })
+                    .insert(RemainingCellsLabel);
+
+                parent
+                    .spawn_bundle(TextBundle {
+                        // This label is set in the show_mistakes system
+                        text: Text::with_section("", text_style.clone(), Default::default()),
+                        ..Default::default()
+                    })
+                    .insert(MistakesLabel);
+
+                parent
+                    .spawn_bundle(TextBundle {
+                        // This label is set in the show_seed system
+                        text: Text::with_section("", text_style.clone(), Default::default()),
+                        ..Default::default()
+                    })
+                    .insert(SeedLabel);
+
+                parent
+                    .spawn_bundle(TextBundle {
+                        // This label is set in the show_par_time system
+                        text: Text::with_section("", text_style.clone(), Default::default()),
+                        ..Default::default()
+                    })
+                    .insert(ParTimeLabel);
+
+                parent
+                    .spawn_bundle(TextBundle {
+                        // This label is set in the show_generating system
+                        text: Text::with_section("", text_style.clone(), Default::default()),
+                        ..Default::default()
+                    })
+                    .insert(GeneratingLabel);
+            });
+    }
+}
+
+mod actions {
+    use super::setup::{
+        GeneratingLabel, MistakesLabel, ParTimeLabel, RemainingCellsLabel, SeedLabel,
+    };
+    use super::*;
+
+    /// Shows how many cells are left to fill, and how much of the puzzle is complete
+    pub fn show_remaining_cells(
+        cell_query: Query<(&Value, &Fixed), With<Cell>>,
+        changed_query: Query<(), (With<Cell>, Or<(Changed<Value>, Changed<Fixed>)>)>,
+        mut label_query: Query<&mut Text, With<RemainingCellsLabel>>,
+        localization: Res<Localization>,
+    ) {
+        if changed_query.iter().next().is_none() && !localization.is_changed() {
+            return;
+        }
+
+        let n_remaining = cell_query
+            .iter()
+            .filter(|(value, _)| !matches!(value, Value::Filled(_)))
+            .count();
+        let percent_complete = (completion(cell_query.iter()) * 100.0).round() as u32;
+
+        for mut text in label_query.iter_mut() {
+            text.sections[0].value = localization.format(
+                "hud-remaining-cells",
+                &[
+                    ("cells", n_remaining.into()),
+                    ("percent", percent_complete.into()),
+                ],
+            );
+        }
+    }
+
+    /// Shows how many mistakes have been made, out of the limit in limited-mistakes mode
+    pub fn show_mistakes(
+        mistakes: Res<Mistakes>,
+        settings: Res<Settings>,
+        mut label_query: Query<&mut Text, With<MistakesLabel>>,
+        localization: Res<Localization>,
+    ) {
+        if !mistakes.is_changed() && !settings.is_changed() && !localization.is_changed() {
+            return;
+        }
+
+        for mut text in label_query.iter_mut() {
+            text.sections[0].value = if settings.limited_mistakes {
+                localization.format(
+                    "hud-limited-mistakes",
+                    &[
+                        ("mistakes", mistakes.count.into()),
+                        ("limit", MISTAKE_LIMIT.into()),
+                    ],
+                )
+            } else {
+                localization.format("hud-mistakes", &[("mistakes", mistakes.count.into())])
+            };
+        }
+    }
+
+    /// Shows the seed of the current puzzle, so it can be shared with other players
+    pub fn show_seed(
+        puzzle_seed: Res<PuzzleSeed>,
+        mut label_query: Query<&mut Text, With<SeedLabel>>,
+        localization: Res<Localization>,
+    ) {
+        if !puzzle_seed.is_changed() && !localization.is_changed() {
+            return;
+        }
+
+        for mut text in label_query.iter_mut() {
+            text.sections[0].value = match puzzle_seed.0 {
+                // Passed as text, so that the seed is never written with digit separators
+                Some(seed) => localization.format("hud-seed", &[("seed", seed.to_string().into())]),
+                None => "".to_string(),
+            };
+        }
+    }
+
+    /// Shows the par time of the current puzzle, so the player knows what to aim for before starting
+    pub fn show_par_time(
+        par_time: Res<ParTime>,
+        mut label_query: Query<&mut Text, With<ParTimeLabel>>,
+        localization: Res<Localization>,
+    ) {
+        if !par_time.is_changed() && !localization.is_changed() {
+            return;
+        }
+
+        for mut text in label_query.iter_mut() {
+            text.sections[0].value = match par_time.0 {
+                Some(par) => {
+                    localization.format("hud-par-time", &[("time", format_duration(par).into())])
+                }
+                None => "".to_string(),
+            };
+        }
+    }
+
+    /// Shows that a new puzzle is being made in the background, which takes a few seconds on the largest boards
+    pub fn show_generating(
+        generation: Res<PuzzleGeneration>,
+        mut label_query: Query<&mut Text, With<GeneratingLabel>>,
+        localization: Res<Localization>,
+    ) {
+        if !generation.is_changed() && !localization.is_changed() {
+            return;
+        }
+
+        for mut text in label_query.iter_mut() {
+            text.sections[0].value = if generation.is_running() {
+                localization.get("hud-generating")
+            } else {
+                "".to_string()
+            };
+        }
+    }
+}
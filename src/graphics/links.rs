@@ -0,0 +1,133 @@
+/// Draw the graph of links for the chosen digit over the board
+use crate::logic::{
+    board::{Cell, Coordinates},
+    links::LinkGraph,
+};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use self::assets::*;
+use self::config::*;
+
+pub struct LinkDisplayPlugin;
+
+impl Plugin for LinkDisplayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // ASSETS
+            .init_resource::<LinkMaterials>()
+            // ACTIONS
+            .add_system(actions::draw_link_graph.system());
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+
+    pub const NODE_COLOR: Color = Color::rgb(0.55, 0.2, 0.7);
+    pub const STRONG_LINK_COLOR: Color = Color::rgb(0.55, 0.2, 0.7);
+    pub const WEAK_LINK_COLOR: Color = Color::rgba(0.55, 0.2, 0.7, 0.35);
+
+    /// The width and height of the marker drawn on each cell that could hold the digit
+    pub const NODE_SIZE: f32 = 10.0;
+    pub const STRONG_LINK_THICKNESS: f32 = 4.0;
+    pub const WEAK_LINK_THICKNESS: f32 = 2.0;
+    /// The graph is drawn over the cells, their digits and the grid
+    pub const LINK_Z: f32 = 4.0;
+    pub const NODE_Z: f32 = 5.0;
+}
+
+mod assets {
+    use super::*;
+
+    /// The materials used to draw the nodes and links of the graph
+    pub struct LinkMaterials {
+        pub node: Handle<ColorMaterial>,
+        pub strong: Handle<ColorMaterial>,
+        pub weak: Handle<ColorMaterial>,
+    }
+
+    impl FromWorld for LinkMaterials {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            LinkMaterials {
+                node: materials.add(NODE_COLOR.into()),
+                strong: materials.add(STRONG_LINK_COLOR.into()),
+                weak: materials.add(WEAK_LINK_COLOR.into()),
+            }
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Marker component for the sprites making up the link graph
+    pub struct LinkSprite;
+
+    /// Redraws the link graph from scratch whenever it changes
+    ///
+    /// Strong links are drawn thick and solid, and weak links thin and faded
+    pub fn draw_link_graph(
+        link_graph: Res<LinkGraph>,
+        cell_query: Query<(&Coordinates, &Transform), With<Cell>>,
+        sprite_query: Query<Entity, With<LinkSprite>>,
+        materials: Res<LinkMaterials>,
+        mut commands: Commands,
+    ) {
+        if !link_graph.is_changed() {
+            return;
+        }
+
+        for entity in sprite_query.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        let centers: HashMap<&Coordinates, Vec2> = cell_query
+            .iter()
+            .map(|(coordinates, transform)| (coordinates, transform.translation.truncate()))
+            .collect();
+
+        for coordinates in link_graph.nodes.iter() {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite::new(Vec2::new(NODE_SIZE, NODE_SIZE)),
+                    transform: Transform::from_translation(centers[coordinates].extend(NODE_Z)),
+                    material: materials.node.clone(),
+                    ..Default::default()
+                })
+                .insert(LinkSprite);
+        }
+
+        for link in link_graph.links.iter() {
+            let (from, to) = (centers[&link.from], centers[&link.to]);
+            let offset = to - from;
+            let (thickness, material) = if link.strong {
+                (STRONG_LINK_THICKNESS, materials.strong.clone())
+            } else {
+                (WEAK_LINK_THICKNESS, materials.weak.clone())
+            };
+
+            // Each link is a thin sprite centered between its cells, turned to point from one to the other
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite::new(Vec2::new(offset.length(), thickness)),
+                    transform: Transform {
+                        translation: (from + 0.5 * offset).extend(LINK_Z),
+                        rotation: Quat::from_rotation_z(offset.y.atan2(offset.x)),
+                        ..Default::default()
+                    },
+                    material,
+                    // Weak links are translucent
+                    visible: Visible {
+                        is_visible: true,
+                        is_transparent: true,
+                    },
+                    ..Default::default()
+                })
+                .insert(LinkSprite);
+        }
+    }
+}
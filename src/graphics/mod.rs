@@ -1,7 +1,18 @@
 /// Display the Sudoku game
 pub mod board;
 pub mod buttons;
+pub mod celebration;
+pub mod dialog;
+pub mod hud;
+pub mod links;
+pub mod rules;
+pub mod setter;
+pub mod toasts;
+pub mod tooltips;
+pub mod trainer;
+pub mod tutorial;
 
+use crate::settings::Settings;
 use bevy::prelude::*;
 
 pub const BACKGROUND_COLOR: Color = Color::rgb(1.0, 1.0, 1.0);
@@ -20,3 +31,21 @@ pub fn spawn_cameras(mut commands: Commands) {
         .spawn_bundle(UiCameraBundle::default())
         .insert(UiCamera);
 }
+
+/// Rounds the UI scale to a whole number in low-spec mode,
+/// avoiding the cost and blurriness of fractional scaling on weak machines
+pub fn integer_scaling(settings: Res<Settings>, mut windows: ResMut<Windows>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let window = windows
+        .get_primary_mut()
+        .expect("Primary window not found.");
+    let scale_factor_override = if settings.low_spec {
+        Some(window.backend_scale_factor().round().max(1.0))
+    } else {
+        None
+    };
+    window.set_scale_factor_override(scale_factor_override);
+}
@@ -0,0 +1,223 @@
+/// Show the rules of variant puzzles, in a panel the player can fold away
+///
+/// The rules are written out from the puzzle's constraints, after any rules given by its author
+use super::board::assets::FixedFont;
+use super::buttons::assets::{ButtonMaterials, HoveredMaterial, NormalMaterial, PressedMaterial};
+use super::tooltips::Tooltip;
+use crate::{
+    input::buttons::ToggleRules,
+    localization::Localization,
+    logic::{
+        constraints::ConstraintSet,
+        sudoku_generation::PuzzleMetadata,
+        xv::{V_SUM, X_SUM},
+    },
+    CommonLabels,
+};
+use bevy::prelude::*;
+use std::marker::PhantomData;
+
+use self::assets::*;
+use self::config::*;
+
+pub struct RulesDisplayPlugin;
+
+impl Plugin for RulesDisplayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<RulesShown>()
+            // ASSETS
+            .init_resource::<RulesPanelColor>()
+            .init_resource::<ButtonMaterials<ToggleRules>>()
+            // ACTIONS
+            .add_system(actions::toggle_rules.system().label(CommonLabels::Action))
+            .add_system(actions::show_rules.system().after(CommonLabels::Action));
+    }
+}
+
+/// Resource storing whether the rules are written out, or folded away under the panel's title
+pub struct RulesShown(pub bool);
+
+impl Default for RulesShown {
+    fn default() -> Self {
+        RulesShown(true)
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+
+    pub const PANEL_COLOR: Color = Color::rgb(0.92, 0.92, 0.92);
+    pub const TEXT_COLOR: Color = Color::BLACK;
+    pub const FONT_SIZE: f32 = 18.0;
+    /// The distance between the panel and the top right corner of the window
+    pub const MARGIN: f32 = 10.0;
+    pub const PANEL_WIDTH: f32 = 320.0;
+    pub const BUTTON_HEIGHT: f32 = 32.0;
+}
+
+mod assets {
+    use super::*;
+
+    /// The background of the rules panel
+    pub struct RulesPanelColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for RulesPanelColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            RulesPanelColor(materials.add(PANEL_COLOR.into()))
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<ToggleRules> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.8, 0.8, 0.8).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Marker component for the panel showing the rules of the puzzle
+    pub struct RulesPanel;
+
+    /// Folds the rules away under the panel's title, or writes them out again
+    pub fn toggle_rules(
+        mut event_reader: EventReader<ToggleRules>,
+        mut rules_shown: ResMut<RulesShown>,
+    ) {
+        for _ in event_reader.iter() {
+            rules_shown.0 = !rules_shown.0;
+        }
+    }
+
+    /// The rules of the puzzle, one sentence to a line, starting with any given by its author
+    fn rules_text(
+        constraints: &ConstraintSet,
+        metadata: &PuzzleMetadata,
+        localization: &Localization,
+    ) -> String {
+        let mut lines: Vec<String> = metadata.rules.iter().cloned().collect();
+        // The sums of XV pairs are the only values the rules need
+        let args = [("x", X_SUM.into()), ("v", V_SUM.into())];
+        lines.extend(
+            constraints
+                .rules_ids()
+                .into_iter()
+                .map(|id| localization.format(id, &args)),
+        );
+        lines.join("\n")
+    }
+
+    /// Replaces the panel whenever the puzzle, its rules or the language changes,
+    /// removing it for puzzles that follow the rules of classic sudoku alone
+    pub fn show_rules(
+        constraints: Res<ConstraintSet>,
+        metadata: Res<PuzzleMetadata>,
+        rules_shown: Res<RulesShown>,
+        panel_query: Query<Entity, With<RulesPanel>>,
+        mut commands: Commands,
+        panel_color: Res<RulesPanelColor>,
+        button_materials: Res<ButtonMaterials<ToggleRules>>,
+        font: Res<FixedFont>,
+        localization: Res<Localization>,
+    ) {
+        if !constraints.is_changed()
+            && !metadata.is_changed()
+            && !rules_shown.is_changed()
+            && !localization.is_changed()
+        {
+            return;
+        }
+        for entity in panel_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        if !constraints.is_variant() {
+            return;
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    // Placed over the layout, rather than taking space from the board or buttons
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        top: Val::Px(MARGIN),
+                        right: Val::Px(MARGIN),
+                        ..Default::default()
+                    },
+                    size: Size::new(Val::Px(PANEL_WIDTH), Val::Auto),
+                    // The title above the rules
+                    flex_direction: FlexDirection::ColumnReverse,
+                    padding: Rect::all(Val::Px(10.0)),
+                    ..Default::default()
+                },
+                material: panel_color.0.clone(),
+                ..Default::default()
+            })
+            .insert(RulesPanel)
+            .with_children(|parent| {
+                // This button isn't focusable, so that the keyboard keeps controlling the board
+                parent
+                    .spawn_bundle(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(100.0), Val::Px(BUTTON_HEIGHT)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        material: button_materials.normal.clone(),
+                        ..Default::default()
+                    })
+                    .insert(ToggleRules)
+                    .insert(NormalMaterial(button_materials.normal.clone()))
+                    .insert(HoveredMaterial(button_materials.hovered.clone()))
+                    .insert(PressedMaterial(button_materials.pressed.clone()))
+                    .insert(Tooltip::new("tooltip-toggle-rules"))
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                localization.get("rules-title"),
+                                text_style.clone(),
+                                Default::default(),
+                            ),
+                            ..Default::default()
+                        });
+                    });
+
+                if rules_shown.0 {
+                    parent.spawn_bundle(TextBundle {
+                        style: Style {
+                            margin: Rect {
+                                top: Val::Px(10.0),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        text: Text::with_section(
+                            rules_text(&*constraints, &*metadata, &*localization),
+                            text_style,
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    });
+                }
+            });
+    }
+}
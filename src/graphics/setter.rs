@@ -0,0 +1,250 @@
+/// Show how many solutions a puzzle being set has, warning when it has none or several,
+/// along with the progress of the minimizer and the buttons to minimize the puzzle and lock it in
+use super::board::assets::FixedFont;
+use super::buttons::assets::{ButtonMaterials, HoveredMaterial, NormalMaterial, PressedMaterial};
+use super::tooltips::Tooltip;
+use crate::{
+    input::buttons::{LockPuzzle, MinimizePuzzle},
+    localization::Localization,
+    logic::setter::{Setter, SOLUTION_LIMIT},
+    CommonLabels,
+};
+use bevy::{ecs::component::Component, prelude::*};
+use std::marker::PhantomData;
+
+use self::assets::*;
+use self::config::*;
+
+pub struct SetterDisplayPlugin;
+
+impl Plugin for SetterDisplayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // ASSETS
+            .init_resource::<SetterPanelColor>()
+            .init_resource::<ButtonMaterials<LockPuzzle>>()
+            .init_resource::<ButtonMaterials<MinimizePuzzle>>()
+            // ACTIONS
+            .add_system(
+                actions::show_setter_panel
+                    .system()
+                    .after(CommonLabels::Action),
+            );
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+
+    pub const PANEL_COLOR: Color = Color::rgb(0.95, 0.9, 0.8);
+    pub const TEXT_COLOR: Color = Color::BLACK;
+    /// The color of the solution count when the puzzle doesn't have exactly one solution
+    pub const WARNING_COLOR: Color = Color::rgb(0.75, 0.1, 0.1);
+    pub const FONT_SIZE: f32 = 20.0;
+    /// The distance between the panel and the bottom left corner of the window
+    pub const MARGIN: f32 = 10.0;
+    pub const BUTTON_WIDTH: f32 = 120.0;
+    pub const BUTTON_HEIGHT: f32 = 32.0;
+}
+
+mod assets {
+    use super::*;
+
+    /// The background of the setter panel
+    pub struct SetterPanelColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for SetterPanelColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            SetterPanelColor(materials.add(PANEL_COLOR.into()))
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<MinimizePuzzle> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.8, 0.8, 0.8).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<LockPuzzle> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Marker component for the panel shown while a puzzle is being set
+    pub struct SetterPanel;
+
+    /// Replaces the panel whenever the solution count, the minimizer's progress or the language changes,
+    /// removing it once the puzzle is locked in
+    pub fn show_setter_panel(
+        setter: Res<Setter>,
+        panel_query: Query<Entity, With<SetterPanel>>,
+        mut commands: Commands,
+        panel_color: Res<SetterPanelColor>,
+        lock_button_materials: Res<ButtonMaterials<LockPuzzle>>,
+        minimize_button_materials: Res<ButtonMaterials<MinimizePuzzle>>,
+        font: Res<FixedFont>,
+        localization: Res<Localization>,
+    ) {
+        if !setter.is_changed() && !localization.is_changed() {
+            return;
+        }
+        for entity in panel_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        if !setter.active {
+            return;
+        }
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        let (status, warning) = match (&setter.minimization, setter.solutions) {
+            (Some(minimization), _) => (
+                localization.format(
+                    "setter-minimizing",
+                    &[
+                        ("checked", minimization.checked().into()),
+                        ("total", minimization.total.into()),
+                        ("removed", minimization.removed.into()),
+                    ],
+                ),
+                false,
+            ),
+            (None, None) => (localization.get("setter-counting"), false),
+            (None, Some(None)) => (localization.get("setter-undetermined"), true),
+            (None, Some(Some(0))) => (localization.get("setter-no-solutions"), true),
+            (None, Some(Some(1))) => (localization.get("setter-unique"), false),
+            (None, Some(Some(count))) if count >= SOLUTION_LIMIT => (
+                localization.format("setter-solution-limit", &[("count", count.into())]),
+                true,
+            ),
+            (None, Some(Some(count))) => (
+                localization.format("setter-multiple-solutions", &[("count", count.into())]),
+                true,
+            ),
+        };
+        let text = Text {
+            sections: vec![
+                TextSection {
+                    value: format!("{}\n", localization.get("setter-instructions")),
+                    style: text_style.clone(),
+                },
+                TextSection {
+                    value: status,
+                    style: TextStyle {
+                        color: if warning { WARNING_COLOR } else { TEXT_COLOR },
+                        ..text_style.clone()
+                    },
+                },
+            ],
+            ..Default::default()
+        };
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    // Placed over the layout, rather than taking space from the board or buttons
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        bottom: Val::Px(MARGIN),
+                        left: Val::Px(MARGIN),
+                        ..Default::default()
+                    },
+                    align_items: AlignItems::Center,
+                    padding: Rect::all(Val::Px(10.0)),
+                    ..Default::default()
+                },
+                material: panel_color.0.clone(),
+                ..Default::default()
+            })
+            .insert(SetterPanel)
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text,
+                    ..Default::default()
+                });
+
+                if setter.minimization.is_none() {
+                    spawn_panel_button(
+                        parent,
+                        MinimizePuzzle,
+                        &*minimize_button_materials,
+                        &localization.get("setter-minimize"),
+                        Tooltip::new("tooltip-minimize"),
+                        text_style.clone(),
+                    );
+                }
+                spawn_panel_button(
+                    parent,
+                    LockPuzzle,
+                    &*lock_button_materials,
+                    &localization.get("setter-lock"),
+                    Tooltip::new("tooltip-lock"),
+                    text_style,
+                );
+            });
+    }
+
+    /// Spawns a labelled button that sends an event of the Marker type when pressed
+    ///
+    /// These buttons aren't focusable, so that the keyboard keeps controlling the board
+    fn spawn_panel_button<Marker: Component>(
+        parent: &mut ChildBuilder,
+        marker: Marker,
+        materials: &ButtonMaterials<Marker>,
+        label: &str,
+        tooltip: Tooltip,
+        text_style: TextStyle,
+    ) {
+        parent
+            .spawn_bundle(ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(BUTTON_WIDTH), Val::Px(BUTTON_HEIGHT)),
+                    margin: Rect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: materials.normal.clone(),
+                ..Default::default()
+            })
+            .insert(marker)
+            .insert(NormalMaterial(materials.normal.clone()))
+            .insert(HoveredMaterial(materials.hovered.clone()))
+            .insert(PressedMaterial(materials.pressed.clone()))
+            .insert(tooltip)
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(label, text_style, Default::default()),
+                    ..Default::default()
+                });
+            });
+    }
+}
@@ -0,0 +1,173 @@
+/// Show short messages that slide in from the edge of the window and dismiss themselves
+///
+/// Any system can queue a message with the `Toasts` resource, and they are shown one at a time
+use super::board::assets::FixedFont;
+use crate::{settings::Settings, CommonLabels};
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use self::assets::*;
+use self::config::*;
+
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Toasts>()
+            // ASSETS
+            .init_resource::<ToastPanelColor>()
+            // ACTIONS
+            .add_system_set(
+                SystemSet::new()
+                    .after(CommonLabels::Action)
+                    .with_system(actions::show_next_toast.system())
+                    .with_system(actions::animate_toasts.system()),
+            );
+    }
+}
+
+/// Resource queueing the messages to show the player, in the order they were sent
+#[derive(Default)]
+pub struct Toasts(VecDeque<String>);
+
+impl Toasts {
+    /// Queues a message to be shown once the messages before it have been dismissed
+    ///
+    /// A message identical to the last one queued is dropped, so that repeated actions don't pile up
+    pub fn push(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        if self.0.back() != Some(&message) {
+            self.0.push_back(message);
+        }
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+    use std::time::Duration;
+
+    pub const PANEL_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+    pub const TEXT_COLOR: Color = Color::WHITE;
+    pub const FONT_SIZE: f32 = 20.0;
+    /// The distance between the toast and the top right corner of the window
+    pub const MARGIN: f32 = 10.0;
+    pub const TOAST_WIDTH: f32 = 320.0;
+    /// How long each toast stays on screen, including the time spent sliding in and out
+    pub const TOAST_DURATION: Duration = Duration::from_secs(3);
+    pub const SLIDE_DURATION: Duration = Duration::from_millis(250);
+}
+
+mod assets {
+    use super::*;
+
+    /// The background of the toasts
+    pub struct ToastPanelColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for ToastPanelColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ToastPanelColor(materials.add(PANEL_COLOR.into()))
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Component for the toast being shown, storing how long it has been on screen
+    #[derive(Default)]
+    pub struct Toast {
+        age: Duration,
+    }
+
+    /// How far the toast has slid into the window, from 0 when hidden to 1 when fully shown
+    fn slide_progress(age: Duration) -> f32 {
+        let slide = SLIDE_DURATION.as_secs_f32();
+        let shown_in = age.as_secs_f32() / slide;
+        let hidden_in = TOAST_DURATION.saturating_sub(age).as_secs_f32() / slide;
+        shown_in.min(hidden_in).min(1.0)
+    }
+
+    /// The offset from the right edge of the window, starting just outside it
+    ///
+    /// When animations are turned off, the toast appears and disappears in place instead of sliding
+    fn right_offset(age: Duration, settings: &Settings) -> Val {
+        if !settings.animated() {
+            return Val::Px(MARGIN);
+        }
+
+        let hidden = -TOAST_WIDTH;
+        Val::Px(hidden + (MARGIN - hidden) * slide_progress(age))
+    }
+
+    /// Shows the next queued message once the previous toast has been dismissed
+    pub fn show_next_toast(
+        mut toasts: ResMut<Toasts>,
+        toast_query: Query<(), With<Toast>>,
+        mut commands: Commands,
+        panel_color: Res<ToastPanelColor>,
+        font: Res<FixedFont>,
+        settings: Res<Settings>,
+    ) {
+        if toast_query.iter().next().is_some() {
+            return;
+        }
+        let message = match toasts.0.pop_front() {
+            Some(message) => message,
+            None => return,
+        };
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    // Placed over the layout, rather than taking space from the board or buttons
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        top: Val::Px(MARGIN),
+                        right: right_offset(Duration::default(), &*settings),
+                        ..Default::default()
+                    },
+                    size: Size::new(Val::Px(TOAST_WIDTH), Val::Auto),
+                    padding: Rect::all(Val::Px(10.0)),
+                    ..Default::default()
+                },
+                material: panel_color.0.clone(),
+                ..Default::default()
+            })
+            .insert(Toast::default())
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        message,
+                        TextStyle {
+                            font: font.0.clone(),
+                            font_size: FONT_SIZE,
+                            color: TEXT_COLOR,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                });
+            });
+    }
+
+    /// Slides the toast in, then back out once it has been shown long enough, removing it afterwards
+    pub fn animate_toasts(
+        time: Res<Time>,
+        mut toast_query: Query<(Entity, &mut Toast, &mut Style)>,
+        settings: Res<Settings>,
+        mut commands: Commands,
+    ) {
+        for (entity, mut toast, mut style) in toast_query.iter_mut() {
+            toast.age += time.delta();
+            if toast.age >= TOAST_DURATION {
+                commands.entity(entity).despawn_recursive();
+            } else {
+                style.position.right = right_offset(toast.age, &*settings);
+            }
+        }
+    }
+}
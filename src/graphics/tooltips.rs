@@ -0,0 +1,175 @@
+/// Name what each side panel button does, and its keyboard shortcut, when the mouse rests on it
+use super::board::assets::FixedFont;
+use crate::localization::Localization;
+use crate::logic::grid::digit_symbol;
+use crate::CommonLabels;
+use bevy::prelude::*;
+use std::time::Duration;
+
+use self::assets::*;
+use self::config::*;
+
+pub struct TooltipPlugin;
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // ASSETS
+            .init_resource::<TooltipColor>()
+            // ACTIONS
+            .add_system(actions::show_tooltips.system().after(CommonLabels::Action));
+    }
+}
+
+/// Component for buttons that explain themselves when hovered over
+pub struct Tooltip {
+    /// The id of the message to show
+    message: &'static str,
+    /// The digit the button enters, which is filled into the message
+    digit: Option<u8>,
+}
+
+impl Tooltip {
+    pub fn new(message: &'static str) -> Self {
+        Tooltip {
+            message,
+            digit: None,
+        }
+    }
+
+    pub fn with_digit(message: &'static str, digit: u8) -> Self {
+        Tooltip {
+            message,
+            digit: Some(digit),
+        }
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+    use std::time::Duration;
+
+    pub const TOOLTIP_COLOR: Color = Color::rgb(1.0, 1.0, 0.85);
+    pub const TEXT_COLOR: Color = Color::BLACK;
+    pub const FONT_SIZE: f32 = 18.0;
+    /// How long the mouse must rest on a button before its tooltip is shown
+    pub const TOOLTIP_DELAY: Duration = Duration::from_millis(600);
+    /// The distance between the mouse and the tooltip
+    pub const CURSOR_OFFSET: f32 = 12.0;
+}
+
+mod assets {
+    use super::*;
+
+    /// The background of the tooltips
+    pub struct TooltipColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for TooltipColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            TooltipColor(materials.add(TOOLTIP_COLOR.into()))
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Marker component for the tooltip being shown
+    pub struct TooltipPanel;
+
+    /// The button the mouse is resting on, how long it has been there, and whether its tooltip is shown
+    #[derive(Default)]
+    pub struct Hover {
+        button: Option<Entity>,
+        duration: Duration,
+        shown: bool,
+    }
+
+    /// Shows the tooltip of the hovered button once the mouse has rested on it for a moment,
+    /// removing it as soon as the mouse moves off or the button is pressed
+    pub fn show_tooltips(
+        time: Res<Time>,
+        windows: Res<Windows>,
+        button_query: Query<(Entity, &Interaction, &Tooltip)>,
+        tooltip_query: Query<Entity, With<TooltipPanel>>,
+        mut hover: Local<Hover>,
+        mut commands: Commands,
+        tooltip_color: Res<TooltipColor>,
+        font: Res<FixedFont>,
+        localization: Res<Localization>,
+    ) {
+        let hovered = button_query
+            .iter()
+            .find(|(_, interaction, _)| **interaction == Interaction::Hovered);
+        let hovered_button = hovered.map(|(entity, _, _)| entity);
+
+        if hovered_button != hover.button {
+            for entity in tooltip_query.iter() {
+                commands.entity(entity).despawn_recursive();
+            }
+            *hover = Hover {
+                button: hovered_button,
+                ..Default::default()
+            };
+        }
+
+        let tooltip = match hovered {
+            Some((_, _, tooltip)) if !hover.shown => tooltip,
+            _ => return,
+        };
+        hover.duration += time.delta();
+        if hover.duration < TOOLTIP_DELAY {
+            return;
+        }
+
+        let window = windows.get_primary().expect("Primary window not found.");
+        let cursor = match window.cursor_position() {
+            Some(cursor) => cursor,
+            None => return,
+        };
+        hover.shown = true;
+
+        let message = match tooltip.digit {
+            Some(digit) => localization.format(
+                tooltip.message,
+                &[("digit", digit_symbol(digit).to_string().into())],
+            ),
+            None => localization.get(tooltip.message),
+        };
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    // Above and to the left of the mouse, as the side panel runs to the right edge of the window
+                    position: Rect {
+                        right: Val::Px(window.width() - cursor.x + CURSOR_OFFSET),
+                        bottom: Val::Px(cursor.y + CURSOR_OFFSET),
+                        ..Default::default()
+                    },
+                    padding: Rect::all(Val::Px(5.0)),
+                    ..Default::default()
+                },
+                material: tooltip_color.0.clone(),
+                ..Default::default()
+            })
+            .insert(TooltipPanel)
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        message,
+                        TextStyle {
+                            font: font.0.clone(),
+                            font_size: FONT_SIZE,
+                            color: TEXT_COLOR,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                });
+            });
+    }
+}
@@ -0,0 +1,205 @@
+/// Show the technique trainer's coaching while a practice puzzle is played
+use super::board::assets::FixedFont;
+use super::buttons::assets::{ButtonMaterials, HoveredMaterial, NormalMaterial, PressedMaterial};
+use super::tooltips::Tooltip;
+use crate::{
+    input::buttons::{CoachPlayer, StopTraining},
+    localization::Localization,
+    trainer::{Trainer, MAX_COACHING},
+    CommonLabels,
+};
+use bevy::{ecs::component::Component, prelude::*};
+use std::marker::PhantomData;
+
+use self::assets::*;
+use self::config::*;
+
+pub struct TrainerDisplayPlugin;
+
+impl Plugin for TrainerDisplayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // ASSETS
+            .init_resource::<CoachingPanelColor>()
+            .init_resource::<ButtonMaterials<CoachPlayer>>()
+            .init_resource::<ButtonMaterials<StopTraining>>()
+            // ACTIONS
+            .add_system(actions::show_coaching.system().after(CommonLabels::Action));
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+
+    pub const PANEL_COLOR: Color = Color::rgb(0.85, 0.95, 0.85);
+    pub const TEXT_COLOR: Color = Color::BLACK;
+    pub const FONT_SIZE: f32 = 20.0;
+    /// The distance between the coaching and the bottom left corner of the window
+    pub const MARGIN: f32 = 10.0;
+    pub const BUTTON_WIDTH: f32 = 80.0;
+    pub const BUTTON_HEIGHT: f32 = 32.0;
+}
+
+mod assets {
+    use super::*;
+
+    /// The background of the coaching panel
+    pub struct CoachingPanelColor(pub Handle<ColorMaterial>);
+
+    impl FromWorld for CoachingPanelColor {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            CoachingPanelColor(materials.add(PANEL_COLOR.into()))
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<CoachPlayer> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.6, 0.8, 1.0).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<StopTraining> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.8, 0.8, 0.8).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Marker component for the panel showing the trainer's coaching
+    pub struct CoachingPanel;
+
+    /// Replaces the coaching whenever the practice session or language changes, removing it once the session is over
+    pub fn show_coaching(
+        trainer: Res<Trainer>,
+        panel_query: Query<Entity, With<CoachingPanel>>,
+        mut commands: Commands,
+        panel_color: Res<CoachingPanelColor>,
+        coach_button_materials: Res<ButtonMaterials<CoachPlayer>>,
+        stop_button_materials: Res<ButtonMaterials<StopTraining>>,
+        font: Res<FixedFont>,
+        localization: Res<Localization>,
+    ) {
+        if !trainer.is_changed() && !localization.is_changed() {
+            return;
+        }
+        for entity in panel_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        let session = match &trainer.session {
+            Some(session) => session,
+            None => return,
+        };
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    // Placed over the layout, rather than taking space from the board or buttons
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        bottom: Val::Px(MARGIN),
+                        left: Val::Px(MARGIN),
+                        ..Default::default()
+                    },
+                    align_items: AlignItems::Center,
+                    padding: Rect::all(Val::Px(10.0)),
+                    ..Default::default()
+                },
+                material: panel_color.0.clone(),
+                ..Default::default()
+            })
+            .insert(CoachingPanel)
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        session.message(&*localization),
+                        text_style.clone(),
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                });
+
+                if !session.completed && session.coaching < MAX_COACHING {
+                    spawn_panel_button(
+                        parent,
+                        CoachPlayer,
+                        &*coach_button_materials,
+                        &localization.get("trainer-help"),
+                        Tooltip::new("tooltip-coach"),
+                        text_style.clone(),
+                    );
+                }
+                spawn_panel_button(
+                    parent,
+                    StopTraining,
+                    &*stop_button_materials,
+                    &localization.get("trainer-done"),
+                    Tooltip::new("tooltip-stop-training"),
+                    text_style,
+                );
+            });
+    }
+
+    /// Spawns a labelled button that sends an event of the Marker type when pressed
+    ///
+    /// These buttons aren't focusable, so that the keyboard keeps controlling the board
+    fn spawn_panel_button<Marker: Component>(
+        parent: &mut ChildBuilder,
+        marker: Marker,
+        materials: &ButtonMaterials<Marker>,
+        label: &str,
+        tooltip: Tooltip,
+        text_style: TextStyle,
+    ) {
+        parent
+            .spawn_bundle(ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(BUTTON_WIDTH), Val::Px(BUTTON_HEIGHT)),
+                    margin: Rect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                material: materials.normal.clone(),
+                ..Default::default()
+            })
+            .insert(marker)
+            .insert(NormalMaterial(materials.normal.clone()))
+            .insert(HoveredMaterial(materials.hovered.clone()))
+            .insert(PressedMaterial(materials.pressed.clone()))
+            .insert(tooltip)
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(label, text_style, Default::default()),
+                    ..Default::default()
+                });
+            });
+    }
+}
@@ -0,0 +1,239 @@
+/// Show the tutorial's instructions, and highlight the buttons it asks the player to press
+use super::board::assets::FixedFont;
+use super::buttons::assets::{ButtonMaterials, HoveredMaterial, NormalMaterial, PressedMaterial};
+use super::tooltips::Tooltip;
+use crate::{
+    input::{
+        buttons::{GetHint, SkipTutorial},
+        input_mode::InputMode,
+    },
+    localization::LocalizedText,
+    tutorial::{Tutorial, TutorialStep},
+    CommonLabels,
+};
+use bevy::prelude::*;
+use std::marker::PhantomData;
+
+use self::assets::*;
+use self::config::*;
+
+pub struct TutorialDisplayPlugin;
+
+impl Plugin for TutorialDisplayPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app
+            // ASSETS
+            .init_resource::<TutorialMaterials>()
+            .init_resource::<ButtonMaterials<SkipTutorial>>()
+            // ACTIONS
+            .add_system_set(
+                SystemSet::new()
+                    .after(CommonLabels::Action)
+                    .with_system(actions::show_instructions.system())
+                    .with_system(actions::highlight_buttons.system()),
+            );
+    }
+}
+
+mod config {
+    use bevy::prelude::*;
+
+    pub const PANEL_COLOR: Color = Color::rgb(0.95, 0.95, 0.8);
+    /// The color of the button the current step asks the player to press
+    pub const HIGHLIGHT_COLOR: Color = Color::rgb(1.0, 0.8, 0.3);
+    pub const TEXT_COLOR: Color = Color::BLACK;
+    pub const FONT_SIZE: f32 = 20.0;
+    /// The distance between the instructions and the bottom left corner of the window
+    pub const MARGIN: f32 = 10.0;
+    pub const SKIP_BUTTON_WIDTH: f32 = 80.0;
+    pub const SKIP_BUTTON_HEIGHT: f32 = 32.0;
+}
+
+mod assets {
+    use super::*;
+
+    /// The materials of the instructions panel and the highlighted buttons
+    pub struct TutorialMaterials {
+        pub panel: Handle<ColorMaterial>,
+        pub highlight: Handle<ColorMaterial>,
+    }
+
+    impl FromWorld for TutorialMaterials {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            TutorialMaterials {
+                panel: materials.add(PANEL_COLOR.into()),
+                highlight: materials.add(HIGHLIGHT_COLOR.into()),
+            }
+        }
+    }
+
+    impl FromWorld for ButtonMaterials<SkipTutorial> {
+        fn from_world(world: &mut World) -> Self {
+            let mut materials = world
+                .get_resource_mut::<Assets<ColorMaterial>>()
+                .expect("ResMut<Assets<ColorMaterial>> not found.");
+            ButtonMaterials {
+                normal: materials.add(Color::rgb(0.8, 0.8, 0.8).into()),
+                hovered: materials.add(Color::rgb(0.25, 0.25, 0.25).into()),
+                pressed: materials.add(Color::rgb(0.35, 0.75, 0.35).into()),
+                _marker: PhantomData,
+            }
+        }
+    }
+}
+
+mod actions {
+    use super::*;
+
+    /// Marker component for the panel showing the tutorial's instructions
+    pub struct TutorialPanel;
+
+    /// Replaces the instructions whenever the tutorial moves on to another step,
+    /// removing them once it is over
+    pub fn show_instructions(
+        tutorial: Res<Tutorial>,
+        panel_query: Query<Entity, With<TutorialPanel>>,
+        mut commands: Commands,
+        materials: Res<TutorialMaterials>,
+        skip_button_materials: Res<ButtonMaterials<SkipTutorial>>,
+        font: Res<FixedFont>,
+    ) {
+        if !tutorial.is_changed() {
+            return;
+        }
+        for entity in panel_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        let step = match tutorial.step {
+            Some(step) => step,
+            None => return,
+        };
+
+        let text_style = TextStyle {
+            font: font.0.clone(),
+            font_size: FONT_SIZE,
+            color: TEXT_COLOR,
+        };
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    // Placed over the layout, rather than taking space from the board or buttons
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        bottom: Val::Px(MARGIN),
+                        left: Val::Px(MARGIN),
+                        ..Default::default()
+                    },
+                    align_items: AlignItems::Center,
+                    padding: Rect::all(Val::Px(10.0)),
+                    ..Default::default()
+                },
+                material: materials.panel.clone(),
+                ..Default::default()
+            })
+            .insert(TutorialPanel)
+            .with_children(|parent| {
+                // The instructions and the button's label are translated in the localize_text system
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section("", text_style.clone(), Default::default()),
+                        ..Default::default()
+                    })
+                    .insert(LocalizedText(step.instructions_id()));
+
+                // Not focusable, so that the keyboard keeps controlling the board rather than this button
+                parent
+                    .spawn_bundle(ButtonBundle {
+                        style: Style {
+                            size: Size::new(
+                                Val::Px(SKIP_BUTTON_WIDTH),
+                                Val::Px(SKIP_BUTTON_HEIGHT),
+                            ),
+                            margin: Rect::all(Val::Px(5.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        material: skip_button_materials.normal.clone(),
+                        ..Default::default()
+                    })
+                    .insert(SkipTutorial)
+                    .insert(Tooltip::new("tooltip-skip-tutorial"))
+                    .insert(NormalMaterial(skip_button_materials.normal.clone()))
+                    .insert(HoveredMaterial(skip_button_materials.hovered.clone()))
+                    .insert(PressedMaterial(skip_button_materials.pressed.clone()))
+                    .with_children(|parent| {
+                        parent
+                            .spawn_bundle(TextBundle {
+                                text: Text::with_section("", text_style, Default::default()),
+                                ..Default::default()
+                            })
+                            .insert(LocalizedText("tutorial-skip"));
+                    });
+            });
+    }
+
+    /// Does the current step ask the player to press this button
+    fn is_highlighted(
+        step: Option<TutorialStep>,
+        input_mode: Option<&InputMode>,
+        hint: Option<&GetHint>,
+    ) -> bool {
+        match step {
+            Some(TutorialStep::CenterMarkMode) => input_mode == Some(&InputMode::CenterMark),
+            Some(TutorialStep::CornerMarkMode) => input_mode == Some(&InputMode::CornerMark),
+            Some(TutorialStep::FillMode) => input_mode == Some(&InputMode::Fill),
+            Some(TutorialStep::AskForHint) => hint.is_some(),
+            _ => false,
+        }
+    }
+
+    /// Shows the button the current step asks for in the highlight color, unless the mouse is over it,
+    /// and restores buttons that are no longer asked for
+    pub fn highlight_buttons(
+        tutorial: Res<Tutorial>,
+        current_input_mode: Res<InputMode>,
+        materials: Res<TutorialMaterials>,
+        mut button_query: Query<
+            (
+                &Interaction,
+                &mut Handle<ColorMaterial>,
+                &NormalMaterial,
+                &PressedMaterial,
+                Option<&InputMode>,
+                Option<&GetHint>,
+            ),
+            With<Button>,
+        >,
+        mut last_step: Local<Option<TutorialStep>>,
+    ) {
+        let step_changed = *last_step != tutorial.step;
+
+        for (interaction, mut material, normal_material, pressed_material, input_mode, hint) in
+            button_query.iter_mut()
+        {
+            if *interaction != Interaction::None {
+                continue;
+            }
+
+            if is_highlighted(tutorial.step, input_mode, hint) {
+                if *material != materials.highlight {
+                    *material = materials.highlight.clone();
+                }
+            } else if step_changed && is_highlighted(*last_step, input_mode, hint) {
+                // The selected input mode is always shown as pressed
+                *material = if input_mode == Some(&*current_input_mode) {
+                    pressed_material.0.clone()
+                } else {
+                    normal_material.0.clone()
+                };
+            }
+        }
+
+        *last_step = tutorial.step;
+    }
+}
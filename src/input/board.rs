@@ -5,14 +5,27 @@ use self::cell_index::CellIndex;
 use crate::{graphics::MainCamera, logic::board::Cell};
 use bevy::prelude::*;
 
+/// Which mouse button a click was made with
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClickKind {
+    /// Left clicks select cells
+    Primary,
+    /// Right clicks deselect cells, and erase marks when dragged
+    Secondary,
+}
+
 /// Event to dispatch cell clicks
 pub struct CellClick {
+    /// The mouse button used
+    pub kind: ClickKind,
     /// Some(entity) if a cell was clicked, otherwise None
     pub selected_cell: Option<Entity>,
     /// Should we select multiple cells at once
     pub multi: bool,
     /// Was the mouse dragged
     pub drag: bool,
+    /// The cells inside the rectangle dragged out by the mouse, if Alt is held
+    pub lasso: Option<Vec<Entity>>,
 }
 
 /// Turns raw clicks into `CellClick` events
@@ -23,48 +36,70 @@ pub fn cell_click(
     windows: Res<Windows>,
     cell_index: Res<CellIndex>,
     mut cell_click_events: EventWriter<CellClick>,
+    mut drag_start: Local<Vec2>,
 ) {
-    if mouse_button_input.pressed(MouseButton::Left) {
-        // Our game only has one window
-        let window = windows.get_primary().expect("Primary window not found.");
-        // These coordinates are in terms of the window's coordinates
-        // and must be converted to the world coordinates used by our cell
-        let mut cursor_position = window
-            .cursor_position()
-            .expect("Cursor position not found.");
-        // QUALITY: use https://github.com/bevyengine/bevy/pull/1799 once merged instead
-        let camera_transform = camera_query.single().expect("Camera not found.");
-        let window_size = Vec2::new(window.width() as f32, window.height() as f32);
-
-        // World coordinates are measured from the center
-        // while screen coordinates are measures from the bottom left.
-        cursor_position -= 0.5 * window_size;
-
-        // Apply the camera's transform to correct for scale, angle etc.
-        // Returning a quaternion
-        let world_quat =
-            camera_transform.compute_matrix() * cursor_position.extend(0.0).extend(1.0);
-
-        let cursor_position_world = Vec2::new(world_quat.x, world_quat.y);
-
-        // Use the CellIndex resource to map the mouse position to a particular cell
-        let selected_cell = cell_index.get(cursor_position_world);
-
-        // Send a multi select event when Shift or Control is held
-        let multi = keyboard_input.pressed(KeyCode::LShift)
-            || keyboard_input.pressed(KeyCode::RShift)
-            || keyboard_input.pressed(KeyCode::LControl)
-            || keyboard_input.pressed(KeyCode::RControl);
-
-        // Send a drag event when the mouse was not just pressed
-        let drag = !mouse_button_input.just_pressed(MouseButton::Left);
-
-        cell_click_events.send(CellClick {
-            selected_cell,
-            multi,
-            drag,
-        })
+    // The left mouse button takes priority if both are held
+    let (kind, button) = if mouse_button_input.pressed(MouseButton::Left) {
+        (ClickKind::Primary, MouseButton::Left)
+    } else if mouse_button_input.pressed(MouseButton::Right) {
+        (ClickKind::Secondary, MouseButton::Right)
+    } else {
+        return;
+    };
+
+    // Our game only has one window
+    let window = windows.get_primary().expect("Primary window not found.");
+    // These coordinates are in terms of the window's coordinates
+    // and must be converted to the world coordinates used by our cell
+    let mut cursor_position = window
+        .cursor_position()
+        .expect("Cursor position not found.");
+    // QUALITY: use https://github.com/bevyengine/bevy/pull/1799 once merged instead
+    let camera_transform = camera_query.single().expect("Camera not found.");
+    let window_size = Vec2::new(window.width() as f32, window.height() as f32);
+
+    // World coordinates are measured from the center
+    // while screen coordinates are measures from the bottom left.
+    cursor_position -= 0.5 * window_size;
+
+    // Apply the camera's transform to correct for scale, angle etc.
+    // Returning a quaternion
+    let world_quat = camera_transform.compute_matrix() * cursor_position.extend(0.0).extend(1.0);
+
+    let cursor_position_world = Vec2::new(world_quat.x, world_quat.y);
+
+    // Use the CellIndex resource to map the mouse position to a particular cell
+    let selected_cell = cell_index.get(cursor_position_world);
+
+    // Send a multi select event when Shift or Control is held
+    let multi = keyboard_input.pressed(KeyCode::LShift)
+        || keyboard_input.pressed(KeyCode::RShift)
+        || keyboard_input.pressed(KeyCode::LControl)
+        || keyboard_input.pressed(KeyCode::RControl);
+
+    // Send a drag event when the mouse was not just pressed
+    let drag = !mouse_button_input.just_pressed(button);
+
+    // Remember where the mouse was first pressed, so we can select the rectangle dragged out
+    if !drag {
+        *drag_start = cursor_position_world;
     }
+
+    // Select every cell in the dragged rectangle when Alt is held
+    let alt = keyboard_input.pressed(KeyCode::LAlt) || keyboard_input.pressed(KeyCode::RAlt);
+    let lasso = if alt && kind == ClickKind::Primary {
+        Some(cell_index.get_within(*drag_start, cursor_position_world))
+    } else {
+        None
+    };
+
+    cell_click_events.send(CellClick {
+        kind,
+        selected_cell,
+        multi,
+        drag,
+        lasso,
+    })
 }
 
 pub mod cell_index {
@@ -95,13 +130,36 @@ pub mod cell_index {
             // Return None if no matches found
             None
         }
+
+        /// Returns every cell that overlaps the rectangle with the corners provided
+        pub fn get_within(&self, corner: Vec2, opposite_corner: Vec2) -> Vec<Entity> {
+            let bottom_left = corner.min(opposite_corner);
+            let top_right = corner.max(opposite_corner);
+
+            self.cell_map
+                .iter()
+                .filter(|(_, bounding_box)| {
+                    // Boxes overlap when each starts before the other ends, on both x and y
+                    let overlaps = bounding_box.bottom_left.cmple(top_right)
+                        & bounding_box.top_right.cmpge(bottom_left);
+                    overlaps.all()
+                })
+                .map(|(entity, _)| *entity)
+                .collect()
+        }
     }
 
-    /// Builds a `CellIndex` for cells whose `Transform` has been changed
+    /// Builds a `CellIndex` for cells whose `Transform` has been changed,
+    /// dropping the cells removed when the board is rebuilt for a grid of a different size
     pub fn index_cells(
         query: Query<(Entity, &Sprite, &Transform), (With<Cell>, Changed<Transform>)>,
+        removed: RemovedComponents<Cell>,
         mut cell_index: ResMut<CellIndex>,
     ) {
+        for entity in removed.iter() {
+            cell_index.cell_map.remove(&entity);
+        }
+
         // Our Changed<Transform> filter ensures that this system only does work
         // on entities whose Transforms were added or mutated since the last time
         // this system ran
@@ -1,30 +1,298 @@
 use bevy::{ecs::component::Component, prelude::*};
 
-use super::input_mode::InputMode;
+use super::{
+    focus::Focusable, input_mode::InputMode, shortcuts::Shortcut, CellInput, ScrambleRemaining,
+};
+use crate::{audio::SoundEffect, localization::Language};
 
 /// Marker component for NewPuzzle button
 #[derive(Default, Clone)]
-pub struct NewPuzzle;
+pub struct NewPuzzle {
+    /// Has the player agreed to discard their progress on the current puzzle
+    pub confirmed: bool,
+}
 /// Marker component for ResetPuzzle button
 #[derive(Default, Clone)]
-pub struct ResetPuzzle;
+pub struct ResetPuzzle {
+    /// Has the player agreed to discard their progress on the current puzzle
+    pub confirmed: bool,
+}
+/// Component for buttons that exit the game, sent by Escape when no menu is open
+#[derive(Default, Clone)]
+pub struct QuitGame {
+    /// Has the player agreed to discard their progress on the current puzzle
+    pub confirmed: bool,
+}
 /// Marker component for SolvePuzzle button
 #[derive(Default, Clone)]
 pub struct SolvePuzzle;
+/// Marker component for RemixPuzzle button
+#[derive(Default, Clone)]
+pub struct RemixPuzzle {
+    /// Has the player agreed to discard their progress on the current puzzle
+    pub confirmed: bool,
+}
+/// Marker component for GetHint button
+#[derive(Default, Clone)]
+pub struct GetHint;
+/// Marker component for FindMistake button
+#[derive(Default, Clone)]
+pub struct FindMistake;
+/// Marker component for CheckPuzzle button
+#[derive(Default, Clone)]
+pub struct CheckPuzzle;
+/// Marker component for FillCandidates button
+#[derive(Default, Clone)]
+pub struct FillCandidates;
+/// Marker component for BookmarkPuzzle button
+#[derive(Default, Clone)]
+pub struct BookmarkPuzzle;
+/// Marker component for CancelDialog button
+#[derive(Default, Clone)]
+pub struct CancelDialog;
+/// Component for buttons that show a page of the puzzle collection
+#[derive(Default, Clone)]
+pub struct BrowseCollection {
+    pub page: usize,
+}
+/// Marker component for the button that changes which archived puzzles are listed
+#[derive(Default, Clone)]
+pub struct CycleArchiveFilter;
+/// Component for buttons that star a puzzle to play again later
+///
+/// Stars the archived puzzle listed in the browser at this index, or the puzzle being played if there is none
+#[derive(Default, Clone)]
+pub struct StarPuzzle(pub Option<usize>);
+/// Marker component for the button that ends the tutorial early
+#[derive(Default, Clone)]
+pub struct SkipTutorial;
+/// Event to open the list of techniques to practise
+#[derive(Default, Clone)]
+pub struct ShowTechniques;
+/// Component for buttons that start a practice puzzle for a technique, by its index in `Technique::ALL`
+#[derive(Default, Clone)]
+pub struct TrainTechnique(pub usize);
+/// Marker component for the button that asks the technique trainer for more help
+#[derive(Default, Clone)]
+pub struct CoachPlayer;
+/// Marker component for the button that ends a practice session
+#[derive(Default, Clone)]
+pub struct StopTraining;
+/// Marker component for the button that shows or hides the rules of a variant puzzle
+#[derive(Default, Clone)]
+pub struct ToggleRules;
+/// Marker component for the button that turns the digits entered while setting a puzzle into its givens
+#[derive(Default, Clone)]
+pub struct LockPuzzle;
+/// Marker component for the button that removes redundant givens from a puzzle being set
+#[derive(Default, Clone)]
+pub struct MinimizePuzzle;
+/// Marker component for buttons that open the campaign's level select screen
+#[derive(Default, Clone)]
+pub struct ShowLevelSelect;
+/// Component for buttons that start playing a level of the campaign, by its index
+#[derive(Default, Clone)]
+pub struct PlayLevel(pub usize);
+/// Component for buttons that start playing a puzzle from the collection, by its index
+#[derive(Default, Clone)]
+pub struct PlayCollectionPuzzle(pub usize);
+/// Marker component for the button that opens the settings menu
+#[derive(Default, Clone)]
+pub struct ShowSettings;
+/// Component for buttons in the settings menu that show text in this language
+#[derive(Clone)]
+pub struct ChooseLanguage(pub Language);
+/// Component for buttons in the settings menu that mute or unmute a category of sound effect
+#[derive(Clone)]
+pub struct ToggleSound(pub SoundEffect);
+/// Marker component for the button in the settings menu that changes how many cells scrambling empties
+#[derive(Default, Clone)]
+pub struct CycleScrambleSize;
+/// Marker component for the button in the settings menu that changes the size of board new puzzles are played on
+#[derive(Default, Clone)]
+pub struct CycleGridSize;
+/// Marker component for the button in the settings menu that shows the player's statistics
+#[derive(Default, Clone)]
+pub struct ShowStats;
+/// Marker component for the button in the settings menu that asks for the seed of a puzzle to play
+#[derive(Default, Clone)]
+pub struct EnterSeed;
+/// Marker component for the button that plays the puzzle of the seed typed into the seed entry dialog
+#[derive(Default, Clone)]
+pub struct PlaySeed;
+/// Marker component for the button in the settings menu that lists the players' profiles
+#[derive(Default, Clone)]
+pub struct ShowProfiles;
+/// Component for buttons that switch to playing as a profile, by its index
+#[derive(Default, Clone)]
+pub struct ChooseProfile(pub usize);
+/// Marker component for the button in the profile list that asks for the name of a new profile
+#[derive(Default, Clone)]
+pub struct AddProfile;
+/// Marker component for the button that creates a profile with the name typed into the profile name dialog
+#[derive(Default, Clone)]
+pub struct CreateProfile;
+
+/// Buttons that do the same as a keyboard shortcut, so the accessibility audit can tell which controls have one
+pub trait ShortcutButton: Component {
+    /// The shortcut pressing this button stands in for
+    fn shortcut(&self) -> Shortcut;
+}
+
+impl ShortcutButton for NewPuzzle {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::NewPuzzle
+    }
+}
+
+impl ShortcutButton for ResetPuzzle {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::ResetPuzzle
+    }
+}
+
+impl ShortcutButton for SolvePuzzle {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::SolvePuzzle
+    }
+}
+
+impl ShortcutButton for RemixPuzzle {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::RemixPuzzle
+    }
+}
+
+impl ShortcutButton for GetHint {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::GetHint
+    }
+}
+
+impl ShortcutButton for FindMistake {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::FindMistake
+    }
+}
+
+impl ShortcutButton for CheckPuzzle {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::CheckPuzzle
+    }
+}
+
+impl ShortcutButton for FillCandidates {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::FillCandidates
+    }
+}
+
+impl ShortcutButton for ScrambleRemaining {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::ScrambleRemaining
+    }
+}
+
+impl ShortcutButton for ShowTechniques {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::Techniques
+    }
+}
+
+impl ShortcutButton for CoachPlayer {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::CoachPlayer
+    }
+}
+
+impl ShortcutButton for LockPuzzle {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::LockPuzzle
+    }
+}
+
+impl ShortcutButton for MinimizePuzzle {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::MinimizePuzzle
+    }
+}
+
+impl ShortcutButton for ShowLevelSelect {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::LevelSelect
+    }
+}
+
+impl ShortcutButton for ShowSettings {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::ShowSettings
+    }
+}
+
+impl ShortcutButton for EnterSeed {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::EnterSeed
+    }
+}
+
+impl ShortcutButton for ChooseLanguage {
+    fn shortcut(&self) -> Shortcut {
+        Shortcut::CycleLanguage
+    }
+}
+
+impl ShortcutButton for InputMode {
+    fn shortcut(&self) -> Shortcut {
+        match self {
+            InputMode::Fill => Shortcut::FillMode,
+            InputMode::CenterMark => Shortcut::CenterMarkMode,
+            InputMode::CornerMark => Shortcut::CornerMarkMode,
+            InputMode::Color => Shortcut::ColorMode,
+        }
+    }
+}
 
 /// Sends the event type associated with the button when pressed
 /// using the data stored on the component of that type
+///
+/// While a menu is open, only its own buttons respond, so the rest of the game can't be changed beneath it
 pub fn puzzle_button<Marker: Component + Clone>(
-    query: Query<(&Interaction, &Marker)>,
+    query: Query<(&Interaction, &Marker, Option<&Focusable>)>,
+    menu_query: Query<(), With<Focusable>>,
     mut event_writer: EventWriter<Marker>,
 ) {
-    for (interaction, marker) in query.iter() {
+    let menu_open = menu_query.iter().next().is_some();
+
+    for (interaction, marker, focusable) in query.iter() {
+        if menu_open && focusable.is_none() {
+            continue;
+        }
         if *interaction == Interaction::Clicked {
             event_writer.send(marker.clone());
         }
     }
 }
 
+/// Component for buttons that paint the selected cells with a color from the palette
+#[derive(Clone)]
+pub struct ColorSwatch(pub u8);
+
+/// Switches to coloring cells and paints the selected cells when a swatch is pressed
+pub fn color_swatch_buttons(
+    button_query: Query<(&Interaction, &ColorSwatch), Changed<Interaction>>,
+    mut input_mode: ResMut<InputMode>,
+    mut event_writer: EventWriter<CellInput>,
+) {
+    for (interaction, color_swatch) in button_query.iter() {
+        if *interaction == Interaction::Clicked {
+            *input_mode = InputMode::Color;
+            event_writer.send(CellInput {
+                num: color_swatch.0,
+            });
+        }
+    }
+}
+
 /// Changes the input mode of the puzzle when these buttons are pressed
 pub fn input_mode_buttons(
     button_query: Query<(&Interaction, &InputMode), Changed<Interaction>>,
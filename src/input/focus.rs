@@ -0,0 +1,209 @@
+/// Lets menus and dialogs be navigated without a mouse, using the arrow keys or a gamepad
+///
+/// Only buttons marked as `Focusable` take part; the puzzle itself has its own controls
+use super::buttons::{CancelDialog, QuitGame, SkipTutorial, StopTraining};
+use bevy::{ecs::component::Component, prelude::*};
+
+/// Marker component for buttons that can be reached with the keyboard or a gamepad
+pub struct Focusable;
+
+/// Marker component for the button that should be focused when its menu opens
+///
+/// This should be the least destructive choice available
+pub struct DefaultFocus;
+
+/// Resource tracking which button is currently focused
+#[derive(Default)]
+pub struct Focus {
+    /// The button that will be activated by Enter
+    pub current: Option<Entity>,
+    /// Buttons that were focused before the current menu was opened, most recent last
+    ///
+    /// Focus returns to these when the menu on top of them closes
+    remembered: Vec<Entity>,
+}
+
+/// A direction to move focus in, or an action to take on the focused button
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Navigation {
+    Previous,
+    Next,
+    Activate,
+    Back,
+}
+
+/// Reads navigation commands from the arrow keys, Tab, Enter and Escape
+fn keyboard_navigation(keyboard_input: &Input<KeyCode>) -> Vec<Navigation> {
+    use KeyCode::*;
+    use Navigation::*;
+
+    let shift = keyboard_input.pressed(LShift) || keyboard_input.pressed(RShift);
+
+    keyboard_input
+        .get_just_pressed()
+        .filter_map(|key_code| match key_code {
+            Tab if shift => Some(Previous),
+            Left | Up => Some(Previous),
+            Right | Down | Tab => Some(Next),
+            Return | NumpadEnter | Space => Some(Activate),
+            Escape => Some(Back),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reads navigation commands from the d-pad and face buttons of any connected gamepad
+fn gamepad_navigation(gamepad_input: &Input<GamepadButton>) -> Vec<Navigation> {
+    use GamepadButtonType::*;
+    use Navigation::*;
+
+    gamepad_input
+        .get_just_pressed()
+        .filter_map(|gamepad_button| match gamepad_button.1 {
+            DPadLeft | DPadUp => Some(Previous),
+            DPadRight | DPadDown => Some(Next),
+            South => Some(Activate),
+            East => Some(Back),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Keeps focus on a live button as menus open and close
+///
+/// Newly opened menus take focus, remembering where it was,
+/// and return it once they are closed
+pub fn track_focus(
+    added_query: Query<(Entity, Option<&DefaultFocus>), Added<Focusable>>,
+    focusable_query: Query<Entity, With<Focusable>>,
+    mut focus: ResMut<Focus>,
+) {
+    // Fall back to the most recently remembered button that still exists
+    while let Some(entity) = focus.current {
+        if focusable_query.get(entity).is_ok() {
+            break;
+        }
+        focus.current = focus.remembered.pop();
+    }
+
+    let mut added: Vec<_> = added_query.iter().collect();
+    if added.is_empty() {
+        return;
+    }
+    // Prefer the button that asked to be focused by default
+    added.sort_by_key(|(entity, default_focus)| (default_focus.is_none(), *entity));
+
+    if let Some(previous) = focus.current {
+        focus.remembered.push(previous);
+    }
+    focus.current = Some(added[0].0);
+}
+
+/// Moves focus between buttons with the arrow keys or d-pad,
+/// ordered from left to right, then top to bottom
+pub fn move_focus(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    query: Query<(Entity, &GlobalTransform), With<Focusable>>,
+    mut focus: ResMut<Focus>,
+) {
+    let mut focusables: Vec<_> = query
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation))
+        .collect();
+    if focusables.is_empty() {
+        return;
+    }
+    // UI coordinates increase upwards, so higher rows come first
+    focusables.sort_by(|(_, a), (_, b)| {
+        b.y.partial_cmp(&a.y)
+            .unwrap()
+            .then(a.x.partial_cmp(&b.x).unwrap())
+    });
+
+    let navigation = keyboard_navigation(&*keyboard_input)
+        .into_iter()
+        .chain(gamepad_navigation(&*gamepad_input));
+
+    for command in navigation {
+        let current_index = focus
+            .current
+            .and_then(|current| focusables.iter().position(|(entity, _)| *entity == current));
+        let n = focusables.len();
+
+        let new_index = match (command, current_index) {
+            (Navigation::Previous, Some(i)) => (i + n - 1) % n,
+            (Navigation::Next, Some(i)) => (i + 1) % n,
+            (Navigation::Previous, None) | (Navigation::Next, None) => 0,
+            _ => continue,
+        };
+        focus.current = Some(focusables[new_index].0);
+    }
+}
+
+/// Only runs the systems it guards while no menu is open,
+/// so that menus block the board and keyboard shortcuts beneath them
+pub fn no_menu_open(query: Query<(), With<Focusable>>) -> ShouldRun {
+    if query.iter().next().is_none() {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+/// Sends the event type associated with the focused button when Enter or the South button is pressed
+///
+/// The keyboard counterpart to `puzzle_button`
+pub fn activate_focused<Marker: Component + Clone>(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    query: Query<&Marker, With<Focusable>>,
+    focus: Res<Focus>,
+    mut event_writer: EventWriter<Marker>,
+) {
+    let activated = keyboard_navigation(&*keyboard_input)
+        .into_iter()
+        .chain(gamepad_navigation(&*gamepad_input))
+        .any(|command| command == Navigation::Activate);
+
+    if activated {
+        if let Some(marker) = focus.current.and_then(|entity| query.get(entity).ok()) {
+            event_writer.send(marker.clone());
+        }
+    }
+}
+
+/// Backs out of the open menu with Escape or the East button
+///
+/// With no menu open, Escape skips the tutorial or ends the practice puzzle if either is running,
+/// and otherwise asks to quit the game
+pub fn go_back(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    query: Query<(), With<Focusable>>,
+    skip_query: Query<(), With<SkipTutorial>>,
+    stop_query: Query<(), With<StopTraining>>,
+    mut cancel_writer: EventWriter<CancelDialog>,
+    mut skip_writer: EventWriter<SkipTutorial>,
+    mut stop_writer: EventWriter<StopTraining>,
+    mut quit_writer: EventWriter<QuitGame>,
+) {
+    let menu_open = query.iter().next().is_some();
+
+    if gamepad_navigation(&*gamepad_input).contains(&Navigation::Back) && menu_open {
+        cancel_writer.send(CancelDialog);
+    }
+
+    if keyboard_navigation(&*keyboard_input).contains(&Navigation::Back) {
+        if menu_open {
+            cancel_writer.send(CancelDialog);
+        // The tutorial and trainer panels only have these buttons while they are shown
+        } else if skip_query.iter().next().is_some() {
+            skip_writer.send(SkipTutorial);
+        } else if stop_query.iter().next().is_some() {
+            stop_writer.send(StopTraining);
+        } else {
+            quit_writer.send(QuitGame::default());
+        }
+    }
+}
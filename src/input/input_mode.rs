@@ -1,8 +1,13 @@
 /// Process the action events created via player inputs
-use crate::logic::board::{
-    marks::{CenterMarks, CornerMarks, Marks},
-    Value,
+use super::Selected;
+use crate::{
+    logic::board::{
+        marks::{CenterMarks, CornerMarks, Marks},
+        Value,
+    },
+    settings::Settings,
 };
+use bevy::prelude::*;
 
 /// Different ways to enter a number into a cell
 #[derive(Copy, Clone, PartialEq, Eq, Hash)]
@@ -13,6 +18,8 @@ pub enum InputMode {
     CenterMark,
     /// This value must be within one of these cells in the box
     CornerMark,
+    /// Paints the cell with one of the colors in the palette
+    Color,
 }
 
 impl Default for InputMode {
@@ -21,6 +28,97 @@ impl Default for InputMode {
     }
 }
 
+/// Whether one cell or several are selected
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SelectionKind {
+    Single,
+    Multiple,
+}
+
+impl Default for SelectionKind {
+    fn default() -> Self {
+        SelectionKind::Single
+    }
+}
+
+/// Resource remembering the input mode last used with each kind of selection
+#[derive(Default, Clone, Copy)]
+pub struct InputModeMemory {
+    pub single: InputMode,
+    pub multiple: InputMode,
+}
+
+impl InputModeMemory {
+    pub fn get(&self, kind: SelectionKind) -> InputMode {
+        match kind {
+            SelectionKind::Single => self.single,
+            SelectionKind::Multiple => self.multiple,
+        }
+    }
+
+    pub fn set(&mut self, kind: SelectionKind, input_mode: InputMode) {
+        match kind {
+            SelectionKind::Single => self.single = input_mode,
+            SelectionKind::Multiple => self.multiple = input_mode,
+        }
+    }
+}
+
+/// Records the input mode used with the current kind of selection,
+/// switching back to the mode last used with the other kind when the selection changes between them
+pub fn remember_input_mode(
+    selected_query: Query<(), With<Selected>>,
+    settings: Res<Settings>,
+    mut input_mode: ResMut<InputMode>,
+    mut input_mode_memory: ResMut<InputModeMemory>,
+    mut last_kind: Local<SelectionKind>,
+) {
+    let kind = if selected_query.iter().nth(1).is_some() {
+        SelectionKind::Multiple
+    } else {
+        SelectionKind::Single
+    };
+
+    if settings.input_mode_per_selection && kind != *last_kind {
+        let remembered = input_mode_memory.get(kind);
+        if *input_mode != remembered {
+            *input_mode = remembered;
+        }
+    } else if input_mode_memory.get(kind) != *input_mode {
+        input_mode_memory.set(kind, *input_mode);
+    }
+
+    *last_kind = kind;
+}
+
+/// Resource for digit-first input, where a digit is chosen before the cells it is placed in
+#[derive(Default)]
+pub struct ArmedDigit {
+    /// Is digit-first input currently in use
+    pub enabled: bool,
+    /// The digit that will be entered into cells when they are clicked
+    pub num: Option<u8>,
+}
+
+impl ArmedDigit {
+    /// Arms the digit provided, or disarms it if it was already armed
+    pub fn toggle(&mut self, num: u8) {
+        self.num = match self.num {
+            Some(old_num) if old_num == num => None,
+            _ => Some(num),
+        };
+    }
+
+    /// Returns the digit to place in clicked cells, if digit-first input is active
+    pub fn armed(&self) -> Option<u8> {
+        if self.enabled {
+            self.num
+        } else {
+            None
+        }
+    }
+}
+
 // QUALITY: refactor these to properly use a trait
 pub fn update_value_fill(old_value: &Value, new_num: u8) -> Value {
     match old_value.clone() {
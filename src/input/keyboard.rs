@@ -1,13 +1,30 @@
 /// Handle player input from the keyboard, converting it into actions
-use super::{input_mode::InputMode, CellInput, Selected};
-use crate::logic::board::{Cell, Fixed, Value};
+use super::{
+    buttons::{
+        CheckPuzzle, CoachPlayer, EnterSeed, FillCandidates, FindMistake, GetHint, LockPuzzle,
+        MinimizePuzzle, NewPuzzle, RemixPuzzle, ResetPuzzle, SolvePuzzle,
+    },
+    focus::Focusable,
+    input_mode::{ArmedDigit, InputMode},
+    shortcuts::{Modifier, Shortcut, Shortcuts},
+    CellInput, OpenPuzzleFile, ResumeBookmark, SavePuzzleFile, ScrambleRemaining, Selected,
+    SetPuzzle, SharePuzzle, ToggleVariant,
+};
+use crate::logic::{
+    board::{Cell, CellsErased, Coordinates, Fixed, Locked, Value},
+    constraints::{ConstraintSet, Variant},
+};
 use bevy::prelude::*;
 
 pub mod cell_input {
-    use super::CellInput;
+    use super::{CellInput, Modifier, Shortcuts};
+    use crate::logic::{constraints::ConstraintSet, grid::GridSize};
     use bevy::prelude::*;
     use bevy::utils::HashMap;
     /// Contains keybindings for converting key presses into numbers
+    ///
+    /// The digits beyond 9 are entered with the letters they are written as,
+    /// which only enter digits on grids large enough to use them
     pub struct CellInputMap {
         map: HashMap<KeyCode, u8>,
     }
@@ -17,8 +34,22 @@ pub mod cell_input {
             self.map.insert(k, v);
         }
 
-        fn get(&self, k: &KeyCode) -> Option<&u8> {
-            self.map.get(k)
+        /// The number entered by the key on this grid
+        ///
+        /// The numbers 1 to 9 are always entered, as they also pick colors to paint with
+        fn get(&self, k: &KeyCode, grid: GridSize) -> Option<u8> {
+            self.map
+                .get(k)
+                .copied()
+                .filter(|num| *num <= 9 || *num <= grid.digits())
+        }
+
+        /// The letter keys entering digits on this grid
+        fn letter_keys(&self, grid: GridSize) -> impl Iterator<Item = KeyCode> + '_ {
+            self.map
+                .iter()
+                .filter(move |(_, num)| **num > 9 && **num <= grid.digits())
+                .map(|(key_code, _)| *key_code)
         }
     }
 
@@ -52,6 +83,15 @@ pub mod cell_input {
             input_map.insert(Numpad8, 8);
             input_map.insert(Numpad9, 9);
 
+            // Letters, for the digits beyond 9
+            input_map.insert(A, 10);
+            input_map.insert(B, 11);
+            input_map.insert(C, 12);
+            input_map.insert(D, 13);
+            input_map.insert(E, 14);
+            input_map.insert(F, 15);
+            input_map.insert(G, 16);
+
             input_map
         }
     }
@@ -60,27 +100,66 @@ pub mod cell_input {
     pub fn cell_keyboard_input(
         keyboard_input: Res<Input<KeyCode>>,
         input_map: Res<CellInputMap>,
+        constraints: Res<ConstraintSet>,
         mut event_writer: EventWriter<CellInput>,
     ) {
+        // Letters held with Ctrl or Alt are still shortcuts, even on grids where they enter digits
+        let plain = Modifier::held(&keyboard_input) == Some(Modifier::Plain);
+
         for key_code in keyboard_input.get_just_pressed() {
-            let maybe_value = input_map.get(key_code);
+            let maybe_value = input_map.get(key_code, constraints.grid());
 
-            if let Some(value) = maybe_value {
-                event_writer.send(CellInput { num: *value });
+            if let Some(value) = maybe_value.filter(|value| *value <= 9 || plain) {
+                event_writer.send(CellInput { num: value });
             }
         }
     }
+
+    /// Sets aside the letter keys for entering digits while the grid uses them,
+    /// handing them back to their shortcuts otherwise
+    pub fn reserve_digit_keys(
+        constraints: Res<ConstraintSet>,
+        input_map: Res<CellInputMap>,
+        mut shortcuts: ResMut<Shortcuts>,
+    ) {
+        if constraints.is_changed() {
+            shortcuts.reserve(input_map.letter_keys(constraints.grid()));
+        }
+    }
 }
 
 /// Clears all selected cells when Backspace or Delete is pressed
 pub fn erase_selected_cells(
-    mut query: Query<(&mut Value, &Fixed), With<Selected>>,
+    mut query: Query<(&mut Value, &Fixed, &Locked), With<Selected>>,
     keyboard_input: Res<Input<KeyCode>>,
+    mut event_writer: EventWriter<CellsErased>,
 ) {
     if keyboard_input.just_pressed(KeyCode::Delete) || keyboard_input.just_pressed(KeyCode::Back) {
-        for (mut value, is_fixed) in query.iter_mut() {
-            if !is_fixed.0 {
+        let mut erased = false;
+        for (mut value, is_fixed, is_locked) in query.iter_mut() {
+            if !is_fixed.0 && !is_locked.0 && *value != Value::Empty {
                 *value = Value::Empty;
+                erased = true;
+            }
+        }
+
+        if erased {
+            event_writer.send(CellsErased);
+        }
+    }
+}
+
+/// Locks or unlocks the digits filled into the selected cells when L is pressed
+pub fn toggle_locks(
+    mut query: Query<(&Value, &Fixed, &mut Locked), With<Selected>>,
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+) {
+    if shortcuts.just_pressed(Shortcut::ToggleLocks, &keyboard_input) {
+        for (value, is_fixed, mut is_locked) in query.iter_mut() {
+            // Only digits entered by the player can be locked in
+            if !is_fixed.0 && matches!(value, Value::Filled(_)) {
+                is_locked.0 = !is_locked.0;
             }
         }
     }
@@ -90,25 +169,268 @@ pub fn erase_selected_cells(
 pub fn select_all(
     query: Query<Entity, With<Cell>>,
     keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
     mut commands: Commands,
 ) {
-    let ctrl =
-        keyboard_input.pressed(KeyCode::LControl) || keyboard_input.pressed(KeyCode::RControl);
-
-    if ctrl && keyboard_input.just_pressed(KeyCode::A) {
+    if shortcuts.just_pressed(Shortcut::SelectAll, &keyboard_input) {
         for entity in query.iter() {
             commands.entity(entity).insert(Selected);
         }
     }
 }
 
+/// Moves the selection around the board with the arrow keys, wrapping around its edges
+///
+/// Holding Shift or Control adds the cells moved to to the selection, rather than replacing it
+pub fn move_selection(
+    keyboard_input: Res<Input<KeyCode>>,
+    cell_query: Query<(Entity, &Coordinates, Option<&Selected>), With<Cell>>,
+    focusable_query: Query<(), With<Focusable>>,
+    constraints: Res<ConstraintSet>,
+    mut cursor: Local<Option<(u8, u8)>>,
+    mut commands: Commands,
+) {
+    // The arrow keys move focus between the buttons of an open menu instead
+    if focusable_query.iter().next().is_some() {
+        return;
+    }
+
+    let moves: Vec<(i8, i8)> = keyboard_input
+        .get_just_pressed()
+        .filter_map(|key_code| match key_code {
+            KeyCode::Up => Some((-1, 0)),
+            KeyCode::Down => Some((1, 0)),
+            KeyCode::Left => Some((0, -1)),
+            KeyCode::Right => Some((0, 1)),
+            _ => None,
+        })
+        .collect();
+    if moves.is_empty() {
+        return;
+    }
+
+    // Moves continue from the last cell moved to, as long as the mouse hasn't changed the selection since,
+    // then from the first selected cell, and otherwise from the center of the board
+    let selected: Vec<(u8, u8)> = cell_query
+        .iter()
+        .filter(|(_, _, maybe_selected)| maybe_selected.is_some())
+        .map(|(_, coordinates, _)| (coordinates.row, coordinates.column))
+        .collect();
+    let grid = constraints.grid();
+    let width = grid.width();
+    let center = (width + 1) / 2;
+    let (mut row, mut column) = cursor
+        .filter(|position| selected.contains(position))
+        .or_else(|| selected.iter().min().copied())
+        .unwrap_or((center, center));
+
+    // Rows and columns are counted from 1 up to the width of the grid
+    let wrap =
+        |position: u8, step: i8| ((position as i8 - 1 + step).rem_euclid(width as i8) + 1) as u8;
+    for (row_step, column_step) in moves {
+        // The gaps between the grids of a samurai board are jumped over,
+        // and every row and column of the board has a cell somewhere to land on
+        loop {
+            row = wrap(row, row_step);
+            column = wrap(column, column_step);
+            if grid.contains(&Coordinates { row, column }) {
+                break;
+            }
+        }
+    }
+
+    let extend = keyboard_input.pressed(KeyCode::LShift)
+        || keyboard_input.pressed(KeyCode::RShift)
+        || keyboard_input.pressed(KeyCode::LControl)
+        || keyboard_input.pressed(KeyCode::RControl);
+
+    for (entity, coordinates, maybe_selected) in cell_query.iter() {
+        if (coordinates.row, coordinates.column) == (row, column) {
+            commands.entity(entity).insert(Selected);
+        } else if !extend && maybe_selected.is_some() {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+    *cursor = Some((row, column));
+}
+
 /// Swaps the input mode based on keyboard input
-pub fn swap_input_mode(keyboard_input: Res<Input<KeyCode>>, mut input_mode: ResMut<InputMode>) {
-    if keyboard_input.just_pressed(KeyCode::Q) {
+pub fn swap_input_mode(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut input_mode: ResMut<InputMode>,
+) {
+    let pressed = |shortcut| shortcuts.just_pressed(shortcut, &keyboard_input);
+
+    if pressed(Shortcut::FillMode) {
         *input_mode = InputMode::Fill;
-    } else if keyboard_input.just_pressed(KeyCode::W) {
+    } else if pressed(Shortcut::CenterMarkMode) {
         *input_mode = InputMode::CenterMark;
-    } else if keyboard_input.just_pressed(KeyCode::E) {
+    } else if pressed(Shortcut::CornerMarkMode) {
         *input_mode = InputMode::CornerMark;
+    } else if pressed(Shortcut::ColorMode) {
+        *input_mode = InputMode::Color;
+    }
+}
+
+/// Toggles digit-first input when R is pressed
+pub fn toggle_digit_first(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut armed_digit: ResMut<ArmedDigit>,
+) {
+    if shortcuts.just_pressed(Shortcut::DigitFirst, &keyboard_input) {
+        armed_digit.enabled = !armed_digit.enabled;
+        // Start each use of digit-first input fresh
+        armed_digit.num = None;
+    }
+}
+
+/// Resumes the most recently bookmarked puzzle when B is pressed
+pub fn resume_bookmark(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut event_writer: EventWriter<ResumeBookmark>,
+) {
+    if shortcuts.just_pressed(Shortcut::ResumeBookmark, &keyboard_input) {
+        event_writer.send(ResumeBookmark);
+    }
+}
+
+/// Fills in every candidate as center marks when C is pressed
+pub fn fill_candidates(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut event_writer: EventWriter<FillCandidates>,
+) {
+    if shortcuts.just_pressed(Shortcut::FillCandidates, &keyboard_input) {
+        event_writer.send(FillCandidates);
+    }
+}
+
+/// Presses the game control buttons of the side panel from the keyboard
+pub fn game_controls(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut new_writer: EventWriter<NewPuzzle>,
+    mut reset_writer: EventWriter<ResetPuzzle>,
+    mut solve_writer: EventWriter<SolvePuzzle>,
+    mut remix_writer: EventWriter<RemixPuzzle>,
+    mut seed_writer: EventWriter<EnterSeed>,
+    mut hint_writer: EventWriter<GetHint>,
+    mut find_mistake_writer: EventWriter<FindMistake>,
+    mut check_writer: EventWriter<CheckPuzzle>,
+) {
+    let pressed = |shortcut| shortcuts.just_pressed(shortcut, &keyboard_input);
+
+    if pressed(Shortcut::NewPuzzle) {
+        new_writer.send(NewPuzzle::default());
+    }
+    if pressed(Shortcut::ResetPuzzle) {
+        reset_writer.send(ResetPuzzle::default());
+    }
+    if pressed(Shortcut::SolvePuzzle) {
+        solve_writer.send(SolvePuzzle);
+    }
+    if pressed(Shortcut::RemixPuzzle) {
+        remix_writer.send(RemixPuzzle::default());
+    }
+    if pressed(Shortcut::EnterSeed) {
+        seed_writer.send(EnterSeed);
+    }
+    if pressed(Shortcut::GetHint) {
+        hint_writer.send(GetHint);
+    }
+    if pressed(Shortcut::FindMistake) {
+        find_mistake_writer.send(FindMistake);
+    }
+    if pressed(Shortcut::CheckPuzzle) {
+        check_writer.send(CheckPuzzle);
+    }
+}
+
+/// Asks the technique trainer for more help when Z is pressed
+pub fn ask_for_coaching(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut event_writer: EventWriter<CoachPlayer>,
+) {
+    if shortcuts.just_pressed(Shortcut::CoachPlayer, &keyboard_input) {
+        event_writer.send(CoachPlayer);
+    }
+}
+
+/// Scrambles the cells that are still unfilled into a new practice position when P is pressed
+pub fn scramble_remaining(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut event_writer: EventWriter<ScrambleRemaining>,
+) {
+    if shortcuts.just_pressed(Shortcut::ScrambleRemaining, &keyboard_input) {
+        event_writer.send(ScrambleRemaining);
+    }
+}
+
+/// Copies a link to the current puzzle to the clipboard when S is pressed
+pub fn share_puzzle(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut event_writer: EventWriter<SharePuzzle>,
+) {
+    if shortcuts.just_pressed(Shortcut::SharePuzzle, &keyboard_input) {
+        event_writer.send(SharePuzzle);
+    }
+}
+
+/// Opens a puzzle file with Ctrl + O, and saves the current puzzle with Ctrl + S
+pub fn puzzle_files(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut open_writer: EventWriter<OpenPuzzleFile>,
+    mut save_writer: EventWriter<SavePuzzleFile>,
+) {
+    if shortcuts.just_pressed(Shortcut::OpenPuzzleFile, &keyboard_input) {
+        open_writer.send(OpenPuzzleFile);
+    }
+
+    if shortcuts.just_pressed(Shortcut::SavePuzzleFile, &keyboard_input) {
+        save_writer.send(SavePuzzleFile);
+    }
+}
+
+/// Switches whether new puzzles follow the anti-knight rule with Alt + N,
+/// the anti-king rule with Alt + K and the non-consecutive rule with Alt + C,
+/// whether they are jigsaws with Alt + J or windoku with Alt + W,
+/// and whether they have disjoint groups with Alt + D or diagonals with Alt + X
+pub fn toggle_variants(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut event_writer: EventWriter<ToggleVariant>,
+) {
+    for variant in Variant::ALL.iter() {
+        if shortcuts.just_pressed(Shortcut::ToggleVariant(*variant), &keyboard_input) {
+            event_writer.send(ToggleVariant(*variant));
+        }
+    }
+}
+
+/// Starts setting a puzzle with Ctrl + N, minimizes it with Ctrl + M and locks in its givens with Ctrl + L
+pub fn setter_controls(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut set_writer: EventWriter<SetPuzzle>,
+    mut minimize_writer: EventWriter<MinimizePuzzle>,
+    mut lock_writer: EventWriter<LockPuzzle>,
+) {
+    if shortcuts.just_pressed(Shortcut::SetPuzzle, &keyboard_input) {
+        set_writer.send(SetPuzzle);
+    }
+
+    if shortcuts.just_pressed(Shortcut::MinimizePuzzle, &keyboard_input) {
+        minimize_writer.send(MinimizePuzzle);
+    }
+
+    if shortcuts.just_pressed(Shortcut::LockPuzzle, &keyboard_input) {
+        lock_writer.send(LockPuzzle);
     }
 }
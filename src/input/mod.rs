@@ -1,12 +1,15 @@
 use bevy::prelude::*;
 
+use crate::logic::constraints::Variant;
 use crate::CommonLabels;
 
 pub mod input_mode;
 // These are low-level, and shouldn't need to be exposed
 pub mod board;
 pub mod buttons;
+pub mod focus;
 mod keyboard;
+pub mod shortcuts;
 
 pub struct InteractionPlugin;
 
@@ -16,39 +19,182 @@ impl Plugin for InteractionPlugin {
             // INPUT EVENTS
             .add_event::<buttons::NewPuzzle>()
             .add_event::<buttons::ResetPuzzle>()
+            .add_event::<buttons::QuitGame>()
             .add_event::<buttons::SolvePuzzle>()
+            .add_event::<buttons::RemixPuzzle>()
+            .add_event::<buttons::GetHint>()
+            .add_event::<buttons::FindMistake>()
+            .add_event::<buttons::CheckPuzzle>()
+            .add_event::<buttons::FillCandidates>()
+            .add_event::<buttons::BookmarkPuzzle>()
+            .add_event::<buttons::CancelDialog>()
+            .add_event::<buttons::BrowseCollection>()
+            .add_event::<buttons::PlayCollectionPuzzle>()
+            .add_event::<buttons::CycleArchiveFilter>()
+            .add_event::<buttons::StarPuzzle>()
+            .add_event::<buttons::ShowLevelSelect>()
+            .add_event::<buttons::PlayLevel>()
+            .add_event::<buttons::SkipTutorial>()
+            .add_event::<buttons::ShowTechniques>()
+            .add_event::<buttons::TrainTechnique>()
+            .add_event::<buttons::CoachPlayer>()
+            .add_event::<buttons::StopTraining>()
+            .add_event::<buttons::ToggleRules>()
+            .add_event::<buttons::LockPuzzle>()
+            .add_event::<buttons::MinimizePuzzle>()
+            .add_event::<buttons::ShowSettings>()
+            .add_event::<buttons::ChooseLanguage>()
+            .add_event::<buttons::ToggleSound>()
+            .add_event::<buttons::CycleScrambleSize>()
+            .add_event::<buttons::CycleGridSize>()
+            .add_event::<buttons::ShowStats>()
+            .add_event::<buttons::EnterSeed>()
+            .add_event::<buttons::PlaySeed>()
+            .add_event::<buttons::ShowProfiles>()
+            .add_event::<buttons::ChooseProfile>()
+            .add_event::<buttons::AddProfile>()
+            .add_event::<buttons::CreateProfile>()
             .add_event::<board::CellClick>()
             .add_event::<CellInput>()
+            .add_event::<ResumeBookmark>()
+            .add_event::<ScrambleRemaining>()
+            .add_event::<SharePuzzle>()
+            .add_event::<OpenPuzzleFile>()
+            .add_event::<SavePuzzleFile>()
+            .add_event::<SetPuzzle>()
+            .add_event::<ToggleVariant>()
             .init_resource::<keyboard::cell_input::CellInputMap>()
+            .init_resource::<shortcuts::Shortcuts>()
             .init_resource::<board::cell_index::CellIndex>()
             .init_resource::<input_mode::InputMode>()
+            .init_resource::<input_mode::InputModeMemory>()
+            .init_resource::<input_mode::ArmedDigit>()
+            .init_resource::<focus::Focus>()
             // Should run before input to ensure mapping from position to cell is correct
             .add_system(
                 board::cell_index::index_cells
                     .system()
                     .before(CommonLabels::Input),
             )
+            // Letters must be set aside for digits before any shortcut is checked
+            .add_system(
+                keyboard::cell_input::reserve_digit_keys
+                    .system()
+                    .before(CommonLabels::Input),
+            )
+            // Must see this frame's selection, and restore the input mode before it is used
+            .add_system(
+                input_mode::remember_input_mode
+                    .system()
+                    .after(CommonLabels::Input)
+                    .before(CommonLabels::Action),
+            )
             // INPUT HANDLING
+            // The board and its controls are blocked while a menu is open over them
             .add_system_set(
                 SystemSet::new()
                     .label(CommonLabels::Input)
+                    .with_run_criteria(focus::no_menu_open.system())
                     // BOARD
                     .with_system(board::cell_click.system())
                     // BUTTONS
-                    .with_system(buttons::puzzle_button::<buttons::NewPuzzle>.system())
-                    .with_system(buttons::puzzle_button::<buttons::ResetPuzzle>.system())
-                    .with_system(buttons::puzzle_button::<buttons::SolvePuzzle>.system())
-                    .with_system(buttons::puzzle_button::<CellInput>.system())
                     .with_system(buttons::input_mode_buttons.system())
+                    .with_system(buttons::color_swatch_buttons.system())
                     // KEYBOARD
                     .with_system(keyboard::select_all.system())
+                    .with_system(keyboard::move_selection.system())
                     .with_system(keyboard::cell_input::cell_keyboard_input.system())
                     .with_system(keyboard::erase_selected_cells.system())
-                    .with_system(keyboard::swap_input_mode.system()),
+                    .with_system(keyboard::toggle_locks.system())
+                    .with_system(keyboard::swap_input_mode.system())
+                    .with_system(keyboard::toggle_digit_first.system())
+                    .with_system(keyboard::resume_bookmark.system())
+                    .with_system(keyboard::fill_candidates.system())
+                    .with_system(keyboard::game_controls.system())
+                    .with_system(keyboard::ask_for_coaching.system())
+                    .with_system(keyboard::scramble_remaining.system())
+                    .with_system(keyboard::share_puzzle.system())
+                    .with_system(keyboard::puzzle_files.system())
+                    .with_system(keyboard::toggle_variants.system())
+                    .with_system(keyboard::setter_controls.system()),
+            )
+            .add_system_set(
+                SystemSet::new()
+                    .label(CommonLabels::Input)
+                    // BUTTONS
+                    .with_system(buttons::puzzle_button::<buttons::NewPuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ResetPuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::QuitGame>.system())
+                    .with_system(buttons::puzzle_button::<buttons::SolvePuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::RemixPuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::GetHint>.system())
+                    .with_system(buttons::puzzle_button::<buttons::FindMistake>.system())
+                    .with_system(buttons::puzzle_button::<buttons::CheckPuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::FillCandidates>.system())
+                    .with_system(buttons::puzzle_button::<buttons::BookmarkPuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::CancelDialog>.system())
+                    .with_system(buttons::puzzle_button::<buttons::BrowseCollection>.system())
+                    .with_system(buttons::puzzle_button::<buttons::PlayCollectionPuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::CycleArchiveFilter>.system())
+                    .with_system(buttons::puzzle_button::<buttons::StarPuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ShowLevelSelect>.system())
+                    .with_system(buttons::puzzle_button::<buttons::PlayLevel>.system())
+                    .with_system(buttons::puzzle_button::<buttons::SkipTutorial>.system())
+                    .with_system(buttons::puzzle_button::<buttons::TrainTechnique>.system())
+                    .with_system(buttons::puzzle_button::<buttons::CoachPlayer>.system())
+                    .with_system(buttons::puzzle_button::<buttons::StopTraining>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ToggleRules>.system())
+                    .with_system(buttons::puzzle_button::<buttons::LockPuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::MinimizePuzzle>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ShowSettings>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ChooseLanguage>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ToggleSound>.system())
+                    .with_system(buttons::puzzle_button::<buttons::CycleScrambleSize>.system())
+                    .with_system(buttons::puzzle_button::<buttons::CycleGridSize>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ShowStats>.system())
+                    .with_system(buttons::puzzle_button::<buttons::EnterSeed>.system())
+                    .with_system(buttons::puzzle_button::<buttons::PlaySeed>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ShowProfiles>.system())
+                    .with_system(buttons::puzzle_button::<buttons::ChooseProfile>.system())
+                    .with_system(buttons::puzzle_button::<buttons::AddProfile>.system())
+                    .with_system(buttons::puzzle_button::<buttons::CreateProfile>.system())
+                    .with_system(buttons::puzzle_button::<ScrambleRemaining>.system())
+                    .with_system(buttons::puzzle_button::<CellInput>.system())
+                    // MENU NAVIGATION
+                    .with_system(focus::track_focus.system().label(FocusLabel))
+                    .with_system(focus::move_focus.system().after(FocusLabel))
+                    .with_system(focus::activate_focused::<buttons::NewPuzzle>.system())
+                    .with_system(focus::activate_focused::<buttons::BookmarkPuzzle>.system())
+                    .with_system(focus::activate_focused::<buttons::CancelDialog>.system())
+                    .with_system(focus::activate_focused::<buttons::ResetPuzzle>.system())
+                    .with_system(focus::activate_focused::<buttons::QuitGame>.system())
+                    .with_system(focus::activate_focused::<buttons::BrowseCollection>.system())
+                    .with_system(focus::activate_focused::<buttons::PlayCollectionPuzzle>.system())
+                    .with_system(focus::activate_focused::<buttons::CycleArchiveFilter>.system())
+                    .with_system(focus::activate_focused::<buttons::StarPuzzle>.system())
+                    .with_system(focus::activate_focused::<buttons::ShowLevelSelect>.system())
+                    .with_system(focus::activate_focused::<buttons::PlayLevel>.system())
+                    .with_system(focus::activate_focused::<buttons::TrainTechnique>.system())
+                    .with_system(focus::activate_focused::<buttons::ChooseLanguage>.system())
+                    .with_system(focus::activate_focused::<buttons::ToggleSound>.system())
+                    .with_system(focus::activate_focused::<buttons::CycleScrambleSize>.system())
+                    .with_system(focus::activate_focused::<buttons::CycleGridSize>.system())
+                    .with_system(focus::activate_focused::<buttons::ShowStats>.system())
+                    .with_system(focus::activate_focused::<buttons::EnterSeed>.system())
+                    .with_system(focus::activate_focused::<buttons::PlaySeed>.system())
+                    .with_system(focus::activate_focused::<buttons::ShowProfiles>.system())
+                    .with_system(focus::activate_focused::<buttons::ChooseProfile>.system())
+                    .with_system(focus::activate_focused::<buttons::AddProfile>.system())
+                    .with_system(focus::activate_focused::<buttons::CreateProfile>.system())
+                    .with_system(focus::go_back.system()),
             );
     }
 }
 
+/// Focus must be on a live button before it can be moved
+#[derive(SystemLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+struct FocusLabel;
+
 /// Marker component for selected cells
 #[derive(Debug)]
 pub struct Selected;
@@ -58,3 +204,25 @@ pub struct Selected;
 pub struct CellInput {
     pub num: u8,
 }
+
+/// Event to return to the most recently bookmarked puzzle
+pub struct ResumeBookmark;
+
+/// Event to replace the unfilled cells with a fresh practice position that agrees with the filled ones
+#[derive(Default, Clone)]
+pub struct ScrambleRemaining;
+
+/// Event to copy a link to the current puzzle to the clipboard
+pub struct SharePuzzle;
+
+/// Event to choose an `.sdk` file to play the puzzle in
+pub struct OpenPuzzleFile;
+
+/// Event to save the current puzzle and the player's progress to an `.sdk` file
+pub struct SavePuzzleFile;
+
+/// Event to clear the board, so that the player can set a puzzle of their own
+pub struct SetPuzzle;
+
+/// Event to switch whether newly generated puzzles follow a variant rule
+pub struct ToggleVariant(pub Variant);
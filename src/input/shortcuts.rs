@@ -0,0 +1,300 @@
+/// Keyboard shortcuts for the game's controls, gathered into one map so that no two controls share a key
+///
+/// Each shortcut is triggered by its key with exactly its modifier held,
+/// so Ctrl + O opens a file without also pressing the O shortcut beneath it.
+/// Keys reserved for entering digits, such as the letters of a 16x16 grid, trigger no shortcut without a modifier
+use crate::logic::constraints::Variant;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+
+/// The modifier held along with the key of a shortcut
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Modifier {
+    /// Neither Ctrl nor Alt is held
+    Plain,
+    Ctrl,
+    Alt,
+}
+
+impl Modifier {
+    /// The modifier being held, or `None` if both Ctrl and Alt are, which no shortcut uses
+    ///
+    /// Shift is left out, as it extends the selection instead
+    pub fn held(keyboard_input: &Input<KeyCode>) -> Option<Modifier> {
+        let ctrl =
+            keyboard_input.pressed(KeyCode::LControl) || keyboard_input.pressed(KeyCode::RControl);
+        let alt = keyboard_input.pressed(KeyCode::LAlt) || keyboard_input.pressed(KeyCode::RAlt);
+
+        match (ctrl, alt) {
+            (false, false) => Some(Modifier::Plain),
+            (true, false) => Some(Modifier::Ctrl),
+            (false, true) => Some(Modifier::Alt),
+            (true, true) => None,
+        }
+    }
+}
+
+/// The controls that can be reached from the keyboard
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Shortcut {
+    // BOARD
+    SelectAll,
+    ToggleLocks,
+    FillMode,
+    CenterMarkMode,
+    CornerMarkMode,
+    ColorMode,
+    DigitFirst,
+    FillCandidates,
+    // GAME CONTROLS
+    NewPuzzle,
+    ResetPuzzle,
+    SolvePuzzle,
+    RemixPuzzle,
+    EnterSeed,
+    GetHint,
+    FindMistake,
+    CheckPuzzle,
+    ResumeBookmark,
+    ScrambleRemaining,
+    SharePuzzle,
+    OpenPuzzleFile,
+    SavePuzzleFile,
+    ToggleVariant(Variant),
+    // SETTING PUZZLES
+    SetPuzzle,
+    MinimizePuzzle,
+    LockPuzzle,
+    // MENUS
+    LevelSelect,
+    Collection,
+    Archive,
+    Techniques,
+    CoachPlayer,
+    RestartTutorial,
+    ShowSettings,
+    // SETTINGS
+    MatchMarks,
+    DisplayPreset,
+    RemoveCandidates,
+    SnyderMarks,
+    LowSpec,
+    DisableCompletedDigits,
+    LimitedMistakes,
+    AutoCheck,
+    ShowProvenance,
+    InputModePerSelection,
+    CycleLanguage,
+    ReduceMotion,
+    // TOOLS
+    Likelihoods,
+    LinkGraph,
+    AnalyzePosition,
+    CaptureFrames,
+    AccessibilityAudit,
+}
+
+impl Shortcut {
+    /// Every control, in the order they are declared, for checking that each can be reached
+    pub fn all() -> Vec<Shortcut> {
+        use Shortcut::*;
+
+        let fixed = [
+            SelectAll,
+            ToggleLocks,
+            FillMode,
+            CenterMarkMode,
+            CornerMarkMode,
+            ColorMode,
+            DigitFirst,
+            FillCandidates,
+            NewPuzzle,
+            ResetPuzzle,
+            SolvePuzzle,
+            RemixPuzzle,
+            EnterSeed,
+            GetHint,
+            FindMistake,
+            CheckPuzzle,
+            ResumeBookmark,
+            ScrambleRemaining,
+            SharePuzzle,
+            OpenPuzzleFile,
+            SavePuzzleFile,
+        ];
+        let variants = Variant::ALL.iter().map(|variant| ToggleVariant(*variant));
+        let rest = [
+            SetPuzzle,
+            MinimizePuzzle,
+            LockPuzzle,
+            LevelSelect,
+            Collection,
+            Archive,
+            Techniques,
+            CoachPlayer,
+            RestartTutorial,
+            ShowSettings,
+            MatchMarks,
+            DisplayPreset,
+            RemoveCandidates,
+            SnyderMarks,
+            LowSpec,
+            DisableCompletedDigits,
+            LimitedMistakes,
+            AutoCheck,
+            ShowProvenance,
+            InputModePerSelection,
+            CycleLanguage,
+            ReduceMotion,
+            Likelihoods,
+            LinkGraph,
+            AnalyzePosition,
+            CaptureFrames,
+            AccessibilityAudit,
+        ];
+
+        fixed
+            .iter()
+            .copied()
+            .chain(variants)
+            .chain(rest.iter().copied())
+            .collect()
+    }
+}
+
+/// Resource storing the key and modifier of each shortcut
+pub struct Shortcuts {
+    map: HashMap<(Modifier, KeyCode), Shortcut>,
+    /// Keys currently used to enter digits, whose plain shortcuts are set aside
+    reserved: HashSet<KeyCode>,
+}
+
+impl Shortcuts {
+    /// Binds the shortcut to the key and modifier provided, which must not be taken already
+    fn insert(&mut self, modifier: Modifier, key_code: KeyCode, shortcut: Shortcut) {
+        let previous = self.map.insert((modifier, key_code), shortcut);
+        assert!(
+            previous.is_none(),
+            "{:?} and {:?} share a key",
+            previous,
+            shortcut
+        );
+    }
+
+    /// Sets aside these keys for entering digits, replacing any reserved before,
+    /// so that pressing them without a modifier no longer triggers their shortcuts
+    pub fn reserve(&mut self, keys: impl IntoIterator<Item = KeyCode>) {
+        self.reserved = keys.into_iter().collect();
+    }
+
+    /// The key and modifier that trigger the shortcut, or `None` if it has no key
+    /// or its key is set aside for entering digits
+    pub fn binding(&self, shortcut: Shortcut) -> Option<(Modifier, KeyCode)> {
+        self.map
+            .iter()
+            .find(|(_, bound)| **bound == shortcut)
+            .map(|(binding, _)| *binding)
+            .filter(|(modifier, key_code)| {
+                *modifier != Modifier::Plain || !self.reserved.contains(key_code)
+            })
+    }
+
+    /// Was the key of the shortcut just pressed, with its modifier held
+    pub fn just_pressed(&self, shortcut: Shortcut, keyboard_input: &Input<KeyCode>) -> bool {
+        let modifier = match Modifier::held(keyboard_input) {
+            Some(modifier) => modifier,
+            None => return false,
+        };
+
+        keyboard_input
+            .get_just_pressed()
+            .filter(|key_code| modifier != Modifier::Plain || !self.reserved.contains(key_code))
+            .any(|key_code| self.map.get(&(modifier, *key_code)) == Some(&shortcut))
+    }
+}
+
+impl Default for Shortcuts {
+    fn default() -> Self {
+        use KeyCode::*;
+        use Modifier::*;
+        use Shortcut::*;
+
+        let mut shortcuts = Shortcuts {
+            map: HashMap::default(),
+            reserved: HashSet::default(),
+        };
+
+        // Board
+        shortcuts.insert(Ctrl, A, SelectAll);
+        shortcuts.insert(Plain, L, ToggleLocks);
+        shortcuts.insert(Plain, Q, FillMode);
+        shortcuts.insert(Plain, W, CenterMarkMode);
+        shortcuts.insert(Plain, E, CornerMarkMode);
+        shortcuts.insert(Plain, T, ColorMode);
+        shortcuts.insert(Plain, R, DigitFirst);
+        shortcuts.insert(Plain, C, FillCandidates);
+
+        // Game controls
+        shortcuts.insert(Plain, N, NewPuzzle);
+        shortcuts.insert(Plain, X, ResetPuzzle);
+        shortcuts.insert(Plain, V, SolvePuzzle);
+        shortcuts.insert(Plain, Y, RemixPuzzle);
+        shortcuts.insert(Ctrl, E, EnterSeed);
+        shortcuts.insert(Plain, J, GetHint);
+        shortcuts.insert(Plain, F, FindMistake);
+        shortcuts.insert(Plain, D, CheckPuzzle);
+        shortcuts.insert(Plain, B, ResumeBookmark);
+        shortcuts.insert(Plain, P, ScrambleRemaining);
+        shortcuts.insert(Plain, S, SharePuzzle);
+        shortcuts.insert(Ctrl, O, OpenPuzzleFile);
+        shortcuts.insert(Ctrl, S, SavePuzzleFile);
+
+        // Variants each take the Alt version of a key
+        shortcuts.insert(Alt, N, ToggleVariant(Variant::AntiKnight));
+        shortcuts.insert(Alt, K, ToggleVariant(Variant::AntiKing));
+        shortcuts.insert(Alt, C, ToggleVariant(Variant::NonConsecutive));
+        shortcuts.insert(Alt, J, ToggleVariant(Variant::Jigsaw));
+        shortcuts.insert(Alt, W, ToggleVariant(Variant::Windoku));
+        shortcuts.insert(Alt, D, ToggleVariant(Variant::DisjointGroups));
+        shortcuts.insert(Alt, X, ToggleVariant(Variant::Diagonals));
+
+        // Setting puzzles
+        shortcuts.insert(Ctrl, N, SetPuzzle);
+        shortcuts.insert(Ctrl, M, MinimizePuzzle);
+        shortcuts.insert(Ctrl, L, LockPuzzle);
+
+        // Menus
+        shortcuts.insert(Plain, M, LevelSelect);
+        shortcuts.insert(Plain, K, Collection);
+        shortcuts.insert(Plain, H, Archive);
+        shortcuts.insert(Plain, G, Techniques);
+        shortcuts.insert(Plain, Z, CoachPlayer);
+        shortcuts.insert(Plain, Slash, RestartTutorial);
+        shortcuts.insert(Ctrl, Comma, ShowSettings);
+
+        // Settings
+        shortcuts.insert(Plain, F1, MatchMarks);
+        shortcuts.insert(Plain, F2, DisplayPreset);
+        shortcuts.insert(Plain, F3, RemoveCandidates);
+        shortcuts.insert(Plain, F4, SnyderMarks);
+        shortcuts.insert(Plain, F5, LowSpec);
+        shortcuts.insert(Plain, F6, DisableCompletedDigits);
+        shortcuts.insert(Plain, F7, LimitedMistakes);
+        shortcuts.insert(Plain, F8, AutoCheck);
+        shortcuts.insert(Plain, O, ShowProvenance);
+        shortcuts.insert(Ctrl, I, InputModePerSelection);
+        shortcuts.insert(Plain, I, CycleLanguage);
+        shortcuts.insert(Plain, U, ReduceMotion);
+
+        // Analysis and debugging tools
+        shortcuts.insert(Plain, F9, Likelihoods);
+        shortcuts.insert(Plain, F10, LinkGraph);
+        shortcuts.insert(Plain, F11, AnalyzePosition);
+        shortcuts.insert(Plain, F12, CaptureFrames);
+        shortcuts.insert(Ctrl, F12, AccessibilityAudit);
+
+        shortcuts
+    }
+}
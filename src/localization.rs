@@ -0,0 +1,159 @@
+/// Translations of the text shown to the player, so the game isn't English-only
+///
+/// Each language has a Fluent file in `assets/locales`, and text is looked up by the id of its message
+use crate::settings::Settings;
+use bevy::{prelude::*, utils::HashMap};
+use fluent::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use unic_langid::LanguageIdentifier;
+
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Localization>()
+            .add_system(switch_language.system().label(LocalizationLabel))
+            .add_system(localize_text.system().after(LocalizationLabel));
+    }
+}
+
+/// The language must be switched before text is translated into it
+#[derive(SystemLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+struct LocalizationLabel;
+
+/// A language the game has been translated into
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    French,
+}
+
+impl Language {
+    /// Every language, starting with the one other translations fall back to
+    pub const ALL: [Language; 2] = [Language::English, Language::French];
+
+    /// The language that follows this one when cycling through them
+    pub fn next(self) -> Language {
+        use Language::*;
+        match self {
+            English => French,
+            French => English,
+        }
+    }
+
+    /// The name of the language, written in that language so that players can always find their own
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+        }
+    }
+
+    /// The Unicode identifier of the language, which decides how numbers and plurals are written
+    fn identifier(self) -> &'static str {
+        match self {
+            Language::English => "en-US",
+            Language::French => "fr",
+        }
+    }
+
+    /// The translations, which are built into the game so that it can't start without them
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../assets/locales/en-US.ftl"),
+            Language::French => include_str!("../assets/locales/fr.ftl"),
+        }
+    }
+}
+
+/// Resource storing the translations into each language, and the language being shown
+pub struct Localization {
+    pub language: Language,
+    bundles: HashMap<Language, FluentBundle<FluentResource>>,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        let bundles = Language::ALL
+            .iter()
+            .map(|language| {
+                let identifier: LanguageIdentifier = language
+                    .identifier()
+                    .parse()
+                    .expect("Invalid language identifier");
+                let resource = FluentResource::try_new(language.source().to_string())
+                    .expect("Translations could not be parsed");
+
+                let mut bundle = FluentBundle::new_concurrent(vec![identifier]);
+                // Bevy's text rendering would show the invisible marks placed around arguments as boxes
+                bundle.set_use_isolating(false);
+                bundle
+                    .add_resource(resource)
+                    .expect("Translations contain duplicate messages");
+                (*language, bundle)
+            })
+            .collect();
+
+        Localization {
+            language: Language::English,
+            bundles,
+        }
+    }
+}
+
+impl Localization {
+    /// Looks up the message with this id in the current language
+    pub fn get(&self, id: &str) -> String {
+        self.format(id, &[])
+    }
+
+    /// Looks up the message with this id in the current language, filling in the arguments provided
+    ///
+    /// Messages missing from a translation are shown in English, or as their id if they are missing there too
+    pub fn format(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+
+        for language in [self.language, Language::English].iter() {
+            let bundle = &self.bundles[language];
+            if let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) {
+                let mut errors = Vec::new();
+                let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+                for error in errors {
+                    warn!("Could not translate {}: {:?}", id, error);
+                }
+                return text.into_owned();
+            }
+        }
+
+        warn!("No translation found for {}", id);
+        id.to_string()
+    }
+}
+
+/// Component for text that is always the message with this id, translated into the current language
+pub struct LocalizedText(pub &'static str);
+
+/// Shows text in the language chosen in the settings
+fn switch_language(settings: Res<Settings>, mut localization: ResMut<Localization>) {
+    if settings.is_changed() && localization.language != settings.language {
+        localization.language = settings.language;
+    }
+}
+
+/// Translates newly spawned text, and all text once the language changes
+fn localize_text(
+    localization: Res<Localization>,
+    added_query: Query<(), Added<LocalizedText>>,
+    mut text_query: Query<(&LocalizedText, &mut Text)>,
+) {
+    if !localization.is_changed() && added_query.iter().next().is_none() {
+        return;
+    }
+
+    for (localized_text, mut text) in text_query.iter_mut() {
+        text.sections[0].value = localization.get(localized_text.0);
+    }
+}
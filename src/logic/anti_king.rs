@@ -0,0 +1,30 @@
+/// Anti-king sudoku, where cells a chess king's move apart can't contain the same digit
+use crate::logic::constraints::Constraint;
+use serde_json::Value as JsonValue;
+
+/// The rule that cells touching, even diagonally, can't contain the same digit
+///
+/// There's nothing to draw, as the rule applies across the whole board
+pub struct AntiKing;
+
+impl Constraint for AntiKing {
+    /// Each pair of diagonally touching cells, counted once
+    ///
+    /// Cells touching along an edge already share a row or column
+    fn houses(&self) -> Vec<Vec<usize>> {
+        let mut houses = Vec::new();
+        for i in (0..72).filter(|i| i % 9 != 8) {
+            houses.push(vec![i, i + 10]);
+            houses.push(vec![i + 1, i + 9]);
+        }
+        houses
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-anti-king"]
+    }
+
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        Some(vec![("antiking", JsonValue::Bool(true))])
+    }
+}
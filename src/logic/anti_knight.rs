@@ -0,0 +1,34 @@
+/// Anti-knight sudoku, where cells a chess knight's move apart can't contain the same digit
+use crate::logic::constraints::Constraint;
+use serde_json::Value as JsonValue;
+
+/// The rule that cells a knight's move apart can't contain the same digit
+///
+/// There's nothing to draw, as the rule applies across the whole board
+pub struct AntiKnight;
+
+impl Constraint for AntiKnight {
+    /// Each pair of cells a knight's move apart, counted once
+    fn houses(&self) -> Vec<Vec<usize>> {
+        let moves = [(1, 2), (2, 1), (1, -2), (2, -1)];
+        let mut houses = Vec::new();
+        for i in 0..81 {
+            let (row, column) = ((i / 9) as i32, (i % 9) as i32);
+            for &(down, across) in moves.iter() {
+                let (other_row, other_column) = (row + down, column + across);
+                if (0..9).contains(&other_row) && (0..9).contains(&other_column) {
+                    houses.push(vec![i, (other_row * 9 + other_column) as usize]);
+                }
+            }
+        }
+        houses
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-anti-knight"]
+    }
+
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        Some(vec![("antiknight", JsonValue::Bool(true))])
+    }
+}
@@ -0,0 +1,325 @@
+/// A record of every puzzle the player has played, kept on disk between sessions
+use crate::companion::CompanionMode;
+use crate::graphics::dialog::CollectionBrowser;
+use crate::input::{
+    buttons::{BrowseCollection, CycleArchiveFilter, StarPuzzle},
+    focus::Focusable,
+    shortcuts::{Shortcut, Shortcuts},
+};
+use crate::logic::{
+    board::PuzzleSolved,
+    collections::{Collection, CollectionPuzzle, PAGE_SIZE},
+    constraints::ConstraintSet,
+    notation::Position,
+    records::Difficulty,
+    sudoku_generation::{InitialPuzzle, PuzzleMetadata, PuzzleSeed},
+};
+use crate::persistence::{check_integrity, load_json, reload_json, save_json, SaveFile};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct ArchivePlugin;
+
+impl Plugin for ArchivePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Archive>()
+            .add_startup_system(load_json::<Archive>.system())
+            .add_system(archive_puzzles.system())
+            .add_system(record_archived_solves.system())
+            .add_system(browse_archive.system())
+            .add_system(search_archive.system())
+            .add_system(star_puzzles.system())
+            .add_system_to_stage(CoreStage::PreUpdate, reload_json::<Archive>.system())
+            .add_system(save_json::<Archive>.system())
+            .add_system(check_integrity::<Archive>.system());
+    }
+}
+
+/// A puzzle the player has played, and how they have done on it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ArchiveEntry {
+    /// The givens of the puzzle, as 81 characters with `.` for empty cells
+    pub givens: String,
+    pub seed: Option<u64>,
+    pub title: Option<String>,
+    /// Puzzles without a unique solution have no difficulty
+    pub difficulty: Option<Difficulty>,
+    pub solved: bool,
+    /// Starred puzzles are kept aside for the player to play again later
+    #[serde(default)]
+    pub starred: bool,
+}
+
+impl ArchiveEntry {
+    /// Does the puzzle's title, seed or givens contain the text searched for, ignoring case
+    fn matches(&self, search: &str) -> bool {
+        let search = search.to_lowercase();
+        let title = self.title.as_deref().unwrap_or_default().to_lowercase();
+        let seed = self.seed.map(|seed| seed.to_string()).unwrap_or_default();
+
+        [title, seed, self.givens.clone()]
+            .iter()
+            .any(|text| text.contains(&search))
+    }
+}
+
+/// Resource storing every puzzle the player has played, in the order they were first played
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct Archive {
+    pub entries: Vec<ArchiveEntry>,
+}
+
+/// The archive is stored next to the game, like frame captures
+impl SaveFile for Archive {
+    const PATH: &'static str = "archive.json";
+    const DESCRIPTION: &'static str = "puzzle archive";
+    const NAME_ID: &'static str = "save-archive";
+}
+
+/// Which of the archived puzzles are listed in the browser
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArchiveFilter {
+    All,
+    Starred,
+    Unsolved,
+    Easy,
+    Hard,
+}
+
+impl ArchiveFilter {
+    /// The filter that follows this one when cycling through them
+    pub fn next(self) -> ArchiveFilter {
+        use ArchiveFilter::*;
+        match self {
+            All => Starred,
+            Starred => Unsolved,
+            Unsolved => Easy,
+            Easy => Hard,
+            Hard => All,
+        }
+    }
+
+    pub fn accepts(self, entry: &ArchiveEntry) -> bool {
+        match self {
+            ArchiveFilter::All => true,
+            ArchiveFilter::Starred => entry.starred,
+            ArchiveFilter::Unsolved => !entry.solved,
+            ArchiveFilter::Easy => entry.difficulty == Some(Difficulty::Easy),
+            ArchiveFilter::Hard => entry.difficulty == Some(Difficulty::Hard),
+        }
+    }
+
+    /// The id of the message naming this filter
+    pub fn name_id(self) -> &'static str {
+        match self {
+            ArchiveFilter::All => "archive-filter-all",
+            ArchiveFilter::Starred => "archive-filter-starred",
+            ArchiveFilter::Unsolved => "archive-filter-unsolved",
+            ArchiveFilter::Easy => "archive-filter-easy",
+            ArchiveFilter::Hard => "archive-filter-hard",
+        }
+    }
+}
+
+impl Archive {
+    /// Lists the archived puzzles that pass the filter and match the search in the collection browser, newest first
+    pub fn to_collection(&self, filter: ArchiveFilter, search: &str) -> Collection {
+        let mut collection = Collection {
+            // The archive is titled by the collection browser, which names its filter
+            name: String::new(),
+            archive_filter: Some(filter),
+            archive_search: search.to_string(),
+            ..Default::default()
+        };
+
+        for entry in self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| filter.accepts(entry) && entry.matches(search))
+        {
+            let position = match Position::parse(&entry.givens) {
+                Ok(position) => position,
+                Err(_) => continue,
+            };
+            if entry.solved {
+                collection.completed.insert(collection.puzzles.len());
+            }
+            collection.puzzles.push(CollectionPuzzle {
+                position,
+                difficulty: entry.difficulty,
+                name: entry
+                    .title
+                    .clone()
+                    .or_else(|| entry.seed.map(|seed| format!("seed {}", seed))),
+                starred: entry.starred,
+            });
+        }
+        collection
+    }
+}
+
+/// Adds each new puzzle to the archive the first time it is played
+fn archive_puzzles(
+    initial_puzzle: Res<InitialPuzzle>,
+    puzzle_seed: Res<PuzzleSeed>,
+    puzzle_metadata: Res<PuzzleMetadata>,
+    companion_mode: Res<CompanionMode>,
+    constraints: Res<ConstraintSet>,
+    mut archive: ResMut<Archive>,
+) {
+    // Companion puzzles are typed in as they are solved on paper, so have no fixed givens to archive
+    if !initial_puzzle.is_changed() || companion_mode.0 {
+        return;
+    }
+
+    let position = initial_puzzle.position();
+    if position.digits.iter().all(Option::is_none) {
+        return;
+    }
+
+    let givens = position.to_givens();
+    if archive.entries.iter().any(|entry| entry.givens == givens) {
+        return;
+    }
+
+    archive.entries.push(ArchiveEntry {
+        givens,
+        seed: puzzle_seed.0,
+        title: puzzle_metadata.title.clone(),
+        difficulty: position.grade(&constraints).map(Difficulty::from),
+        solved: false,
+        starred: false,
+    });
+}
+
+/// Marks archived puzzles as solved
+///
+/// Their solve times are kept with the rest of the player's best times
+fn record_archived_solves(
+    mut event_reader: EventReader<PuzzleSolved>,
+    initial_puzzle: Res<InitialPuzzle>,
+    mut archive: ResMut<Archive>,
+) {
+    for _ in event_reader.iter() {
+        let givens = initial_puzzle.position().to_givens();
+
+        if let Some(entry) = archive
+            .entries
+            .iter_mut()
+            .find(|entry| entry.givens == givens)
+        {
+            entry.solved = true;
+        }
+    }
+}
+
+/// Lists the archive in the collection browser when H is pressed,
+/// and moves on to the next filter when the browser's filter button is pressed
+fn browse_archive(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut filter_reader: EventReader<CycleArchiveFilter>,
+    focusable_query: Query<(), With<Focusable>>,
+    archive: Res<Archive>,
+    mut collection: ResMut<Collection>,
+    mut browse_writer: EventWriter<BrowseCollection>,
+) {
+    // Don't open the archive on top of another menu
+    let opened = shortcuts.just_pressed(Shortcut::Archive, &keyboard_input)
+        && focusable_query.iter().next().is_none();
+
+    // The search is kept as the filter changes, and cleared when the archive is opened again
+    let (filter, search) = if filter_reader.iter().count() > 0 {
+        let filter = collection
+            .archive_filter
+            .map_or(ArchiveFilter::All, ArchiveFilter::next);
+        (filter, collection.archive_search.clone())
+    } else if opened {
+        (ArchiveFilter::All, String::new())
+    } else {
+        return;
+    };
+
+    *collection = archive.to_collection(filter, &search);
+    browse_writer.send(BrowseCollection { page: 0 });
+}
+
+/// Stars the puzzle being played from the victory dialog, or toggles the star of a puzzle listed in the archive,
+/// listing the archive again so the change is shown
+fn star_puzzles(
+    mut event_reader: EventReader<StarPuzzle>,
+    initial_puzzle: Res<InitialPuzzle>,
+    mut archive: ResMut<Archive>,
+    mut collection: ResMut<Collection>,
+    mut browse_writer: EventWriter<BrowseCollection>,
+) {
+    for event in event_reader.iter() {
+        let givens = match event.0 {
+            Some(i) => match collection.puzzles.get(i) {
+                Some(puzzle) => puzzle.position.to_givens(),
+                None => continue,
+            },
+            None => initial_puzzle.position().to_givens(),
+        };
+
+        let entry = match archive
+            .entries
+            .iter_mut()
+            .find(|entry| entry.givens == givens)
+        {
+            Some(entry) => entry,
+            None => continue,
+        };
+        // The victory dialog can't show the star, so pressing it twice shouldn't take the star away
+        entry.starred = event.0.is_none() || !entry.starred;
+
+        if let (Some(i), Some(filter)) = (event.0, collection.archive_filter) {
+            let search = collection.archive_search.clone();
+            *collection = archive.to_collection(filter, &search);
+            browse_writer.send(BrowseCollection {
+                page: i / PAGE_SIZE,
+            });
+        }
+    }
+}
+
+/// Narrows the archive listed in the browser down to the puzzles whose title, seed or givens contain the text typed,
+/// with Backspace removing the last character typed
+///
+/// Spaces are left out, as they press the focused button
+fn search_archive(
+    mut char_reader: EventReader<ReceivedCharacter>,
+    keyboard_input: Res<Input<KeyCode>>,
+    browser_query: Query<(), With<CollectionBrowser>>,
+    archive: Res<Archive>,
+    mut collection: ResMut<Collection>,
+    mut browse_writer: EventWriter<BrowseCollection>,
+) {
+    // Every character must be read, so that text typed elsewhere isn't searched for once the archive is opened
+    let typed: String = char_reader
+        .iter()
+        .map(|event| event.char)
+        .filter(|character| character.is_alphanumeric() || *character == '.')
+        .collect();
+
+    let filter = match collection.archive_filter {
+        Some(filter) => filter,
+        None => return,
+    };
+    if browser_query.iter().next().is_none() {
+        return;
+    }
+
+    let mut search = collection.archive_search.clone();
+    search.push_str(&typed);
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        search.pop();
+    }
+    if search == collection.archive_search {
+        return;
+    }
+
+    *collection = archive.to_collection(filter, &search);
+    browse_writer.send(BrowseCollection { page: 0 });
+}
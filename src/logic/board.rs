@@ -1,55 +1,70 @@
 use crate::{
     input::{
-        board::CellClick,
-        input_mode::{update_value_center, update_value_corner, update_value_fill, InputMode},
+        board::{CellClick, ClickKind},
+        input_mode::{
+            update_value_center, update_value_corner, update_value_fill, ArmedDigit, InputMode,
+        },
         CellInput, Selected,
     },
+    logic::{constraints::ConstraintSet, sudoku_generation::CompletePuzzle},
+    settings::Settings,
     CommonLabels,
 };
 
 /// Core data structures and logic for the Sudoku game board
-use self::marks::{CenterMarks, CornerMarks};
+use self::marks::{CenterMarks, CornerMarks, Marks};
 use bevy::prelude::*;
+use bevy::utils::HashSet;
 
 pub struct LogicPlugin;
 
 impl Plugin for LogicPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        // ACTION HANDLING
-        app.add_system_set(
-            SystemSet::new()
-                .label(CommonLabels::Action)
-                .after(CommonLabels::Input)
-                .with_system(handle_clicks.system())
-                .with_system(set_cell_value.system()),
-        );
+        app.init_resource::<CompletedDigits>()
+            .add_event::<DigitPlaced>()
+            .add_event::<BlockedEdit>()
+            .add_event::<CellsErased>()
+            .add_event::<HouseCompleted>()
+            .add_event::<PuzzleSolved>()
+            // Input must be checked against the digits completed so far
+            .add_system(update_completed_digits.system().before(CommonLabels::Input))
+            // ACTION HANDLING
+            .add_system_set(
+                SystemSet::new()
+                    .label(CommonLabels::Action)
+                    .after(CommonLabels::Input)
+                    .with_system(handle_clicks.system())
+                    .with_system(place_armed_digit.system())
+                    .with_system(erase_swept_marks.system())
+                    .with_system(set_cell_value.system()),
+            )
+            // Houses and the puzzle can only be checked once the new digits are in place
+            .add_system(detect_completed_houses.system().after(CommonLabels::Action))
+            .add_system(
+                detect_solved_puzzle
+                    .system()
+                    .label(BoardLabels::DetectSolved)
+                    .after(CommonLabels::Action),
+            );
     }
 }
 
+#[derive(SystemLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+pub enum BoardLabels {
+    /// `PuzzleSolved` events are sent for the digits placed this frame
+    DetectSolved,
+}
+
 pub struct Cell;
+/// The position of a cell on the board
+///
+/// The region it is in depends on the puzzle, and is found with `ConstraintSet::regions`
 #[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct Coordinates {
-    /// Between 1 and 9, counted from top to bottom
+    /// From 1 up to the width of the grid, counted from top to bottom
     pub row: u8,
-    /// Between 1 and 9, counted from left to right
+    /// From 1 up to the width of the grid, counted from left to right
     pub column: u8,
-    /// Squares are counted from 1 to 9 starting at the top left,
-    /// in standard left-to-right reading order
-    ///
-    /// The standard term for the 3x3 box a cell is in is `box`,
-    /// but that's a reserved word in Rust
-    pub square: u8,
-}
-
-impl Coordinates {
-    /// Computes which 3x3 square a cell is in based on its row and column
-    pub fn compute_square(row: u8, column: u8) -> u8 {
-        const WIDTH: u8 = 3;
-        let major_row = (row - 1) / WIDTH;
-        let major_col = (column - 1) / WIDTH;
-
-        major_col + major_row * WIDTH + 1
-    }
 }
 
 /// The number(s) marked inside of each cell
@@ -64,8 +79,39 @@ pub enum Value {
 }
 
 impl Value {
+    /// Does this value contain `num`, either as its digit or optionally as one of its marks
+    pub fn matches(&self, num: u8, include_marks: bool) -> bool {
+        match self {
+            Value::Empty => false,
+            Value::Filled(n) => *n == num,
+            Value::Marked(center, corner) => {
+                include_marks && (center.contains(num) || corner.contains(num))
+            }
+        }
+    }
+
+    /// Removes the number from both the center and corner marks, if present
+    pub fn remove_mark(&self, num: u8) -> Value {
+        match self {
+            Value::Marked(center, corner) => {
+                let center = if center.contains(num) {
+                    center.update(num)
+                } else {
+                    center.clone()
+                };
+                let corner = if corner.contains(num) {
+                    corner.update(num)
+                } else {
+                    corner.clone()
+                };
+                Value::Marked(center, corner).cleanup()
+            }
+            _ => self.clone(),
+        }
+    }
+
     /// Converts empty marks into an empty cell state
-    fn cleanup(&mut self) -> Value {
+    pub fn cleanup(&mut self) -> Value {
         let empty_marks = Value::Marked(CenterMarks::default(), CornerMarks::default());
 
         if *self == empty_marks {
@@ -79,7 +125,67 @@ impl Value {
 /// A component that specifies whether digits were provided by the puzzle
 pub struct Fixed(pub bool);
 
+/// A component that specifies whether the player has locked in the digit they filled,
+/// protecting it from being overwritten or erased by accident
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Locked(pub bool);
+
+/// A component recording who filled in the digit of a cell that isn't fixed
+///
+/// Only meaningful while the cell is filled
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Provenance {
+    Player,
+    Hint,
+    Solver,
+}
+
+impl Default for Provenance {
+    fn default() -> Self {
+        Provenance::Player
+    }
+}
+
+/// The color the player has painted this cell, as an index into the palette from 1 to 9
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct CellColor(pub Option<u8>);
+
+impl CellColor {
+    /// Paints the cell with the color provided, or clears it if it was already that color
+    pub fn toggle(&mut self, color: u8) {
+        self.0 = match self.0 {
+            Some(old_color) if old_color == color => None,
+            _ => Some(color),
+        };
+    }
+}
+
+/// The fraction of the cells not given by the puzzle that have been filled in
+pub fn completion<'a>(cells: impl Iterator<Item = (&'a Value, &'a Fixed)>) -> f32 {
+    let (mut n_filled, mut n_fillable) = (0, 0);
+    for (value, is_fixed) in cells {
+        if !is_fixed.0 {
+            n_fillable += 1;
+            if let Value::Filled(_) = value {
+                n_filled += 1;
+            }
+        }
+    }
+
+    if n_fillable == 0 {
+        1.0
+    } else {
+        n_filled as f32 / n_fillable as f32
+    }
+}
+
+/// Has the player entered anything into the cells not given by the puzzle
+pub fn in_progress<'a>(mut cells: impl Iterator<Item = (&'a Value, &'a Fixed)>) -> bool {
+    cells.any(|(value, is_fixed)| !is_fixed.0 && *value != Value::Empty)
+}
+
 pub mod marks {
+    use crate::logic::grid::digit_symbol;
     use bevy::utils::HashSet;
     /// Marks are notes about the possible value of a cell
     pub trait Marks: PartialEq + Eq + Clone {
@@ -88,6 +194,15 @@ pub mod marks {
 
         /// Updates the value of the marks given a new input
         fn update(&self, num: u8) -> Self;
+
+        /// Are there no marks at all
+        fn is_empty(&self) -> bool;
+
+        /// Has this number been marked
+        fn contains(&self, num: u8) -> bool;
+
+        /// The numbers marked, in ascending order
+        fn digits(&self) -> Vec<u8>;
     }
     /// The value of this cell could be any of the possibilities written in the center of the cell
     #[derive(PartialEq, Eq, Clone, Default)]
@@ -109,6 +224,27 @@ pub mod marks {
             }
             out
         }
+
+        fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        fn contains(&self, num: u8) -> bool {
+            self.0.contains(&num)
+        }
+
+        fn digits(&self) -> Vec<u8> {
+            let mut vec: Vec<_> = self.0.iter().copied().collect();
+            vec.sort_unstable();
+            vec
+        }
+    }
+
+    impl CenterMarks {
+        /// Creates center marks containing each of the digits provided
+        pub fn from_digits(digits: impl IntoIterator<Item = u8>) -> CenterMarks {
+            CenterMarks(digits.into_iter().collect())
+        }
     }
 
     impl ToString for CenterMarks {
@@ -116,7 +252,10 @@ pub mod marks {
             let mut vec: Vec<_> = self.0.iter().collect();
             // We want to return the numbers in order, but our storage type is unordered
             vec.sort();
-            let maybe_string = vec.iter().map(|m| m.to_string()).reduce(|a, b| a + &b);
+            let maybe_string = vec
+                .iter()
+                .map(|m| digit_symbol(**m).to_string())
+                .reduce(|a, b| a + &b);
             match maybe_string {
                 Some(string) => string,
                 None => "".to_string(),
@@ -128,6 +267,16 @@ pub mod marks {
     #[derive(PartialEq, Eq, Clone, Default)]
     pub struct CornerMarks(HashSet<u8>);
 
+    /// Only this many corner marks fit around the edges of a cell, leaving its center for center marks
+    pub const MAX_CORNER_MARKS: usize = 8;
+
+    impl CornerMarks {
+        /// Is there no room around the edges of the cell for another mark
+        pub fn is_full(&self) -> bool {
+            self.0.len() >= MAX_CORNER_MARKS
+        }
+    }
+
     impl Marks for CornerMarks {
         fn new(num: u8) -> CornerMarks {
             let mut marks = CornerMarks::default();
@@ -144,6 +293,20 @@ pub mod marks {
             }
             out
         }
+
+        fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+
+        fn contains(&self, num: u8) -> bool {
+            self.0.contains(&num)
+        }
+
+        fn digits(&self) -> Vec<u8> {
+            let mut vec: Vec<_> = self.0.iter().copied().collect();
+            vec.sort_unstable();
+            vec
+        }
     }
 
     impl ToString for CornerMarks {
@@ -151,7 +314,10 @@ pub mod marks {
             let mut vec: Vec<_> = self.0.iter().collect();
             // We want to return the numbers in order, but our storage type is unordered
             vec.sort();
-            let maybe_string = vec.iter().map(|m| m.to_string()).reduce(|a, b| a + &b);
+            let maybe_string = vec
+                .iter()
+                .map(|m| digit_symbol(**m).to_string())
+                .reduce(|a, b| a + &b);
             match maybe_string {
                 Some(string) => string,
                 None => "".to_string(),
@@ -160,44 +326,463 @@ pub mod marks {
     }
 }
 
+/// Resource storing the digits that have every one of their cells correctly filled
+#[derive(Default)]
+pub struct CompletedDigits(pub HashSet<u8>);
+
+/// Keeps track of which digits are complete
+fn update_completed_digits(
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    complete_puzzle: Res<CompletePuzzle>,
+    constraints: Res<ConstraintSet>,
+    mut completed_digits: ResMut<CompletedDigits>,
+) {
+    if changed_query.iter().next().is_none() {
+        return;
+    }
+
+    // Each digit appears once in every box
+    let grid = constraints.grid();
+    let digits = grid.digits();
+    let mut correct_counts = vec![0; digits as usize];
+    for (coordinates, value) in query.iter() {
+        if let Value::Filled(num) = value {
+            if complete_puzzle.numbers.get(coordinates) == Some(value) && *num <= digits {
+                correct_counts[(*num - 1) as usize] += 1;
+            }
+        }
+    }
+
+    let completed: HashSet<u8> = (1..=digits)
+        .filter(|num| correct_counts[(*num - 1) as usize] == grid.digit_count())
+        .collect();
+
+    // Avoid triggering change detection every time a cell changes
+    if completed != completed_digits.0 {
+        completed_digits.0 = completed;
+    }
+}
+
+/// Computes the new value of a cell when `num` is entered using the `input_mode` provided
+pub fn apply_input(old_value: &Value, input_mode: InputMode, num: u8) -> Value {
+    use InputMode::*;
+    // The behavior of setting the cell's value varies based on which input mode we're in
+    match input_mode {
+        // Set the cell's value based on the event's contents
+        Fill => update_value_fill(old_value, num),
+        CenterMark => update_value_center(old_value, num).cleanup(),
+        CornerMark => update_value_corner(old_value, num).cleanup(),
+        // Colors are stored separately, in the `CellColor` component
+        Color => old_value.clone(),
+    }
+}
+
+/// Event sent whenever the player fills a digit into a cell
+pub struct DigitPlaced {
+    pub coordinates: Coordinates,
+    pub num: u8,
+}
+
+/// Event sent when the player clears the contents of one or more cells
+pub struct CellsErased;
+
+/// Event sent when the player tries to change a cell given by the puzzle or locked by them,
+/// or to add a corner mark to a cell with no room left for one
+pub struct BlockedEdit {
+    pub cell: Entity,
+}
+
+/// Event sent when a digit placed by the player completes a house with a cell for every digit, such as a row, without conflicts
+pub struct HouseCompleted {
+    /// The cells of the house, in reading order
+    pub cells: Vec<Coordinates>,
+}
+
+/// Checks the houses of each digit placed, looking for ones that are now complete
+fn detect_completed_houses(
+    mut event_reader: EventReader<DigitPlaced>,
+    mut event_writer: EventWriter<HouseCompleted>,
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+    constraints: Res<ConstraintSet>,
+) {
+    let grid = constraints.grid();
+    let placed: Vec<usize> = event_reader
+        .iter()
+        .filter(|event| grid.contains(&event.coordinates))
+        .map(|event| grid.index(&event.coordinates))
+        .collect();
+    if placed.is_empty() {
+        return;
+    }
+
+    let mut digits = vec![None; grid.cell_count()];
+    for (coordinates, value) in query.iter() {
+        if let (Value::Filled(num), true) = (value, grid.contains(coordinates)) {
+            digits[grid.index(coordinates)] = Some(*num);
+        }
+    }
+    // A digit that breaks a constraint spoils the house, even if the cell it clashes with lies outside it
+    let violations = constraints.violations(&digits);
+
+    // Each house is only checked once, even when several digits were placed in it at once
+    let houses = constraints.houses().iter().filter(|house| {
+        house.len() == grid.digits() as usize && placed.iter().any(|i| house.contains(i))
+    });
+    for house in houses {
+        let complete = house
+            .iter()
+            .all(|&i| digits[i].is_some() && !violations.contains(&i));
+
+        if complete {
+            let mut cells = house.clone();
+            cells.sort_unstable();
+            event_writer.send(HouseCompleted {
+                cells: cells.into_iter().map(|i| grid.coordinates(i)).collect(),
+            });
+        }
+    }
+}
+
+/// Event sent when a digit placed by the player completes the puzzle
+pub struct PuzzleSolved;
+
+/// Checks whether the puzzle matches its solution once the player places a digit
+fn detect_solved_puzzle(
+    mut event_reader: EventReader<DigitPlaced>,
+    mut event_writer: EventWriter<PuzzleSolved>,
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+    complete_puzzle: Res<CompletePuzzle>,
+) {
+    // Several digits may be placed at once, but the puzzle is only solved once
+    if event_reader.iter().count() == 0 {
+        return;
+    }
+
+    let solved = query
+        .iter()
+        .all(|(coordinates, value)| complete_puzzle.numbers.get(coordinates) == Some(value));
+    if solved {
+        event_writer.send(PuzzleSolved);
+    }
+}
+
 /// Set the value of the selected cells from cell input events
 pub fn set_cell_value(
-    mut query: Query<(&mut Value, &Fixed), With<Selected>>,
+    mut queries: QuerySet<(
+        Query<
+            (
+                Entity,
+                &Coordinates,
+                &mut Value,
+                &mut CellColor,
+                &Fixed,
+                &Locked,
+                &mut Provenance,
+            ),
+            With<Selected>,
+        >,
+        Query<(&Coordinates, &mut Value), With<Cell>>,
+    )>,
     input_mode: Res<InputMode>,
+    settings: Res<Settings>,
+    completed_digits: Res<CompletedDigits>,
+    constraints: Res<ConstraintSet>,
+    mut armed_digit: ResMut<ArmedDigit>,
     mut event_reader: EventReader<CellInput>,
+    mut placed_writer: EventWriter<DigitPlaced>,
+    mut blocked_writer: EventWriter<BlockedEdit>,
 ) {
-    use InputMode::*;
+    let mut placed = Vec::new();
+
     // FIXME: match on event's input type to control behavior
     // Existing logic is for Fill only
     for event in event_reader.iter() {
-        for (mut old_value, is_fixed) in query.iter_mut() {
+        // Completed digits have nowhere left to go, though their numbers still pick colors
+        if settings.disable_completed_digits
+            && *input_mode != InputMode::Color
+            && completed_digits.0.contains(&event.num)
+        {
+            continue;
+        }
+        // Nor do digits beyond those used by the size of grid being played, or colors beyond the palette
+        let limit = match *input_mode {
+            InputMode::Color => 9,
+            _ => constraints.grid().digits(),
+        };
+        if event.num > limit {
+            continue;
+        }
+
+        // In digit-first mode, inputs choose the digit to place rather than changing the selection
+        if armed_digit.enabled {
+            armed_digit.toggle(event.num);
+            continue;
+        }
+
+        // When marking several cells at once, the mark is removed only if every cell has it,
+        // and is otherwise added to the cells missing it, so the selection ends up consistent.
+        // Filled cells can't hold marks, so they have no say
+        let all_marked = queries
+            .q0()
+            .iter()
+            .filter(|(_, _, value, _, is_fixed, is_locked, _)| {
+                !is_fixed.0 && !is_locked.0 && !matches!(value, Value::Filled(_))
+            })
+            .all(|(_, _, value, _, _, _, _)| has_mark(value, *input_mode, event.num));
+
+        for (
+            entity,
+            coordinates,
+            mut old_value,
+            mut cell_color,
+            is_fixed,
+            is_locked,
+            mut provenance,
+        ) in queries.q0_mut().iter_mut()
+        {
+            // Any cell can be painted, including those given by the puzzle
+            if *input_mode == InputMode::Color {
+                cell_color.toggle(event.num);
+                continue;
+            }
+
             // Don't change the values of cells given by the puzzle
             if is_fixed.0 {
-                break;
+                blocked_writer.send(BlockedEdit { cell: entity });
+                continue;
+            }
+
+            // Nor those the player has locked in
+            if is_locked.0 {
+                blocked_writer.send(BlockedEdit { cell: entity });
+                continue;
+            }
+
+            if has_mark(&*old_value, *input_mode, event.num) && !all_marked {
+                continue;
+            }
+
+            if corner_marks_full(&*old_value, *input_mode, event.num) {
+                blocked_writer.send(BlockedEdit { cell: entity });
+                continue;
+            }
+
+            let new_value = apply_input(&*old_value, *input_mode, event.num);
+            if is_placement(&*old_value, &new_value) {
+                placed.push((coordinates.clone(), event.num));
+                *provenance = Provenance::Player;
+            }
+            *old_value = new_value;
+        }
+    }
+
+    if settings.remove_candidates {
+        remove_candidates(queries.q1_mut(), &placed, &constraints);
+    }
+
+    for (coordinates, num) in placed {
+        placed_writer.send(DigitPlaced { coordinates, num });
+    }
+}
+
+/// Does the cell already have `num` marked in the way the input mode would mark it
+fn has_mark(value: &Value, input_mode: InputMode, num: u8) -> bool {
+    match (value, input_mode) {
+        (Value::Marked(center, _), InputMode::CenterMark) => center.contains(num),
+        (Value::Marked(_, corner), InputMode::CornerMark) => corner.contains(num),
+        _ => false,
+    }
+}
+
+/// Would the input add a corner mark to a cell whose edges already hold as many as fit
+fn corner_marks_full(value: &Value, input_mode: InputMode, num: u8) -> bool {
+    match (value, input_mode) {
+        (Value::Marked(_, corner), InputMode::CornerMark) => {
+            !corner.contains(num) && corner.is_full()
+        }
+        _ => false,
+    }
+}
+
+/// Was a digit just filled into the cell
+fn is_placement(old_value: &Value, new_value: &Value) -> bool {
+    matches!(new_value, Value::Filled(_)) && new_value != old_value
+}
+
+/// Strips each placed digit from the marks of the cells that can see it
+fn remove_candidates(
+    query: &mut Query<(&Coordinates, &mut Value), With<Cell>>,
+    placed: &[(Coordinates, u8)],
+    constraints: &ConstraintSet,
+) {
+    for (placed_coordinates, num) in placed {
+        for (coordinates, mut value) in query.iter_mut() {
+            if constraints.sees_cells(coordinates, placed_coordinates) {
+                *value = value.remove_mark(*num);
+            }
+        }
+    }
+}
+
+/// Places the armed digit into cells as they are clicked when using digit-first input
+pub fn place_armed_digit(
+    mut cell_click_events: EventReader<CellClick>,
+    mut queries: QuerySet<(
+        Query<
+            (
+                &Coordinates,
+                &mut Value,
+                &mut CellColor,
+                &Fixed,
+                &Locked,
+                &mut Provenance,
+            ),
+            With<Cell>,
+        >,
+        Query<(&Coordinates, &mut Value), With<Cell>>,
+    )>,
+    input_mode: Res<InputMode>,
+    armed_digit: Res<ArmedDigit>,
+    settings: Res<Settings>,
+    completed_digits: Res<CompletedDigits>,
+    constraints: Res<ConstraintSet>,
+    mut placed_writer: EventWriter<DigitPlaced>,
+    mut blocked_writer: EventWriter<BlockedEdit>,
+) {
+    let num = match armed_digit.armed() {
+        Some(num) => num,
+        None => return,
+    };
+
+    // Completed digits have nowhere left to go, just as when they are typed
+    if settings.disable_completed_digits
+        && *input_mode != InputMode::Color
+        && completed_digits.0.contains(&num)
+    {
+        return;
+    }
+
+    let mut placed = Vec::new();
+
+    for click_event in cell_click_events.iter() {
+        // Dragging would toggle the digit on and off every frame
+        if click_event.drag || click_event.kind != ClickKind::Primary {
+            continue;
+        }
+
+        if let Some(entity) = click_event.selected_cell {
+            if let Ok((
+                coordinates,
+                mut value,
+                mut cell_color,
+                is_fixed,
+                is_locked,
+                mut provenance,
+            )) = queries.q0_mut().get_mut(entity)
+            {
+                if *input_mode == InputMode::Color {
+                    cell_color.toggle(num);
+                // Don't change the values of cells given by the puzzle or locked by the player,
+                // nor add corner marks that won't fit
+                } else if is_fixed.0 || is_locked.0 || corner_marks_full(&*value, *input_mode, num)
+                {
+                    blocked_writer.send(BlockedEdit { cell: entity });
+                } else {
+                    let new_value = apply_input(&*value, *input_mode, num);
+                    if is_placement(&*value, &new_value) {
+                        placed.push((coordinates.clone(), num));
+                        *provenance = Provenance::Player;
+                    }
+                    *value = new_value;
+                }
             }
+        }
+    }
+
+    if settings.remove_candidates {
+        remove_candidates(queries.q1_mut(), &placed, &constraints);
+    }
 
-            // The behavior of setting the cell's value varies based on which input mode we're in
-            *old_value = match *input_mode {
-                // Set the cell's value based on the event's contents
-                Fill => update_value_fill(&*old_value, event.num),
-                CenterMark => update_value_center(&*old_value, event.num).cleanup(),
-                CornerMark => update_value_corner(&*old_value, event.num).cleanup(),
+    for (coordinates, num) in placed {
+        placed_writer.send(DigitPlaced { coordinates, num });
+    }
+}
+
+/// Erases the marks in cells that are right-click dragged over, including the cell the drag started from
+pub fn erase_swept_marks(
+    mut cell_click_events: EventReader<CellClick>,
+    mut query: Query<&mut Value, With<Cell>>,
+    mut event_writer: EventWriter<CellsErased>,
+    mut drag_start: Local<Option<Entity>>,
+) {
+    let mut erased = false;
+    for click_event in cell_click_events.iter() {
+        if click_event.kind != ClickKind::Secondary {
+            continue;
+        }
+        // Right clicks without dragging deselect cells instead,
+        // but their cell is swept too if the mouse goes on to be dragged
+        if !click_event.drag {
+            *drag_start = click_event.selected_cell;
+            continue;
+        }
+
+        for entity in drag_start
+            .take()
+            .into_iter()
+            .chain(click_event.selected_cell)
+        {
+            if let Ok(mut value) = query.get_mut(entity) {
+                // Marks can never be in fixed cells, so there's no need to check
+                if let Value::Marked(_, _) = *value {
+                    *value = Value::Empty;
+                    erased = true;
+                }
             }
         }
     }
+
+    if erased {
+        event_writer.send(CellsErased);
+    }
 }
 
 /// Selects cells based on the clicks received
 pub fn handle_clicks(
     mut cell_click_events: EventReader<CellClick>,
     cell_query: Query<(Entity, Option<&Selected>, &Value), With<Cell>>,
+    armed_digit: Res<ArmedDigit>,
+    settings: Res<Settings>,
     mut commands: Commands,
 ) {
     // Usually there's just going to be one of these per frame
     // But we may as well loop through all just in case
     for click_event in cell_click_events.iter() {
+        // Right clicks remove the clicked cell from the selection
+        if click_event.kind == ClickKind::Secondary {
+            if let (Some(entity), false) = (click_event.selected_cell, click_event.drag) {
+                commands.entity(entity).remove::<Selected>();
+            }
+            continue;
+        }
+
+        // Clicks place the armed digit instead of selecting cells in digit-first mode
+        if armed_digit.armed().is_some() {
+            continue;
+        }
+
+        // Rectangle selection replaces the selection with every cell in the rectangle
+        if let Some(lasso) = &click_event.lasso {
+            for (entity, _, _) in cell_query.iter() {
+                commands.entity(entity).remove::<Selected>();
+            }
+
+            for entity in lasso.iter() {
+                commands.entity(*entity).insert(Selected);
+            }
         // If the user clicks outside of the grid, unselect everything
-        if click_event.selected_cell.is_none() {
+        } else if click_event.selected_cell.is_none() {
             for (entity, _, _) in cell_query.iter() {
                 commands.entity(entity).remove::<Selected>();
             }
@@ -240,7 +825,13 @@ pub fn handle_clicks(
                     // On a double click, select all tiles with a matching number
                     if maybe_selected.is_some() && n_selected <= 1 {
                         for (entity, _, value) in cell_query.iter() {
-                            if *value == *current_value {
+                            let matching = match current_value {
+                                // Cells marked with the digit can optionally be included
+                                Value::Filled(num) => value.matches(*num, settings.match_marks),
+                                _ => *value == *current_value,
+                            };
+
+                            if matching {
                                 commands.entity(entity).insert(Selected);
                             }
                         }
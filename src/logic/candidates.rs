@@ -0,0 +1,152 @@
+/// Computes which digits could still be placed in each cell
+use crate::{
+    input::buttons::FillCandidates,
+    logic::{
+        board::{
+            marks::{CenterMarks, CornerMarks, Marks},
+            Cell, Coordinates, Value,
+        },
+        constraints::ConstraintSet,
+        sudoku_generation::InitialPuzzle,
+    },
+    settings::Settings,
+    CommonLabels,
+};
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+
+pub struct CandidatePlugin;
+
+impl Plugin for CandidatePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system(
+            fill_candidates
+                .system()
+                .label(CommonLabels::Action)
+                .after(CommonLabels::Input),
+        )
+        // Reacts to the digits placed by the actions this frame
+        .add_system(suggest_snyder_marks.system().after(CommonLabels::Action));
+    }
+}
+
+/// The digits that are not ruled out by a filled cell that can see each cell
+///
+/// Only cells without a digit are included
+pub fn compute_candidates(
+    cells: &[(&Coordinates, &Value)],
+    constraints: &ConstraintSet,
+) -> HashMap<Coordinates, HashSet<u8>> {
+    let filled: Vec<_> = cells
+        .iter()
+        .filter_map(|(coordinates, value)| match value {
+            Value::Filled(num) => Some((coordinates, *num)),
+            _ => None,
+        })
+        .collect();
+
+    cells
+        .iter()
+        .filter(|(_, value)| !matches!(value, Value::Filled(_)))
+        .map(|(coordinates, _)| {
+            let mut candidates: HashSet<u8> = (1..=constraints.grid().digits()).collect();
+            for (other_coordinates, num) in filled.iter() {
+                if constraints.sees_cells(coordinates, other_coordinates) {
+                    candidates.remove(num);
+                }
+            }
+            ((*coordinates).clone(), candidates)
+        })
+        .collect()
+}
+
+/// Replaces the center marks of every unfilled cell with all of its candidates
+fn fill_candidates(
+    mut event_reader: EventReader<FillCandidates>,
+    mut query: Query<(&Coordinates, &mut Value), With<Cell>>,
+    constraints: Res<ConstraintSet>,
+) {
+    for _ in event_reader.iter() {
+        let cells: Vec<_> = query.iter().collect();
+        let candidates = compute_candidates(&cells, &constraints);
+
+        for (coordinates, mut value) in query.iter_mut() {
+            if let Some(digits) = candidates.get(coordinates) {
+                // Existing corner marks are kept
+                let corner = match &*value {
+                    Value::Marked(_, corner) => corner.clone(),
+                    _ => CornerMarks::default(),
+                };
+
+                *value = Value::Marked(CenterMarks::from_digits(digits.iter().copied()), corner)
+                    .cleanup();
+            }
+        }
+    }
+}
+
+/// Adds corner marks for digits that can only go in two cells of a region, following Snyder notation
+///
+/// Each suggestion is only made once per puzzle, so that players can remove the ones they don't want
+fn suggest_snyder_marks(
+    mut query: Query<(&Coordinates, &mut Value), With<Cell>>,
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    settings: Res<Settings>,
+    initial_puzzle: Res<InitialPuzzle>,
+    constraints: Res<ConstraintSet>,
+    mut suggested: Local<HashSet<(Coordinates, u8)>>,
+) {
+    if initial_puzzle.is_changed() {
+        suggested.clear();
+    }
+
+    if !settings.snyder_marks {
+        return;
+    }
+
+    if !settings.is_changed() && changed_query.iter().next().is_none() {
+        return;
+    }
+
+    let cells: Vec<_> = query.iter().collect();
+    let candidates = compute_candidates(&cells, &constraints);
+
+    let grid = constraints.grid();
+    let mut pairs = Vec::new();
+    for region in 1..=constraints.regions().count() {
+        for num in 1..=grid.digits() {
+            let positions: Vec<_> = candidates
+                .iter()
+                .filter(|(coordinates, digits)| {
+                    grid.contains(coordinates)
+                        && constraints.regions().region(grid.index(coordinates)) == region
+                        && digits.contains(&num)
+                })
+                .map(|(coordinates, _)| coordinates.clone())
+                .collect();
+
+            if positions.len() == 2 {
+                pairs.extend(positions.into_iter().map(|coordinates| (coordinates, num)));
+            }
+        }
+    }
+
+    for (coordinates, mut value) in query.iter_mut() {
+        for (pair_coordinates, num) in pairs.iter() {
+            if pair_coordinates != coordinates
+                || !suggested.insert((pair_coordinates.clone(), *num))
+            {
+                continue;
+            }
+
+            let new_value = match &*value {
+                Value::Empty => Value::Marked(CenterMarks::default(), CornerMarks::new(*num)),
+                Value::Marked(center, corner) if !corner.contains(*num) && !corner.is_full() => {
+                    Value::Marked(center.clone(), corner.update(*num))
+                }
+                _ => continue,
+            };
+            *value = new_value;
+        }
+    }
+}
@@ -0,0 +1,180 @@
+/// Collections of puzzles loaded from `.sdm` files, which list one puzzle per line
+use crate::input::{
+    buttons::{BrowseCollection, PlayCollectionPuzzle},
+    focus::Focusable,
+    shortcuts::{Shortcut, Shortcuts},
+};
+use crate::localization::Localization;
+use crate::logic::{
+    archive::ArchiveFilter,
+    board::PuzzleSolved,
+    constraints::ConstraintSet,
+    notation::Position,
+    records::{BestTimes, Difficulty},
+    sudoku_generation::{ImportPuzzle, InitialPuzzle},
+    timer::format_duration,
+};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+pub struct CollectionPlugin;
+
+impl Plugin for CollectionPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Collection>()
+            .add_system(browse_collection.system())
+            .add_system(play_collection_puzzle.system())
+            .add_system(record_completed_puzzles.system());
+    }
+}
+
+/// How many puzzles the browser lists at once
+pub const PAGE_SIZE: usize = 6;
+
+/// A puzzle from a collection, along with how hard it is
+pub struct CollectionPuzzle {
+    pub position: Position,
+    /// Puzzles without a unique solution have no difficulty, and can't be played
+    pub difficulty: Option<Difficulty>,
+    /// A title or seed to tell the puzzle apart by, if it has one
+    pub name: Option<String>,
+    /// Has the player starred this puzzle in the archive
+    pub starred: bool,
+}
+
+/// Resource storing the puzzle collection the player has opened, if any
+#[derive(Default)]
+pub struct Collection {
+    /// The name of the file the collection was loaded from
+    pub name: String,
+    pub puzzles: Vec<CollectionPuzzle>,
+    /// The indexes of the puzzles the player has solved
+    pub completed: HashSet<usize>,
+    /// The filter applied when the puzzles are listed from the archive, rather than a file
+    pub archive_filter: Option<ArchiveFilter>,
+    /// The text typed to search the archive with, which every archived puzzle listed contains
+    pub archive_search: String,
+}
+
+impl Collection {
+    /// Reads the contents of an `.sdm` file, which lists a puzzle of 81 characters on each line
+    ///
+    /// Lines that aren't valid puzzles are skipped, while the givens of smaller grids are accepted too
+    pub fn parse(name: String, contents: &str) -> Collection {
+        let puzzles = contents
+            .lines()
+            .filter_map(|line| match Position::parse(line) {
+                Ok(position) => Some(position),
+                Err(error) => {
+                    if !line.trim().is_empty() {
+                        warn!("Skipping line of collection: {:?}", error);
+                    }
+                    None
+                }
+            })
+            // The format has no way to describe variant rules, so its puzzles are always classic
+            .map(|position| CollectionPuzzle {
+                difficulty: position
+                    .grade(&ConstraintSet::for_grid(position.grid))
+                    .map(Difficulty::from),
+                position,
+                name: None,
+                starred: false,
+            })
+            .collect();
+
+        Collection {
+            name,
+            puzzles,
+            ..Default::default()
+        }
+    }
+
+    /// The number of pages the browser splits the collection into
+    ///
+    /// An empty collection still has a page, so the archive's filter can be changed when nothing passes it
+    pub fn pages(&self) -> usize {
+        ((self.puzzles.len() + PAGE_SIZE - 1) / PAGE_SIZE).max(1)
+    }
+
+    /// A short description of the puzzle for the browser, such as "#12 easy (solved) 04:12",
+    /// or "#3 seed 1234 hard" for puzzles with a name, ending with the best time if it has been solved
+    pub fn label(&self, i: usize, best_times: &BestTimes, localization: &Localization) -> String {
+        let difficulty = localization.get(match self.puzzles[i].difficulty {
+            Some(difficulty) => difficulty.name_id(),
+            None => "difficulty-invalid",
+        });
+        let mut label = match &self.puzzles[i].name {
+            Some(name) => format!("#{} {} {}", i + 1, name, difficulty),
+            None => format!("#{} {}", i + 1, difficulty),
+        };
+        let status = match (self.completed.contains(&i), self.puzzles[i].starred) {
+            (true, true) => Some("collection-solved-starred"),
+            (true, false) => Some("collection-solved"),
+            (false, true) => Some("collection-starred"),
+            (false, false) => None,
+        };
+        if let Some(status) = status {
+            label.push_str(&format!(" ({})", localization.get(status)));
+        }
+        if let Some(time) = best_times.of_puzzle(&self.puzzles[i].position) {
+            label.push_str(&format!(" {}", format_duration(time)));
+        }
+        label
+    }
+}
+
+/// Opens the collection browser when K is pressed, at the page of the first unsolved puzzle
+fn browse_collection(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    collection: Res<Collection>,
+    focusable_query: Query<(), With<Focusable>>,
+    mut event_writer: EventWriter<BrowseCollection>,
+) {
+    // Don't open the browser on top of another menu
+    if !shortcuts.just_pressed(Shortcut::Collection, &keyboard_input)
+        || collection.puzzles.is_empty()
+        || focusable_query.iter().next().is_some()
+    {
+        return;
+    }
+
+    let first_unsolved = (0..collection.puzzles.len())
+        .find(|i| !collection.completed.contains(i))
+        .unwrap_or(0);
+    event_writer.send(BrowseCollection {
+        page: first_unsolved / PAGE_SIZE,
+    });
+}
+
+/// Starts playing the puzzle chosen from the collection
+fn play_collection_puzzle(
+    mut event_reader: EventReader<PlayCollectionPuzzle>,
+    collection: Res<Collection>,
+    mut import_writer: EventWriter<ImportPuzzle>,
+) {
+    for event in event_reader.iter() {
+        if let Some(puzzle) = collection.puzzles.get(event.0) {
+            import_writer.send(ImportPuzzle(puzzle.position.to_givens()));
+        }
+    }
+}
+
+/// Marks puzzles from the collection as completed once they are solved
+///
+/// Puzzles are recognized by their givens, so they count however they were started
+fn record_completed_puzzles(
+    mut event_reader: EventReader<PuzzleSolved>,
+    initial_puzzle: Res<InitialPuzzle>,
+    mut collection: ResMut<Collection>,
+) {
+    for _ in event_reader.iter() {
+        let digits = initial_puzzle.position().digits;
+
+        let solved: Vec<usize> = (0..collection.puzzles.len())
+            .filter(|i| collection.puzzles[*i].position.digits == digits)
+            .collect();
+        collection.completed.extend(solved);
+    }
+}
@@ -0,0 +1,201 @@
+/// Detects digits that break the rules of Sudoku, and helps the player resolve them
+use crate::{
+    input::buttons::{CheckPuzzle, FindMistake},
+    logic::{
+        board::{Cell, Coordinates, Fixed, Value},
+        constraints::ConstraintSet,
+        sudoku_generation::CompletePuzzle,
+    },
+    settings::Settings,
+    CommonLabels,
+};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use std::time::Duration;
+
+pub struct ConflictPlugin;
+
+impl Plugin for ConflictPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.add_system_set(
+            SystemSet::new()
+                .label(CommonLabels::Action)
+                .after(CommonLabels::Input)
+                .with_system(find_mistake.system())
+                .with_system(check_puzzle.system()),
+        )
+        // Must run after the values have been updated by player actions
+        .add_system_set(
+            SystemSet::new()
+                .label(ConflictLabels::Detection)
+                .after(CommonLabels::Action)
+                .with_system(detect_conflicts.system())
+                .with_system(clear_mistakes.system())
+                .with_system(check_digits.system())
+                .with_system(clear_flags.system()),
+        );
+    }
+}
+
+#[derive(SystemLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+pub enum ConflictLabels {
+    Detection,
+}
+
+/// Marker component for cells whose digit breaks a constraint, such as being shared by another cell in the same row
+pub struct Conflicting;
+
+/// Marker component for cells that have been revealed to contain the wrong digit
+pub struct Mistaken;
+
+/// How long cells stay flagged after the puzzle is checked
+const FLAG_DURATION: Duration = Duration::from_secs(3);
+
+/// Component for cells found to contain the wrong digit when the player checked the puzzle,
+/// which is removed once its timer finishes
+pub struct Flagged(pub Timer);
+
+/// Marker component for cells whose digit doesn't match the solution, found by auto-check
+pub struct Incorrect;
+
+/// Marks all cells containing a digit that breaks one of the puzzle's constraints
+fn detect_conflicts(
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    query: Query<(Entity, &Coordinates, &Value, Option<&Conflicting>), With<Cell>>,
+    constraints: Res<ConstraintSet>,
+    mut commands: Commands,
+) {
+    // Conflicts can only change when values do, or when the rules themselves change
+    if changed_query.iter().next().is_none() && !constraints.is_changed() {
+        return;
+    }
+
+    // Cells off the grid belong to a board that is about to be rebuilt, and never conflict
+    let grid = constraints.grid();
+    let mut digits = vec![None; grid.cell_count()];
+    for (_, coordinates, value, _) in query.iter() {
+        if let (Value::Filled(num), true) = (value, grid.contains(coordinates)) {
+            digits[grid.index(coordinates)] = Some(*num);
+        }
+    }
+    let conflicting: HashSet<usize> = constraints.violations(&digits).into_iter().collect();
+
+    for (entity, coordinates, _, maybe_conflicting) in query.iter() {
+        let is_conflicting =
+            grid.contains(coordinates) && conflicting.contains(&grid.index(coordinates));
+        match (is_conflicting, maybe_conflicting.is_some()) {
+            (true, false) => {
+                commands.entity(entity).insert(Conflicting);
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Conflicting>();
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Does this value differ from the solution
+///
+/// Nothing is wrong in puzzles without a known solution, such as those being set or solved on paper
+fn is_wrong(complete_puzzle: &CompletePuzzle, coordinates: &Coordinates, value: &Value) -> bool {
+    complete_puzzle
+        .numbers
+        .get(coordinates)
+        .map_or(false, |correct_value| correct_value != value)
+}
+
+/// Reveals which of the conflicting digits do not match the solution
+fn find_mistake(
+    mut event_reader: EventReader<FindMistake>,
+    query: Query<(Entity, &Coordinates, &Value, &Fixed), (With<Cell>, With<Conflicting>)>,
+    complete_puzzle: Res<CompletePuzzle>,
+    mut commands: Commands,
+) {
+    for _ in event_reader.iter() {
+        for (entity, coordinates, value, is_fixed) in query.iter() {
+            // Digits given by the puzzle are always correct
+            if is_fixed.0 {
+                continue;
+            }
+
+            if is_wrong(&complete_puzzle, coordinates, value) {
+                commands.entity(entity).insert(Mistaken);
+            }
+        }
+    }
+}
+
+/// Briefly flags every digit entered by the player that doesn't match the solution
+fn check_puzzle(
+    mut event_reader: EventReader<CheckPuzzle>,
+    query: Query<(Entity, &Coordinates, &Value, &Fixed), With<Cell>>,
+    complete_puzzle: Res<CompletePuzzle>,
+    mut commands: Commands,
+) {
+    for _ in event_reader.iter() {
+        for (entity, coordinates, value, is_fixed) in query.iter() {
+            let wrong = !is_fixed.0
+                && matches!(value, Value::Filled(_))
+                && is_wrong(&complete_puzzle, coordinates, value);
+
+            if wrong {
+                commands
+                    .entity(entity)
+                    .insert(Flagged(Timer::new(FLAG_DURATION, false)));
+            }
+        }
+    }
+}
+
+/// Removes flags once their time is up, or once the player has changed the digit
+fn clear_flags(
+    mut query: Query<(Entity, &mut Flagged, ChangeTrackers<Value>)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut flagged, value_tracker) in query.iter_mut() {
+        flagged.0.tick(time.delta());
+
+        if flagged.0.finished() || value_tracker.is_changed() {
+            commands.entity(entity).remove::<Flagged>();
+        }
+    }
+}
+
+/// Stops highlighting mistakes once the player has changed the digit
+fn clear_mistakes(query: Query<Entity, (With<Mistaken>, Changed<Value>)>, mut commands: Commands) {
+    for entity in query.iter() {
+        commands.entity(entity).remove::<Mistaken>();
+    }
+}
+
+/// Marks every digit entered by the player that doesn't match the solution, when auto-check is enabled
+fn check_digits(
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    query: Query<(Entity, &Coordinates, &Value, &Fixed, Option<&Incorrect>), With<Cell>>,
+    complete_puzzle: Res<CompletePuzzle>,
+    settings: Res<Settings>,
+    mut commands: Commands,
+) {
+    if !settings.is_changed() && changed_query.iter().next().is_none() {
+        return;
+    }
+
+    for (entity, coordinates, value, is_fixed, maybe_incorrect) in query.iter() {
+        let incorrect = settings.auto_check
+            && !is_fixed.0
+            && matches!(value, Value::Filled(_))
+            && is_wrong(&complete_puzzle, coordinates, value);
+
+        match (incorrect, maybe_incorrect.is_some()) {
+            (true, false) => {
+                commands.entity(entity).insert(Incorrect);
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Incorrect>();
+            }
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,385 @@
+/// The rules a puzzle is played under, expressed as a set of constraints on its digits
+///
+/// Classic sudoku is the single constraint that no digit repeats in a row, column or region,
+/// where the regions are the boxes of the grid, such as the 3x3 squares, unless the puzzle is a jigsaw.
+/// Variants add constraints of their own, which conflict detection, the solver and the generator all respect,
+/// and which can draw overlays on the board to show the player where they apply
+use crate::logic::{
+    anti_king::AntiKing, anti_knight::AntiKnight, board::Coordinates, diagonals::Diagonals,
+    disjoint_groups::DisjointGroups, extra_regions::ExtraRegions, grid::GridSize,
+    non_consecutive::NonConsecutive, regions::Regions,
+};
+use bevy::prelude::*;
+use serde_json::Value as JsonValue;
+use std::sync::Arc;
+
+pub struct ConstraintPlugin;
+
+impl Plugin for ConstraintPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<ConstraintSet>();
+    }
+}
+
+/// A rule that the digits of a solution must follow
+///
+/// Cells are referred to by their index in reading order
+pub trait Constraint: Send + Sync + 'static {
+    /// Groups of cells that can't contain the same digit twice
+    fn houses(&self) -> Vec<Vec<usize>> {
+        Vec::new()
+    }
+
+    /// The cells breaking this constraint, beyond any digits repeated within its houses
+    ///
+    /// Empty cells are `None`, and should only count as breaking the constraint
+    /// once no digit could be placed in them to fix it
+    fn violations(&self, _digits: &[Option<u8>]) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Removes candidates ruled out by this constraint, where bit `n` is set for digit `n`
+    ///
+    /// Repeats within houses are already ruled out, so only the rest of the rule needs checking.
+    /// Returns false if the candidates can't satisfy the constraint
+    fn prune(&self, _candidates: &mut [u32]) -> bool {
+        true
+    }
+
+    /// The markings drawn on the board to show this constraint to the player
+    fn overlays(&self) -> Vec<Overlay> {
+        Vec::new()
+    }
+
+    /// The ids of the messages explaining this constraint to the player
+    fn rules_ids(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// The f-puzzles keys this constraint is written under when the puzzle is shared, along with their values
+    ///
+    /// Returns `None` if f-puzzles can't write this constraint, so that it is never dropped from a shared puzzle unnoticed
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        None
+    }
+}
+
+/// A marking drawn over the board, which the graphics draw without knowing the rule behind it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Overlay {
+    /// A line traced just inside the edge of a group of cells, with an optional label in its top left corner
+    Outline {
+        cells: Vec<usize>,
+        label: Option<String>,
+        /// Broken into dashes, as killer cages are drawn
+        dashed: bool,
+    },
+    /// A thick line running through the centers of the cells in turn
+    Line { cells: Vec<usize>, color: LineColor },
+    /// A thin line running through the centers of the cells in turn, and on to the edges of the first and last cells,
+    /// such as the diagonals of X-sudoku
+    ThinLine { cells: Vec<usize> },
+    /// A large circle in the cell, either filled in like the bulb of a thermometer,
+    /// or left empty like the ends of a between line
+    Bulb { cell: usize, filled: bool },
+    /// A small circle on the edge between two neighbouring cells, either filled in or left white
+    Dot { cells: (usize, usize), filled: bool },
+    /// A short label on the edge between two neighbouring cells, such as an X or a V
+    EdgeLabel {
+        cells: (usize, usize),
+        label: String,
+    },
+    /// A circle on the corner shared by a 2x2 group of cells, with a label inside it such as the digits of a quadruple
+    CornerLabel { cells: Vec<usize>, label: String },
+    /// Shading beneath the grid lines and digits, such as the windows of windoku
+    Shade { cells: Vec<usize> },
+    /// A label in the band outside the grid, with an arrow pointing into the cells it applies to
+    OuterClue {
+        /// The cells the clue applies to, starting from the one nearest the clue
+        cells: Vec<usize>,
+        /// The direction of the arrow, as rows down and columns across
+        step: (i32, i32),
+        label: String,
+    },
+}
+
+/// The color of a line, which tells apart the rules of lines that look alike otherwise
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineColor {
+    /// Thermometers and palindromes
+    Gray,
+    /// German whispers
+    Green,
+    /// Renban lines
+    Purple,
+}
+
+/// Each pair of neighbouring cells of a 9x9 grid, by their index in reading order
+pub fn neighbours() -> impl Iterator<Item = (usize, usize)> {
+    let across = (0..81).filter(|i| i % 9 != 8).map(|i| (i, i + 1));
+    let down = (0..72).map(|i| (i, i + 9));
+    across.chain(down)
+}
+
+/// The candidates of the first cell that leave some candidate of the second for which `allows` holds
+pub fn supported(first: u32, second: u32, allows: impl Fn(u8, u8) -> bool) -> u32 {
+    (1..=9u8)
+        .filter(|&a| first & 1 << a != 0)
+        .filter(|&a| (1..=9u8).any(|b| second & 1 << b != 0 && allows(a, b)))
+        .fold(0, |mask, a| mask | 1 << a)
+}
+
+/// The rules of classic sudoku on a grid of this size: no digit repeats in a row or column
+///
+/// Digits can't repeat in a region either, but the regions belong to the `ConstraintSet`,
+/// as they also decide where the board's thick lines are drawn
+pub struct ClassicSudoku(pub GridSize);
+
+impl Constraint for ClassicSudoku {
+    fn houses(&self) -> Vec<Vec<usize>> {
+        let mut houses = self.0.rows();
+        houses.extend(self.0.columns());
+        houses
+    }
+
+    /// The rows and columns of a samurai board stop at the edges of each grid, which the player is told
+    fn rules_ids(&self) -> Vec<&'static str> {
+        match self.0 {
+            GridSize::Samurai => vec!["rules-classic", "rules-samurai"],
+            _ => vec!["rules-classic"],
+        }
+    }
+
+    /// f-puzzles only holds a single square grid, which follows the classic rules without any keys
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        match self.0 {
+            GridSize::Samurai => None,
+            _ => Some(Vec::new()),
+        }
+    }
+}
+
+/// Rules that apply across the whole board, which can be switched on for generated 9x9 puzzles
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    AntiKnight,
+    AntiKing,
+    NonConsecutive,
+    /// Irregular regions in place of the 3x3 squares
+    Jigsaw,
+    /// Four extra regions, the windows set one cell in from each corner
+    Windoku,
+    /// Cells in the same place within their 3x3 squares can't contain the same digit
+    DisjointGroups,
+    /// Both main diagonals can't repeat a digit, as in X-sudoku
+    Diagonals,
+}
+
+impl Variant {
+    /// Every rule that can be switched on
+    pub const ALL: [Variant; 7] = [
+        Variant::AntiKnight,
+        Variant::AntiKing,
+        Variant::NonConsecutive,
+        Variant::Jigsaw,
+        Variant::Windoku,
+        Variant::DisjointGroups,
+        Variant::Diagonals,
+    ];
+
+    /// The constraint enforcing this rule
+    ///
+    /// Jigsaws change the regions rather than adding a constraint, so have none
+    pub fn constraint(self) -> Option<Arc<dyn Constraint>> {
+        match self {
+            Variant::AntiKnight => Some(Arc::new(AntiKnight)),
+            Variant::AntiKing => Some(Arc::new(AntiKing)),
+            Variant::NonConsecutive => Some(Arc::new(NonConsecutive)),
+            Variant::Jigsaw => None,
+            Variant::Windoku => Some(Arc::new(ExtraRegions::windoku())),
+            Variant::DisjointGroups => Some(Arc::new(DisjointGroups)),
+            Variant::Diagonals => Some(Arc::new(Diagonals::both())),
+        }
+    }
+
+    /// The id of the message naming this rule
+    pub fn name_id(self) -> &'static str {
+        match self {
+            Variant::AntiKnight => "variant-anti-knight",
+            Variant::AntiKing => "variant-anti-king",
+            Variant::NonConsecutive => "variant-non-consecutive",
+            Variant::Jigsaw => "variant-jigsaw",
+            Variant::Windoku => "variant-windoku",
+            Variant::DisjointGroups => "variant-disjoint-groups",
+            Variant::Diagonals => "variant-diagonals",
+        }
+    }
+}
+
+/// Resource storing every constraint the current puzzle is played under
+///
+/// Cheap to clone, so that it can be handed to work done in the background
+#[derive(Clone)]
+pub struct ConstraintSet {
+    constraints: Vec<Arc<dyn Constraint>>,
+    regions: Regions,
+    /// The houses of every constraint, computed once as they are checked constantly
+    houses: Vec<Vec<usize>>,
+    /// The other cells sharing a house with each cell
+    peers: Vec<Vec<usize>>,
+}
+
+impl Default for ConstraintSet {
+    fn default() -> Self {
+        ConstraintSet::classic()
+    }
+}
+
+impl ConstraintSet {
+    /// The constraints provided, with the 3x3 squares of a 9x9 grid as regions
+    pub fn new(constraints: Vec<Arc<dyn Constraint>>) -> ConstraintSet {
+        ConstraintSet::with_regions(constraints, Regions::squares())
+    }
+
+    /// The constraints provided, with the regions of a jigsaw or the boxes of a grid of another size
+    pub fn with_regions(constraints: Vec<Arc<dyn Constraint>>, regions: Regions) -> ConstraintSet {
+        let houses: Vec<Vec<usize>> = constraints
+            .iter()
+            .flat_map(|constraint| constraint.houses())
+            .chain(regions.houses())
+            .collect();
+
+        let mut peers = vec![Vec::new(); regions.grid().cell_count()];
+        for house in houses.iter() {
+            for &i in house.iter() {
+                for &j in house.iter() {
+                    if i != j && !peers[i].contains(&j) {
+                        peers[i].push(j);
+                    }
+                }
+            }
+        }
+
+        ConstraintSet {
+            constraints,
+            regions,
+            houses,
+            peers,
+        }
+    }
+
+    /// The rules of classic sudoku alone
+    pub fn classic() -> ConstraintSet {
+        ConstraintSet::for_grid(GridSize::Nine)
+    }
+
+    /// The rules of classic sudoku alone, on a grid of this size
+    pub fn for_grid(grid: GridSize) -> ConstraintSet {
+        ConstraintSet::with_regions(vec![Arc::new(ClassicSudoku(grid))], Regions::boxes(grid))
+    }
+
+    /// The size of the board the puzzle is played on
+    pub fn grid(&self) -> GridSize {
+        self.regions.grid()
+    }
+
+    /// The region each cell is in
+    pub fn regions(&self) -> &Regions {
+        &self.regions
+    }
+
+    /// Every group of cells that can't contain the same digit twice
+    pub fn houses(&self) -> &[Vec<usize>] {
+        &self.houses
+    }
+
+    /// The other cells that can't contain the same digit as cell `i`
+    pub fn peers(&self, i: usize) -> &[usize] {
+        &self.peers[i]
+    }
+
+    /// Can cells `i` and `j` never contain the same digit
+    pub fn sees(&self, i: usize, j: usize) -> bool {
+        self.peers[i].contains(&j)
+    }
+
+    /// Can the cells at these coordinates never contain the same digit
+    ///
+    /// Cells off the grid see nothing, as the board may not have been rebuilt for a new grid yet
+    pub fn sees_cells(&self, a: &Coordinates, b: &Coordinates) -> bool {
+        let grid = self.grid();
+        grid.contains(a) && grid.contains(b) && self.sees(grid.index(a), grid.index(b))
+    }
+
+    /// The cells whose digits break a constraint, in reading order
+    pub fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = vec![false; digits.len()];
+        for (i, digit) in digits.iter().enumerate() {
+            if let Some(num) = digit {
+                if self.peers[i].iter().any(|&j| digits[j] == Some(*num)) {
+                    violating[i] = true;
+                }
+            }
+        }
+        for constraint in self.constraints.iter() {
+            for i in constraint.violations(digits) {
+                violating[i] = true;
+            }
+        }
+
+        (0..digits.len()).filter(|&i| violating[i]).collect()
+    }
+
+    /// Removes candidates ruled out by constraints beyond the houses
+    ///
+    /// Returns false if the candidates can't satisfy every constraint
+    pub fn prune(&self, candidates: &mut [u32]) -> bool {
+        self.constraints
+            .iter()
+            .all(|constraint| constraint.prune(candidates))
+    }
+
+    /// The ids of the messages explaining every constraint, without repeats
+    ///
+    /// Jigsaws add a message of their own, as their regions aren't a constraint
+    pub fn rules_ids(&self) -> Vec<&'static str> {
+        let jigsaw = (!self.regions.is_boxes()).then(|| "rules-jigsaw");
+        let mut ids = Vec::new();
+        for id in self
+            .constraints
+            .iter()
+            .flat_map(|constraint| constraint.rules_ids())
+            .chain(jigsaw)
+        {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    /// Are there any rules beyond those of classic sudoku
+    pub fn is_variant(&self) -> bool {
+        self.rules_ids().iter().any(|&id| id != "rules-classic")
+    }
+
+    /// The markings of every constraint, to be drawn on the board
+    pub fn overlays(&self) -> Vec<Overlay> {
+        self.constraints
+            .iter()
+            .flat_map(|constraint| constraint.overlays())
+            .collect()
+    }
+
+    /// The f-puzzles keys and values of every constraint, or `None` if any of them can't be written
+    ///
+    /// Keys may appear more than once, such as the list of negative constraints, which both kropki dots and XV pairs add to
+    pub fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let entries = self
+            .constraints
+            .iter()
+            .map(|constraint| constraint.fpuzzles())
+            .collect::<Option<Vec<_>>>()?;
+        Some(entries.into_iter().flatten().collect())
+    }
+}
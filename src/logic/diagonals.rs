@@ -0,0 +1,59 @@
+/// X-sudoku, where the digits along the main diagonals can't repeat either
+use crate::logic::constraints::{Constraint, Overlay};
+use serde_json::Value as JsonValue;
+
+/// The diagonals of the grid that can't contain the same digit twice
+pub struct Diagonals {
+    /// The diagonal running up from the bottom left corner to the top right
+    pub positive: bool,
+    /// The diagonal running down from the top left corner to the bottom right
+    pub negative: bool,
+}
+
+impl Diagonals {
+    /// Both diagonals, as in X-sudoku
+    pub fn both() -> Diagonals {
+        Diagonals {
+            positive: true,
+            negative: true,
+        }
+    }
+
+    /// The cells of each diagonal in use, starting from the top row
+    fn diagonals(&self) -> Vec<Vec<usize>> {
+        let positive = (0..9).map(|row| row * 9 + 8 - row).collect();
+        let negative = (0..9).map(|row| row * 9 + row).collect();
+        self.positive
+            .then(|| positive)
+            .into_iter()
+            .chain(self.negative.then(|| negative))
+            .collect()
+    }
+}
+
+impl Constraint for Diagonals {
+    fn houses(&self) -> Vec<Vec<usize>> {
+        self.diagonals()
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        self.diagonals()
+            .into_iter()
+            .map(|cells| Overlay::ThinLine { cells })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-diagonals"]
+    }
+
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let keys = [("diagonal+", self.positive), ("diagonal-", self.negative)];
+        Some(
+            keys.iter()
+                .filter(|(_, used)| *used)
+                .map(|&(key, _)| (key, JsonValue::Bool(true)))
+                .collect(),
+        )
+    }
+}
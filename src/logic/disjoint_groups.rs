@@ -0,0 +1,35 @@
+/// Disjoint groups, where cells in the same place within their 3x3 squares can't contain the same digit
+use crate::logic::constraints::Constraint;
+use serde_json::Value as JsonValue;
+
+/// The rule that the cells in the same place within each 3x3 square form a group which can't repeat a digit
+///
+/// There's nothing to draw, as the rule applies across the whole board
+pub struct DisjointGroups;
+
+impl Constraint for DisjointGroups {
+    /// The nine groups, one for each place within a square
+    ///
+    /// The squares are those of classic sudoku even in a jigsaw, as they are for the groups of f-puzzles
+    fn houses(&self) -> Vec<Vec<usize>> {
+        (0..9)
+            .map(|place| {
+                (0..9)
+                    .map(|square| {
+                        let row = square / 3 * 3 + place / 3;
+                        let column = square % 3 * 3 + place % 3;
+                        row * 9 + column
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-disjoint-groups"]
+    }
+
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        Some(vec![("disjointgroups", JsonValue::Bool(true))])
+    }
+}
@@ -0,0 +1,53 @@
+/// Extra regions, groups of cells which can't contain the same digit twice on top of the rows, columns and regions
+///
+/// Windoku has four of them, the 3x3 windows set one cell in from each corner of the grid
+use crate::logic::{
+    constraints::{Constraint, Overlay},
+    fpuzzles::write_cells,
+};
+use serde_json::{json, Value as JsonValue};
+
+/// The extra regions of a puzzle, each listing its cells by their index in reading order
+pub struct ExtraRegions(pub Vec<Vec<usize>>);
+
+impl ExtraRegions {
+    /// The four windows of windoku
+    pub fn windoku() -> ExtraRegions {
+        let corners = [(1, 1), (1, 5), (5, 1), (5, 5)];
+        ExtraRegions(
+            corners
+                .iter()
+                .map(|&(top, left)| (0..9).map(|k| (top + k / 3) * 9 + left + k % 3).collect())
+                .collect(),
+        )
+    }
+}
+
+impl Constraint for ExtraRegions {
+    fn houses(&self) -> Vec<Vec<usize>> {
+        self.0.clone()
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        self.0
+            .iter()
+            .map(|cells| Overlay::Shade {
+                cells: cells.clone(),
+            })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-extra-regions"]
+    }
+
+    /// Windoku's windows are written as extra regions too, as f-puzzles has no key of their own for them
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let regions = self
+            .0
+            .iter()
+            .map(|cells| json!({ "cells": write_cells(cells) }))
+            .collect();
+        Some(vec![("extraregion", JsonValue::Array(regions))])
+    }
+}
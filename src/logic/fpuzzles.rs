@@ -0,0 +1,767 @@
+/// Reading and writing puzzles shared as f-puzzles or SudokuPad links
+///
+/// Both sites store the whole puzzle in the link, as JSON compressed with lz-string and encoded in base64.
+/// The givens, jigsaw and extra regions, the anti-knight, anti-king, non-consecutive and disjoint groups rules, the diagonals of X-sudoku, killer cages, thermometers, German whispers, renban, palindrome and between lines, kropki dots, quadruples, XV pairs and little killer clues are read,
+/// and written back out when sharing a puzzle, while puzzles using other variant constraints are refused
+use crate::logic::{
+    constraints::{ClassicSudoku, Constraint, ConstraintSet, Variant},
+    diagonals::Diagonals,
+    extra_regions::ExtraRegions,
+    grid::GridSize,
+    killer::{Cage, KillerCages},
+    kropki::{Dot, DotKind, KropkiDots},
+    lines::{Line, LineKind, Lines, WHISPER_DIFFERENCE},
+    little_killer::{LittleKillerClue, LittleKillers},
+    notation::Position,
+    quadruples::{Quadruple, Quadruples},
+    regions::Regions,
+    sudoku_generation::PuzzleMetadata,
+    thermometers::{Thermometer, Thermometers},
+    xv::{XvKind, XvPair, XvPairs},
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// f-puzzles keys for constraints that change the rules of the puzzle, which can't be played yet
+const VARIANT_CONSTRAINTS: [&str; 7] = [
+    "sandwichsum",
+    "clone",
+    "arrow",
+    "minimum",
+    "maximum",
+    "odd",
+    "even",
+];
+
+/// SudokuPad links to puzzles in the f-puzzles format start with this prefix
+const FPUZZLES_PREFIX: &str = "fpuz";
+
+/// Exported puzzles are opened in f-puzzles, which can pass them on to SudokuPad
+const FPUZZLES_URL: &str = "https://www.f-puzzles.com/?load=";
+
+/// A problem encountered when reading a puzzle link
+#[derive(Debug, PartialEq, Eq)]
+pub enum LinkError {
+    /// The link doesn't point to a puzzle at all
+    MissingPuzzle,
+    /// The puzzle is stored in a format other than f-puzzles', such as SudokuPad's own,
+    /// or only named by a short link that must be looked up online
+    UnsupportedFormat,
+    /// The puzzle could not be decompressed or read as f-puzzles JSON
+    InvalidPayload,
+    /// Only 9x9 puzzles can be played
+    InvalidSize(usize),
+    /// The puzzle uses variant constraints that can't be played yet, listed by their f-puzzles key
+    UnsupportedConstraints(Vec<&'static str>),
+}
+
+/// The parts of an f-puzzles puzzle that we understand
+#[derive(Serialize, Deserialize)]
+struct FPuzzle {
+    size: usize,
+    /// Rows of cells, from top to bottom
+    grid: Vec<Vec<FCell>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    /// The rules of the puzzle, as written by its author
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ruleset: Option<String>,
+    #[serde(flatten)]
+    other: HashMap<String, JsonValue>,
+}
+
+/// A constraint that f-puzzles stores as a group of cells with a value, such as a killer cage, a kropki dot or an XV pair,
+/// or without one, such as an extra region
+#[derive(Deserialize)]
+struct FCellGroup {
+    /// Cells written as `R1C1`
+    cells: Vec<String>,
+    /// The sum of a cage, the difference or ratio of a dot, or the letter of an XV pair,
+    /// which f-puzzles usually writes as a string
+    #[serde(default)]
+    value: Option<JsonValue>,
+}
+
+/// A little killer clue, as f-puzzles stores it
+#[derive(Deserialize)]
+struct FLittleKiller {
+    /// The position of the clue outside the grid, written as `R0C1`
+    cell: String,
+    /// Which way the arrow points: `UL`, `UR`, `DL` or `DR`
+    direction: String,
+    #[serde(default)]
+    value: Option<JsonValue>,
+}
+
+/// A thermometer or another line, as f-puzzles stores it
+///
+/// Branching thermometers have a line for each branch, all starting from the shared bulb
+#[derive(Deserialize)]
+struct FLines {
+    /// The cells along each line, written as `R1C1` and starting from the bulb of a thermometer
+    lines: Vec<Vec<String>>,
+    /// The smallest difference between neighbouring digits of a German whisper
+    #[serde(default)]
+    value: Option<JsonValue>,
+}
+
+/// A quadruple, as f-puzzles stores it
+#[derive(Deserialize)]
+struct FQuadruple {
+    /// The four cells around the circle, written as `R1C1`
+    cells: Vec<String>,
+    /// The digits in the circle
+    #[serde(default)]
+    values: Vec<JsonValue>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct FCell {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<u8>,
+    /// Values that aren't givens are the progress of whoever shared the puzzle
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    given: bool,
+    /// The region of a jigsaw the cell is in, counted from 0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<u8>,
+}
+
+/// Does this look like a link to f-puzzles or SudokuPad, rather than a notation
+pub fn is_link(notation: &str) -> bool {
+    let notation = notation.trim();
+    notation.starts_with("http://")
+        || notation.starts_with("https://")
+        || notation.contains("f-puzzles.com")
+        || notation.contains("sudokupad.app")
+}
+
+/// Reads the givens of the puzzle stored in an f-puzzles or SudokuPad link
+///
+/// Puzzles using variant constraints that can't be played yet are refused,
+/// as their givens alone could have other solutions
+pub fn parse_link(link: &str) -> Result<Position, LinkError> {
+    let puzzle = decode(link)?;
+
+    let unsupported: Vec<&'static str> = VARIANT_CONSTRAINTS
+        .iter()
+        .copied()
+        .filter(|key| puzzle.other.get(*key).map_or(false, is_present))
+        .collect();
+    if !unsupported.is_empty() {
+        return Err(LinkError::UnsupportedConstraints(unsupported));
+    }
+
+    let mut position = Position::empty(GridSize::Nine);
+    for (i, cell) in puzzle.grid.iter().flatten().enumerate() {
+        match cell.value {
+            Some(num @ 1..=9) if cell.given => {
+                position.digits[i] = Some(num);
+                position.candidates[i] = 1 << num;
+            }
+            Some(0..=9) | None => (),
+            Some(_) => return Err(LinkError::InvalidPayload),
+        }
+    }
+    Ok(position)
+}
+
+/// Reads the constraints of the puzzle stored in an f-puzzles or SudokuPad link,
+/// which are the classic rules along with jigsaw and extra regions, the anti-knight, anti-king, non-consecutive and disjoint groups rules, the diagonals of X-sudoku, killer cages, thermometers, German whispers, renban, palindrome and between lines, kropki dots, quadruples, XV pairs and little killer clues
+pub fn parse_constraints(link: &str) -> Result<ConstraintSet, LinkError> {
+    let puzzle = decode(link)?;
+    let mut constraints: Vec<Arc<dyn Constraint>> = vec![Arc::new(ClassicSudoku(GridSize::Nine))];
+
+    let variants = [
+        ("antiknight", Variant::AntiKnight),
+        ("antiking", Variant::AntiKing),
+        ("nonconsecutive", Variant::NonConsecutive),
+        ("disjointgroups", Variant::DisjointGroups),
+    ];
+    for &(key, variant) in variants.iter() {
+        if puzzle.other.get(key) == Some(&JsonValue::Bool(true)) {
+            constraints.extend(variant.constraint());
+        }
+    }
+
+    let positive = puzzle.other.get("diagonal+") == Some(&JsonValue::Bool(true));
+    let negative = puzzle.other.get("diagonal-") == Some(&JsonValue::Bool(true));
+    if positive || negative {
+        constraints.push(Arc::new(Diagonals { positive, negative }));
+    }
+
+    if let Some(cages) = read_constraint::<FCellGroup>(&puzzle, "killercage")? {
+        let cages = cages
+            .into_iter()
+            .map(|cage| {
+                Some(Cage {
+                    cells: parse_cells(&cage.cells)?,
+                    // Cages without a sum still keep their digits different
+                    sum: parse_value(&cage.value),
+                })
+            })
+            .collect::<Option<Vec<Cage>>>()
+            .ok_or(LinkError::InvalidPayload)?;
+        constraints.push(Arc::new(KillerCages::new(cages)));
+    }
+
+    if let Some(regions) = read_constraint::<FCellGroup>(&puzzle, "extraregion")? {
+        let regions = regions
+            .iter()
+            .map(|region| parse_cells(&region.cells))
+            .collect::<Option<Vec<Vec<usize>>>>()
+            .ok_or(LinkError::InvalidPayload)?;
+        constraints.push(Arc::new(ExtraRegions(regions)));
+    }
+
+    if let Some(thermometers) = read_constraint::<FLines>(&puzzle, "thermometer")? {
+        let thermometers = thermometers
+            .iter()
+            .flat_map(|thermometer| thermometer.lines.iter())
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                Some(Thermometer {
+                    cells: parse_cells(line)?,
+                })
+            })
+            .collect::<Option<Vec<Thermometer>>>()
+            .ok_or(LinkError::InvalidPayload)?;
+        constraints.push(Arc::new(Thermometers(thermometers)));
+    }
+
+    let mut lines = Vec::new();
+    for &(key, kind) in [
+        ("whispers", LineKind::Whisper(WHISPER_DIFFERENCE)),
+        ("renban", LineKind::Renban),
+        ("palindrome", LineKind::Palindrome),
+        ("betweenline", LineKind::Between),
+    ]
+    .iter()
+    {
+        for group in read_constraint::<FLines>(&puzzle, key)?.unwrap_or_default() {
+            let kind = match (kind, parse_value(&group.value)) {
+                (LineKind::Whisper(_), Some(difference)) => LineKind::Whisper(difference as u8),
+                _ => kind,
+            };
+            for cells in group.lines.iter().filter(|line| !line.is_empty()) {
+                lines.push(Line {
+                    cells: parse_cells(cells).ok_or(LinkError::InvalidPayload)?,
+                    kind,
+                });
+            }
+        }
+    }
+    if !lines.is_empty() {
+        constraints.push(Arc::new(Lines(lines)));
+    }
+
+    if let Some(quadruples) = read_constraint::<FQuadruple>(&puzzle, "quadruple")? {
+        let quadruples = quadruples
+            .iter()
+            .map(|quadruple| {
+                let mut digits = quadruple
+                    .values
+                    .iter()
+                    .map(|value| match parse_value(&Some(value.clone()))? {
+                        num @ 1..=9 => Some(num as u8),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<u8>>>()?;
+                digits.sort_unstable();
+                Some(Quadruple {
+                    cells: parse_cells(&quadruple.cells)?,
+                    digits,
+                })
+            })
+            .collect::<Option<Vec<Quadruple>>>()
+            .ok_or(LinkError::InvalidPayload)?;
+        constraints.push(Arc::new(Quadruples(quadruples)));
+    }
+
+    if let Some(clues) = read_constraint::<FLittleKiller>(&puzzle, "littlekillersum")? {
+        let clues = clues
+            .iter()
+            // Clues without a sum only show an arrow, and don't constrain anything
+            .filter_map(|clue| Some((clue, parse_value(&clue.value)?)))
+            .map(|(clue, sum)| {
+                let (row, column) = parse_position(&clue.cell)?;
+                let step = match clue.direction.to_uppercase().as_str() {
+                    "UL" => (-1, -1),
+                    "UR" => (-1, 1),
+                    "DL" => (1, -1),
+                    "DR" => (1, 1),
+                    _ => return None,
+                };
+                // Positions are counted from 1, so the band around the grid is at 0 and 10
+                Some(LittleKillerClue::new(row - 1, column - 1, step, sum))
+            })
+            .collect::<Option<Vec<LittleKillerClue>>>()
+            .ok_or(LinkError::InvalidPayload)?;
+        constraints.push(Arc::new(LittleKillers(clues)));
+    }
+
+    let white_dots = read_constraint::<FCellGroup>(&puzzle, "difference")?.unwrap_or_default();
+    let black_dots = read_constraint::<FCellGroup>(&puzzle, "ratio")?.unwrap_or_default();
+    let all_white_given = is_negative(&puzzle, "difference");
+    let all_black_given = is_negative(&puzzle, "ratio");
+    if !white_dots.is_empty() || !black_dots.is_empty() || all_white_given || all_black_given {
+        let white_dots = white_dots.iter().map(|dot| {
+            (
+                dot,
+                DotKind::Difference(parse_value(&dot.value).unwrap_or(1) as u8),
+            )
+        });
+        let black_dots = black_dots.iter().map(|dot| {
+            (
+                dot,
+                DotKind::Ratio(parse_value(&dot.value).unwrap_or(2) as u8),
+            )
+        });
+        let dots = white_dots
+            .chain(black_dots)
+            .map(|(dot, kind)| match parse_cells(&dot.cells)?.as_slice() {
+                &[first, second] => Some(Dot {
+                    cells: (first, second),
+                    kind,
+                }),
+                _ => None,
+            })
+            .collect::<Option<Vec<Dot>>>()
+            .ok_or(LinkError::InvalidPayload)?;
+        constraints.push(Arc::new(KropkiDots {
+            dots,
+            all_white_given,
+            all_black_given,
+        }));
+    }
+
+    let letters = read_constraint::<FCellGroup>(&puzzle, "xv")?.unwrap_or_default();
+    let all_given = is_negative(&puzzle, "xv");
+    if !letters.is_empty() || all_given {
+        let pairs = letters
+            .iter()
+            .map(|letter| {
+                let kind = match letter.value.as_ref().and_then(JsonValue::as_str) {
+                    Some("X") | Some("x") => XvKind::X,
+                    Some("V") | Some("v") => XvKind::V,
+                    _ => return None,
+                };
+                match parse_cells(&letter.cells)?.as_slice() {
+                    &[first, second] => Some(XvPair {
+                        cells: (first, second),
+                        kind,
+                    }),
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<XvPair>>>()
+            .ok_or(LinkError::InvalidPayload)?;
+        constraints.push(Arc::new(XvPairs { pairs, all_given }));
+    }
+
+    // Cells without a region of their own stay in their 3x3 square
+    let squares = Regions::squares();
+    let mut map = [0; 81];
+    for (i, cell) in puzzle.grid.iter().flatten().take(81).enumerate() {
+        map[i] = match cell.region {
+            Some(region) => region.saturating_add(1),
+            None => squares.region(i),
+        };
+    }
+    let regions = Regions::new(map).ok_or(LinkError::InvalidPayload)?;
+
+    Ok(ConstraintSet::with_regions(constraints, regions))
+}
+
+/// Reads the list stored under one of the f-puzzles constraint keys, if the puzzle uses it
+fn read_constraint<T: DeserializeOwned>(
+    puzzle: &FPuzzle,
+    key: &str,
+) -> Result<Option<Vec<T>>, LinkError> {
+    match puzzle.other.get(key).filter(|value| is_present(value)) {
+        Some(value) => serde_json::from_value(value.clone())
+            .map(Some)
+            .map_err(|_| LinkError::InvalidPayload),
+        None => Ok(None),
+    }
+}
+
+/// Does the puzzle use the negative constraint for this f-puzzles key,
+/// where every marking of its kind is given, so cells without one can't have the relationship it shows
+fn is_negative(puzzle: &FPuzzle, key: &str) -> bool {
+    match puzzle.other.get("negative") {
+        Some(JsonValue::Array(keys)) => keys.iter().any(|negative| negative.as_str() == Some(key)),
+        _ => false,
+    }
+}
+
+/// Reads the cells of a constraint, each written as `R1C1`
+fn parse_cells(cells: &[String]) -> Option<Vec<usize>> {
+    cells.iter().map(|cell| parse_cell(cell)).collect()
+}
+
+/// Reads the number stored as the value of a constraint, which is usually written as a string
+fn parse_value(value: &Option<JsonValue>) -> Option<u32> {
+    match value {
+        Some(JsonValue::String(value)) => value.trim().parse().ok(),
+        Some(JsonValue::Number(value)) => value.as_u64().map(|value| value as u32),
+        _ => None,
+    }
+}
+
+/// Reads a cell written as `R1C1` into its index in reading order
+fn parse_cell(cell: &str) -> Option<usize> {
+    match parse_position(cell)? {
+        (row @ 1..=9, column @ 1..=9) => Some((row as usize - 1) * 9 + column as usize - 1),
+        _ => None,
+    }
+}
+
+/// Writes each cell, given by its index in reading order, as `R1C1`
+pub fn write_cells(cells: &[usize]) -> Vec<String> {
+    cells.iter().map(|&i| write_cell(i)).collect()
+}
+
+/// Writes a cell of a 9x9 grid, given by its index in reading order, as `R1C1`
+pub fn write_cell(i: usize) -> String {
+    format!("R{}C{}", i / 9 + 1, i % 9 + 1)
+}
+
+/// Reads a position written as `R1C1` into its row and column, which may lie outside the grid
+fn parse_position(cell: &str) -> Option<(i32, i32)> {
+    let cell = cell.to_uppercase();
+    let (row, column) = cell.strip_prefix('R')?.split_once('C')?;
+    Some((row.parse().ok()?, column.parse().ok()?))
+}
+
+/// Reads the title, author and rules of the puzzle stored in an f-puzzles or SudokuPad link
+pub fn parse_metadata(link: &str) -> Result<PuzzleMetadata, LinkError> {
+    let puzzle = decode(link)?;
+    Ok(PuzzleMetadata {
+        title: puzzle.title,
+        author: puzzle.author,
+        rules: puzzle.ruleset,
+    })
+}
+
+/// Writes a link that opens the givens of the position in f-puzzles, along with its constraints and the puzzle's metadata
+///
+/// Returns `None` if some constraint can't be written, as the puzzle shared without it could have other solutions
+pub fn export_link(
+    position: &Position,
+    constraints: &ConstraintSet,
+    metadata: &PuzzleMetadata,
+) -> Option<String> {
+    // Keys shared by several constraints, such as the negative constraints, hold a list they each add to
+    let mut other: HashMap<String, JsonValue> = HashMap::new();
+    for (key, value) in constraints.fpuzzles()? {
+        match (other.get_mut(key), value) {
+            (Some(JsonValue::Array(existing)), JsonValue::Array(added)) => existing.extend(added),
+            (_, value) => {
+                other.insert(key.to_string(), value);
+            }
+        }
+    }
+
+    // Regions are only written for jigsaws, as f-puzzles assumes the boxes of the grid otherwise
+    let regions = constraints.regions();
+    let jigsaw = !regions.is_boxes();
+    let width = position.grid.width() as usize;
+    let puzzle = FPuzzle {
+        size: width,
+        grid: (0..width)
+            .map(|row| {
+                (0..width)
+                    .map(|column| {
+                        let i = row * width + column;
+                        FCell {
+                            value: position.digits[i],
+                            given: position.digits[i].is_some(),
+                            region: if jigsaw {
+                                Some(regions.region(i) - 1)
+                            } else {
+                                None
+                            },
+                        }
+                    })
+                    .collect()
+            })
+            .collect(),
+        title: metadata.title.clone(),
+        author: metadata.author.clone(),
+        ruleset: metadata.rules.clone(),
+        other,
+    };
+
+    let json = serde_json::to_string(&puzzle).expect("f-puzzles JSON could not be written");
+    Some(format!(
+        "{}{}",
+        FPUZZLES_URL,
+        lz_str::compress_to_base64(&json)
+    ))
+}
+
+/// Decompresses and reads the f-puzzles JSON stored in the link
+fn decode(link: &str) -> Result<FPuzzle, LinkError> {
+    let payload = payload(link.trim()).ok_or(LinkError::MissingPuzzle)?;
+    let payload = percent_decode(payload);
+    // f-puzzles payloads are plain, while SudokuPad marks the format in front
+    let payload = if link.contains("f-puzzles.com") {
+        payload.as_str()
+    } else {
+        payload
+            .strip_prefix(FPUZZLES_PREFIX)
+            .ok_or(LinkError::UnsupportedFormat)?
+    };
+
+    let json = lz_str::decompress_from_base64(payload)
+        .and_then(|utf16| String::from_utf16(&utf16).ok())
+        .ok_or(LinkError::InvalidPayload)?;
+    let puzzle: FPuzzle = serde_json::from_str(&json).map_err(|_| LinkError::InvalidPayload)?;
+
+    if puzzle.size != 9 || puzzle.grid.len() != 9 || puzzle.grid.iter().any(|row| row.len() != 9) {
+        return Err(LinkError::InvalidSize(puzzle.size));
+    }
+    Ok(puzzle)
+}
+
+/// Finds the compressed puzzle in the link
+///
+/// f-puzzles uses the `load` query parameter, while SudokuPad uses either the path or the `puzzle` query parameter
+fn payload(link: &str) -> Option<&str> {
+    let link = link.split('#').next().unwrap_or_default();
+    let (path, query) = match link.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (link, None),
+    };
+
+    let parameter = query.and_then(|query| {
+        query
+            .split('&')
+            .find_map(|pair| match pair.split_once('=') {
+                Some(("load", value)) | Some(("puzzle", value)) => Some(value),
+                _ => None,
+            })
+    });
+
+    let last_segment = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|segment| !segment.contains('.') && !segment.is_empty());
+
+    parameter.or(last_segment)
+}
+
+/// Undoes the escaping of the characters base64 uses that aren't allowed in links
+fn percent_decode(payload: &str) -> String {
+    payload
+        .replace("%2B", "+")
+        .replace("%2b", "+")
+        .replace("%2F", "/")
+        .replace("%2f", "/")
+        .replace("%3D", "=")
+        .replace("%3d", "=")
+        // Some sites turn an unescaped `+` into a space
+        .replace(' ', "+")
+}
+
+/// Is this constraint actually used by the puzzle, rather than left empty or switched off
+fn is_present(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::Bool(enabled) => *enabled,
+        JsonValue::Array(constraints) => !constraints.is_empty(),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Every constraint `parse_constraints` reads, written the way `export_link` writes them
+    fn every_constraint() -> JsonValue {
+        json!({
+            "antiknight": true,
+            "antiking": true,
+            "nonconsecutive": true,
+            "disjointgroups": true,
+            "diagonal+": true,
+            "diagonal-": true,
+            "killercage": [
+                { "cells": ["R1C1", "R1C2"], "value": "10" },
+                { "cells": ["R9C8", "R9C9"] },
+            ],
+            "extraregion": [{ "cells": ["R2C2", "R2C3", "R3C2", "R3C3"] }],
+            "thermometer": [{ "lines": [["R4C1", "R5C1", "R6C1"]] }],
+            "whispers": [
+                { "lines": [["R7C1", "R7C2", "R7C3"]] },
+                { "lines": [["R8C1", "R8C2"]], "value": "4" },
+            ],
+            "renban": [{ "lines": [["R4C4", "R4C5", "R4C6"]] }],
+            "palindrome": [{ "lines": [["R5C4", "R5C5", "R5C6"]] }],
+            "betweenline": [{ "lines": [["R6C4", "R6C5", "R6C6"]] }],
+            "quadruple": [{ "cells": ["R1C7", "R1C8", "R2C7", "R2C8"], "values": [1, 2] }],
+            "littlekillersum": [
+                { "cell": "R0C1", "direction": "DR", "value": "12" },
+                { "cell": "R10C9", "direction": "UL", "value": "45" },
+            ],
+            "difference": [
+                { "cells": ["R3C7", "R3C8"] },
+                { "cells": ["R4C7", "R4C8"], "value": "3" },
+            ],
+            "ratio": [{ "cells": ["R5C7", "R5C8"] }],
+            "xv": [
+                { "cells": ["R6C7", "R6C8"], "value": "X" },
+                { "cells": ["R7C7", "R7C8"], "value": "V" },
+            ],
+            "negative": ["difference", "ratio", "xv"],
+        })
+    }
+
+    /// A link to a puzzle with a few givens and the constraints provided, placing cells in jigsaw regions if there are any
+    fn link(constraints: JsonValue, regions: Option<[u8; 81]>) -> String {
+        let givens = [(0, 4), (10, 7), (40, 1), (80, 9)];
+        let grid: Vec<Vec<JsonValue>> = (0..9)
+            .map(|row| {
+                (0..9)
+                    .map(|column| {
+                        let i = row * 9 + column;
+                        let mut cell = json!({});
+                        if let Some(&(_, num)) = givens.iter().find(|(given, _)| *given == i) {
+                            cell["value"] = json!(num);
+                            cell["given"] = json!(true);
+                        }
+                        if let Some(regions) = regions {
+                            cell["region"] = json!(regions[i] - 1);
+                        }
+                        cell
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut puzzle = json!({
+            "size": 9,
+            "grid": grid,
+            "title": "Round trip",
+            "author": "bevy-sudoku",
+            "ruleset": "Normal sudoku rules apply.",
+        });
+        for (key, value) in constraints
+            .as_object()
+            .expect("Constraints are keyed")
+            .iter()
+        {
+            puzzle[key] = value.clone();
+        }
+        format!(
+            "{}{}",
+            FPUZZLES_URL,
+            lz_str::compress_to_base64(&puzzle.to_string())
+        )
+    }
+
+    /// Imports the link, exports it again, and checks that nothing was lost on the way
+    fn assert_round_trip(original: &str) {
+        let position = parse_link(original).expect("The link can be read");
+        let constraints = parse_constraints(original).expect("The constraints can be read");
+        let metadata = parse_metadata(original).expect("The metadata can be read");
+        let exported =
+            export_link(&position, &constraints, &metadata).expect("Every constraint is written");
+
+        assert_eq!(parse_link(&exported).unwrap().digits, position.digits);
+        assert_eq!(parse_metadata(&exported).unwrap(), metadata);
+
+        let (before, after) = (decode(original).unwrap(), decode(&exported).unwrap());
+        for key in every_constraint().as_object().unwrap().keys() {
+            assert_eq!(
+                before.other.get(key),
+                after.other.get(key),
+                "{} changed",
+                key
+            );
+        }
+
+        let reread = parse_constraints(&exported).unwrap();
+        assert_eq!(reread.houses(), constraints.houses());
+        assert_eq!(reread.overlays(), constraints.overlays());
+        assert_eq!(reread.rules_ids(), constraints.rules_ids());
+        for i in 0..81 {
+            assert_eq!(reread.regions().region(i), constraints.regions().region(i));
+        }
+    }
+
+    #[test]
+    fn givens_and_metadata_are_read_from_links() {
+        let original = link(json!({}), None);
+        assert!(is_link(&original));
+        assert!(!is_link(&"1".repeat(81)));
+
+        let position = parse_link(&original).unwrap();
+        assert_eq!(position.grid, GridSize::Nine);
+        assert_eq!(position.digits[0], Some(4));
+        assert_eq!(position.digits[10], Some(7));
+        assert_eq!(position.digits[40], Some(1));
+        assert_eq!(position.digits[80], Some(9));
+        assert_eq!(position.digits.iter().flatten().count(), 4);
+
+        let metadata = parse_metadata(&original).unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("Round trip"));
+        assert_eq!(metadata.author.as_deref(), Some("bevy-sudoku"));
+    }
+
+    #[test]
+    fn every_constraint_survives_a_round_trip() {
+        assert_round_trip(&link(every_constraint(), None));
+    }
+
+    #[test]
+    fn jigsaw_regions_survive_a_round_trip() {
+        // Two cells are swapped between the first two squares, keeping both regions connected
+        let mut regions = [0; 81];
+        for (i, region) in regions.iter_mut().enumerate() {
+            *region = Regions::squares().region(i);
+        }
+        regions[2] = 2;
+        regions[12] = 1;
+        assert_round_trip(&link(json!({}), Some(regions)));
+    }
+
+    #[test]
+    fn classic_puzzles_are_written_without_constraints() {
+        let original = link(json!({}), None);
+        let position = parse_link(&original).unwrap();
+        let exported = export_link(
+            &position,
+            &ConstraintSet::classic(),
+            &PuzzleMetadata::default(),
+        )
+        .unwrap();
+        assert!(decode(&exported).unwrap().other.is_empty());
+        assert!(decode(&exported)
+            .unwrap()
+            .grid
+            .iter()
+            .flatten()
+            .all(|cell| cell.region.is_none()));
+    }
+
+    #[test]
+    fn samurai_puzzles_are_not_written() {
+        let grid = GridSize::Samurai;
+        let position = Position::empty(grid);
+        let constraints = ConstraintSet::for_grid(grid);
+        assert_eq!(
+            export_link(&position, &constraints, &PuzzleMetadata::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,337 @@
+/// The sizes of board that can be played, from small grids for younger players up to 16x16 giants and samurai boards
+///
+/// Each size uses as many digits as there are cells in a row, and is split into boxes of that many cells.
+/// Digits beyond 9 are written as letters, so 16x16 grids use 1 to 9 followed by A to G.
+/// Samurai boards are made up of five overlapping 9x9 grids, each with rows and columns of its own,
+/// which leave gaps between them where there are no cells.
+/// Cells are referred to by their index in reading order, which depends on the size of the board
+use crate::logic::board::Coordinates;
+
+/// The index of the first cell of each row of a samurai board, followed by the number of cells on the board
+///
+/// Rows crossing two corner grids have 18 cells, those crossing the center grid as well have 21,
+/// and those between the corner grids only cross the center grid, so have 9
+const SAMURAI_ROW_STARTS: [usize; 22] = [
+    0, 18, 36, 54, 72, 90, 108, 129, 150, 171, 180, 189, 198, 219, 240, 261, 279, 297, 315, 333,
+    351, 369,
+];
+
+/// The size of the board, and the shape of its boxes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GridSize {
+    /// 4x4 grids with 2x2 boxes, for younger players
+    Four,
+    /// 6x6 grids with boxes two rows tall and three columns wide
+    Six,
+    /// The classic 9x9 grid with 3x3 boxes
+    Nine,
+    /// 16x16 grids with 4x4 boxes, using the letters A to G for the digits 10 to 16
+    Sixteen,
+    /// Five 9x9 grids, one in each corner of a 21x21 board and one in its center,
+    /// where the center grid shares a corner box with each of the others
+    Samurai,
+}
+
+impl Default for GridSize {
+    fn default() -> Self {
+        GridSize::Nine
+    }
+}
+
+impl GridSize {
+    /// Every size, from smallest to largest
+    pub const ALL: [GridSize; 5] = [
+        GridSize::Four,
+        GridSize::Six,
+        GridSize::Nine,
+        GridSize::Sixteen,
+        GridSize::Samurai,
+    ];
+
+    /// The number of digits used, which is also the number of cells in each row, column and box
+    pub fn digits(self) -> u8 {
+        match self {
+            GridSize::Four => 4,
+            GridSize::Six => 6,
+            GridSize::Nine | GridSize::Samurai => 9,
+            GridSize::Sixteen => 16,
+        }
+    }
+
+    /// The number of rows and columns in each box
+    pub fn box_shape(self) -> (u8, u8) {
+        match self {
+            GridSize::Four => (2, 2),
+            GridSize::Six => (2, 3),
+            GridSize::Nine | GridSize::Samurai => (3, 3),
+            GridSize::Sixteen => (4, 4),
+        }
+    }
+
+    /// The number of cells across the board, and down it, including any gaps between its grids
+    pub fn width(self) -> u8 {
+        match self {
+            GridSize::Samurai => 21,
+            _ => self.digits(),
+        }
+    }
+
+    /// The top left corner of each square grid making up the board, as the rows and columns before it
+    ///
+    /// Every grid is as wide as the number of digits, and most boards are a single grid
+    pub fn subgrids(self) -> Vec<(u8, u8)> {
+        match self {
+            GridSize::Samurai => vec![(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)],
+            _ => vec![(0, 0)],
+        }
+    }
+
+    /// The number of cells on the board
+    pub fn cell_count(self) -> usize {
+        match self {
+            GridSize::Samurai => SAMURAI_ROW_STARTS[21],
+            _ => self.width() as usize * self.width() as usize,
+        }
+    }
+
+    /// How many times each digit appears in a solution, which is once for each box
+    pub fn digit_count(self) -> usize {
+        self.cell_count() / self.digits() as usize
+    }
+
+    /// The size with this many cells, as found from the length of a puzzle's givens
+    pub fn from_cell_count(cell_count: usize) -> Option<GridSize> {
+        GridSize::ALL
+            .iter()
+            .copied()
+            .find(|grid| grid.cell_count() == cell_count)
+    }
+
+    /// Is there a cell at these coordinates
+    pub fn contains(self, coordinates: &Coordinates) -> bool {
+        let size = self.digits();
+        self.subgrids().iter().any(|&(top, left)| {
+            (top + 1..=top + size).contains(&coordinates.row)
+                && (left + 1..=left + size).contains(&coordinates.column)
+        })
+    }
+
+    /// The position of the cell in reading order
+    pub fn index(self, coordinates: &Coordinates) -> usize {
+        match self {
+            GridSize::Samurai => {
+                let row = coordinates.row;
+                let before = (1..coordinates.column)
+                    .filter(|&column| self.contains(&Coordinates { row, column }))
+                    .count();
+                SAMURAI_ROW_STARTS[(row - 1) as usize] + before
+            }
+            _ => {
+                (coordinates.row - 1) as usize * self.width() as usize
+                    + (coordinates.column - 1) as usize
+            }
+        }
+    }
+
+    /// The coordinates of the cell at this position in reading order
+    pub fn coordinates(self, i: usize) -> Coordinates {
+        match self {
+            GridSize::Samurai => {
+                let row = SAMURAI_ROW_STARTS
+                    .iter()
+                    .rposition(|&start| start <= i)
+                    .expect("The first row starts at 0");
+                let column = (1..=self.width())
+                    .filter(|&column| {
+                        self.contains(&Coordinates {
+                            row: row as u8 + 1,
+                            column,
+                        })
+                    })
+                    .nth(i - SAMURAI_ROW_STARTS[row])
+                    .expect("Each cell is within its row");
+                Coordinates {
+                    row: row as u8 + 1,
+                    column,
+                }
+            }
+            _ => {
+                let width = self.width() as usize;
+                Coordinates {
+                    row: (i / width + 1) as u8,
+                    column: (i % width + 1) as u8,
+                }
+            }
+        }
+    }
+
+    /// Every cell of the board, in reading order
+    pub fn cells(self) -> impl Iterator<Item = Coordinates> {
+        (0..self.cell_count()).map(move |i| self.coordinates(i))
+    }
+
+    /// A bitmask with a bit set for each digit used, where bit `n` is set for digit `n`
+    pub fn all_candidates(self) -> u32 {
+        (1..=self.digits()).fold(0, |mask, num| mask | 1 << num)
+    }
+
+    /// Each row of each grid making up the board, listing its cells by index from left to right
+    ///
+    /// The grids of a samurai board are separate puzzles, so a row of the board crossing several of them
+    /// is split into a row of each
+    pub fn rows(self) -> Vec<Vec<usize>> {
+        let size = self.digits();
+        let mut rows = Vec::new();
+        for (top, left) in self.subgrids() {
+            for row in top + 1..=top + size {
+                rows.push(
+                    (left + 1..=left + size)
+                        .map(|column| self.index(&Coordinates { row, column }))
+                        .collect(),
+                );
+            }
+        }
+        rows
+    }
+
+    /// Each column of each grid making up the board, listing its cells by index from top to bottom
+    pub fn columns(self) -> Vec<Vec<usize>> {
+        let size = self.digits();
+        let mut columns = Vec::new();
+        for (top, left) in self.subgrids() {
+            for column in left + 1..=left + size {
+                columns.push(
+                    (top + 1..=top + size)
+                        .map(|row| self.index(&Coordinates { row, column }))
+                        .collect(),
+                );
+            }
+        }
+        columns
+    }
+
+    /// The box cell `i` is in, counted from 1 in reading order
+    ///
+    /// The boxes shared by two grids of a samurai board are only counted once,
+    /// and the gaps between its grids are skipped
+    pub fn box_of(self, i: usize) -> u8 {
+        let (box_height, box_width) = self.box_shape();
+        let coordinates = self.coordinates(i);
+        let boxes_across = self.width() / box_width;
+        let position = (coordinates.row - 1) / box_height * boxes_across
+            + (coordinates.column - 1) / box_width;
+        // A box is on the board when its top left cell is, as the grids line up with the boxes
+        let boxes_before = (0..position)
+            .filter(|&other| {
+                self.contains(&Coordinates {
+                    row: other / boxes_across * box_height + 1,
+                    column: other % boxes_across * box_width + 1,
+                })
+            })
+            .count();
+        boxes_before as u8 + 1
+    }
+
+    /// Each pair of cells sharing an edge, by their index in reading order
+    pub fn neighbours(self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (i, coordinates) in self.cells().enumerate() {
+            let right = Coordinates {
+                row: coordinates.row,
+                column: coordinates.column + 1,
+            };
+            let below = Coordinates {
+                row: coordinates.row + 1,
+                column: coordinates.column,
+            };
+            for other in [right, below].iter().filter(|other| self.contains(other)) {
+                pairs.push((i, self.index(other)));
+            }
+        }
+        pairs
+    }
+
+    /// The size that follows this one when cycling through them in the settings menu
+    pub fn next(self) -> GridSize {
+        let position = GridSize::ALL
+            .iter()
+            .position(|grid| *grid == self)
+            .expect("Every size is listed");
+        GridSize::ALL[(position + 1) % GridSize::ALL.len()]
+    }
+
+    /// The id of the message naming this size
+    pub fn name_id(self) -> &'static str {
+        match self {
+            GridSize::Four => "grid-size-four",
+            GridSize::Six => "grid-size-six",
+            GridSize::Nine => "grid-size-nine",
+            GridSize::Sixteen => "grid-size-sixteen",
+            GridSize::Samurai => "grid-size-samurai",
+        }
+    }
+}
+
+/// The character a digit is written with, which is a letter from A for the digits beyond 9
+pub fn digit_symbol(num: u8) -> char {
+    match num {
+        0..=9 => (b'0' + num) as char,
+        _ => (b'A' + num - 10) as char,
+    }
+}
+
+/// The digit written with this character, accepting letters in either case, if it is one
+pub fn parse_symbol(c: char) -> Option<u8> {
+    match c.to_ascii_uppercase() {
+        c @ '1'..='9' => Some(c as u8 - b'0'),
+        c @ 'A'..='G' => Some(c as u8 - b'A' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_cell_survives_a_round_trip_through_its_coordinates() {
+        for grid in GridSize::ALL.iter().copied() {
+            for i in 0..grid.cell_count() {
+                let coordinates = grid.coordinates(i);
+                assert!(grid.contains(&coordinates), "{:?} cell {}", grid, i);
+                assert_eq!(grid.index(&coordinates), i, "{:?} cell {}", grid, i);
+            }
+        }
+    }
+
+    #[test]
+    fn samurai_boards_leave_gaps_between_their_grids() {
+        let grid = GridSize::Samurai;
+        assert_eq!(grid.cell_count(), 369);
+        assert_eq!(grid.cells().count(), 369);
+        // Between the top corner grids, and beside the center grid
+        assert!(!grid.contains(&Coordinates { row: 1, column: 11 }));
+        assert!(!grid.contains(&Coordinates { row: 11, column: 1 }));
+        // The corner box the top left grid shares with the center grid
+        assert!(grid.contains(&Coordinates { row: 8, column: 8 }));
+        assert_eq!(grid.coordinates(0), Coordinates { row: 1, column: 1 });
+        assert_eq!(
+            grid.coordinates(368),
+            Coordinates {
+                row: 21,
+                column: 21
+            }
+        );
+    }
+
+    #[test]
+    fn digits_survive_a_round_trip_through_their_symbols() {
+        for num in 1..=16 {
+            assert_eq!(parse_symbol(digit_symbol(num)), Some(num));
+        }
+        assert_eq!(digit_symbol(10), 'A');
+        assert_eq!(parse_symbol('g'), Some(16));
+        assert_eq!(parse_symbol('0'), None);
+        assert_eq!(parse_symbol('H'), None);
+    }
+}
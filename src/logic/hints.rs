@@ -0,0 +1,215 @@
+/// Hints reveal the solution to a single cell, at the cost of a time penalty
+///
+/// When a single fills the cell on a classic board, the hint explains it through the template of its technique
+use crate::{
+    graphics::toasts::Toasts,
+    input::{buttons::GetHint, Selected},
+    localization::Localization,
+    logic::{
+        board::{Cell, Coordinates, DigitPlaced, Fixed, Provenance, Value},
+        constraints::ConstraintSet,
+        grid::GridSize,
+        notation::Position,
+        regions::Regions,
+        sudoku_generation::{CompletePuzzle, InitialPuzzle},
+        techniques::{find_single_for, Deduction},
+        timer::{format_duration, PuzzleTimer},
+    },
+    trainer::explain,
+    CommonLabels,
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub struct HintPlugin;
+
+impl Plugin for HintPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<HintConfig>()
+            .init_resource::<HintCooldown>()
+            .add_event::<HintGiven>()
+            .add_system(tick_hint_cooldown.system())
+            .add_system(
+                give_hint
+                    .system()
+                    .label(CommonLabels::Action)
+                    .after(CommonLabels::Input),
+            );
+    }
+}
+
+/// Controls how costly hints are
+pub struct HintConfig {
+    /// The minimum time between hints
+    pub cooldown: Duration,
+    /// The time penalty for the first hint
+    ///
+    /// Each subsequent hint on the same puzzle costs this much more than the last
+    pub base_penalty: Duration,
+}
+
+impl Default for HintConfig {
+    fn default() -> Self {
+        HintConfig {
+            cooldown: Duration::from_secs(30),
+            base_penalty: Duration::from_secs(15),
+        }
+    }
+}
+
+/// The time remaining until another hint can be used
+#[derive(Default)]
+pub struct HintCooldown(pub Duration);
+
+impl HintConfig {
+    /// The penalty for the next hint, given how many have been used so far
+    pub fn penalty(&self, hints_used: u32) -> Duration {
+        self.base_penalty * (hints_used + 1)
+    }
+}
+
+/// Event sent when a hint has filled in a cell
+pub struct HintGiven {
+    pub coordinates: Coordinates,
+    /// The single that fills the cell, if there is one, for tools that present hints their own way
+    pub explanation: Option<Deduction>,
+}
+
+/// The single that fills the cell given the digits placed so far, ignoring the player's marks
+///
+/// Techniques only know the rows, columns and squares of a 9x9 board,
+/// so hints on other boards and on jigsaws go unexplained
+fn explain_hint(
+    cells: &[(&Coordinates, &Value)],
+    coordinates: &Coordinates,
+    constraints: &ConstraintSet,
+) -> Option<Deduction> {
+    if constraints.grid() != GridSize::Nine || *constraints.regions() != Regions::squares() {
+        return None;
+    }
+
+    let filled: Vec<_> = cells
+        .iter()
+        .copied()
+        .filter(|(_, value)| matches!(value, Value::Filled(_)))
+        .collect();
+    let position = Position::from_cells(GridSize::Nine, &filled).eliminate(constraints)?;
+    find_single_for(&position, GridSize::Nine.index(coordinates))
+}
+
+/// Counts down the time until another hint can be used
+///
+/// New puzzles start without a cooldown, while resetting a puzzle keeps the cooldown of its hints,
+/// just as it keeps their penalties
+fn tick_hint_cooldown(
+    time: Res<Time>,
+    initial_puzzle: Res<InitialPuzzle>,
+    puzzle_timer: Res<PuzzleTimer>,
+    mut hint_cooldown: ResMut<HintCooldown>,
+) {
+    if initial_puzzle.is_changed() && puzzle_timer.hints_used == 0 {
+        hint_cooldown.0 = Duration::from_secs(0);
+    } else {
+        hint_cooldown.0 = hint_cooldown.0.saturating_sub(time.delta());
+    }
+}
+
+/// Fills in the correct value of a single cell, which counts as placing its digit
+///
+/// Selected cells are preferred, and otherwise the first cell in reading order is used
+fn give_hint(
+    mut event_reader: EventReader<GetHint>,
+    mut query: Query<
+        (
+            &Coordinates,
+            &mut Value,
+            &Fixed,
+            &mut Provenance,
+            Option<&Selected>,
+        ),
+        With<Cell>,
+    >,
+    complete_puzzle: Res<CompletePuzzle>,
+    constraints: Res<ConstraintSet>,
+    hint_config: Res<HintConfig>,
+    mut hint_cooldown: ResMut<HintCooldown>,
+    mut puzzle_timer: ResMut<PuzzleTimer>,
+    mut hint_writer: EventWriter<HintGiven>,
+    mut placed_writer: EventWriter<DigitPlaced>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for _ in event_reader.iter() {
+        if hint_cooldown.0 > Duration::from_secs(0) {
+            toasts.push(localization.format(
+                "toast-hint-cooldown",
+                &[("time", format_duration(hint_cooldown.0).into())],
+            ));
+            continue;
+        }
+
+        // The explanation is found from the board as it was before the hint
+        let board: Vec<(Coordinates, Value)> = query
+            .iter()
+            .map(|(coordinates, value, _, _, _)| (coordinates.clone(), value.clone()))
+            .collect();
+        let cells: Vec<_> = board
+            .iter()
+            .map(|(coordinates, value)| (coordinates, value))
+            .collect();
+
+        // Find the cells which do not yet contain the right answer, if the puzzle has a solution to give
+        let mut candidates: Vec<_> = query
+            .iter_mut()
+            .filter(|(coordinates, value, is_fixed, _, _)| {
+                !is_fixed.0
+                    && complete_puzzle
+                        .numbers
+                        .get(*coordinates)
+                        .map_or(false, |correct_value| correct_value != &**value)
+            })
+            .collect();
+
+        candidates.sort_by_key(|(coordinates, _, _, _, maybe_selected)| {
+            (
+                maybe_selected.is_none(),
+                coordinates.row,
+                coordinates.column,
+            )
+        });
+
+        if let Some((coordinates, value, _, provenance, _)) = candidates.first_mut() {
+            let explanation = explain_hint(&cells, *coordinates, &constraints);
+            let correct_value = complete_puzzle
+                .numbers
+                .get(*coordinates)
+                .expect("No values found in puzzle for these coordinates");
+            **value = correct_value.clone();
+            **provenance = Provenance::Hint;
+
+            let penalty = hint_config.penalty(puzzle_timer.hints_used);
+            puzzle_timer.penalty += penalty;
+            puzzle_timer.hints_used += 1;
+            hint_cooldown.0 = hint_config.cooldown;
+
+            toasts.push(localization.format(
+                "toast-hint-penalty",
+                &[("time", format_duration(penalty).into())],
+            ));
+            if let Some(deduction) = &explanation {
+                toasts.push(explain(deduction, &localization));
+            }
+            hint_writer.send(HintGiven {
+                coordinates: (*coordinates).clone(),
+                explanation,
+            });
+            // Hints place digits like the player does, so they can complete houses and solve the puzzle
+            if let Value::Filled(num) = correct_value {
+                placed_writer.send(DigitPlaced {
+                    coordinates: (*coordinates).clone(),
+                    num: *num,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+/// Rules that the state of the board must always obey
+///
+/// These are checked every frame in development builds to catch logic errors,
+/// and can be called directly when testing changes to the game logic
+use crate::logic::{
+    board::{marks::Marks, Coordinates, Fixed, Value},
+    constraints::ConstraintSet,
+};
+use bevy::utils::HashMap;
+
+/// The data stored on each cell that our invariants are concerned with
+pub type CellState<'a> = (&'a Coordinates, &'a Value, &'a Fixed);
+
+/// A rule about the state of the board that has been broken
+#[derive(Debug)]
+pub enum InvariantViolation {
+    /// Two cells given by the puzzle can see each other and contain the same digit
+    ConflictingFixed(Coordinates, Coordinates),
+    /// A cell given by the puzzle does not contain a digit
+    EmptyFixed(Coordinates),
+    /// A cell is marked, but has no marks
+    EmptyMarks(Coordinates),
+    /// A cell given by the puzzle does not match the solution
+    IncorrectGiven(Coordinates),
+}
+
+/// Checks every invariant, returning the first that was broken
+pub fn check_invariants(
+    cells: &[CellState],
+    solution: &HashMap<Coordinates, Value>,
+    constraints: &ConstraintSet,
+) -> Result<(), InvariantViolation> {
+    check_fixed_filled(cells)?;
+    check_fixed_conflicts(cells, constraints)?;
+    check_marks(cells)?;
+    check_solution(cells, solution)
+}
+
+/// Cells given by the puzzle must contain a digit
+pub fn check_fixed_filled(cells: &[CellState]) -> Result<(), InvariantViolation> {
+    for (coordinates, value, is_fixed) in cells.iter() {
+        if is_fixed.0 && !matches!(value, Value::Filled(_)) {
+            return Err(InvariantViolation::EmptyFixed((*coordinates).clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Cells given by the puzzle must never conflict with each other
+pub fn check_fixed_conflicts(
+    cells: &[CellState],
+    constraints: &ConstraintSet,
+) -> Result<(), InvariantViolation> {
+    let fixed_cells: Vec<_> = cells.iter().filter(|(_, _, is_fixed)| is_fixed.0).collect();
+
+    for (i, (coordinates, value, _)) in fixed_cells.iter().enumerate() {
+        for (other_coordinates, other_value, _) in fixed_cells[i + 1..].iter() {
+            if value == other_value && constraints.sees_cells(coordinates, other_coordinates) {
+                return Err(InvariantViolation::ConflictingFixed(
+                    (*coordinates).clone(),
+                    (*other_coordinates).clone(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Marked cells must contain at least one mark, as empty marks are converted to `Value::Empty`
+///
+/// The `Value` enum already ensures that marks are only stored on cells without a digit
+pub fn check_marks(cells: &[CellState]) -> Result<(), InvariantViolation> {
+    for (coordinates, value, _) in cells.iter() {
+        if let Value::Marked(center, corner) = value {
+            if center.is_empty() && corner.is_empty() {
+                return Err(InvariantViolation::EmptyMarks((*coordinates).clone()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The digits given by the puzzle must agree with its solution
+pub fn check_solution(
+    cells: &[CellState],
+    solution: &HashMap<Coordinates, Value>,
+) -> Result<(), InvariantViolation> {
+    for (coordinates, value, is_fixed) in cells.iter() {
+        if !is_fixed.0 {
+            continue;
+        }
+
+        if let Some(correct_value) = solution.get(*coordinates) {
+            if *value != correct_value {
+                return Err(InvariantViolation::IncorrectGiven((*coordinates).clone()));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::board::marks::{CenterMarks, CornerMarks};
+
+    fn at(row: u8, column: u8) -> Coordinates {
+        Coordinates { row, column }
+    }
+
+    #[test]
+    fn consistent_boards_break_no_invariant() {
+        let (a, b) = (at(1, 1), at(1, 2));
+        let (five, marked) = (
+            Value::Filled(5),
+            Value::Marked(CenterMarks::from_digits(vec![1, 2]), CornerMarks::default()),
+        );
+        let cells = [(&a, &five, &Fixed(true)), (&b, &marked, &Fixed(false))];
+        let solution: HashMap<_, _> = vec![(a.clone(), Value::Filled(5))].into_iter().collect();
+
+        assert!(check_invariants(&cells, &solution, &ConstraintSet::classic()).is_ok());
+    }
+
+    #[test]
+    fn givens_must_be_filled() {
+        let a = at(1, 1);
+        let cells = [(&a, &Value::Empty, &Fixed(true))];
+        assert!(matches!(
+            check_fixed_filled(&cells),
+            Err(InvariantViolation::EmptyFixed(_))
+        ));
+    }
+
+    #[test]
+    fn givens_must_not_clash() {
+        let (a, b, c) = (at(1, 1), at(1, 9), at(2, 4));
+        let five = Value::Filled(5);
+        let constraints = ConstraintSet::classic();
+
+        // The same digit is fine in cells that can't see each other
+        let apart = [(&a, &five, &Fixed(true)), (&c, &five, &Fixed(true))];
+        assert!(check_fixed_conflicts(&apart, &constraints).is_ok());
+
+        let clashing = [(&a, &five, &Fixed(true)), (&b, &five, &Fixed(true))];
+        assert!(matches!(
+            check_fixed_conflicts(&clashing, &constraints),
+            Err(InvariantViolation::ConflictingFixed(..))
+        ));
+    }
+
+    #[test]
+    fn marked_cells_must_have_marks() {
+        let a = at(1, 1);
+        let empty_marks = Value::Marked(CenterMarks::default(), CornerMarks::default());
+        let cells = [(&a, &empty_marks, &Fixed(false))];
+        assert!(matches!(
+            check_marks(&cells),
+            Err(InvariantViolation::EmptyMarks(_))
+        ));
+    }
+
+    #[test]
+    fn givens_must_match_the_solution() {
+        let a = at(1, 1);
+        let cells = [(&a, &Value::Filled(5), &Fixed(true))];
+        let solution: HashMap<_, _> = vec![(a.clone(), Value::Filled(6))].into_iter().collect();
+        assert!(matches!(
+            check_solution(&cells, &solution),
+            Err(InvariantViolation::IncorrectGiven(_))
+        ));
+    }
+}
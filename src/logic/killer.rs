@@ -0,0 +1,156 @@
+/// Killer sudoku, where cages of cells must add up to the sum shown and can't repeat a digit
+use crate::logic::{
+    constraints::{Constraint, Overlay},
+    fpuzzles::write_cells,
+};
+use serde_json::{json, Value as JsonValue};
+
+/// A group of cells whose digits are all different, and add up to the cage's sum
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cage {
+    /// The cells in the cage, by their index in reading order
+    pub cells: Vec<usize>,
+    /// The total of the digits in the cage, if the puzzle gives one
+    pub sum: Option<u32>,
+}
+
+impl Cage {
+    /// The sets of different digits that could fill the cage and add up to its sum,
+    /// where bit `n` is set for digit `n`
+    fn combinations(&self) -> Vec<u32> {
+        let sum = match self.sum {
+            Some(sum) => sum,
+            None => return Vec::new(),
+        };
+
+        (0..1u32 << 9)
+            .map(|subset| subset << 1)
+            .filter(|mask| {
+                let digits = (1..=9).filter(|num| mask & 1 << num != 0);
+                mask.count_ones() as usize == self.cells.len() && digits.sum::<u32>() == sum
+            })
+            .collect()
+    }
+}
+
+/// The cages of a killer sudoku
+pub struct KillerCages {
+    cages: Vec<Cage>,
+    /// The combinations of digits that could fill each cage, computed once as the solver checks them constantly
+    combinations: Vec<Vec<u32>>,
+}
+
+impl KillerCages {
+    pub fn new(cages: Vec<Cage>) -> KillerCages {
+        let combinations = cages.iter().map(Cage::combinations).collect();
+        KillerCages {
+            cages,
+            combinations,
+        }
+    }
+}
+
+impl Constraint for KillerCages {
+    fn houses(&self) -> Vec<Vec<usize>> {
+        self.cages.iter().map(|cage| cage.cells.clone()).collect()
+    }
+
+    /// Cages whose digits add up to more than their sum, or to less once they are full,
+    /// have every digit in them marked
+    fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = Vec::new();
+        for cage in self.cages.iter() {
+            let sum = match cage.sum {
+                Some(sum) => sum,
+                None => continue,
+            };
+
+            let filled: Vec<usize> = cage
+                .cells
+                .iter()
+                .copied()
+                .filter(|&i| digits[i].is_some())
+                .collect();
+            let total: u32 = filled
+                .iter()
+                .filter_map(|&i| digits[i])
+                .map(u32::from)
+                .sum();
+
+            // Empty cells still need at least 1 each, so reaching the sum early breaks it too
+            let full = filled.len() == cage.cells.len();
+            if (full && total != sum) || (!full && total >= sum) {
+                violating.extend(filled);
+            }
+        }
+        violating
+    }
+
+    /// Keeps only the candidates that belong to a combination of digits that could still fill each cage
+    fn prune(&self, candidates: &mut [u32]) -> bool {
+        for (cage, combinations) in self.cages.iter().zip(self.combinations.iter()) {
+            if cage.sum.is_none() {
+                continue;
+            }
+            // No combination of different digits fits in a cage larger than nine cells
+            if cage.cells.len() > 9 {
+                return false;
+            }
+
+            let mut allowed = [0u32; 9];
+            let mut possible = false;
+            for &combination in combinations.iter() {
+                let mut options = [0u32; 9];
+                let mut covered = 0;
+                for (k, &i) in cage.cells.iter().enumerate() {
+                    options[k] = candidates[i] & combination;
+                    covered |= options[k];
+                }
+
+                // Every cell needs a digit from the combination, and every digit needs a cell
+                if options[..cage.cells.len()].contains(&0) || covered != combination {
+                    continue;
+                }
+                possible = true;
+                for (allowed, option) in allowed.iter_mut().zip(options.iter()) {
+                    *allowed |= option;
+                }
+            }
+
+            if !possible {
+                return false;
+            }
+            for (k, &i) in cage.cells.iter().enumerate() {
+                candidates[i] &= allowed[k];
+            }
+        }
+        true
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        self.cages
+            .iter()
+            .map(|cage| Overlay::Outline {
+                cells: cage.cells.clone(),
+                label: cage.sum.map(|sum| sum.to_string()),
+                dashed: true,
+            })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-killer"]
+    }
+
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let cages = self
+            .cages
+            .iter()
+            .map(|cage| match cage.sum {
+                Some(sum) => json!({ "cells": write_cells(&cage.cells), "value": sum.to_string() }),
+                None => json!({ "cells": write_cells(&cage.cells) }),
+            })
+            .collect();
+        Some(vec![("killercage", JsonValue::Array(cages))])
+    }
+}
@@ -0,0 +1,185 @@
+/// Kropki dots, drawn between neighbouring cells to relate their digits
+///
+/// A white dot joins digits that differ by one, and a black dot digits where one is double the other
+use crate::logic::{
+    constraints::{neighbours, supported, Constraint, Overlay},
+    fpuzzles::write_cells,
+};
+use serde_json::{json, Value as JsonValue};
+
+/// The relationship a dot shows between the digits of its two cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DotKind {
+    /// A white dot: the digits differ by this much, usually 1
+    Difference(u8),
+    /// A black dot: one digit is this many times the other, usually 2
+    Ratio(u8),
+}
+
+impl DotKind {
+    /// Can these two digits sit either side of the dot
+    fn allows(self, a: u8, b: u8) -> bool {
+        match self {
+            DotKind::Difference(difference) => a.max(b) - a.min(b) == difference,
+            DotKind::Ratio(ratio) => {
+                let (a, b, ratio) = (u32::from(a), u32::from(b), u32::from(ratio));
+                a * ratio == b || b * ratio == a
+            }
+        }
+    }
+}
+
+/// A dot on the edge between two neighbouring cells, by their index in reading order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dot {
+    pub cells: (usize, usize),
+    pub kind: DotKind,
+}
+
+/// The dots of a puzzle
+pub struct KropkiDots {
+    pub dots: Vec<Dot>,
+    /// Every white dot is given, so neighbours without one can't be consecutive
+    pub all_white_given: bool,
+    /// Every black dot is given, so neighbours without one can't be in a ratio of 1:2
+    pub all_black_given: bool,
+}
+
+impl KropkiDots {
+    /// Each pair of cells whose digits are related, along with the dot between them
+    ///
+    /// Pairs without a dot are only related when every dot of a color is given
+    fn pairs(&self) -> Vec<((usize, usize), Option<DotKind>)> {
+        let mut pairs: Vec<_> = self
+            .dots
+            .iter()
+            .map(|dot| (dot.cells, Some(dot.kind)))
+            .collect();
+
+        if self.all_white_given || self.all_black_given {
+            for (i, j) in neighbours() {
+                let has_dot = self
+                    .dots
+                    .iter()
+                    .any(|dot| dot.cells == (i, j) || dot.cells == (j, i));
+                if !has_dot {
+                    pairs.push(((i, j), None));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Can these two digits sit either side of the dot, or either side of an edge without one
+    fn allows(&self, kind: Option<DotKind>, a: u8, b: u8) -> bool {
+        match kind {
+            Some(kind) => kind.allows(a, b),
+            None => {
+                !(self.all_white_given && DotKind::Difference(1).allows(a, b))
+                    && !(self.all_black_given && DotKind::Ratio(2).allows(a, b))
+            }
+        }
+    }
+}
+
+impl Constraint for KropkiDots {
+    fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = Vec::new();
+        for ((i, j), kind) in self.pairs() {
+            if let (Some(a), Some(b)) = (digits[i], digits[j]) {
+                if !self.allows(kind, a, b) {
+                    violating.push(i);
+                    violating.push(j);
+                }
+            }
+        }
+        violating
+    }
+
+    /// Keeps only the candidates that have a partner across each dot
+    fn prune(&self, candidates: &mut [u32]) -> bool {
+        for ((i, j), kind) in self.pairs() {
+            candidates[i] = supported(candidates[i], candidates[j], |a, b| self.allows(kind, a, b));
+            candidates[j] = supported(candidates[j], candidates[i], |b, a| self.allows(kind, a, b));
+            if candidates[i] == 0 || candidates[j] == 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        self.dots
+            .iter()
+            .map(|dot| Overlay::Dot {
+                cells: dot.cells,
+                filled: matches!(dot.kind, DotKind::Ratio(_)),
+            })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        let white = self
+            .dots
+            .iter()
+            .any(|dot| matches!(dot.kind, DotKind::Difference(_)));
+        let black = self
+            .dots
+            .iter()
+            .any(|dot| matches!(dot.kind, DotKind::Ratio(_)));
+
+        let mut ids = Vec::new();
+        if white || self.all_white_given {
+            ids.push("rules-kropki-white");
+        }
+        if black || self.all_black_given {
+            ids.push("rules-kropki-black");
+        }
+        if self.all_white_given {
+            ids.push("rules-kropki-all-white");
+        }
+        if self.all_black_given {
+            ids.push("rules-kropki-all-black");
+        }
+        ids
+    }
+
+    /// The usual difference of 1 and ratio of 2 are left unwritten, as f-puzzles assumes them
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let (mut white, mut black) = (Vec::new(), Vec::new());
+        for dot in self.dots.iter() {
+            let cells = write_cells(&[dot.cells.0, dot.cells.1]);
+            match dot.kind {
+                DotKind::Difference(1) => white.push(json!({ "cells": cells })),
+                DotKind::Difference(difference) => {
+                    white.push(json!({ "cells": cells, "value": difference.to_string() }))
+                }
+                DotKind::Ratio(2) => black.push(json!({ "cells": cells })),
+                DotKind::Ratio(ratio) => {
+                    black.push(json!({ "cells": cells, "value": ratio.to_string() }))
+                }
+            }
+        }
+        let negative = [
+            ("difference", self.all_white_given),
+            ("ratio", self.all_black_given),
+        ]
+        .iter()
+        .filter(|(_, all_given)| *all_given)
+        .map(|&(key, _)| json!(key))
+        .collect();
+
+        let entries = vec![
+            ("difference", white),
+            ("ratio", black),
+            ("negative", negative),
+        ];
+        Some(
+            entries
+                .into_iter()
+                .filter(|(_, list)| !list.is_empty())
+                .map(|(key, list)| (key, JsonValue::Array(list)))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,182 @@
+/// Estimates how likely each digit is to belong in each cell, by sampling random completions of the board
+///
+/// The solver's random completions don't favour every completion equally,
+/// so the likelihoods shown are only rough estimates
+use crate::graphics::toasts::Toasts;
+use crate::input::shortcuts::{Shortcut, Shortcuts};
+use crate::localization::Localization;
+use crate::logic::{
+    board::{Cell, Coordinates, Value},
+    constraints::ConstraintSet,
+    grid::GridSize,
+    notation::Position,
+};
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
+
+pub struct LikelihoodPlugin;
+
+impl Plugin for LikelihoodPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Likelihoods>()
+            .init_resource::<SampleTask>()
+            .add_system(toggle_likelihoods.system())
+            .add_system(sample_completions.system())
+            .add_system(receive_samples.system());
+    }
+}
+
+/// How many completions are sampled in the background at a time, before they are added to the likelihoods shown
+const SAMPLE_BATCH: u32 = 50;
+/// Sampling stops once this many completions have been found for the current position
+const MAX_SAMPLES: u32 = 1000;
+
+/// Resource counting how often each digit appears in each cell across the completions sampled so far
+pub struct Likelihoods {
+    /// Whether the likelihood view is shown, and so whether completions are being sampled
+    pub enabled: bool,
+    /// For each cell in reading order, the number of samples with each digit in that cell, starting from 1
+    pub counts: Vec<Vec<u32>>,
+    pub samples: u32,
+    /// The size of grid the samples were taken on
+    grid: GridSize,
+    /// The position the board is in has no valid completion
+    pub contradiction: bool,
+}
+
+impl Default for Likelihoods {
+    fn default() -> Self {
+        Likelihoods {
+            enabled: false,
+            counts: Vec::new(),
+            samples: 0,
+            grid: GridSize::Nine,
+            contradiction: false,
+        }
+    }
+}
+
+/// The completions sampled in one batch, or `None` if the position has no valid completion
+struct SampleBatch(Option<Vec<Vec<u32>>>);
+
+/// Resource storing the batch of completions being sampled in the background,
+/// which is dropped whenever the board changes
+///
+/// Kept apart from `Likelihoods`, so that polling it doesn't count as changing what is shown
+#[derive(Default)]
+struct SampleTask(Option<Task<SampleBatch>>);
+
+impl Likelihoods {
+    /// The fraction of samples with `num` in the cell at these coordinates
+    pub fn frequency(&self, coordinates: &Coordinates, num: u8) -> f32 {
+        if self.samples == 0 || !self.grid.contains(coordinates) {
+            return 0.0;
+        }
+        self.counts[self.grid.index(coordinates)][(num - 1) as usize] as f32 / self.samples as f32
+    }
+
+    /// Throws away the samples taken so far, such as when the board changes, ready to sample a grid of this size
+    fn clear(&mut self, grid: GridSize) {
+        self.counts = vec![vec![0; grid.digits() as usize]; grid.cell_count()];
+        self.samples = 0;
+        self.grid = grid;
+        self.contradiction = false;
+    }
+}
+
+/// Shows or hides the likelihood view when F9 is pressed, reminding the player that it only gives estimates
+fn toggle_likelihoods(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut likelihoods: ResMut<Likelihoods>,
+    constraints: Res<ConstraintSet>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    if shortcuts.just_pressed(Shortcut::Likelihoods, &keyboard_input) {
+        likelihoods.enabled = !likelihoods.enabled;
+        likelihoods.clear(constraints.grid());
+        if likelihoods.enabled {
+            toasts.push(localization.get("toast-likelihoods-estimated"));
+        }
+    }
+}
+
+/// Starts sampling a batch of random completions of the board in the background,
+/// treating center marks as the only candidates for their cells
+///
+/// Sampling can be slow on sparse boards, so the board stays responsive as the samples come in
+fn sample_completions(
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    mut likelihoods: ResMut<Likelihoods>,
+    mut task: ResMut<SampleTask>,
+    constraints: Res<ConstraintSet>,
+    task_pool: Res<AsyncComputeTaskPool>,
+) {
+    // Replacing the batch underway drops it, so samples of an out of date board are never counted
+    if changed_query.iter().next().is_some() {
+        likelihoods.clear(constraints.grid());
+        task.0 = None;
+    }
+
+    if task.0.is_some()
+        || !likelihoods.enabled
+        || likelihoods.contradiction
+        || likelihoods.samples >= MAX_SAMPLES
+    {
+        return;
+    }
+
+    let grid = constraints.grid();
+    let cells: Vec<_> = query.iter().collect();
+    let position = Position::from_cells(grid, &cells);
+    let constraints = constraints.clone();
+    let batch = SAMPLE_BATCH.min(MAX_SAMPLES - likelihoods.samples);
+    task.0 = Some(task_pool.spawn(async move {
+        let mut rng = rand::thread_rng();
+        let mut counts = vec![vec![0; grid.digits() as usize]; grid.cell_count()];
+        for _ in 0..batch {
+            let solution = match position.random_solution(&constraints, &mut rng) {
+                Some(solution) => solution,
+                None => return SampleBatch(None),
+            };
+            for (i, num) in solution.iter().enumerate() {
+                counts[i][(*num - 1) as usize] += 1;
+            }
+        }
+        SampleBatch(Some(counts))
+    }));
+}
+
+/// Adds each batch of samples to the likelihoods shown once it has finished
+fn receive_samples(mut likelihoods: ResMut<Likelihoods>, mut task: ResMut<SampleTask>) {
+    let batch = match task.0.as_mut() {
+        Some(sampling) => match future::block_on(future::poll_once(sampling)) {
+            Some(batch) => batch,
+            None => return,
+        },
+        None => return,
+    };
+    task.0 = None;
+
+    // Hiding the view throws away the samples taken so far
+    if !likelihoods.enabled {
+        return;
+    }
+    match batch.0 {
+        Some(counts) => {
+            for (cell, cell_counts) in likelihoods.counts.iter_mut().zip(counts.iter()) {
+                for (count, sampled) in cell.iter_mut().zip(cell_counts.iter()) {
+                    *count += sampled;
+                }
+            }
+            // Every cell of a completion holds exactly one digit
+            likelihoods.samples += counts[0].iter().sum::<u32>();
+        }
+        None => likelihoods.contradiction = true,
+    }
+}
@@ -0,0 +1,300 @@
+/// Lines drawn through the cells, whose digits relate to each other along their length
+///
+/// Neighbouring digits on a German whisper differ by at least 5, a renban line holds a set of consecutive digits in any order,
+/// a palindrome reads the same from either end, and the digits along a between line lie strictly between those in the circles at its ends
+use crate::logic::{
+    constraints::{supported, Constraint, LineColor, Overlay},
+    fpuzzles::write_cells,
+};
+use serde_json::{json, Value as JsonValue};
+
+/// How neighbouring digits on a German whisper usually differ, at the least
+pub const WHISPER_DIFFERENCE: u8 = 5;
+
+/// The rule a line follows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    /// A German whisper: neighbouring digits differ by at least this much, usually 5
+    Whisper(u8),
+    /// A renban line: the digits are consecutive once sorted, and never repeat
+    Renban,
+    /// A palindrome: the digits are the same read from either end
+    Palindrome,
+    /// A between line: the digits along the line lie strictly between the digits at either end
+    Between,
+}
+
+impl LineKind {
+    /// Can these two digits sit next to each other on the line
+    fn allows(self, a: u8, b: u8) -> bool {
+        match self {
+            LineKind::Whisper(difference) => a.max(b) - a.min(b) >= difference,
+            LineKind::Renban | LineKind::Palindrome | LineKind::Between => true,
+        }
+    }
+}
+
+/// A line through the centers of the cells in turn, by their index in reading order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub cells: Vec<usize>,
+    pub kind: LineKind,
+}
+
+impl Line {
+    /// The pairs of cells mirroring each other across the middle of the line
+    fn mirrored(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let length = self.cells.len();
+        (0..length / 2).map(move |k| (self.cells[k], self.cells[length - 1 - k]))
+    }
+
+    /// The cells of the line between its two ends
+    fn inner(&self) -> &[usize] {
+        match self.cells.len() {
+            0..=2 => &[],
+            length => &self.cells[1..length - 1],
+        }
+    }
+}
+
+/// The digits strictly between the digits at either end of a between line
+fn between(a: u8, b: u8) -> u32 {
+    digit_range(a.min(b) + 1, a.max(b).saturating_sub(1))
+}
+
+/// The digits from `low` to `high` inclusive, where bit `n` is set for digit `n`
+fn digit_range(low: u8, high: u8) -> u32 {
+    (low.max(1)..=high.min(9)).fold(0, |mask, num| mask | 1 << num)
+}
+
+/// The lines of a puzzle
+pub struct Lines(pub Vec<Line>);
+
+impl Constraint for Lines {
+    /// Consecutive digits on a renban line can never repeat
+    fn houses(&self) -> Vec<Vec<usize>> {
+        self.0
+            .iter()
+            .filter(|line| line.kind == LineKind::Renban)
+            .map(|line| line.cells.clone())
+            .collect()
+    }
+
+    /// Neighbouring digits too close together on a whisper are both marked,
+    /// as are digits with no partner far enough away, such as a 5.
+    /// Every digit on a renban line spread too far apart is marked,
+    /// along with mismatched pairs on a palindrome.
+    /// Digits along a between line that are outside its ends are marked with both ends,
+    /// as are ends too close together to have any digit between them
+    fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = Vec::new();
+        for line in self.0.iter() {
+            match line.kind {
+                LineKind::Whisper(_) => {
+                    for pair in line.cells.windows(2) {
+                        if let (Some(a), Some(b)) = (digits[pair[0]], digits[pair[1]]) {
+                            if !line.kind.allows(a, b) {
+                                violating.extend_from_slice(pair);
+                            }
+                        }
+                    }
+                    if line.cells.len() > 1 {
+                        violating.extend(line.cells.iter().copied().filter(|&i| {
+                            digits[i].map_or(false, |a| (1..=9).all(|b| !line.kind.allows(a, b)))
+                        }));
+                    }
+                }
+                LineKind::Renban => {
+                    let filled: Vec<usize> = line
+                        .cells
+                        .iter()
+                        .copied()
+                        .filter(|&i| digits[i].is_some())
+                        .collect();
+                    let nums = filled.iter().filter_map(|&i| digits[i]);
+                    if let (Some(lowest), Some(highest)) = (nums.clone().min(), nums.max()) {
+                        if usize::from(highest - lowest) >= line.cells.len() {
+                            violating.extend(filled);
+                        }
+                    }
+                }
+                LineKind::Palindrome => {
+                    for (i, j) in line.mirrored() {
+                        if let (Some(a), Some(b)) = (digits[i], digits[j]) {
+                            if a != b {
+                                violating.push(i);
+                                violating.push(j);
+                            }
+                        }
+                    }
+                }
+                LineKind::Between => {
+                    let (first, last) = match (line.cells.first(), line.cells.last()) {
+                        (Some(&first), Some(&last)) if !line.inner().is_empty() => (first, last),
+                        _ => continue,
+                    };
+                    if let (Some(a), Some(b)) = (digits[first], digits[last]) {
+                        let outside: Vec<usize> = line
+                            .inner()
+                            .iter()
+                            .copied()
+                            .filter(|&i| {
+                                digits[i].map_or(false, |num| between(a, b) & 1 << num == 0)
+                            })
+                            .collect();
+                        if between(a, b) == 0 || !outside.is_empty() {
+                            violating.push(first);
+                            violating.push(last);
+                            violating.extend(outside);
+                        }
+                    }
+                }
+            }
+        }
+        violating
+    }
+
+    /// Keeps only the candidates with a partner far enough away along each whisper,
+    /// the candidates of some run of consecutive digits that fits each renban line,
+    /// the candidates shared with the mirrored cell of each palindrome,
+    /// and the candidates of each between line that fit some pair of digits at its ends
+    fn prune(&self, candidates: &mut [u32]) -> bool {
+        for line in self.0.iter() {
+            match line.kind {
+                LineKind::Whisper(_) => {
+                    for pair in line.cells.windows(2) {
+                        let (i, j) = (pair[0], pair[1]);
+                        candidates[i] =
+                            supported(candidates[i], candidates[j], |a, b| line.kind.allows(a, b));
+                        candidates[j] =
+                            supported(candidates[j], candidates[i], |b, a| line.kind.allows(a, b));
+                        if candidates[i] == 0 || candidates[j] == 0 {
+                            return false;
+                        }
+                    }
+                }
+                LineKind::Renban => {
+                    let length = line.cells.len() as u8;
+                    if length == 0 {
+                        continue;
+                    }
+                    // Every run of digits the line could hold, given the candidates of each of its cells
+                    let allowed = (1..=10u8.saturating_sub(length))
+                        .map(|low| digit_range(low, low + length - 1))
+                        .filter(|&run| line.cells.iter().all(|&i| candidates[i] & run != 0))
+                        .fold(0, |mask, run| mask | run);
+                    for &i in line.cells.iter() {
+                        candidates[i] &= allowed;
+                        if candidates[i] == 0 {
+                            return false;
+                        }
+                    }
+                }
+                LineKind::Palindrome => {
+                    for (i, j) in line.mirrored() {
+                        let shared = candidates[i] & candidates[j];
+                        if shared == 0 {
+                            return false;
+                        }
+                        candidates[i] = shared;
+                        candidates[j] = shared;
+                    }
+                }
+                LineKind::Between => {
+                    let (first, last) = match (line.cells.first(), line.cells.last()) {
+                        (Some(&first), Some(&last)) if !line.inner().is_empty() => (first, last),
+                        _ => continue,
+                    };
+                    // Each pair of digits at the ends leaving a candidate between them for every cell along the line
+                    let fits = |a: u8, b: u8| {
+                        line.inner()
+                            .iter()
+                            .all(|&i| candidates[i] & between(a, b) != 0)
+                    };
+                    let (mut ends, mut inner) = ((0, 0), 0);
+                    for a in (1..=9u8).filter(|&a| candidates[first] & 1 << a != 0) {
+                        for b in (1..=9u8).filter(|&b| candidates[last] & 1 << b != 0) {
+                            if fits(a, b) {
+                                ends = (ends.0 | 1 << a, ends.1 | 1 << b);
+                                inner |= between(a, b);
+                            }
+                        }
+                    }
+                    if inner == 0 {
+                        return false;
+                    }
+                    candidates[first] = ends.0;
+                    candidates[last] = ends.1;
+                    for &i in line.inner() {
+                        candidates[i] &= inner;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Between lines end in an empty circle at either end
+    fn overlays(&self) -> Vec<Overlay> {
+        let mut overlays = Vec::new();
+        for line in self.0.iter() {
+            overlays.push(Overlay::Line {
+                cells: line.cells.clone(),
+                color: match line.kind {
+                    LineKind::Whisper(_) => LineColor::Green,
+                    LineKind::Renban => LineColor::Purple,
+                    LineKind::Palindrome | LineKind::Between => LineColor::Gray,
+                },
+            });
+            if line.kind == LineKind::Between {
+                let ends = line.cells.first().into_iter().chain(line.cells.last());
+                overlays.extend(ends.map(|&cell| Overlay::Bulb {
+                    cell,
+                    filled: false,
+                }));
+            }
+        }
+        overlays
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        let mut ids = Vec::new();
+        for line in self.0.iter() {
+            let id = match line.kind {
+                LineKind::Whisper(_) => "rules-whisper",
+                LineKind::Renban => "rules-renban",
+                LineKind::Palindrome => "rules-palindrome",
+                LineKind::Between => "rules-between",
+            };
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    /// Each line is written as a group of its own, so that a whisper can keep its own difference
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let entries = self
+            .0
+            .iter()
+            .map(|line| {
+                let cells = write_cells(&line.cells);
+                let (key, group) = match line.kind {
+                    LineKind::Whisper(WHISPER_DIFFERENCE) => {
+                        ("whispers", json!({ "lines": [cells] }))
+                    }
+                    LineKind::Whisper(difference) => (
+                        "whispers",
+                        json!({ "lines": [cells], "value": difference.to_string() }),
+                    ),
+                    LineKind::Renban => ("renban", json!({ "lines": [cells] })),
+                    LineKind::Palindrome => ("palindrome", json!({ "lines": [cells] })),
+                    LineKind::Between => ("betweenline", json!({ "lines": [cells] })),
+                };
+                (key, JsonValue::Array(vec![group]))
+            })
+            .collect();
+        Some(entries)
+    }
+}
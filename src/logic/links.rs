@@ -0,0 +1,136 @@
+/// Finds the links between the cells that could hold a chosen digit, as used by coloring and chaining techniques
+use crate::input::shortcuts::{Shortcut, Shortcuts};
+use crate::logic::{
+    board::{marks::Marks, Cell, Coordinates, Value},
+    candidates::compute_candidates,
+    constraints::ConstraintSet,
+};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+pub struct LinkPlugin;
+
+impl Plugin for LinkPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<LinkGraph>()
+            .add_system(cycle_link_digit.system().label(LinkLabels::ChooseDigit))
+            .add_system(compute_links.system().after(LinkLabels::ChooseDigit));
+    }
+}
+
+#[derive(SystemLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+enum LinkLabels {
+    ChooseDigit,
+}
+
+/// A pair of cells in the same row, column or square that could both hold the digit
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Link {
+    pub from: Coordinates,
+    pub to: Coordinates,
+    /// The two cells are the only places left for the digit in a house they share,
+    /// so one of them must hold it
+    ///
+    /// Weak links only mean that both cells can't hold the digit at once
+    pub strong: bool,
+}
+
+/// Resource storing the graph of cells that could hold the chosen digit, and the links between them
+#[derive(Default)]
+pub struct LinkGraph {
+    /// The digit the graph is shown for, if any
+    pub digit: Option<u8>,
+    pub nodes: Vec<Coordinates>,
+    pub links: Vec<Link>,
+}
+
+/// Steps through the digits to show the graph for when F10 is pressed, before hiding it again
+fn cycle_link_digit(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut link_graph: ResMut<LinkGraph>,
+) {
+    if shortcuts.just_pressed(Shortcut::LinkGraph, &keyboard_input) {
+        link_graph.digit = match link_graph.digit {
+            None => Some(1),
+            Some(9) => None,
+            Some(num) => Some(num + 1),
+        };
+    }
+}
+
+/// Rebuilds the graph whenever the board or the chosen digit changes
+///
+/// Center marks are treated as the candidates for their cell,
+/// and other unfilled cells may hold any digit not ruled out by their peers
+fn compute_links(
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    mut link_graph: ResMut<LinkGraph>,
+    constraints: Res<ConstraintSet>,
+    mut last_digit: Local<Option<u8>>,
+) {
+    // Checking the digit directly, as this system's own changes to the graph would trigger change detection
+    let digit_changed = *last_digit != link_graph.digit;
+    if !digit_changed && changed_query.iter().next().is_none() {
+        return;
+    }
+    *last_digit = link_graph.digit;
+
+    let num = match link_graph.digit {
+        Some(num) => num,
+        None => {
+            if !link_graph.nodes.is_empty() {
+                link_graph.nodes.clear();
+                link_graph.links.clear();
+            }
+            return;
+        }
+    };
+
+    let cells: Vec<_> = query.iter().collect();
+    let computed = compute_candidates(&cells, &constraints);
+    let grid = constraints.grid();
+    let mut nodes: Vec<Coordinates> = cells
+        .iter()
+        .filter(|(coordinates, _)| grid.contains(coordinates))
+        .filter(|(coordinates, value)| match value {
+            Value::Marked(center, _) if !center.is_empty() => center.contains(num),
+            Value::Filled(_) => false,
+            _ => computed
+                .get(*coordinates)
+                .map_or(false, |candidates| candidates.contains(&num)),
+        })
+        .map(|(coordinates, _)| (*coordinates).clone())
+        .collect();
+    nodes.sort_by_key(|coordinates| (coordinates.row, coordinates.column));
+
+    // Pairs sharing two houses are linked once, keeping the strongest link
+    let mut links: HashMap<(usize, usize), bool> = HashMap::default();
+    for house in constraints.houses() {
+        let members: Vec<usize> = (0..nodes.len())
+            .filter(|&node| house.contains(&grid.index(&nodes[node])))
+            .collect();
+        // Smaller houses needn't contain the digit at all, so can't force it into one of their two cells
+        let strong = members.len() == 2 && house.len() == grid.digits() as usize;
+
+        for (a, &from) in members.iter().enumerate() {
+            for &to in members[a + 1..].iter() {
+                *links.entry((from, to)).or_insert(false) |= strong;
+            }
+        }
+    }
+
+    let mut links: Vec<_> = links.into_iter().collect();
+    links.sort_unstable_by_key(|(pair, _)| *pair);
+
+    link_graph.links = links
+        .into_iter()
+        .map(|((from, to), strong)| Link {
+            from: nodes[from].clone(),
+            to: nodes[to].clone(),
+            strong,
+        })
+        .collect();
+    link_graph.nodes = nodes;
+}
@@ -0,0 +1,134 @@
+/// Little killer sudoku, where a clue outside the grid gives the sum of the digits along a diagonal
+///
+/// Unlike killer cages, digits may repeat along the diagonal where the classic rules allow it
+use crate::logic::constraints::{Constraint, Overlay};
+use serde_json::{json, Value as JsonValue};
+
+/// A sum written outside the grid, with an arrow pointing along the diagonal it applies to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LittleKillerClue {
+    /// The cells along the diagonal, starting from the one nearest the clue, by their index in reading order
+    pub cells: Vec<usize>,
+    /// The direction of the arrow, as rows down and columns across
+    pub step: (i32, i32),
+    /// The total of the digits along the diagonal
+    pub sum: u32,
+}
+
+impl LittleKillerClue {
+    /// The clue written at `row` and `column`, counted from 0 at the top left cell,
+    /// so that clues in the band around the grid sit at -1 or 9
+    pub fn new(row: i32, column: i32, step: (i32, i32), sum: u32) -> LittleKillerClue {
+        let (mut row, mut column) = (row + step.0, column + step.1);
+        let mut cells = Vec::new();
+        while (0..9).contains(&row) && (0..9).contains(&column) {
+            cells.push((row * 9 + column) as usize);
+            row += step.0;
+            column += step.1;
+        }
+        LittleKillerClue { cells, step, sum }
+    }
+}
+
+/// The little killer clues of a puzzle
+pub struct LittleKillers(pub Vec<LittleKillerClue>);
+
+impl Constraint for LittleKillers {
+    /// Diagonals whose digits add up to more than their sum, or to less once they are full,
+    /// have every digit along them marked
+    fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = Vec::new();
+        for clue in self.0.iter() {
+            let filled: Vec<usize> = clue
+                .cells
+                .iter()
+                .copied()
+                .filter(|&i| digits[i].is_some())
+                .collect();
+            let total: u32 = filled
+                .iter()
+                .filter_map(|&i| digits[i])
+                .map(u32::from)
+                .sum();
+
+            // Empty cells still need at least 1 each
+            let empty = (clue.cells.len() - filled.len()) as u32;
+            let full = empty == 0;
+            if (full && total != clue.sum) || (!full && total + empty > clue.sum) {
+                violating.extend(filled);
+            }
+        }
+        violating
+    }
+
+    /// Keeps only the candidates that leave the other cells of the diagonal able to make up the rest of the sum
+    fn prune(&self, candidates: &mut [u32]) -> bool {
+        for clue in self.0.iter() {
+            if clue.cells.iter().any(|&i| candidates[i] == 0) {
+                return false;
+            }
+            let smallest = |mask: u32| mask.trailing_zeros();
+            let largest = |mask: u32| 31 - mask.leading_zeros();
+            let low: u32 = clue.cells.iter().map(|&i| smallest(candidates[i])).sum();
+            let high: u32 = clue.cells.iter().map(|&i| largest(candidates[i])).sum();
+
+            for &i in clue.cells.iter() {
+                let mask = candidates[i];
+                let (others_low, others_high) = (low - smallest(mask), high - largest(mask));
+                candidates[i] = (1..=9u32)
+                    .filter(|&num| mask & 1 << num != 0)
+                    .filter(|&num| {
+                        num <= clue.sum && (others_low..=others_high).contains(&(clue.sum - num))
+                    })
+                    .fold(0, |mask, num| mask | 1 << num);
+                if candidates[i] == 0 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        self.0
+            .iter()
+            .filter(|clue| !clue.cells.is_empty())
+            .map(|clue| Overlay::OuterClue {
+                cells: clue.cells.clone(),
+                step: clue.step,
+                label: clue.sum.to_string(),
+            })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-little-killer"]
+    }
+
+    /// Each clue is written one step back from the first cell of its diagonal, counting rows and columns from 1
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let clues = self
+            .0
+            .iter()
+            // Clues pointing away from the grid cover no cells, so constrain nothing and can be left out
+            .filter_map(|clue| Some((clue, *clue.cells.first()?)))
+            .map(|(clue, first)| {
+                let direction = match clue.step {
+                    (-1, -1) => "UL",
+                    (-1, 1) => "UR",
+                    (1, -1) => "DL",
+                    (1, 1) => "DR",
+                    _ => return None,
+                };
+                let row = (first / 9) as i32 - clue.step.0 + 1;
+                let column = (first % 9) as i32 - clue.step.1 + 1;
+                Some(json!({
+                    "cell": format!("R{}C{}", row, column),
+                    "direction": direction,
+                    "value": clue.sum.to_string()
+                }))
+            })
+            .collect::<Option<Vec<JsonValue>>>()?;
+        Some(vec![("littlekillersum", JsonValue::Array(clues))])
+    }
+}
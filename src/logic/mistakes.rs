@@ -0,0 +1,63 @@
+/// Counts the wrong digits the player enters, optionally ending the game after too many
+use crate::{
+    logic::{
+        board::DigitPlaced,
+        sudoku_generation::{CompletePuzzle, InitialPuzzle},
+    },
+    settings::Settings,
+    CommonLabels,
+};
+use bevy::prelude::*;
+
+pub struct MistakePlugin;
+
+impl Plugin for MistakePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Mistakes>()
+            .add_event::<GameOver>()
+            .add_system(count_mistakes.system().after(CommonLabels::Action));
+    }
+}
+
+/// In limited-mistakes mode, the game ends once this many mistakes have been made
+pub const MISTAKE_LIMIT: u32 = 3;
+
+/// Resource counting the digits placed on the current puzzle that contradict its solution
+#[derive(Default)]
+pub struct Mistakes {
+    pub count: u32,
+}
+
+/// Event sent when the player runs out of mistakes in limited-mistakes mode
+pub struct GameOver;
+
+/// Checks each digit placed against the solution
+fn count_mistakes(
+    mut event_reader: EventReader<DigitPlaced>,
+    mut game_over_writer: EventWriter<GameOver>,
+    mut mistakes: ResMut<Mistakes>,
+    initial_puzzle: Res<InitialPuzzle>,
+    complete_puzzle: Res<CompletePuzzle>,
+    settings: Res<Settings>,
+) {
+    // Each puzzle starts with a clean slate, including when it is reset
+    if initial_puzzle.is_changed() {
+        mistakes.count = 0;
+    }
+
+    for event in event_reader.iter() {
+        let correct = match complete_puzzle.numbers.get(&event.coordinates) {
+            Some(value) => value.matches(event.num, false),
+            None => true,
+        };
+
+        if !correct {
+            mistakes.count += 1;
+
+            // Mistakes made after dismissing the game over, or before the mode was switched on, end the game too
+            if settings.limited_mistakes && mistakes.count >= MISTAKE_LIMIT {
+                game_over_writer.send(GameOver);
+            }
+        }
+    }
+}
@@ -1,3 +1,36 @@
 /// Sudoku game logic
+pub mod anti_king;
+pub mod anti_knight;
+pub mod archive;
 pub mod board;
+pub mod candidates;
+pub mod collections;
+pub mod conflicts;
+pub mod constraints;
+pub mod diagonals;
+pub mod disjoint_groups;
+pub mod extra_regions;
+pub mod fpuzzles;
+pub mod grid;
+pub mod hints;
+pub mod invariants;
+pub mod killer;
+pub mod kropki;
+pub mod likelihood;
+pub mod lines;
+pub mod links;
+pub mod little_killer;
+pub mod mistakes;
+pub mod non_consecutive;
+pub mod notation;
+pub mod quadruples;
+pub mod records;
+pub mod regions;
+pub mod sdk;
+pub mod setter;
+pub mod stats;
 pub mod sudoku_generation;
+pub mod techniques;
+pub mod thermometers;
+pub mod timer;
+pub mod xv;
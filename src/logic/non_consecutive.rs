@@ -0,0 +1,48 @@
+/// Non-consecutive sudoku, where cells sharing an edge can't contain consecutive digits
+use crate::logic::constraints::{neighbours, supported, Constraint};
+use serde_json::Value as JsonValue;
+
+/// The rule that cells sharing an edge can't contain digits that differ by one
+///
+/// There's nothing to draw, as the rule applies across the whole board
+pub struct NonConsecutive;
+
+/// Can these digits sit in neighbouring cells
+fn allows(a: u8, b: u8) -> bool {
+    a.max(b) - a.min(b) != 1
+}
+
+impl Constraint for NonConsecutive {
+    fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = Vec::new();
+        for (i, j) in neighbours() {
+            if let (Some(a), Some(b)) = (digits[i], digits[j]) {
+                if !allows(a, b) {
+                    violating.push(i);
+                    violating.push(j);
+                }
+            }
+        }
+        violating
+    }
+
+    /// Keeps only the candidates that leave a digit for each neighbour
+    fn prune(&self, candidates: &mut [u32]) -> bool {
+        for (i, j) in neighbours() {
+            candidates[i] = supported(candidates[i], candidates[j], allows);
+            candidates[j] = supported(candidates[j], candidates[i], allows);
+            if candidates[i] == 0 || candidates[j] == 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-non-consecutive"]
+    }
+
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        Some(vec![("nonconsecutive", JsonValue::Bool(true))])
+    }
+}
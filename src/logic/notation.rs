@@ -0,0 +1,613 @@
+/// Reading and solving positions written in external notations
+///
+/// Two notations are supported, both listing cells from left to right, top to bottom:
+/// - 81 character strings of givens, using `.` or `0` for empty cells
+/// - 729 character pencilmark strings, as exported by tools like HoDoKu,
+/// where each cell is written as 9 characters and the `n`th character is `n` when it is a candidate,
+/// and cells with a single candidate are read as givens
+///
+/// Other sizes of grid are written the same way, so a 4x4 puzzle is 16 characters of givens or 64 of pencilmarks,
+/// while a 16x16 puzzle is 256 characters of givens or 4096 of pencilmarks, writing the digits beyond 9 as the letters A to G
+///
+/// Links to puzzles on f-puzzles and SudokuPad are also accepted, see the `fpuzzles` module
+use crate::logic::{
+    board::{
+        marks::{CenterMarks, CornerMarks, Marks},
+        Coordinates, Value,
+    },
+    constraints::ConstraintSet,
+    fpuzzles::{self, LinkError},
+    grid::{digit_symbol, parse_symbol, GridSize},
+};
+use rand::{seq::SliceRandom, Rng};
+
+/// A problem encountered when reading a position
+#[derive(Debug, PartialEq, Eq)]
+pub enum NotationError {
+    /// The string was not the length of the givens or pencilmarks of any size of grid, ignoring whitespace
+    InvalidLength(usize),
+    /// A character other than a digit used by the grid, `.` or `0` was found
+    InvalidCharacter(char),
+    /// The puzzle in an f-puzzles or SudokuPad link could not be read
+    InvalidLink(LinkError),
+}
+
+/// A reason a position can't be played as it stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// The same digit is given in two cells that can see each other, listed by their index in reading order
+    ClashingGivens { digit: u8, cells: (usize, usize) },
+    /// The givens break a constraint of the puzzle other than repeating a digit, at the cell listed
+    BrokenConstraint(usize),
+    /// The givens don't clash directly, but can't all be part of one solution
+    NoSolution,
+    /// The givens allow several solutions, so the player can't be sure of the one digits are checked against
+    MultipleSolutions,
+    /// The solver ran out of guesses before it could tell whether the givens allow exactly one solution
+    Undetermined,
+}
+
+/// Whether a position has exactly one solution, as far as the solver could tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Uniqueness {
+    NoSolution,
+    Unique,
+    Multiple,
+    /// The search ran out of guesses before finding a second solution or ruling one out
+    Undetermined,
+}
+
+/// How difficult a position is to solve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    /// Solvable by repeatedly filling cells with a single candidate,
+    /// or digits with a single possible cell in a row, column or square
+    Singles,
+    /// Requires trial and error, or techniques beyond singles
+    Guessing {
+        /// The number of guesses our solver made before finding the solution
+        guesses: u32,
+    },
+}
+
+/// A sudoku position, including the candidates that remain in each unfilled cell
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Position {
+    /// The size of the board the position is on
+    pub grid: GridSize,
+    /// The digit in each cell, in reading order
+    pub digits: Vec<Option<u8>>,
+    /// The digits that could be placed in each cell, where bit `n` is set for digit `n`
+    pub candidates: Vec<u32>,
+}
+
+/// Is this written as a pencilmark string, rather than as givens or a link
+pub fn is_pencilmarks(notation: &str) -> bool {
+    !fpuzzles::is_link(notation)
+        && pencilmarks_grid(notation.chars().filter(|c| !c.is_whitespace()).count()).is_some()
+}
+
+/// The size of grid written with this many characters of pencilmarks, one for each digit of each cell
+fn pencilmarks_grid(length: usize) -> Option<GridSize> {
+    GridSize::ALL
+        .iter()
+        .copied()
+        .find(|grid| grid.cell_count() * grid.digits() as usize == length)
+}
+
+impl Position {
+    /// Reads a position in either of the supported notations, based on its length, or from a puzzle link
+    pub fn parse(notation: &str) -> Result<Position, NotationError> {
+        if fpuzzles::is_link(notation) {
+            return fpuzzles::parse_link(notation).map_err(NotationError::InvalidLink);
+        }
+
+        let chars: Vec<char> = notation.chars().filter(|c| !c.is_whitespace()).collect();
+        if let Some(grid) = GridSize::from_cell_count(chars.len()) {
+            Position::from_givens(grid, &chars)
+        } else if let Some(grid) = pencilmarks_grid(chars.len()) {
+            Position::from_pencilmarks(grid, &chars)
+        } else {
+            Err(NotationError::InvalidLength(chars.len()))
+        }
+    }
+
+    /// An empty position on a grid of this size, where every digit is a candidate for every cell
+    pub fn empty(grid: GridSize) -> Position {
+        Position {
+            grid,
+            digits: vec![None; grid.cell_count()],
+            candidates: vec![grid.all_candidates(); grid.cell_count()],
+        }
+    }
+
+    fn from_givens(grid: GridSize, chars: &[char]) -> Result<Position, NotationError> {
+        let mut position = Position::empty(grid);
+
+        for (i, c) in chars.iter().enumerate() {
+            if let Some(num) = parse_digit(*c, grid)? {
+                position.digits[i] = Some(num);
+                position.candidates[i] = 1 << num;
+            }
+        }
+        Ok(position)
+    }
+
+    /// Pencilmark exports don't tell givens apart from placed digits, so every solved cell is taken as a given
+    fn from_pencilmarks(grid: GridSize, chars: &[char]) -> Result<Position, NotationError> {
+        let mut position = Position {
+            candidates: vec![0; grid.cell_count()],
+            ..Position::empty(grid)
+        };
+
+        for (i, cell) in chars.chunks(grid.digits() as usize).enumerate() {
+            for c in cell.iter() {
+                if let Some(num) = parse_digit(*c, grid)? {
+                    position.candidates[i] |= 1 << num;
+                }
+            }
+            if position.candidates[i].count_ones() == 1 {
+                position.digits[i] = Some(position.candidates[i].trailing_zeros() as u8);
+            }
+        }
+        Ok(position)
+    }
+
+    /// A position with every cell filled in with the digits of the solution provided
+    pub fn from_solution(grid: GridSize, solution: &[u8]) -> Position {
+        let mut position = Position::empty(grid);
+
+        for (i, num) in solution.iter().enumerate() {
+            position.digits[i] = Some(*num);
+            position.candidates[i] = 1 << num;
+        }
+        position
+    }
+
+    /// Captures the position on the board, treating center marks as the candidates for a cell
+    ///
+    /// Cells without center marks may contain any digit, while cells off the grid are skipped,
+    /// such as those of a board that has yet to be rebuilt for a new grid
+    pub fn from_cells(grid: GridSize, cells: &[(&Coordinates, &Value)]) -> Position {
+        let mut position = Position::empty(grid);
+
+        for (coordinates, value) in cells.iter().filter(|(c, _)| grid.contains(c)) {
+            let i = grid.index(coordinates);
+            match value {
+                Value::Filled(num) => {
+                    position.digits[i] = Some(*num);
+                    position.candidates[i] = 1 << num;
+                }
+                Value::Marked(center, _) if !center.is_empty() => {
+                    position.candidates[i] = (1..=grid.digits())
+                        .filter(|num| center.contains(*num))
+                        .fold(0, |mask, num| mask | 1 << num);
+                }
+                _ => (),
+            }
+        }
+        position
+    }
+
+    /// The value of each cell in reading order, with candidates converted into center marks
+    ///
+    /// Cells where every digit is still a candidate are left empty
+    pub fn values(&self) -> impl Iterator<Item = Value> + '_ {
+        self.digits
+            .iter()
+            .zip(self.candidates.iter())
+            .map(|(digit, mask)| match digit {
+                Some(num) => Value::Filled(*num),
+                None if *mask == self.grid.all_candidates() => Value::Empty,
+                None => Value::Marked(
+                    CenterMarks::from_digits(
+                        (1..=self.grid.digits()).filter(|num| mask & 1 << num != 0),
+                    ),
+                    CornerMarks::default(),
+                ),
+            })
+    }
+
+    /// The digits given by the position in reading order, ignoring candidates
+    pub fn givens(&self) -> impl Iterator<Item = Value> + '_ {
+        self.digits.iter().map(|digit| match digit {
+            Some(num) => Value::Filled(*num),
+            None => Value::Empty,
+        })
+    }
+
+    /// Writes the digits of the position as a string with a character for each cell, using `.` for empty cells
+    pub fn to_givens(&self) -> String {
+        self.digits
+            .iter()
+            .map(|digit| match digit {
+                Some(num) => digit_symbol(*num),
+                None => '.',
+            })
+            .collect()
+    }
+
+    /// Writes the position as a pencilmark string, with a character for each digit of each cell
+    pub fn to_pencilmarks(&self) -> String {
+        let mut out = String::with_capacity(self.grid.cell_count() * self.grid.digits() as usize);
+        for mask in self.candidates.iter() {
+            for num in 1..=self.grid.digits() {
+                if mask & 1 << num != 0 {
+                    out.push(digit_symbol(num));
+                } else {
+                    out.push('.');
+                }
+            }
+        }
+        out
+    }
+
+    /// Finds a solution that agrees with both the digits and the candidates of this position
+    pub fn solve(&self, constraints: &ConstraintSet) -> Option<Vec<u8>> {
+        let mut solver = Solver::new(self, constraints)?;
+        solver.search(1);
+        solver.solutions.pop()
+    }
+
+    /// Finds a solution chosen at random from those that agree with this position
+    ///
+    /// Not every solution is equally likely, but every solution can be found
+    pub fn random_solution(
+        &self,
+        constraints: &ConstraintSet,
+        rng: &mut impl Rng,
+    ) -> Option<Vec<u8>> {
+        let mut solver = Solver::new(self, constraints)?;
+        solver.guess_order.shuffle(rng);
+        solver.search(1);
+        solver.solutions.pop()
+    }
+
+    /// Counts the solutions to this position, stopping once `limit` have been found
+    ///
+    /// Returns `None` if the search runs out of guesses first, as the count is then unknown
+    pub fn count_solutions(&self, constraints: &ConstraintSet, limit: usize) -> Option<usize> {
+        match Solver::new(self, constraints) {
+            Some(mut solver) => {
+                solver.search(limit);
+                if solver.exhausted {
+                    None
+                } else {
+                    Some(solver.solutions.len())
+                }
+            }
+            None => Some(0),
+        }
+    }
+
+    /// Checks whether this position has exactly one solution
+    ///
+    /// A generator removing clues should keep any clue it can't prove unnecessary,
+    /// so only `Uniqueness::Unique` means a clue can go
+    pub fn uniqueness(&self, constraints: &ConstraintSet) -> Uniqueness {
+        match self.count_solutions(constraints, 2) {
+            Some(0) => Uniqueness::NoSolution,
+            Some(1) => Uniqueness::Unique,
+            Some(_) => Uniqueness::Multiple,
+            None => Uniqueness::Undetermined,
+        }
+    }
+
+    /// Removes each digit placed from the candidates of every cell that can see it,
+    /// as a player does when filling in candidates
+    ///
+    /// Returns `None` if the position contradicts itself
+    pub fn eliminate(&self, constraints: &ConstraintSet) -> Option<Position> {
+        let solver = Solver::new(self, constraints)?;
+        Some(Position {
+            candidates: solver.candidates,
+            ..self.clone()
+        })
+    }
+
+    /// Fills in every cell that singles alone can solve,
+    /// giving the position where a player who only knows singles gets stuck
+    ///
+    /// Returns `None` if the position contradicts itself
+    pub fn apply_singles(&self, constraints: &ConstraintSet) -> Option<Position> {
+        let mut solver = Solver::new(self, constraints)?;
+        let mut solved = vec![false; self.grid.cell_count()];
+        if !propagate(&mut solver.candidates, &mut solved, constraints) {
+            return None;
+        }
+
+        let mut position = Position {
+            candidates: solver.candidates.clone(),
+            ..self.clone()
+        };
+        for (i, mask) in solver.candidates.iter().enumerate() {
+            if solved[i] {
+                position.digits[i] = Some(mask.trailing_zeros() as u8);
+            }
+        }
+        Some(position)
+    }
+
+    /// Checks that the position makes a proper puzzle, with givens that don't clash and exactly one solution
+    pub fn validate(&self, constraints: &ConstraintSet) -> Result<(), PuzzleError> {
+        for (i, digit) in self.digits.iter().enumerate() {
+            if let Some(num) = digit {
+                // Each pair of clashing cells is found from its first cell
+                let clash = constraints
+                    .peers(i)
+                    .iter()
+                    .find(|&&j| j > i && self.digits[j] == Some(*num));
+                if let Some(&j) = clash {
+                    return Err(PuzzleError::ClashingGivens {
+                        digit: *num,
+                        cells: (i, j),
+                    });
+                }
+            }
+        }
+        if let Some(&i) = constraints.violations(&self.digits).first() {
+            return Err(PuzzleError::BrokenConstraint(i));
+        }
+
+        match self.uniqueness(constraints) {
+            Uniqueness::NoSolution => Err(PuzzleError::NoSolution),
+            Uniqueness::Unique => Ok(()),
+            Uniqueness::Multiple => Err(PuzzleError::MultipleSolutions),
+            Uniqueness::Undetermined => Err(PuzzleError::Undetermined),
+        }
+    }
+
+    /// Grades the position, returning `None` if it does not have exactly one solution
+    pub fn grade(&self, constraints: &ConstraintSet) -> Option<Grade> {
+        let mut solver = Solver::new(self, constraints)?;
+        solver.search(2);
+        if solver.exhausted || solver.solutions.len() != 1 {
+            return None;
+        }
+
+        Some(match solver.guesses {
+            0 => Grade::Singles,
+            guesses => Grade::Guessing { guesses },
+        })
+    }
+}
+
+/// Converts a character into a digit used by the grid, where `.` and `0` represent the absence of a digit
+fn parse_digit(c: char, grid: GridSize) -> Result<Option<u8>, NotationError> {
+    match c {
+        '.' | '0' => Ok(None),
+        _ => match parse_symbol(c) {
+            Some(num) if num <= grid.digits() => Ok(Some(num)),
+            _ => Err(NotationError::InvalidCharacter(c)),
+        },
+    }
+}
+
+/// Places `num` in cell `i`, removing it from the candidates of its peers
+///
+/// Returns false if `num` was not a candidate, or if this leaves a peer without any candidates
+fn place(candidates: &mut [u32], i: usize, num: u8, constraints: &ConstraintSet) -> bool {
+    if candidates[i] & 1 << num == 0 {
+        return false;
+    }
+    candidates[i] = 1 << num;
+
+    for &j in constraints.peers(i) {
+        candidates[j] &= !(1 << num);
+        if candidates[j] == 0 {
+            return false;
+        }
+    }
+    true
+}
+
+/// Fills in naked and hidden singles until none remain,
+/// pruning the candidates ruled out by the other constraints of the puzzle in between
+///
+/// Returns false if a contradiction is found
+fn propagate(candidates: &mut [u32], solved: &mut [bool], constraints: &ConstraintSet) -> bool {
+    let digits = constraints.grid().digits();
+    let mut progress = true;
+    while progress {
+        progress = false;
+
+        // Naked singles: cells with only one candidate
+        for i in 0..candidates.len() {
+            if !solved[i] && candidates[i].count_ones() == 1 {
+                let num = candidates[i].trailing_zeros() as u8;
+                if !place(candidates, i, num, constraints) {
+                    return false;
+                }
+                solved[i] = true;
+                progress = true;
+            }
+        }
+
+        // Hidden singles: digits with only one possible cell in a house,
+        // which only applies to houses with a cell for every digit, as smaller houses needn't contain every digit
+        for house in constraints
+            .houses()
+            .iter()
+            .filter(|house| house.len() == digits as usize)
+        {
+            for num in 1..=digits {
+                let mut possible = house.iter().filter(|&&i| candidates[i] & 1 << num != 0);
+                match (possible.next(), possible.next()) {
+                    (None, _) => return false,
+                    (Some(&i), None) if !solved[i] => {
+                        if !place(candidates, i, num, constraints) {
+                            return false;
+                        }
+                        solved[i] = true;
+                        progress = true;
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let before = candidates.to_vec();
+        if !constraints.prune(candidates) || candidates.iter().any(|&mask| mask == 0) {
+            return false;
+        }
+        if *candidates != *before {
+            progress = true;
+        }
+    }
+    true
+}
+
+/// The most guesses a search may make before giving up
+///
+/// Classic 9x9 puzzles never come close, but the branching of 16x16 grids can occasionally explode
+const GUESS_BUDGET: u32 = 100_000;
+
+/// A backtracking solver which fills in singles before guessing
+struct Solver<'a> {
+    constraints: &'a ConstraintSet,
+    candidates: Vec<u32>,
+    solutions: Vec<Vec<u8>>,
+    guesses: u32,
+    /// Did the search stop after running out of guesses, rather than after exploring every option
+    exhausted: bool,
+    /// The order in which digits are tried when guessing
+    guess_order: Vec<u8>,
+}
+
+impl<'a> Solver<'a> {
+    /// Sets up the solver, returning `None` if the position already contradicts itself
+    fn new(position: &Position, constraints: &'a ConstraintSet) -> Option<Solver<'a>> {
+        let mut candidates = position.candidates.clone();
+        for (i, digit) in position.digits.iter().enumerate() {
+            if let Some(num) = digit {
+                if !place(&mut candidates, i, *num, constraints) {
+                    return None;
+                }
+            }
+        }
+
+        Some(Solver {
+            constraints,
+            candidates,
+            solutions: Vec::new(),
+            guesses: 0,
+            exhausted: false,
+            guess_order: (1..=position.grid.digits()).collect(),
+        })
+    }
+
+    /// Searches for solutions until `limit` have been found
+    fn search(&mut self, limit: usize) {
+        let mut candidates = self.candidates.clone();
+        let mut solved = vec![false; candidates.len()];
+        self.search_from(&mut candidates, &mut solved, limit);
+    }
+
+    fn search_from(&mut self, candidates: &mut [u32], solved: &mut [bool], limit: usize) {
+        if self.solutions.len() >= limit || !propagate(candidates, solved, self.constraints) {
+            return;
+        }
+
+        // Guess in the unsolved cell with the fewest candidates
+        let guess_cell = (0..candidates.len())
+            .filter(|&i| !solved[i])
+            .min_by_key(|&i| candidates[i].count_ones());
+
+        match guess_cell {
+            None => {
+                let solution: Vec<u8> = candidates
+                    .iter()
+                    .map(|mask| mask.trailing_zeros() as u8)
+                    .collect();
+                let digits: Vec<Option<u8>> = solution.iter().copied().map(Some).collect();
+                // Pruning may not catch every way a full grid can break a constraint
+                if self.constraints.violations(&digits).is_empty() {
+                    self.solutions.push(solution);
+                }
+            }
+            Some(i) => {
+                for num in self.guess_order.clone() {
+                    if candidates[i] & 1 << num == 0 {
+                        continue;
+                    }
+                    if self.guesses >= GUESS_BUDGET {
+                        self.exhausted = true;
+                        return;
+                    }
+
+                    self.guesses += 1;
+                    let mut guess_candidates = candidates.to_vec();
+                    let mut guess_solved = solved.to_vec();
+                    if place(&mut guess_candidates, i, num, self.constraints) {
+                        guess_solved[i] = true;
+                        self.search_from(&mut guess_candidates, &mut guess_solved, limit);
+                    }
+
+                    if self.solutions.len() >= limit {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A classic puzzle with a single solution
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn known_puzzles_have_a_single_solution() {
+        let constraints = ConstraintSet::classic();
+        let position = Position::parse(PUZZLE).unwrap();
+
+        assert_eq!(position.uniqueness(&constraints), Uniqueness::Unique);
+        assert_eq!(position.validate(&constraints), Ok(()));
+        let solution: String = position
+            .solve(&constraints)
+            .unwrap()
+            .iter()
+            .map(|num| digit_symbol(*num))
+            .collect();
+        assert_eq!(solution, SOLUTION);
+    }
+
+    #[test]
+    fn puzzles_without_enough_givens_have_several_solutions() {
+        let constraints = ConstraintSet::classic();
+        let position = Position::empty(GridSize::Nine);
+
+        assert_eq!(position.uniqueness(&constraints), Uniqueness::Multiple);
+        assert_eq!(
+            position.validate(&constraints),
+            Err(PuzzleError::MultipleSolutions)
+        );
+    }
+
+    #[test]
+    fn clashing_givens_are_refused() {
+        let constraints = ConstraintSet::classic();
+        let position = Position::parse(&format!("55{}", ".".repeat(79))).unwrap();
+
+        assert_eq!(
+            position.validate(&constraints),
+            Err(PuzzleError::ClashingGivens {
+                digit: 5,
+                cells: (0, 1)
+            })
+        );
+        assert_eq!(position.uniqueness(&constraints), Uniqueness::NoSolution);
+    }
+
+    #[test]
+    fn givens_survive_a_round_trip() {
+        let position = Position::parse(PUZZLE).unwrap();
+        assert_eq!(position.to_givens(), PUZZLE);
+    }
+}
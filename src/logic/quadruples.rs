@@ -0,0 +1,116 @@
+/// Quadruples, circles on the corner shared by four cells listing digits that must appear among them
+use crate::logic::{
+    constraints::{Constraint, Overlay},
+    fpuzzles::write_cells,
+};
+use serde_json::{json, Value as JsonValue};
+
+/// A circle on the corner of a 2x2 group of cells, whose digits include every digit listed
+///
+/// A digit listed twice must appear twice
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Quadruple {
+    /// The cells around the corner, by their index in reading order
+    pub cells: Vec<usize>,
+    /// The digits listed in the circle, from smallest to largest
+    pub digits: Vec<u8>,
+}
+
+impl Quadruple {
+    /// Each digit listed in the circle, along with how many times it is listed
+    fn counts(&self) -> Vec<(u8, usize)> {
+        let mut counts: Vec<(u8, usize)> = Vec::new();
+        for &num in self.digits.iter() {
+            match counts.iter_mut().find(|(listed, _)| *listed == num) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((num, 1)),
+            }
+        }
+        counts
+    }
+}
+
+/// The quadruples of a puzzle
+pub struct Quadruples(pub Vec<Quadruple>);
+
+impl Constraint for Quadruples {
+    /// Quadruples still missing more of their digits than they have empty cells left
+    /// have every digit around them marked
+    fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = Vec::new();
+        for quadruple in self.0.iter() {
+            let filled: Vec<usize> = quadruple
+                .cells
+                .iter()
+                .copied()
+                .filter(|&i| digits[i].is_some())
+                .collect();
+            let missing: usize = quadruple
+                .counts()
+                .into_iter()
+                .map(|(num, count)| {
+                    let present = filled.iter().filter(|&&i| digits[i] == Some(num)).count();
+                    count.saturating_sub(present)
+                })
+                .sum();
+
+            if missing > quadruple.cells.len() - filled.len() {
+                violating.extend(filled);
+            }
+        }
+        violating
+    }
+
+    /// Checks that each listed digit still fits in enough of the cells,
+    /// and limits the cells to the listed digits once there is one for every cell
+    fn prune(&self, candidates: &mut [u32]) -> bool {
+        for quadruple in self.0.iter() {
+            for (num, count) in quadruple.counts() {
+                let room = quadruple
+                    .cells
+                    .iter()
+                    .filter(|&&i| candidates[i] & 1 << num != 0)
+                    .count();
+                if room < count {
+                    return false;
+                }
+            }
+
+            if quadruple.digits.len() == quadruple.cells.len() {
+                let listed = quadruple.digits.iter().fold(0, |mask, num| mask | 1 << num);
+                for &i in quadruple.cells.iter() {
+                    candidates[i] &= listed;
+                    if candidates[i] == 0 {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        self.0
+            .iter()
+            .map(|quadruple| Overlay::CornerLabel {
+                cells: quadruple.cells.clone(),
+                label: quadruple.digits.iter().map(u8::to_string).collect(),
+            })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-quadruple"]
+    }
+
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let quadruples = self
+            .0
+            .iter()
+            .map(|quadruple| {
+                json!({ "cells": write_cells(&quadruple.cells), "values": quadruple.digits })
+            })
+            .collect();
+        Some(vec![("quadruple", JsonValue::Array(quadruples))])
+    }
+}
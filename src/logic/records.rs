@@ -0,0 +1,225 @@
+/// Keeps the player's fastest solve times for each difficulty, and awards medals for beating par
+use crate::logic::{
+    board::PuzzleSolved,
+    constraints::ConstraintSet,
+    notation::{Grade, Position},
+    sudoku_generation::InitialPuzzle,
+    timer::PuzzleTimer,
+};
+use crate::persistence::{check_integrity, load_json, reload_json, save_json, SaveFile};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub struct RecordsPlugin;
+
+impl Plugin for RecordsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<BestTimes>()
+            .init_resource::<ParTime>()
+            .init_resource::<PuzzleDifficulty>()
+            .add_event::<PuzzleCompleted>()
+            .add_startup_system(load_json::<BestTimes>.system())
+            .add_system(compute_par_time.system())
+            .add_system(record_best_times.system())
+            .add_system_to_stage(CoreStage::PreUpdate, reload_json::<BestTimes>.system())
+            .add_system(save_json::<BestTimes>.system())
+            .add_system(check_integrity::<BestTimes>.system());
+    }
+}
+
+/// The par time for puzzles that can be solved with singles alone
+const SINGLES_PAR: Duration = Duration::from_secs(6 * 60);
+/// The par time for puzzles that need guesses, before adding time for each guess
+const GUESSING_PAR: Duration = Duration::from_secs(12 * 60);
+const PAR_PER_GUESS: Duration = Duration::from_secs(60);
+/// Par never goes above this, however many guesses our solver needed
+const MAX_PAR: Duration = Duration::from_secs(30 * 60);
+/// Each medal is awarded for solving within this multiple of par, from best to worst
+const MEDAL_THRESHOLDS: [(Medal, f32); 3] = [
+    (Medal::Gold, 1.0),
+    (Medal::Silver, 1.5),
+    (Medal::Bronze, 2.0),
+];
+
+/// The broad difficulty that solve times are recorded under
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// Puzzles that can be solved with singles alone
+    Easy,
+    /// Puzzles that need more advanced techniques
+    Hard,
+}
+
+impl From<Grade> for Difficulty {
+    fn from(grade: Grade) -> Self {
+        match grade {
+            Grade::Singles => Difficulty::Easy,
+            Grade::Guessing { .. } => Difficulty::Hard,
+        }
+    }
+}
+
+impl Difficulty {
+    /// The id of the message naming this difficulty
+    pub fn name_id(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "difficulty-easy",
+            Difficulty::Hard => "difficulty-hard",
+        }
+    }
+}
+
+/// Resource storing the difficulty of the current puzzle
+///
+/// Puzzles without a unique solution are harder to solve by logic alone, so they count as hard
+pub struct PuzzleDifficulty(pub Difficulty);
+
+impl Default for PuzzleDifficulty {
+    fn default() -> Self {
+        PuzzleDifficulty(Difficulty::Hard)
+    }
+}
+
+/// A medal for solving a puzzle close to its par time
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Medal {
+    Gold,
+    Silver,
+    Bronze,
+}
+
+impl Medal {
+    /// The best medal earned by solving in this time, if any
+    pub fn award(time: Duration, par: Duration) -> Option<Medal> {
+        MEDAL_THRESHOLDS
+            .iter()
+            .find(|(_, multiple)| time <= par.mul_f32(*multiple))
+            .map(|(medal, _)| *medal)
+    }
+}
+
+impl Medal {
+    /// The id of the message naming this medal
+    pub fn name_id(self) -> &'static str {
+        match self {
+            Medal::Gold => "medal-gold",
+            Medal::Silver => "medal-silver",
+            Medal::Bronze => "medal-bronze",
+        }
+    }
+}
+
+/// Resource storing the time a skilled player should take to solve the current puzzle
+///
+/// Puzzles without a unique solution have no par
+#[derive(Default)]
+pub struct ParTime(pub Option<Duration>);
+
+impl ParTime {
+    /// Harder puzzles are given longer, based on how many guesses our solver needed
+    pub fn from_grade(grade: Grade) -> Duration {
+        match grade {
+            Grade::Singles => SINGLES_PAR,
+            Grade::Guessing { guesses } => (GUESSING_PAR + PAR_PER_GUESS * guesses).min(MAX_PAR),
+        }
+    }
+}
+
+/// Resource storing the player's fastest solve times, for each difficulty and for each puzzle
+///
+/// This is the only record of solve times: the archive and collections look up their times here
+#[derive(Serialize, Deserialize, Default)]
+pub struct BestTimes {
+    /// The fastest time a puzzle of each difficulty has been solved in, including hint penalties
+    pub difficulties: HashMap<Difficulty, Duration>,
+    /// The fastest time each puzzle has been solved in, including hint penalties, by its givens as 81 characters with `.` for empty cells
+    pub puzzles: HashMap<String, Duration>,
+}
+
+/// Best times are stored next to the game, like the puzzle archive
+impl SaveFile for BestTimes {
+    const PATH: &'static str = "best_times.json";
+    const DESCRIPTION: &'static str = "best times";
+    const NAME_ID: &'static str = "save-best-times";
+}
+
+impl BestTimes {
+    /// The fastest time the player has solved this puzzle in, if they have solved it
+    pub fn of_puzzle(&self, position: &Position) -> Option<Duration> {
+        self.puzzles.get(&position.to_givens()).copied()
+    }
+}
+
+/// Event sent once a solved puzzle's time has been recorded
+pub struct PuzzleCompleted {
+    pub difficulty: Difficulty,
+    /// The time taken to solve this puzzle, along with the hints used and the penalties they added
+    pub puzzle_timer: PuzzleTimer,
+    /// The fastest time for this difficulty, including this puzzle
+    pub best: Duration,
+    /// Did this puzzle beat the previous best time
+    pub new_best: bool,
+    /// The par time for this puzzle, if it had one
+    pub par: Option<Duration>,
+    pub medal: Option<Medal>,
+}
+
+/// Grades each new puzzle to find its difficulty and par time
+fn compute_par_time(
+    initial_puzzle: Res<InitialPuzzle>,
+    constraints: Res<ConstraintSet>,
+    mut par_time: ResMut<ParTime>,
+    mut puzzle_difficulty: ResMut<PuzzleDifficulty>,
+) {
+    if !initial_puzzle.is_changed() {
+        return;
+    }
+
+    let grade = initial_puzzle.position().grade(&constraints);
+    *par_time = ParTime(grade.map(ParTime::from_grade));
+    *puzzle_difficulty = PuzzleDifficulty(grade.map_or(Difficulty::Hard, Difficulty::from));
+}
+
+/// Compares the time taken on each solved puzzle against the best for its difficulty,
+/// and keeps the fastest time for the puzzle itself
+fn record_best_times(
+    mut event_reader: EventReader<PuzzleSolved>,
+    mut event_writer: EventWriter<PuzzleCompleted>,
+    mut best_times: ResMut<BestTimes>,
+    initial_puzzle: Res<InitialPuzzle>,
+    puzzle_timer: Res<PuzzleTimer>,
+    par_time: Res<ParTime>,
+    puzzle_difficulty: Res<PuzzleDifficulty>,
+) {
+    for _ in event_reader.iter() {
+        let position = initial_puzzle.position();
+        let difficulty = puzzle_difficulty.0;
+        let time = puzzle_timer.total();
+
+        let new_best = match best_times.difficulties.get(&difficulty) {
+            Some(best) => time < *best,
+            None => true,
+        };
+        if new_best {
+            best_times.difficulties.insert(difficulty, time);
+        }
+        let puzzle_best = best_times
+            .puzzles
+            .entry(position.to_givens())
+            .or_insert(time);
+        *puzzle_best = (*puzzle_best).min(time);
+
+        event_writer.send(PuzzleCompleted {
+            difficulty,
+            puzzle_timer: puzzle_timer.clone(),
+            best: best_times.difficulties[&difficulty],
+            new_best,
+            par: par_time.0,
+            medal: par_time
+                .0
+                .and_then(|par| Medal::award(puzzle_timer.total(), par)),
+        });
+    }
+}
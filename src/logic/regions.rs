@@ -0,0 +1,160 @@
+/// The regions of the board, which can't contain the same digit twice just like rows and columns
+///
+/// Classic sudoku uses the boxes of the grid, such as the nine 3x3 squares,
+/// while jigsaw sudoku has irregular regions of nine connected cells in place of the squares of a 9x9 grid
+use crate::logic::grid::GridSize;
+use rand::Rng;
+
+/// How many times pairs of cells are traded between neighbouring regions when making a random jigsaw
+const JIGSAW_TRADES: usize = 200;
+
+/// The region each cell is in, numbered from 1 and listed by cell index in reading order
+///
+/// The regions also decide the size of the board they are drawn on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regions {
+    grid: GridSize,
+    map: Vec<u8>,
+}
+
+impl Default for Regions {
+    fn default() -> Self {
+        Regions::squares()
+    }
+}
+
+/// The cells sharing an edge with cell `i` of a 9x9 grid
+fn orthogonal_neighbours(i: usize) -> impl Iterator<Item = usize> {
+    let (row, column) = (i / 9, i % 9);
+    let up = (row > 0).then(|| i - 9);
+    let down = (row < 8).then(|| i + 9);
+    let left = (column > 0).then(|| i - 1);
+    let right = (column < 8).then(|| i + 1);
+    up.into_iter().chain(down).chain(left).chain(right)
+}
+
+impl Regions {
+    /// The 3x3 squares of classic sudoku, counted from 1 to 9 in reading order
+    pub fn squares() -> Regions {
+        Regions::boxes(GridSize::Nine)
+    }
+
+    /// The boxes of a grid of this size, counted from 1 in reading order
+    pub fn boxes(grid: GridSize) -> Regions {
+        Regions {
+            grid,
+            map: (0..grid.cell_count()).map(|i| grid.box_of(i)).collect(),
+        }
+    }
+
+    /// Reads a map of the region each cell of a 9x9 grid is in, listed in reading order
+    ///
+    /// Returns `None` unless the regions are numbered from 1 to 9, and each is nine connected cells
+    pub fn new(map: [u8; 81]) -> Option<Regions> {
+        let regions = Regions {
+            grid: GridSize::Nine,
+            map: map.to_vec(),
+        };
+        let valid = (1..=9).all(|region| {
+            let cells = regions.cells(region);
+            cells.len() == 9 && regions.is_connected(region)
+        });
+        if valid {
+            Some(regions)
+        } else {
+            None
+        }
+    }
+
+    /// A random jigsaw on a 9x9 grid, made by trading cells between neighbouring squares
+    pub fn random(rng: &mut impl Rng) -> Regions {
+        let mut regions = Regions::squares();
+        for _ in 0..JIGSAW_TRADES {
+            // A cell on the edge of its region joins its neighbour's region,
+            // and a cell of that region on the edge of the first joins it in return
+            let first = rng.gen_range(0..81);
+            let neighbours: Vec<usize> = orthogonal_neighbours(first)
+                .filter(|&j| regions.map[j] != regions.map[first])
+                .collect();
+            if neighbours.is_empty() {
+                continue;
+            }
+            let (giving, taking) = (
+                regions.map[first],
+                regions.map[neighbours[rng.gen_range(0..neighbours.len())]],
+            );
+            let returned: Vec<usize> = regions
+                .cells(taking)
+                .into_iter()
+                .filter(|&j| {
+                    orthogonal_neighbours(j).any(|k| k != first && regions.map[k] == giving)
+                })
+                .collect();
+            if returned.is_empty() {
+                continue;
+            }
+            let second = returned[rng.gen_range(0..returned.len())];
+
+            let mut traded = regions.clone();
+            traded.map[first] = taking;
+            traded.map[second] = giving;
+            if traded.is_connected(giving) && traded.is_connected(taking) {
+                regions = traded;
+            }
+        }
+        regions
+    }
+
+    /// The size of the board the regions are drawn on
+    pub fn grid(&self) -> GridSize {
+        self.grid
+    }
+
+    /// The region cell `i` is in
+    pub fn region(&self, i: usize) -> u8 {
+        self.map[i]
+    }
+
+    /// The cells of a region, in reading order
+    pub fn cells(&self, region: u8) -> Vec<usize> {
+        (0..self.map.len())
+            .filter(|&i| self.map[i] == region)
+            .collect()
+    }
+
+    /// The number of regions, which matches the number of digits on every board but a samurai one
+    pub fn count(&self) -> u8 {
+        self.map.iter().copied().max().unwrap_or_default()
+    }
+
+    /// Every region, as groups of cells that can't contain the same digit twice
+    pub fn houses(&self) -> Vec<Vec<usize>> {
+        (1..=self.count())
+            .map(|region| self.cells(region))
+            .collect()
+    }
+
+    /// Are these the boxes of the grid, rather than the irregular regions of a jigsaw
+    pub fn is_boxes(&self) -> bool {
+        *self == Regions::boxes(self.grid)
+    }
+
+    /// Can every cell of the region be reached from the others without leaving it
+    fn is_connected(&self, region: u8) -> bool {
+        let cells = self.cells(region);
+        let mut reached = vec![match cells.first() {
+            Some(&first) => first,
+            None => return false,
+        }];
+        let mut k = 0;
+        while k < reached.len() {
+            for j in orthogonal_neighbours(reached[k]) {
+                if self.map[j] == region && !reached.contains(&j) {
+                    reached.push(j);
+                }
+            }
+            k += 1;
+        }
+        reached.len() == cells.len()
+    }
+}
@@ -0,0 +1,254 @@
+/// Reading and writing puzzles in the SadMan Sudoku `.sdk` format, which many puzzle collections are distributed in
+///
+/// Files may begin with `#` metadata lines, such as `#A` for the author and `#D` for a description,
+/// followed by the givens as 9 rows of 9 characters, using `.` or `0` for empty cells.
+/// Saved games mark the givens with a `[Puzzle]` header, and add a `[State]` section listing the digits placed so far
+/// in the same layout, and a `[PencilMarks]` section with a line per row, listing the marks of each cell separated by commas
+use crate::logic::{grid::GridSize, notation::Position, sudoku_generation::PuzzleMetadata};
+
+/// A problem encountered when reading an `.sdk` file
+#[derive(Debug, PartialEq, Eq)]
+pub enum SdkError {
+    /// A section didn't have exactly 9 rows
+    InvalidRowCount(usize),
+    /// A row of the givens or state wasn't 9 characters long
+    InvalidRow(String),
+    /// A character other than a digit, `.` or `0` was found
+    InvalidCharacter(char),
+}
+
+/// A puzzle, and optionally the player's progress on it, as stored in an `.sdk` file
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SdkPuzzle {
+    /// The digits given by the puzzle, in reading order
+    pub givens: [Option<u8>; 81],
+    /// The digits placed by the player, in reading order
+    pub placed: [Option<u8>; 81],
+    /// The pencil marks in each cell, in reading order
+    pub pencil_marks: Vec<Vec<u8>>,
+    pub metadata: PuzzleMetadata,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Puzzle,
+    State,
+    PencilMarks,
+    /// Sections written by other programs, which we skip over
+    Unknown,
+}
+
+impl SdkPuzzle {
+    /// Reads the contents of an `.sdk` file
+    pub fn parse(contents: &str) -> Result<SdkPuzzle, SdkError> {
+        let mut metadata = PuzzleMetadata::default();
+        let mut section = Section::Puzzle;
+        let mut givens = Vec::new();
+        let mut placed = Vec::new();
+        let mut pencil_marks = Vec::new();
+
+        for line in contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+        {
+            if let Some(field) = line.strip_prefix('#') {
+                let value = Some(field.get(1..).unwrap_or_default().trim().to_string());
+                match field.chars().next() {
+                    Some('A') => metadata.author = value,
+                    Some('D') => metadata.title = value,
+                    _ => (),
+                }
+                continue;
+            }
+
+            if line.starts_with('[') {
+                section = match line {
+                    "[Puzzle]" => Section::Puzzle,
+                    "[State]" => Section::State,
+                    "[PencilMarks]" => Section::PencilMarks,
+                    _ => Section::Unknown,
+                };
+                continue;
+            }
+
+            match section {
+                Section::Puzzle => givens.push(line),
+                Section::State => placed.push(line),
+                Section::PencilMarks => pencil_marks.push(line),
+                Section::Unknown => (),
+            }
+        }
+
+        let givens = parse_digits(&givens)?;
+        let placed = if placed.is_empty() {
+            [None; 81]
+        } else {
+            parse_digits(&placed)?
+        };
+        let pencil_marks = if pencil_marks.is_empty() {
+            vec![Vec::new(); 81]
+        } else {
+            parse_pencil_marks(&pencil_marks)?
+        };
+
+        Ok(SdkPuzzle {
+            givens,
+            placed,
+            pencil_marks,
+            metadata,
+        })
+    }
+
+    /// Writes the puzzle and the player's progress in the saved game layout
+    pub fn write(&self) -> String {
+        let mut contents = String::new();
+        if let Some(author) = &self.metadata.author {
+            contents.push_str(&format!("#A {}\n", author));
+        }
+        if let Some(title) = &self.metadata.title {
+            contents.push_str(&format!("#D {}\n", title));
+        }
+
+        contents.push_str("[Puzzle]\n");
+        write_digits(&mut contents, &self.givens);
+        contents.push_str("[State]\n");
+        write_digits(&mut contents, &self.placed);
+
+        contents.push_str("[PencilMarks]\n");
+        for row in self.pencil_marks.chunks(9) {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|marks| marks.iter().map(|num| num.to_string()).collect())
+                .collect();
+            contents.push_str(&cells.join(","));
+            contents.push('\n');
+        }
+        contents
+    }
+
+    /// The position made up of the puzzle's givens alone
+    pub fn position(&self) -> Position {
+        let mut position = Position {
+            digits: self.givens.to_vec(),
+            ..Position::empty(GridSize::Nine)
+        };
+        for (i, digit) in self.givens.iter().enumerate() {
+            if let Some(num) = digit {
+                position.candidates[i] = 1 << num;
+            }
+        }
+        position
+    }
+}
+
+fn parse_digits(rows: &[&str]) -> Result<[Option<u8>; 81], SdkError> {
+    if rows.len() != 9 {
+        return Err(SdkError::InvalidRowCount(rows.len()));
+    }
+
+    let mut digits = [None; 81];
+    for (row, line) in rows.iter().enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.len() != 9 {
+            return Err(SdkError::InvalidRow(line.to_string()));
+        }
+        for (column, c) in chars.into_iter().enumerate() {
+            digits[row * 9 + column] = match c {
+                '.' | '0' => None,
+                '1'..='9' => Some(c as u8 - b'0'),
+                _ => return Err(SdkError::InvalidCharacter(c)),
+            };
+        }
+    }
+    Ok(digits)
+}
+
+fn parse_pencil_marks(rows: &[&str]) -> Result<Vec<Vec<u8>>, SdkError> {
+    if rows.len() != 9 {
+        return Err(SdkError::InvalidRowCount(rows.len()));
+    }
+
+    let mut pencil_marks = Vec::with_capacity(81);
+    for line in rows.iter() {
+        let cells: Vec<&str> = line.split(',').collect();
+        if cells.len() != 9 {
+            return Err(SdkError::InvalidRow(line.to_string()));
+        }
+        for cell in cells {
+            let marks = cell
+                .trim()
+                .chars()
+                .map(|c| match c {
+                    '1'..='9' => Ok(c as u8 - b'0'),
+                    _ => Err(SdkError::InvalidCharacter(c)),
+                })
+                .collect::<Result<Vec<u8>, SdkError>>()?;
+            pencil_marks.push(marks);
+        }
+    }
+    Ok(pencil_marks)
+}
+
+fn write_digits(contents: &mut String, digits: &[Option<u8>; 81]) {
+    for row in digits.chunks(9) {
+        for digit in row {
+            match digit {
+                Some(num) => contents.push_str(&num.to_string()),
+                None => contents.push('.'),
+            }
+        }
+        contents.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "#A Someone
+#D A classic
+53..7....
+6..195...
+.98....6.
+8...6...3
+4..8.3..1
+7...2...6
+.6....28.
+...419..5
+....8..79
+";
+
+    #[test]
+    fn puzzles_are_read_with_their_metadata() {
+        let puzzle = SdkPuzzle::parse(PUZZLE).unwrap();
+        assert_eq!(puzzle.metadata.author.as_deref(), Some("Someone"));
+        assert_eq!(puzzle.metadata.title.as_deref(), Some("A classic"));
+        assert_eq!(puzzle.givens[0], Some(5));
+        assert_eq!(puzzle.givens[2], None);
+        assert_eq!(puzzle.givens[80], Some(9));
+        assert_eq!(puzzle.givens.iter().flatten().count(), 30);
+        assert_eq!(puzzle.placed, [None; 81]);
+    }
+
+    #[test]
+    fn saved_games_survive_a_round_trip() {
+        let mut puzzle = SdkPuzzle::parse(PUZZLE).unwrap();
+        puzzle.placed[2] = Some(4);
+        puzzle.pencil_marks[3] = vec![2, 6];
+
+        assert_eq!(SdkPuzzle::parse(&puzzle.write()).unwrap(), puzzle);
+    }
+
+    #[test]
+    fn malformed_files_are_refused() {
+        let short = PUZZLE.lines().take(10).collect::<Vec<_>>().join("\n");
+        assert_eq!(SdkPuzzle::parse(&short), Err(SdkError::InvalidRowCount(8)));
+
+        let invalid = PUZZLE.replace("53..7....", "53..x....");
+        assert_eq!(
+            SdkPuzzle::parse(&invalid),
+            Err(SdkError::InvalidCharacter('x'))
+        );
+    }
+}
@@ -0,0 +1,305 @@
+/// Setting custom puzzles, by entering givens into an empty grid before locking them in to play
+///
+/// While a puzzle is being set there is no solution to check digits against,
+/// so hints, mistakes and the victory screen stay out of the way until it is locked
+use crate::graphics::toasts::Toasts;
+use crate::input::{
+    buttons::{LockPuzzle, MinimizePuzzle},
+    SetPuzzle,
+};
+use crate::localization::Localization;
+use crate::logic::{
+    board::{Cell, Coordinates, Value},
+    constraints::ConstraintSet,
+    grid::GridSize,
+    notation::{Position, Uniqueness},
+    sudoku_generation::{
+        parse_values, CompletePuzzle, GenerationFilters, ImportPuzzle, InitialPuzzle,
+        PuzzleMetadata, PuzzleSeed,
+    },
+    timer::PuzzleTimer,
+};
+use bevy::{
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
+use rand::seq::SliceRandom;
+
+pub struct SetterPlugin;
+
+impl Plugin for SetterPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Setter>()
+            .init_resource::<SolutionCountTask>()
+            .add_system(start_setting.system())
+            .add_system(leave_setting.system())
+            .add_system(count_solutions.system())
+            .add_system(receive_solution_count.system())
+            .add_system(start_minimizing.system())
+            .add_system(minimize_givens.system())
+            .add_system(lock_puzzle.system());
+    }
+}
+
+/// Solutions are only counted up to this many, as an empty grid has billions of them
+pub const SOLUTION_LIMIT: usize = 100;
+
+/// Resource storing whether the player is setting a puzzle, and how many solutions their givens allow
+#[derive(Default)]
+pub struct Setter {
+    pub active: bool,
+    /// The number of solutions to the givens entered so far, up to `SOLUTION_LIMIT`
+    ///
+    /// `None` until the first count has finished, and `Some(None)` if the count ran out of guesses
+    pub solutions: Option<Option<usize>>,
+    /// Are the solutions being counted again, so that the count shown may be out of date
+    counting: bool,
+    /// The removal of redundant givens underway, if any
+    pub minimization: Option<Minimization>,
+}
+
+/// Progress through removing each redundant given in turn, leaving a puzzle where every given is needed
+pub struct Minimization {
+    /// The cells whose givens are still to be tried, by their index in reading order
+    remaining: Vec<usize>,
+    /// The number of givens when minimizing started
+    pub total: usize,
+    /// The number of givens removed so far
+    pub removed: usize,
+}
+
+impl Minimization {
+    /// The number of givens that have been tried so far
+    pub fn checked(&self) -> usize {
+        self.total - self.remaining.len()
+    }
+}
+
+/// Resource storing the solution count underway in the background,
+/// which is dropped and restarted whenever the givens change
+///
+/// Kept apart from `Setter`, so that polling it doesn't count as changing what is shown
+#[derive(Default)]
+struct SolutionCountTask(Option<Task<Option<usize>>>);
+
+/// Reads the digits filled in on the board as the givens of a puzzle on a grid of this size, ignoring any marks
+///
+/// Cells off the grid are skipped, as the board may not have been rebuilt for the grid yet
+fn entered_givens<'a>(
+    grid: GridSize,
+    cells: impl Iterator<Item = (&'a Coordinates, &'a Value)>,
+) -> Position {
+    let mut position = Position::empty(grid);
+
+    for (coordinates, value) in cells.filter(|(coordinates, _)| grid.contains(coordinates)) {
+        if let Value::Filled(num) = value {
+            let i = grid.index(coordinates);
+            position.digits[i] = Some(*num);
+            position.candidates[i] = 1 << num;
+        }
+    }
+    position
+}
+
+/// Clears the board so that the player can enter the givens of their own puzzle
+fn start_setting(
+    mut event_reader: EventReader<SetPuzzle>,
+    mut setter: ResMut<Setter>,
+    mut initial_puzzle: ResMut<InitialPuzzle>,
+    mut complete_puzzle: ResMut<CompletePuzzle>,
+    mut puzzle_timer: ResMut<PuzzleTimer>,
+    mut puzzle_seed: ResMut<PuzzleSeed>,
+    mut puzzle_metadata: ResMut<PuzzleMetadata>,
+    mut constraints: ResMut<ConstraintSet>,
+    generation_filters: Res<GenerationFilters>,
+) {
+    for _ in event_reader.iter() {
+        // Locked puzzles are imported as plain givens, so only classic puzzles can be set,
+        // on the size of grid picked for new puzzles
+        let grid = generation_filters.size;
+        *constraints = ConstraintSet::for_grid(grid);
+        *initial_puzzle = InitialPuzzle {
+            numbers: parse_values(
+                std::iter::repeat(Value::Empty).take(grid.cell_count()),
+                grid,
+            ),
+        };
+        *complete_puzzle = CompletePuzzle::default();
+        *puzzle_timer = PuzzleTimer::default();
+        *puzzle_seed = PuzzleSeed(None);
+        *puzzle_metadata = PuzzleMetadata::default();
+        *setter = Setter {
+            active: true,
+            ..Default::default()
+        };
+    }
+}
+
+/// Stops setting once a puzzle with givens is loaded, whether it was locked in or the player moved on
+///
+/// Resetting the board while setting clears the entries, but keeps the player setting
+fn leave_setting(initial_puzzle: Res<InitialPuzzle>, mut setter: ResMut<Setter>) {
+    if !initial_puzzle.is_changed() || !setter.active {
+        return;
+    }
+
+    let has_givens = initial_puzzle
+        .numbers
+        .values()
+        .any(|value| *value != Value::Empty);
+    if has_givens {
+        *setter = Setter::default();
+    }
+}
+
+/// Starts counting the solutions to the givens entered so far in the background, each time they change
+///
+/// Sparse givens can take a while to count, so the board stays responsive as the count runs
+fn count_solutions(
+    mut setter: ResMut<Setter>,
+    mut task: ResMut<SolutionCountTask>,
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    constraints: Res<ConstraintSet>,
+    task_pool: Res<AsyncComputeTaskPool>,
+) {
+    // The minimizer only removes givens while the solution stays unique, so there is nothing to count
+    if !setter.active || setter.minimization.is_some() {
+        // Counts for a puzzle that has since been locked in are no longer needed
+        if task.0.is_some() {
+            task.0 = None;
+        }
+        return;
+    }
+    // The first count starts as soon as setting does
+    let first_count = setter.solutions.is_none() && !setter.counting;
+    if !first_count && changed_query.iter().next().is_none() {
+        return;
+    }
+
+    let position = entered_givens(constraints.grid(), query.iter());
+    let constraints = constraints.clone();
+    // Replacing the previous count drops it, cancelling work on givens that are out of date
+    task.0 = Some(
+        task_pool.spawn(async move { position.count_solutions(&constraints, SOLUTION_LIMIT) }),
+    );
+    if !setter.counting {
+        setter.counting = true;
+    }
+}
+
+/// Shows the solution count once the background count has finished
+fn receive_solution_count(mut setter: ResMut<Setter>, mut task: ResMut<SolutionCountTask>) {
+    let solutions = match task.0.as_mut() {
+        Some(counting) => future::block_on(future::poll_once(counting)),
+        None => return,
+    };
+
+    if let Some(solutions) = solutions {
+        task.0 = None;
+        setter.solutions = Some(solutions);
+        setter.counting = false;
+    }
+}
+
+/// Starts removing redundant givens from the puzzle being set, which must have a unique solution
+fn start_minimizing(
+    mut event_reader: EventReader<MinimizePuzzle>,
+    mut setter: ResMut<Setter>,
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+    constraints: Res<ConstraintSet>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for _ in event_reader.iter() {
+        if !setter.active || setter.minimization.is_some() {
+            continue;
+        }
+        if setter.solutions != Some(Some(1)) || setter.counting {
+            toasts.push(localization.get("toast-minimize-not-unique"));
+            continue;
+        }
+
+        let position = entered_givens(constraints.grid(), query.iter());
+        // Trying the givens in a random order gives a different minimal puzzle each time
+        let mut remaining: Vec<usize> = (0..position.digits.len())
+            .filter(|i| position.digits[*i].is_some())
+            .collect();
+        remaining.shuffle(&mut rand::thread_rng());
+
+        setter.minimization = Some(Minimization {
+            total: remaining.len(),
+            remaining,
+            removed: 0,
+        });
+    }
+}
+
+/// Tries removing one given each frame, emptying its cell if the solution stays unique without it
+///
+/// Each check can take a moment, so spreading them out keeps the game responsive and shows the progress made
+fn minimize_givens(
+    mut setter: ResMut<Setter>,
+    mut query: Query<(&Coordinates, &mut Value), With<Cell>>,
+    constraints: Res<ConstraintSet>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    // Checked before borrowing mutably, which would count as changing the setter every frame
+    if setter.minimization.is_none() {
+        return;
+    }
+    let minimization = setter
+        .minimization
+        .as_mut()
+        .expect("Minimization is underway");
+
+    if let Some(i) = minimization.remaining.pop() {
+        let grid = constraints.grid();
+        let mut candidate = entered_givens(grid, query.iter());
+        // The player may have emptied the cell themselves in the meantime
+        if candidate.digits[i].is_some() {
+            candidate.digits[i] = None;
+            candidate.candidates[i] = grid.all_candidates();
+            if candidate.uniqueness(&constraints) == Uniqueness::Unique {
+                for (coordinates, mut value) in query.iter_mut() {
+                    if *coordinates == grid.coordinates(i) {
+                        *value = Value::Empty;
+                    }
+                }
+                minimization.removed += 1;
+            }
+        }
+    }
+
+    if minimization.remaining.is_empty() {
+        toasts.push(localization.format(
+            "toast-minimized",
+            &[("removed", minimization.removed.into())],
+        ));
+        setter.minimization = None;
+        // Recount, in case the player changed the givens while they were being minimized
+        setter.solutions = None;
+    }
+}
+
+/// Turns the digits entered into the givens of a new puzzle, and starts playing it
+///
+/// Givens that clash or have no solution are rejected when imported, leaving the player setting
+fn lock_puzzle(
+    mut event_reader: EventReader<LockPuzzle>,
+    mut import_writer: EventWriter<ImportPuzzle>,
+    setter: Res<Setter>,
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+    constraints: Res<ConstraintSet>,
+) {
+    for _ in event_reader.iter() {
+        if !setter.active {
+            continue;
+        }
+
+        let position = entered_givens(constraints.grid(), query.iter());
+        import_writer.send(ImportPuzzle(position.to_givens()));
+    }
+}
@@ -0,0 +1,125 @@
+/// Statistics on the puzzles the player has solved at each difficulty, kept on disk between sessions
+use crate::logic::{
+    board::{in_progress, Cell, Fixed, PuzzleSolved, Value},
+    mistakes::{GameOver, Mistakes},
+    records::{Difficulty, PuzzleCompleted, PuzzleDifficulty},
+    sudoku_generation::InitialPuzzle,
+};
+use crate::persistence::{check_integrity, load_json, reload_json, save_json, SaveFile};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Stats>()
+            .add_startup_system(load_json::<Stats>.system())
+            .add_system(record_stats.system())
+            .add_system(break_streaks.system())
+            .add_system_to_stage(CoreStage::PreUpdate, reload_json::<Stats>.system())
+            .add_system(save_json::<Stats>.system())
+            .add_system(check_integrity::<Stats>.system());
+    }
+}
+
+/// The player's record at a single difficulty
+///
+/// Hints and mistakes are counted over the puzzles solved, while best times are kept in `BestTimes`
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct DifficultyStats {
+    pub completed: u32,
+    /// The time taken over every puzzle solved, including hint penalties
+    pub total_time: Duration,
+    pub hints_used: u32,
+    /// The time added by hints over every puzzle solved
+    pub hint_penalties: Duration,
+    pub mistakes: u32,
+    /// Puzzles solved in a row, without abandoning one partway through or running out of mistakes
+    pub streak: u32,
+    pub best_streak: u32,
+}
+
+impl DifficultyStats {
+    /// The mean time taken over the puzzles solved, if there are any
+    pub fn average_time(&self) -> Option<Duration> {
+        (self.completed > 0).then(|| self.total_time / self.completed)
+    }
+}
+
+/// Resource storing the player's statistics for each difficulty
+#[derive(Serialize, Deserialize, Default)]
+pub struct Stats {
+    pub difficulties: HashMap<Difficulty, DifficultyStats>,
+}
+
+/// Statistics are stored next to the game, like best times
+impl SaveFile for Stats {
+    const PATH: &'static str = "stats.json";
+    const DESCRIPTION: &'static str = "statistics";
+    const NAME_ID: &'static str = "save-stats";
+}
+
+/// Adds each solved puzzle to the statistics of its difficulty
+fn record_stats(
+    mut event_reader: EventReader<PuzzleCompleted>,
+    mistakes: Res<Mistakes>,
+    mut stats: ResMut<Stats>,
+) {
+    for event in event_reader.iter() {
+        let stats = stats.difficulties.entry(event.difficulty).or_default();
+        stats.completed += 1;
+        stats.total_time += event.puzzle_timer.total();
+        stats.hints_used += event.puzzle_timer.hints_used;
+        stats.hint_penalties += event.puzzle_timer.penalty;
+        stats.mistakes += mistakes.count;
+        stats.streak += 1;
+        stats.best_streak = stats.best_streak.max(stats.streak);
+    }
+}
+
+/// What is known about the puzzle being played, remembered until the next one starts
+#[derive(Default)]
+struct PlayedPuzzle {
+    difficulty: Option<Difficulty>,
+    in_progress: bool,
+    solved: bool,
+}
+
+/// Ends the streak at the current difficulty when the player runs out of mistakes,
+/// or moves on from a puzzle they have started without solving it, including by resetting it
+fn break_streaks(
+    mut solved_reader: EventReader<PuzzleSolved>,
+    mut game_over_reader: EventReader<GameOver>,
+    mut played: Local<PlayedPuzzle>,
+    initial_puzzle: Res<InitialPuzzle>,
+    puzzle_difficulty: Res<PuzzleDifficulty>,
+    mut stats: ResMut<Stats>,
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+    query: Query<(&Value, &Fixed), With<Cell>>,
+) {
+    let mut broken = game_over_reader.iter().count() > 0;
+    // The state remembered is that of the previous puzzle until it is replaced below
+    if initial_puzzle.is_changed() {
+        broken |= played.in_progress && !played.solved;
+        played.solved = false;
+    }
+    if let (true, Some(difficulty)) = (broken, played.difficulty) {
+        if let Some(stats) = stats.difficulties.get_mut(&difficulty) {
+            stats.streak = 0;
+        }
+    }
+
+    if puzzle_difficulty.is_changed() {
+        played.difficulty = Some(puzzle_difficulty.0);
+    }
+    if solved_reader.iter().count() > 0 {
+        played.solved = true;
+    }
+    if changed_query.iter().next().is_some() {
+        played.in_progress = in_progress(query.iter());
+    }
+}
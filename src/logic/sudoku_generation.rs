@@ -1,10 +1,46 @@
 /// Sudoku generation logic
-use crate::input::buttons::{NewPuzzle, ResetPuzzle, SolvePuzzle};
-use crate::logic::board::{Cell, Coordinates, Fixed, Value};
-use bevy::prelude::*;
+use crate::companion::CompanionMode;
+use crate::graphics::{dialog::SeedEntryDialog, toasts::Toasts};
+use crate::input::{
+    buttons::{
+        BookmarkPuzzle, BrowseCollection, CycleGridSize, CycleScrambleSize, EnterSeed, NewPuzzle,
+        PlaySeed, QuitGame, RemixPuzzle, ResetPuzzle, SolvePuzzle,
+    },
+    input_mode::{InputMode, InputModeMemory},
+    OpenPuzzleFile, ResumeBookmark, SavePuzzleFile, ScrambleRemaining, SharePuzzle, ToggleVariant,
+};
+use crate::localization::Localization;
+use crate::logic::{
+    board::{
+        completion, in_progress,
+        marks::{CenterMarks, CornerMarks, Marks},
+        Cell, CellColor, Coordinates, Fixed, Locked, Provenance, Value,
+    },
+    collections::Collection,
+    constraints::{ClassicSudoku, Constraint, ConstraintSet, Variant},
+    fpuzzles::{self, LinkError},
+    grid::{digit_symbol, GridSize},
+    notation::{self, Grade, NotationError, Position, PuzzleError, Uniqueness},
+    regions::Regions,
+    sdk::SdkPuzzle,
+    techniques::cell_name,
+    timer::PuzzleTimer,
+};
+use crate::CommonLabels;
 use bevy::utils::HashMap;
+use bevy::{
+    app::AppExit,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use std::ops::DerefMut;
-use sudoku::Sudoku;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
 
 pub struct GenerationPlugin;
 
@@ -12,13 +48,68 @@ impl Plugin for GenerationPlugin {
     fn build(&self, app: &mut AppBuilder) {
         app.init_resource::<InitialPuzzle>()
             .init_resource::<CompletePuzzle>()
+            .init_resource::<Bookmarks>()
+            .init_resource::<RestoredValues>()
+            .init_resource::<ScrambleConfig>()
+            .init_resource::<GenerationFilters>()
+            .init_resource::<PuzzleSeed>()
+            .init_resource::<PuzzleRng>()
+            .init_resource::<PuzzleGeneration>()
+            .init_resource::<SeedEntry>()
+            .init_resource::<PuzzleMetadata>()
+            .add_event::<SeededPuzzle>()
+            .add_event::<DiscardConfirmation>()
+            .add_event::<ImportPuzzle>()
             .add_startup_system(first_sudoku.system())
             .add_system(fill_puzzle.system().label(GenerationLabels::FillPuzzle))
             // Must occur before we fill the puzzle to ensure
             // that the new puzzle has been generated before we attempt to fill it
-            .add_system(new_sudoku.system().before(GenerationLabels::FillPuzzle))
+            .add_system(seeded_sudoku.system().before(GenerationLabels::FillPuzzle))
+            .add_system(new_sudoku.system())
+            .add_system(remix_sudoku.system())
+            .add_system(
+                receive_generated_puzzle
+                    .system()
+                    .before(GenerationLabels::FillPuzzle),
+            )
+            .add_system(import_sudoku.system().before(GenerationLabels::FillPuzzle))
+            .add_system(
+                open_puzzle_file
+                    .system()
+                    .before(GenerationLabels::FillPuzzle),
+            )
+            .add_system(save_puzzle_file.system())
+            .add_system(scramble_remaining.system())
+            .add_system(
+                cycle_scramble_size
+                    .system()
+                    .label(CommonLabels::Action)
+                    .after(CommonLabels::Input),
+            )
+            .add_system(
+                cycle_grid_size
+                    .system()
+                    .label(CommonLabels::Action)
+                    .after(CommonLabels::Input),
+            )
+            .add_system(type_seed.system())
+            .add_system(
+                play_seed
+                    .system()
+                    .label(CommonLabels::Action)
+                    .after(CommonLabels::Input),
+            )
+            .add_system(
+                resume_bookmark
+                    .system()
+                    .before(GenerationLabels::FillPuzzle),
+            )
+            .add_system(bookmark_sudoku.system())
             .add_system(reset_sudoku.system())
-            .add_system(solve_sudoku.system());
+            .add_system(quit_game.system())
+            .add_system(solve_sudoku.system())
+            .add_system(share_puzzle.system())
+            .add_system(toggle_variant.system());
     }
 }
 
@@ -29,81 +120,1447 @@ enum GenerationLabels {
 
 // QUALITY: refactor to share data with CompletePuzzle struct
 /// The clues and constraints given by the puzzle
-#[derive(Default)]
-struct InitialPuzzle {
-    numbers: HashMap<Coordinates, Value>,
+#[derive(Default, Clone, PartialEq)]
+pub struct InitialPuzzle {
+    pub numbers: HashMap<Coordinates, Value>,
+}
+
+impl InitialPuzzle {
+    /// The size of grid the puzzle fills, which is 9x9 until the first puzzle is loaded
+    pub fn grid(&self) -> GridSize {
+        GridSize::from_cell_count(self.numbers.len()).unwrap_or_default()
+    }
+
+    /// The position made up of the puzzle's givens alone
+    pub fn position(&self) -> Position {
+        let givens: Vec<_> = self.numbers.iter().collect();
+        Position::from_cells(self.grid(), &givens)
+    }
 }
 /// The true solution to the puzzle
+#[derive(Default, Clone)]
+pub struct CompletePuzzle {
+    pub numbers: HashMap<Coordinates, Value>,
+}
+
+/// Resource supplying the randomness of new puzzles, remixes and scrambles
+///
+/// It is reseeded whenever a puzzle is generated from a seed, so that everything that follows,
+/// including the seeds of later puzzles, comes out the same for everyone who entered that seed
+pub struct PuzzleRng(pub StdRng);
+
+impl Default for PuzzleRng {
+    fn default() -> Self {
+        PuzzleRng(StdRng::from_entropy())
+    }
+}
+
+/// A puzzle made in the background, with everything that replaces the current puzzle once it is ready
+pub struct GeneratedPuzzle {
+    /// The rules of a brand new puzzle, while remixes and scrambles keep those of the current puzzle
+    constraints: Option<ConstraintSet>,
+    initial: InitialPuzzle,
+    /// The solution, unless a remix keeps that of the current puzzle
+    complete: Option<CompletePuzzle>,
+    seed: Option<u64>,
+    /// The puzzle RNG, moved on past whatever making the puzzle drew from it
+    rng: StdRng,
+}
+
+/// A puzzle being made in the background, which fails with the id of a message telling the player why
+struct Generating {
+    /// The givens of the puzzle the new one is to replace
+    replaced: InitialPuzzle,
+    task: Task<Result<GeneratedPuzzle, &'static str>>,
+    /// Set once the puzzle is no longer wanted
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Dropping a task doesn't interrupt work already underway, which runs for seconds on a samurai board,
+/// so clue removal is told to stop instead
+impl Drop for Generating {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Resource storing the puzzle being made in the background, if any
+///
+/// Each clue removed is checked for a unique solution, which takes seconds on the largest boards,
+/// so the board stays responsive meanwhile
 #[derive(Default)]
-struct CompletePuzzle {
-    numbers: HashMap<Coordinates, Value>,
+pub struct PuzzleGeneration(Option<Generating>);
+
+impl PuzzleGeneration {
+    /// Is a puzzle being made
+    pub fn is_running(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Starts making a puzzle to replace the current one, cancelling any other puzzle being made
+    ///
+    /// The puzzle is made by `generate`, which should stop early once the flag it is given is set
+    fn start(
+        &mut self,
+        task_pool: &AsyncComputeTaskPool,
+        current: &InitialPuzzle,
+        generate: impl FnOnce(&AtomicBool) -> Result<GeneratedPuzzle, &'static str> + Send + 'static,
+    ) {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+        self.0 = Some(Generating {
+            replaced: current.clone(),
+            task: task_pool.spawn(async move { generate(&flag) }),
+            cancelled,
+        });
+    }
+}
+
+/// Resource storing the digits typed into the seed entry dialog so far
+#[derive(Default)]
+pub struct SeedEntry(pub String);
+
+/// The seed the current puzzle was generated from
+///
+/// Puzzles that were imported, remixed or scrambled have no seed
+#[derive(Default, Clone)]
+pub struct PuzzleSeed(pub Option<u64>);
+
+/// Resource describing where the current puzzle came from, as given by its author
+///
+/// Only puzzles imported from f-puzzles or SudokuPad links have metadata
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct PuzzleMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    /// The rules of the puzzle, including any variant constraints
+    pub rules: Option<String>,
+}
+
+/// Puzzles set aside by the player to be continued later
+#[derive(Default)]
+struct Bookmarks(Vec<Bookmark>);
+
+/// A snapshot of a puzzle in progress
+struct Bookmark {
+    initial_puzzle: InitialPuzzle,
+    complete_puzzle: CompletePuzzle,
+    values: HashMap<Coordinates, (Value, Locked, Provenance)>,
+    puzzle_timer: PuzzleTimer,
+    puzzle_seed: PuzzleSeed,
+    puzzle_metadata: PuzzleMetadata,
+    constraints: ConstraintSet,
+    /// The input modes in use when the puzzle was set aside
+    input_mode: InputMode,
+    input_mode_memory: InputModeMemory,
+}
+
+/// Cell values and locks that should be restored on top of the clues the next time the puzzle is filled
+#[derive(Default)]
+struct RestoredValues(Option<HashMap<Coordinates, (Value, Locked, Provenance)>>);
+
+/// An action that would throw away the player's progress on the current puzzle
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiscardingAction {
+    NewPuzzle,
+    ResetPuzzle,
+    RemixPuzzle,
+    QuitGame,
+}
+
+/// Event sent when the player asks for a new puzzle, a reset, a remix or to quit while partway through the current puzzle
+pub struct DiscardConfirmation {
+    /// What the player asked for, which will be done once they confirm
+    pub action: DiscardingAction,
+    /// The fraction of cells not given by the puzzle that have been filled in
+    pub completion: f32,
+    /// The time spent on the current puzzle so far
+    pub puzzle_timer: PuzzleTimer,
 }
 
-/// Converts a sudoku generated by the `sudoku` crate into a usable format
-fn parse_sudoku(sudoku: Sudoku) -> HashMap<Coordinates, Value> {
-    let (mut row, mut column) = (1, 0);
-    let mut map = HashMap::default();
+/// Event to start playing a puzzle written in one of the notations supported by `Position`
+pub struct ImportPuzzle(pub String);
 
-    // Sudoku::iter() goes from left to right, top to bottom
-    for value in sudoku.iter() {
-        column += 1;
-        if column == 10 {
-            row += 1;
-            column = 1;
+/// Event to generate the puzzle given by a seed, such as one shared by another player
+pub struct SeededPuzzle(pub u64);
+
+/// Controls the practice positions created by scrambling the remaining cells
+pub struct ScrambleConfig {
+    /// How many of the previously unfilled cells are emptied again for the player to solve
+    ///
+    /// Fewer cells are emptied if removing more would give the puzzle several solutions
+    pub removed: usize,
+}
+
+impl ScrambleConfig {
+    /// The numbers of cells that can be chosen in the settings menu, from smallest to largest
+    pub const SIZES: [usize; 4] = [5, 10, 20, 40];
+
+    /// The number of cells that follows the current one when cycling through them
+    fn next_size(&self) -> usize {
+        Self::SIZES
+            .iter()
+            .copied()
+            .find(|&size| size > self.removed)
+            .unwrap_or(Self::SIZES[0])
+    }
+}
+
+impl Default for ScrambleConfig {
+    fn default() -> Self {
+        ScrambleConfig { removed: 10 }
+    }
+}
+
+/// Resource storing the player's requirements for newly generated puzzles
+///
+/// Puzzles that don't meet them are thrown away and generated again from a new seed,
+/// until the retry budget runs out and the last puzzle is kept anyway.
+/// Puzzles generated from a shared seed are never filtered, so that they match the puzzle that was shared
+#[derive(Clone)]
+pub struct GenerationFilters {
+    /// Every region must have at least this many givens
+    pub min_givens_per_square: usize,
+    /// No region may have more than this many givens
+    pub max_givens_per_square: usize,
+    /// Puzzles with fewer givens than this are rejected, such as near-minimal 17-clue puzzles
+    pub min_givens: usize,
+    /// Puzzles must be solvable with singles alone, without guessing or uniqueness-based techniques
+    pub singles_only: bool,
+    /// Puzzles must need at least this many guesses from our solver, so that puzzles are hard enough
+    pub min_guesses: u32,
+    /// The rules puzzles follow as well as the classic ones, which only apply to 9x9 grids
+    pub variants: Vec<Variant>,
+    /// The size of the board puzzles are played on
+    pub size: GridSize,
+    /// How many puzzles are thrown away before one is kept regardless of the filters
+    pub retries: u32,
+}
+
+impl Default for GenerationFilters {
+    fn default() -> Self {
+        GenerationFilters {
+            min_givens_per_square: 0,
+            // As many as the boxes of the largest grid hold, so that no size of grid is limited
+            max_givens_per_square: 16,
+            min_givens: 0,
+            singles_only: false,
+            min_guesses: 0,
+            variants: Vec::new(),
+            size: GridSize::Nine,
+            retries: 50,
         }
-        let square = Coordinates::compute_square(row, column);
+    }
+}
 
-        let coordinates = Coordinates {
-            row,
-            column,
-            square,
-        };
+impl GenerationFilters {
+    /// The rules newly generated puzzles are played under
+    ///
+    /// Variants are only drawn on 9x9 grids, so puzzles of other sizes are always classic.
+    /// Jigsaws get new regions each time, drawn from the RNG provided, which are tried until the solver can fill them in.
+    /// Returns `None` if the retry budget runs out before any regions that can be filled in are found
+    pub fn constraints(&self, rng: &mut StdRng) -> Option<ConstraintSet> {
+        if self.size != GridSize::Nine {
+            return Some(ConstraintSet::for_grid(self.size));
+        }
 
-        let value = match value {
-            Some(v) => Value::Filled(v),
-            None => Value::Empty,
-        };
-        map.insert(coordinates, value);
+        let mut constraints: Vec<Arc<dyn Constraint>> =
+            vec![Arc::new(ClassicSudoku(GridSize::Nine))];
+        constraints.extend(
+            self.variants
+                .iter()
+                .filter_map(|variant| variant.constraint()),
+        );
+        if !self.variants.contains(&Variant::Jigsaw) {
+            return Some(ConstraintSet::new(constraints));
+        }
+
+        for _ in 0..=self.retries {
+            let constraints =
+                ConstraintSet::with_regions(constraints.clone(), Regions::random(rng));
+            if Position::empty(GridSize::Nine).count_solutions(&constraints, 1) == Some(1) {
+                return Some(constraints);
+            }
+        }
+        warn!("No jigsaw regions could be filled in under the generation filters");
+        None
+    }
+
+    /// Does the puzzle meet every requirement
+    pub fn accepts(&self, position: &Position, constraints: &ConstraintSet) -> bool {
+        let mut per_square = vec![0; constraints.regions().count() as usize];
+        for (i, digit) in position.digits.iter().enumerate() {
+            if digit.is_some() {
+                per_square[constraints.regions().region(i) as usize - 1] += 1;
+            }
+        }
+        let givens: usize = per_square.iter().sum();
+
+        let distributed = per_square
+            .iter()
+            .all(|n| (self.min_givens_per_square..=self.max_givens_per_square).contains(n));
+        if !distributed || givens < self.min_givens {
+            return false;
+        }
+
+        // Grading is the slowest check, so it is left for last, and skipped when nothing depends on it
+        if !self.singles_only && self.min_guesses == 0 {
+            return true;
+        }
+        match position.grade(constraints) {
+            Some(Grade::Singles) => self.min_guesses == 0,
+            Some(Grade::Guessing { guesses }) => !self.singles_only && guesses >= self.min_guesses,
+            None => false,
+        }
     }
-    map
+}
+
+/// The name shown for `.sdk` files in the file dialogs
+const SDK_FILTER: &str = "SadMan Sudoku";
+
+/// Requests for a new puzzle sent more rapidly than this are ignored
+const NEW_PUZZLE_THROTTLE: Duration = Duration::from_millis(500);
+
+/// Assigns coordinates to values listed from left to right, top to bottom, across a grid of this size
+pub fn parse_values(
+    values: impl Iterator<Item = Value>,
+    grid: GridSize,
+) -> HashMap<Coordinates, Value> {
+    values
+        .enumerate()
+        .map(|(i, value)| (grid.coordinates(i), value))
+        .collect()
 }
 
 /// Sends an event to create a new sudoku on app startup
-fn first_sudoku(mut event_writer: EventWriter<NewPuzzle>) {
+///
+/// A puzzle can be provided in the first command line argument instead,
+/// either as its givens, a pencilmark string or an f-puzzles or SudokuPad link,
+/// or the seed of a generated puzzle can be passed with `--seed`
+fn first_sudoku(
+    mut event_writer: EventWriter<NewPuzzle>,
+    mut import_writer: EventWriter<ImportPuzzle>,
+    mut seed_writer: EventWriter<SeededPuzzle>,
+    companion_mode: Res<CompanionMode>,
+    generation_filters: Res<GenerationFilters>,
+) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Paper solvers type their puzzle into an empty board of the size chosen for new puzzles, unless they passed it after the flag
+    if companion_mode.0 {
+        let notation = args
+            .get(1)
+            .cloned()
+            .unwrap_or_else(|| ".".repeat(generation_filters.size.cell_count()));
+        import_writer.send(ImportPuzzle(notation));
+        return;
+    }
+    if let Some(flag) = args.first().filter(|flag| *flag == "--seed") {
+        // The seed is never read as a puzzle, even when it isn't a valid seed
+        match args.get(1).map(|seed| seed.parse()) {
+            Some(Ok(seed)) => seed_writer.send(SeededPuzzle(seed)),
+            Some(Err(error)) => {
+                warn!(
+                    "Could not read the seed provided: {}, generating a puzzle instead",
+                    error
+                );
+                event_writer.send(NewPuzzle::default());
+            }
+            None => {
+                warn!(
+                    "No seed was given after {}, generating a puzzle instead",
+                    flag
+                );
+                event_writer.send(NewPuzzle::default());
+            }
+        }
+        return;
+    }
+
+    if let Some(notation) = args.first() {
+        let puzzle = Position::parse(notation).and_then(|position| {
+            let constraints =
+                puzzle_constraints(notation, &position).map_err(NotationError::InvalidLink)?;
+            Ok((position, constraints))
+        });
+        match puzzle {
+            Ok((position, constraints)) if position.solve(&constraints).is_some() => {
+                import_writer.send(ImportPuzzle(notation.clone()));
+                return;
+            }
+            Ok(_) => warn!("The puzzle provided has no solution, generating one instead"),
+            Err(error) => warn!("Could not read the puzzle provided: {:?}", error),
+        }
+    }
+
     event_writer.send(NewPuzzle::default());
 }
 
-/// Creates a new sudoku using the `sudoku` crate
-fn new_sudoku(
-    mut event_reader: EventReader<NewPuzzle>,
+/// The rules of the puzzle written in `notation`, which has been read as `position`
+///
+/// Only puzzle links can describe rules beyond the classic ones, such as killer cages,
+/// while other notations give the classic rules for the size of grid they were written for
+fn puzzle_constraints(notation: &str, position: &Position) -> Result<ConstraintSet, LinkError> {
+    if fpuzzles::is_link(notation) {
+        fpuzzles::parse_constraints(notation)
+    } else {
+        Ok(ConstraintSet::for_grid(position.grid))
+    }
+}
+
+/// Checks a puzzle before it is loaded, telling the player what is wrong with it
+///
+/// Puzzles whose givens clash or have no solution are rejected, returning `false`,
+/// while those with several solutions are loaded with a warning, except in companion mode where nothing is checked.
+/// Puzzles that must have a unique solution, such as pencilmark imports whose candidates the player will work from,
+/// are rejected when they have several.
+/// Puzzles the solver gives up on are rejected too, as there may be no solution to check digits against
+fn validate_import(
+    position: &Position,
+    constraints: &ConstraintSet,
+    companion_mode: bool,
+    require_unique: bool,
+    toasts: &mut Toasts,
+    localization: &Localization,
+) -> bool {
+    let error = match position.validate(constraints) {
+        Ok(()) => return true,
+        Err(error) => error,
+    };
+    warn!("Imported puzzle is invalid: {:?}", error);
+
+    match error {
+        PuzzleError::ClashingGivens { digit, cells } => {
+            toasts.push(localization.format(
+                "toast-clashing-givens",
+                &[
+                    ("digit", digit_symbol(digit).to_string().into()),
+                    ("first", cell_name(cells.0, position.grid).into()),
+                    ("second", cell_name(cells.1, position.grid).into()),
+                ],
+            ));
+            false
+        }
+        PuzzleError::BrokenConstraint(i) => {
+            toasts.push(localization.format(
+                "toast-broken-constraint",
+                &[("cell", cell_name(i, position.grid).into())],
+            ));
+            false
+        }
+        PuzzleError::NoSolution => {
+            toasts.push(localization.get("toast-no-solution"));
+            false
+        }
+        PuzzleError::MultipleSolutions if require_unique => {
+            toasts.push(localization.get("toast-not-unique"));
+            false
+        }
+        PuzzleError::MultipleSolutions => {
+            if !companion_mode {
+                toasts.push(localization.get("toast-multiple-solutions"));
+            }
+            true
+        }
+        PuzzleError::Undetermined => {
+            toasts.push(localization.get("toast-undetermined"));
+            false
+        }
+    }
+}
+
+/// Starts playing a puzzle from its notation, keeping any candidates it contains as center marks
+fn import_sudoku(
+    mut event_reader: EventReader<ImportPuzzle>,
+    mut initial_puzzle: ResMut<InitialPuzzle>,
+    mut complete_puzzle: ResMut<CompletePuzzle>,
+    mut restored_values: ResMut<RestoredValues>,
+    mut puzzle_timer: ResMut<PuzzleTimer>,
+    mut puzzle_seed: ResMut<PuzzleSeed>,
+    mut puzzle_metadata: ResMut<PuzzleMetadata>,
+    companion_mode: Res<CompanionMode>,
+    mut constraints: ResMut<ConstraintSet>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for event in event_reader.iter() {
+        let position = match Position::parse(&event.0) {
+            Ok(position) => position,
+            Err(NotationError::InvalidLink(LinkError::UnsupportedConstraints(keys))) => {
+                warn!("Puzzle uses unsupported constraints: {}", keys.join(", "));
+                toasts.push(localization.format(
+                    "toast-unsupported-constraints",
+                    &[("constraints", keys.join(", ").into())],
+                ));
+                continue;
+            }
+            Err(error) => {
+                warn!("Could not read puzzle: {:?}", error);
+                toasts.push(localization.get("toast-unreadable-puzzle"));
+                continue;
+            }
+        };
+        let puzzle_constraints = match puzzle_constraints(&event.0, &position) {
+            Ok(puzzle_constraints) => puzzle_constraints,
+            Err(error) => {
+                warn!("Could not read the puzzle's constraints: {:?}", error);
+                toasts.push(localization.get("toast-unreadable-puzzle"));
+                continue;
+            }
+        };
+
+        if !validate_import(
+            &position,
+            &puzzle_constraints,
+            companion_mode.0,
+            notation::is_pencilmarks(&event.0),
+            &mut toasts,
+            &localization,
+        ) {
+            continue;
+        }
+        let solution = position
+            .solve(&puzzle_constraints)
+            .expect("Puzzles that passed validation have a solution");
+
+        *constraints = puzzle_constraints;
+
+        *initial_puzzle = InitialPuzzle {
+            numbers: parse_values(position.givens(), position.grid),
+        };
+        // Nothing is checked against the solution in companion mode,
+        // as the puzzle on paper may not match the one the player has typed in so far
+        *complete_puzzle = if companion_mode.0 {
+            CompletePuzzle::default()
+        } else {
+            CompletePuzzle {
+                numbers: parse_values(
+                    solution.iter().map(|num| Value::Filled(*num)),
+                    position.grid,
+                ),
+            }
+        };
+        restored_values.0 = Some(
+            parse_values(position.values(), position.grid)
+                .into_iter()
+                .map(|(coordinates, value)| {
+                    (coordinates, (value, Locked(false), Provenance::Player))
+                })
+                .collect(),
+        );
+        *puzzle_timer = PuzzleTimer::default();
+        *puzzle_seed = PuzzleSeed(None);
+        *puzzle_metadata = if fpuzzles::is_link(&event.0) {
+            fpuzzles::parse_metadata(&event.0).unwrap_or_default()
+        } else {
+            PuzzleMetadata::default()
+        };
+    }
+}
+
+/// Asks the player for an `.sdk` file, then starts playing the puzzle in it from where the file left off
+///
+/// `.sdm` collections are opened in the collection browser instead
+fn open_puzzle_file(
+    mut event_reader: EventReader<OpenPuzzleFile>,
+    mut browse_writer: EventWriter<BrowseCollection>,
+    mut collection: ResMut<Collection>,
     mut initial_puzzle: ResMut<InitialPuzzle>,
     mut complete_puzzle: ResMut<CompletePuzzle>,
+    mut restored_values: ResMut<RestoredValues>,
+    mut puzzle_timer: ResMut<PuzzleTimer>,
+    mut puzzle_seed: ResMut<PuzzleSeed>,
+    mut puzzle_metadata: ResMut<PuzzleMetadata>,
+    mut constraints: ResMut<ConstraintSet>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
 ) {
     for _ in event_reader.iter() {
-        let completed = Sudoku::generate_filled();
-        // Puzzles are generated by removing clues
-        let initial = Sudoku::generate_unique_from(completed);
+        let path = match rfd::FileDialog::new()
+            .add_filter(SDK_FILTER, &["sdk", "sdm"])
+            .pick_file()
+        {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) => {
+                warn!("Could not open {}: {}", path.display(), error);
+                toasts.push(localization.format(
+                    "toast-open-failed",
+                    &[("path", path.display().to_string().into())],
+                ));
+                continue;
+            }
+        };
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "sdm")
+        {
+            let name = path
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+            *collection = Collection::parse(name, &contents);
+            browse_writer.send(BrowseCollection { page: 0 });
+            continue;
+        }
+
+        let puzzle = match SdkPuzzle::parse(&contents) {
+            Ok(puzzle) => puzzle,
+            Err(error) => {
+                warn!("Could not read puzzle from {}: {:?}", path.display(), error);
+                toasts.push(localization.format(
+                    "toast-unreadable-file",
+                    &[("path", path.display().to_string().into())],
+                ));
+                continue;
+            }
+        };
+
+        // The format has no way to describe variant rules, so its puzzles are always classic
+        let puzzle_constraints = ConstraintSet::classic();
+        let position = puzzle.position();
+        if !validate_import(
+            &position,
+            &puzzle_constraints,
+            false,
+            false,
+            &mut toasts,
+            &localization,
+        ) {
+            continue;
+        }
+        let solution = position
+            .solve(&puzzle_constraints)
+            .expect("Puzzles that passed validation have a solution");
+
+        *constraints = puzzle_constraints;
 
         *initial_puzzle = InitialPuzzle {
-            numbers: parse_sudoku(initial),
+            numbers: parse_values(position.givens(), GridSize::Nine),
         };
         *complete_puzzle = CompletePuzzle {
-            numbers: parse_sudoku(completed),
+            numbers: parse_values(
+                solution.iter().map(|num| Value::Filled(*num)),
+                GridSize::Nine,
+            ),
+        };
+        // Pencil marks become center marks, as they list the candidates for their cell
+        let values = puzzle
+            .givens
+            .iter()
+            .zip(puzzle.placed.iter())
+            .zip(puzzle.pencil_marks.iter())
+            .map(|((given, placed), marks)| match given.or(*placed) {
+                Some(num) => Value::Filled(num),
+                None => Value::Marked(
+                    CenterMarks::from_digits(marks.iter().copied()),
+                    CornerMarks::default(),
+                )
+                .cleanup(),
+            });
+        restored_values.0 = Some(
+            parse_values(values, GridSize::Nine)
+                .into_iter()
+                .map(|(coordinates, value)| {
+                    (coordinates, (value, Locked(false), Provenance::Player))
+                })
+                .collect(),
+        );
+        *puzzle_timer = PuzzleTimer::default();
+        *puzzle_seed = PuzzleSeed(None);
+        *puzzle_metadata = puzzle.metadata;
+    }
+}
+
+/// Asks the player where to save the puzzle and their progress on it as an `.sdk` file
+///
+/// Center marks are saved as pencil marks, while corner marks and colors are lost.
+/// The format only holds 9x9 grids, so puzzles of other sizes can't be saved
+fn save_puzzle_file(
+    mut event_reader: EventReader<SavePuzzleFile>,
+    puzzle_metadata: Res<PuzzleMetadata>,
+    constraints: Res<ConstraintSet>,
+    query: Query<(&Coordinates, &Value, &Fixed), With<Cell>>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for _ in event_reader.iter() {
+        if constraints.grid() != GridSize::Nine {
+            toasts.push(localization.get("toast-sdk-grid-size"));
+            continue;
+        }
+
+        let path = match rfd::FileDialog::new()
+            .add_filter(SDK_FILTER, &["sdk"])
+            .save_file()
+        {
+            Some(path) => path.with_extension("sdk"),
+            None => continue,
+        };
+
+        let mut puzzle = SdkPuzzle {
+            givens: [None; 81],
+            placed: [None; 81],
+            pencil_marks: vec![Vec::new(); 81],
+            metadata: puzzle_metadata.clone(),
+        };
+        for (coordinates, value, is_fixed) in query.iter() {
+            let i = GridSize::Nine.index(coordinates);
+            match value {
+                Value::Filled(num) => {
+                    if is_fixed.0 {
+                        puzzle.givens[i] = Some(*num);
+                    }
+                    // The state includes the givens, as other programs expect
+                    puzzle.placed[i] = Some(*num);
+                }
+                Value::Marked(center, _) => puzzle.pencil_marks[i] = center.digits(),
+                Value::Empty => (),
+            }
+        }
+
+        match std::fs::write(&path, puzzle.write()) {
+            Ok(()) => toasts.push(localization.format(
+                "toast-saved",
+                &[("path", path.display().to_string().into())],
+            )),
+            Err(error) => {
+                warn!("Could not save puzzle to {}: {}", path.display(), error);
+                toasts.push(localization.format(
+                    "toast-save-failed",
+                    &[("path", path.display().to_string().into())],
+                ));
+            }
+        }
+    }
+}
+
+/// Generates a puzzle and its solution from a seed, so that the same seed always gives the same puzzle
+/// under the same constraints
+///
+/// Returns `None` if no grid follows every one of the constraints, as happens for some mixes of variants
+pub fn generate_from_seed(
+    seed: u64,
+    constraints: &ConstraintSet,
+) -> Option<(InitialPuzzle, CompletePuzzle)> {
+    generate_until_cancelled(seed, constraints, &AtomicBool::new(false))
+}
+
+/// Generates the puzzle given by a seed like `generate_from_seed`,
+/// but stops removing clues once `cancelled` is set, as the puzzle is no longer wanted
+fn generate_until_cancelled(
+    seed: u64,
+    constraints: &ConstraintSet,
+    cancelled: &AtomicBool,
+) -> Option<(InitialPuzzle, CompletePuzzle)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let grid = constraints.grid();
+
+    // Filling the boxes on the diagonal independently gives the solver a varied starting point,
+    // as they share no rows or columns, though only square boxes line up along the diagonal
+    let mut position = Position::empty(grid);
+    let (box_height, box_width) = grid.box_shape();
+    if box_height == box_width {
+        for corner in (0..grid.width()).step_by(box_width as usize) {
+            let mut digits: Vec<u8> = (1..=grid.digits()).collect();
+            digits.shuffle(&mut rng);
+            for (k, num) in digits.into_iter().enumerate() {
+                let i = grid.index(&Coordinates {
+                    row: corner + k as u8 / box_width + 1,
+                    column: corner + k as u8 % box_width + 1,
+                });
+                position.digits[i] = Some(num);
+                position.candidates[i] = 1 << num;
+            }
+        }
+    }
+
+    // Constraints beyond the classic rules, or the regions of a jigsaw, may rule out the boxes chosen,
+    // so the grid is then filled from scratch
+    let solution = position
+        .random_solution(constraints, &mut rng)
+        .or_else(|| Position::empty(grid).random_solution(constraints, &mut rng))?;
+
+    // Puzzles are generated by removing clues
+    let mut order: Vec<usize> = (0..grid.cell_count()).collect();
+    order.shuffle(&mut rng);
+    let puzzle = remove_clues(
+        Position::from_solution(grid, &solution),
+        order,
+        grid.cell_count(),
+        constraints,
+        cancelled,
+    );
+
+    Some((
+        InitialPuzzle {
+            numbers: parse_values(puzzle.givens(), grid),
+        },
+        CompletePuzzle {
+            numbers: parse_values(solution.iter().map(|num| Value::Filled(*num)), grid),
+        },
+    ))
+}
+
+/// Generates puzzles from random seeds until one passes the filters or the retry budget runs out
+///
+/// Returns `None` if the constraints can't be satisfied at all
+fn generate_filtered(
+    filters: &GenerationFilters,
+    constraints: &ConstraintSet,
+    rng: &mut StdRng,
+    cancelled: &AtomicBool,
+) -> Option<(u64, InitialPuzzle, CompletePuzzle)> {
+    let mut attempts = 0;
+    loop {
+        let seed = rng.gen();
+        let (initial, complete) = generate_until_cancelled(seed, constraints, cancelled)?;
+
+        if cancelled.load(Ordering::Relaxed) || filters.accepts(&initial.position(), constraints) {
+            return Some((seed, initial, complete));
+        }
+
+        attempts += 1;
+        if attempts > filters.retries {
+            warn!("No puzzle met the generation filters, keeping the last one generated");
+            return Some((seed, initial, complete));
+        }
+    }
+}
+
+/// Searches the seeds from `first_seed` upwards for a puzzle that passes the filters,
+/// so that the same search always finds the same puzzle
+///
+/// Like `generate_filtered`, the last seed tried is kept if the retry budget runs out,
+/// and `None` is returned if the constraints can't be satisfied at all
+pub fn find_seed(
+    first_seed: u64,
+    filters: &GenerationFilters,
+    constraints: &ConstraintSet,
+) -> Option<u64> {
+    let mut seed = first_seed;
+    for _ in 0..filters.retries {
+        let (initial, _) = generate_from_seed(seed, constraints)?;
+        if filters.accepts(&initial.position(), constraints) {
+            return Some(seed);
+        }
+        seed = seed.wrapping_add(1);
+    }
+    warn!("No seed met the generation filters, keeping the last one tried");
+    Some(seed)
+}
+
+/// Empties the cells provided in turn, skipping any whose removal would give the puzzle more than one solution,
+/// until `limit` cells have been emptied or `cancelled` is set
+fn remove_clues(
+    mut position: Position,
+    cells: Vec<usize>,
+    limit: usize,
+    constraints: &ConstraintSet,
+    cancelled: &AtomicBool,
+) -> Position {
+    let mut n_removed = 0;
+    for i in cells {
+        if n_removed == limit || cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let mut candidate = position.clone();
+        candidate.digits[i] = None;
+        candidate.candidates[i] = candidate.grid.all_candidates();
+        if candidate.uniqueness(constraints) == Uniqueness::Unique {
+            position = candidate;
+            n_removed += 1;
+        }
+    }
+    position
+}
+
+/// Starts making a new sudoku from a random seed in the background
+///
+/// If the player has already made progress, they are asked to confirm first.
+/// The current puzzle is kept if no puzzle can follow every variant rule switched on
+fn new_sudoku(
+    mut event_reader: EventReader<NewPuzzle>,
+    mut confirmation_writer: EventWriter<DiscardConfirmation>,
+    mut generation: ResMut<PuzzleGeneration>,
+    initial_puzzle: Res<InitialPuzzle>,
+    puzzle_timer: Res<PuzzleTimer>,
+    generation_filters: Res<GenerationFilters>,
+    puzzle_rng: Res<PuzzleRng>,
+    task_pool: Res<AsyncComputeTaskPool>,
+    mut last_request: Local<Option<Duration>>,
+    time: Res<Time>,
+    query: Query<(&Value, &Fixed), With<Cell>>,
+) {
+    for event in event_reader.iter() {
+        if !event.confirmed {
+            // Buttons send events every frame while they are held down,
+            // so we throttle requests to avoid generating a flurry of puzzles
+            let now = time.time_since_startup();
+            let throttled = matches!(*last_request, Some(last) if now - last < NEW_PUZZLE_THROTTLE);
+            *last_request = Some(now);
+            if throttled {
+                continue;
+            }
+
+            if in_progress(query.iter()) {
+                confirmation_writer.send(DiscardConfirmation {
+                    action: DiscardingAction::NewPuzzle,
+                    completion: completion(query.iter()),
+                    puzzle_timer: puzzle_timer.clone(),
+                });
+                continue;
+            }
+        }
+
+        let filters = generation_filters.clone();
+        let rng = puzzle_rng.0.clone();
+        generation.start(&task_pool, &initial_puzzle, move |cancelled| {
+            generate_new(filters, rng, cancelled)
+        });
+    }
+}
+
+/// Makes a new puzzle under the rules drawn from the filters, along with its solution
+///
+/// Fails if no puzzle can follow every variant rule switched on
+fn generate_new(
+    filters: GenerationFilters,
+    mut rng: StdRng,
+    cancelled: &AtomicBool,
+) -> Result<GeneratedPuzzle, &'static str> {
+    let generated = filters.constraints(&mut rng).and_then(|constraints| {
+        generate_filtered(&filters, &constraints, &mut rng, cancelled)
+            .map(|generated| (constraints, generated))
+    });
+    let (constraints, (seed, initial, complete)) =
+        generated.ok_or("toast-unsatisfiable-variants")?;
+
+    // Seeded puzzles are always classic 9x9 puzzles, so the seed of any other puzzle wouldn't give it back
+    let classic = !constraints.is_variant() && constraints.grid() == GridSize::Nine;
+    Ok(GeneratedPuzzle {
+        constraints: Some(constraints),
+        initial,
+        complete: Some(complete),
+        seed: classic.then(|| seed),
+        rng,
+    })
+}
+
+/// Replaces the current puzzle with the one made in the background once it is ready,
+/// unless another puzzle was started some other way in the meantime, such as by importing one
+fn receive_generated_puzzle(
+    mut generation: ResMut<PuzzleGeneration>,
+    mut initial_puzzle: ResMut<InitialPuzzle>,
+    mut complete_puzzle: ResMut<CompletePuzzle>,
+    mut puzzle_timer: ResMut<PuzzleTimer>,
+    mut puzzle_seed: ResMut<PuzzleSeed>,
+    mut puzzle_metadata: ResMut<PuzzleMetadata>,
+    mut puzzle_rng: ResMut<PuzzleRng>,
+    mut constraints: ResMut<ConstraintSet>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    let generated = match generation.0.as_mut() {
+        Some(generating) => match future::block_on(future::poll_once(&mut generating.task)) {
+            Some(generated) => generated,
+            None => return,
+        },
+        None => return,
+    };
+    let generating = generation.0.take().expect("The puzzle was being made");
+    if *initial_puzzle != generating.replaced {
+        return;
+    }
+
+    let generated = match generated {
+        Ok(generated) => generated,
+        Err(message) => {
+            toasts.push(localization.get(message));
+            return;
+        }
+    };
+    if let Some(new_constraints) = generated.constraints {
+        *constraints = new_constraints;
+    }
+    *initial_puzzle = generated.initial;
+    if let Some(complete) = generated.complete {
+        *complete_puzzle = complete;
+    }
+    *puzzle_timer = PuzzleTimer::default();
+    *puzzle_seed = PuzzleSeed(generated.seed);
+    *puzzle_metadata = PuzzleMetadata::default();
+    *puzzle_rng = PuzzleRng(generated.rng);
+}
+
+/// Switches whether newly generated puzzles follow a variant rule, leaving the current puzzle alone
+fn toggle_variant(
+    mut event_reader: EventReader<ToggleVariant>,
+    mut generation_filters: ResMut<GenerationFilters>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for ToggleVariant(variant) in event_reader.iter() {
+        let variants = &mut generation_filters.variants;
+        let message = if variants.contains(variant) {
+            variants.retain(|other| other != variant);
+            "toast-variant-off"
+        } else {
+            variants.push(*variant);
+            "toast-variant-on"
         };
+        let rule = localization.get(variant.name_id());
+        toasts.push(localization.format(message, &[("rule", rule.into())]));
+    }
+}
+
+/// Generates the puzzle given by a seed, without asking for confirmation,
+/// and reseeds the puzzle RNG so that what follows the puzzle can be repeated too
+fn seeded_sudoku(
+    mut event_reader: EventReader<SeededPuzzle>,
+    mut initial_puzzle: ResMut<InitialPuzzle>,
+    mut complete_puzzle: ResMut<CompletePuzzle>,
+    mut puzzle_timer: ResMut<PuzzleTimer>,
+    mut puzzle_seed: ResMut<PuzzleSeed>,
+    mut puzzle_metadata: ResMut<PuzzleMetadata>,
+    mut puzzle_rng: ResMut<PuzzleRng>,
+    mut constraints: ResMut<ConstraintSet>,
+) {
+    for event in event_reader.iter() {
+        // Seeds only give the same puzzle under the same rules, so seeded puzzles are always classic
+        *constraints = ConstraintSet::classic();
+        let (initial, complete) = generate_from_seed(event.0, &constraints)
+            .expect("Classic puzzles can always be generated");
+
+        *initial_puzzle = initial;
+        *complete_puzzle = complete;
+        *puzzle_timer = PuzzleTimer::default();
+        *puzzle_seed = PuzzleSeed(Some(event.0));
+        *puzzle_metadata = PuzzleMetadata::default();
+        *puzzle_rng = PuzzleRng(StdRng::seed_from_u64(event.0));
+    }
+}
+
+/// Collects the digits typed while the seed entry dialog is open, with Backspace removing the last one,
+/// and clears them each time the dialog is opened
+fn type_seed(
+    mut char_reader: EventReader<ReceivedCharacter>,
+    mut open_reader: EventReader<EnterSeed>,
+    keyboard_input: Res<Input<KeyCode>>,
+    dialog_query: Query<(), With<SeedEntryDialog>>,
+    mut seed_entry: ResMut<SeedEntry>,
+) {
+    // Every character must be read, so that digits typed into the board aren't entered once the dialog opens
+    let typed: String = char_reader
+        .iter()
+        .map(|event| event.char)
+        .filter(char::is_ascii_digit)
+        .collect();
+
+    if open_reader.iter().count() > 0 {
+        seed_entry.0.clear();
+        return;
+    }
+    if dialog_query.iter().next().is_none() {
+        return;
+    }
+
+    let mut seed = seed_entry.0.clone();
+    seed.push_str(&typed);
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        seed.pop();
+    }
+    if seed != seed_entry.0 {
+        seed_entry.0 = seed;
+    }
+}
+
+/// Generates the puzzle of the seed typed into the seed entry dialog,
+/// or tells the player that it isn't a seed, keeping the dialog open
+fn play_seed(
+    mut event_reader: EventReader<PlaySeed>,
+    mut seed_writer: EventWriter<SeededPuzzle>,
+    seed_entry: Res<SeedEntry>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for _ in event_reader.iter() {
+        match seed_entry.0.parse() {
+            Ok(seed) => seed_writer.send(SeededPuzzle(seed)),
+            Err(error) => {
+                warn!("Could not read the seed entered: {}", error);
+                toasts.push(localization.get("toast-invalid-seed"));
+            }
+        }
+    }
+}
+
+/// Saves the current puzzle to a bookmark, then starts a new puzzle
+fn bookmark_sudoku(
+    mut event_reader: EventReader<BookmarkPuzzle>,
+    mut new_puzzle_writer: EventWriter<NewPuzzle>,
+    mut bookmarks: ResMut<Bookmarks>,
+    initial_puzzle: Res<InitialPuzzle>,
+    complete_puzzle: Res<CompletePuzzle>,
+    puzzle_timer: Res<PuzzleTimer>,
+    puzzle_seed: Res<PuzzleSeed>,
+    puzzle_metadata: Res<PuzzleMetadata>,
+    constraints: Res<ConstraintSet>,
+    input_mode: Res<InputMode>,
+    input_mode_memory: Res<InputModeMemory>,
+    query: Query<(&Coordinates, &Value, &Locked, &Provenance), With<Cell>>,
+) {
+    for _ in event_reader.iter() {
+        bookmarks.0.push(Bookmark {
+            initial_puzzle: initial_puzzle.clone(),
+            complete_puzzle: complete_puzzle.clone(),
+            values: query
+                .iter()
+                .map(|(coordinates, value, is_locked, provenance)| {
+                    (
+                        coordinates.clone(),
+                        (value.clone(), *is_locked, *provenance),
+                    )
+                })
+                .collect(),
+            puzzle_timer: puzzle_timer.clone(),
+            puzzle_seed: puzzle_seed.clone(),
+            puzzle_metadata: puzzle_metadata.clone(),
+            constraints: constraints.clone(),
+            input_mode: *input_mode,
+            input_mode_memory: *input_mode_memory,
+        });
+
+        new_puzzle_writer.send(NewPuzzle { confirmed: true });
+    }
+}
+
+/// Returns to the most recently bookmarked puzzle
+fn resume_bookmark(
+    mut event_reader: EventReader<ResumeBookmark>,
+    mut bookmarks: ResMut<Bookmarks>,
+    mut initial_puzzle: ResMut<InitialPuzzle>,
+    mut complete_puzzle: ResMut<CompletePuzzle>,
+    mut restored_values: ResMut<RestoredValues>,
+    mut puzzle_timer: ResMut<PuzzleTimer>,
+    mut puzzle_seed: ResMut<PuzzleSeed>,
+    mut puzzle_metadata: ResMut<PuzzleMetadata>,
+    mut constraints: ResMut<ConstraintSet>,
+    mut input_mode: ResMut<InputMode>,
+    mut input_mode_memory: ResMut<InputModeMemory>,
+) {
+    for _ in event_reader.iter() {
+        if let Some(bookmark) = bookmarks.0.pop() {
+            *initial_puzzle = bookmark.initial_puzzle;
+            *complete_puzzle = bookmark.complete_puzzle;
+            restored_values.0 = Some(bookmark.values);
+            *puzzle_timer = bookmark.puzzle_timer;
+            *puzzle_seed = bookmark.puzzle_seed;
+            *puzzle_metadata = bookmark.puzzle_metadata;
+            *constraints = bookmark.constraints;
+            *input_mode = bookmark.input_mode;
+            *input_mode_memory = bookmark.input_mode_memory;
+        }
+    }
+}
+
+/// Starts making a new puzzle in the background with a different set of clues from the solution of the current puzzle,
+/// trying new clues until they pass the generation filters or the retry budget runs out
+///
+/// Puzzles without a known solution, such as those being set or solved on paper, can't be remixed.
+/// If the player has made progress without solving the puzzle yet, they are asked to confirm first
+fn remix_sudoku(
+    mut event_reader: EventReader<RemixPuzzle>,
+    mut confirmation_writer: EventWriter<DiscardConfirmation>,
+    mut generation: ResMut<PuzzleGeneration>,
+    initial_puzzle: Res<InitialPuzzle>,
+    puzzle_timer: Res<PuzzleTimer>,
+    complete_puzzle: Res<CompletePuzzle>,
+    generation_filters: Res<GenerationFilters>,
+    puzzle_rng: Res<PuzzleRng>,
+    constraints: Res<ConstraintSet>,
+    task_pool: Res<AsyncComputeTaskPool>,
+    mut last_request: Local<Option<Duration>>,
+    time: Res<Time>,
+    query: Query<(&Coordinates, &Value, &Fixed), With<Cell>>,
+) {
+    for event in event_reader.iter() {
+        let grid = constraints.grid();
+        let mut solution = vec![0; grid.cell_count()];
+        for (coordinates, value) in complete_puzzle.numbers.iter() {
+            if let (Value::Filled(num), true) = (value, grid.contains(coordinates)) {
+                solution[grid.index(coordinates)] = *num;
+            }
+        }
+        if solution.contains(&0) {
+            continue;
+        }
+
+        if !event.confirmed {
+            // Like new puzzles, remixes are throttled as buttons send events every frame while they are held down
+            let now = time.time_since_startup();
+            let throttled = matches!(*last_request, Some(last) if now - last < NEW_PUZZLE_THROTTLE);
+            *last_request = Some(now);
+            if throttled {
+                continue;
+            }
+
+            let cells = || query.iter().map(|(_, value, is_fixed)| (value, is_fixed));
+            let solved = query.iter().all(|(coordinates, value, _)| {
+                complete_puzzle.numbers.get(coordinates) == Some(value)
+            });
+            if in_progress(cells()) && !solved {
+                confirmation_writer.send(DiscardConfirmation {
+                    action: DiscardingAction::RemixPuzzle,
+                    completion: completion(cells()),
+                    puzzle_timer: puzzle_timer.clone(),
+                });
+                continue;
+            }
+        }
+
+        let filters = generation_filters.clone();
+        let constraints = constraints.clone();
+        let rng = puzzle_rng.0.clone();
+        generation.start(&task_pool, &initial_puzzle, move |cancelled| {
+            generate_remix(solution, filters, constraints, rng, cancelled)
+        });
+    }
+}
+
+/// Makes a new puzzle from the solution provided, keeping the rules and solution of the current puzzle
+fn generate_remix(
+    solution: Vec<u8>,
+    filters: GenerationFilters,
+    constraints: ConstraintSet,
+    mut rng: StdRng,
+    cancelled: &AtomicBool,
+) -> Result<GeneratedPuzzle, &'static str> {
+    // Clues are removed at random, so the new puzzle shares only its solution with the old one
+    let grid = constraints.grid();
+    let mut attempts = 0;
+    let remixed = loop {
+        let mut order: Vec<usize> = (0..grid.cell_count()).collect();
+        order.shuffle(&mut rng);
+        let remixed = remove_clues(
+            Position::from_solution(grid, &solution),
+            order,
+            grid.cell_count(),
+            &constraints,
+            cancelled,
+        );
+
+        attempts += 1;
+        if cancelled.load(Ordering::Relaxed) || filters.accepts(&remixed, &constraints) {
+            break remixed;
+        }
+        if attempts > filters.retries {
+            warn!("No remix met the generation filters, keeping the last one");
+            break remixed;
+        }
+    };
+
+    Ok(GeneratedPuzzle {
+        constraints: None,
+        initial: InitialPuzzle {
+            numbers: parse_values(remixed.givens(), grid),
+        },
+        complete: None,
+        seed: None,
+        rng,
+    })
+}
+
+/// Starts replacing the unfilled cells with a random completion that respects the filled ones in the background,
+/// then empties some of those cells again to create a new practice position
+///
+/// If the filled digits can't be completed, the completion respects only the puzzle's clues
+fn scramble_remaining(
+    mut event_reader: EventReader<ScrambleRemaining>,
+    mut generation: ResMut<PuzzleGeneration>,
+    initial_puzzle: Res<InitialPuzzle>,
+    scramble_config: Res<ScrambleConfig>,
+    puzzle_rng: Res<PuzzleRng>,
+    constraints: Res<ConstraintSet>,
+    task_pool: Res<AsyncComputeTaskPool>,
+    query: Query<(&Coordinates, &Value), With<Cell>>,
+) {
+    for _ in event_reader.iter() {
+        // Marks are ignored: only placed digits constrain the completion
+        let filled: Vec<_> = query
+            .iter()
+            .filter(|(_, value)| matches!(value, Value::Filled(_)))
+            .collect();
+
+        let position = Position::from_cells(constraints.grid(), &filled);
+        let clues = initial_puzzle.position();
+        let removed = scramble_config.removed;
+        let constraints = constraints.clone();
+        let rng = puzzle_rng.0.clone();
+        generation.start(&task_pool, &initial_puzzle, move |cancelled| {
+            generate_scramble(position, clues, removed, constraints, rng, cancelled)
+        });
+    }
+}
+
+/// Completes the position, or failing that the puzzle's clues, then empties up to `removed` of the cells that were unfilled
+///
+/// Fails if even the clues can't be completed
+fn generate_scramble(
+    position: Position,
+    clues: Position,
+    removed: usize,
+    constraints: ConstraintSet,
+    mut rng: StdRng,
+    cancelled: &AtomicBool,
+) -> Result<GeneratedPuzzle, &'static str> {
+    let (position, solution) = match position.random_solution(&constraints, &mut rng) {
+        Some(solution) => (position, solution),
+        None => {
+            let solution = clues
+                .random_solution(&constraints, &mut rng)
+                .ok_or("toast-no-solution")?;
+            (clues, solution)
+        }
+    };
+
+    // Only cells that were unfilled are emptied again, in a random order
+    let grid = constraints.grid();
+    let mut unfilled: Vec<usize> = (0..grid.cell_count())
+        .filter(|i| position.digits[*i].is_none())
+        .collect();
+    unfilled.shuffle(&mut rng);
+
+    let scrambled = remove_clues(
+        Position::from_solution(grid, &solution),
+        unfilled,
+        removed,
+        &constraints,
+        cancelled,
+    );
+
+    Ok(GeneratedPuzzle {
+        constraints: None,
+        initial: InitialPuzzle {
+            numbers: parse_values(scrambled.givens(), grid),
+        },
+        complete: Some(CompletePuzzle {
+            numbers: parse_values(solution.iter().map(|num| Value::Filled(*num)), grid),
+        }),
+        seed: None,
+        rng,
+    })
+}
+
+/// Changes how many cells scrambling empties, as picked in the settings menu
+fn cycle_scramble_size(
+    mut event_reader: EventReader<CycleScrambleSize>,
+    mut scramble_config: ResMut<ScrambleConfig>,
+) {
+    for _ in event_reader.iter() {
+        scramble_config.removed = scramble_config.next_size();
+    }
+}
+
+/// Changes the size of board new puzzles are played on, as picked in the settings menu,
+/// leaving the current puzzle alone
+fn cycle_grid_size(
+    mut event_reader: EventReader<CycleGridSize>,
+    mut generation_filters: ResMut<GenerationFilters>,
+) {
+    for _ in event_reader.iter() {
+        generation_filters.size = generation_filters.size.next();
+    }
+}
+
+/// Copies a link to the current puzzle's givens to the clipboard, for opening in f-puzzles or sharing with others
+///
+/// Generated puzzles are titled with their seed, while samurai puzzles and puzzles with rules f-puzzles can't hold aren't shared,
+/// as the link would lose part of the puzzle
+fn share_puzzle(
+    mut event_reader: EventReader<SharePuzzle>,
+    initial_puzzle: Res<InitialPuzzle>,
+    puzzle_seed: Res<PuzzleSeed>,
+    puzzle_metadata: Res<PuzzleMetadata>,
+    constraints: Res<ConstraintSet>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for _ in event_reader.iter() {
+        // f-puzzles only holds a single square grid
+        if constraints.grid() == GridSize::Samurai {
+            toasts.push(localization.get("toast-link-samurai"));
+            continue;
+        }
+
+        let mut metadata = puzzle_metadata.clone();
+        if metadata.title.is_none() {
+            metadata.title = puzzle_seed
+                .0
+                .map(|seed| format!("bevy-sudoku seed {}", seed));
+        }
+        let link = match fpuzzles::export_link(&initial_puzzle.position(), &constraints, &metadata)
+        {
+            Some(link) => link,
+            None => {
+                toasts.push(localization.get("toast-link-unsupported"));
+                continue;
+            }
+        };
+
+        // The link is logged as well, in case there is no clipboard to copy it to
+        info!("Share this puzzle with {}", link);
+        match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(link)) {
+            Ok(()) => toasts.push(localization.get("toast-link-copied")),
+            Err(error) => {
+                warn!("Could not copy the link to the clipboard: {}", error);
+                toasts.push(localization.get("toast-link-logged"));
+            }
+        }
     }
 }
 
 /// Fills fixed values from the puzzle into the board
+///
+/// A puzzle on a grid of a new size waits until the board has been rebuilt to match it
 fn fill_puzzle(
     initial_puzzle: Res<InitialPuzzle>,
-    mut query: Query<(&Coordinates, &mut Value, &mut Fixed), With<Cell>>,
+    mut restored_values: ResMut<RestoredValues>,
+    added_query: Query<(), Added<Cell>>,
+    mut query: Query<
+        (
+            &Coordinates,
+            &mut Value,
+            &mut Fixed,
+            &mut Locked,
+            &mut CellColor,
+            &mut Provenance,
+        ),
+        With<Cell>,
+    >,
 ) {
-    // Only run when the puzzle is changed
-    if !initial_puzzle.is_changed() {
+    // Only run when the puzzle or the board is changed
+    if !initial_puzzle.is_changed() && added_query.iter().next().is_none() {
+        return;
+    }
+    let matches_board = query.iter_mut().count() == initial_puzzle.numbers.len()
+        && query
+            .iter_mut()
+            .all(|(coordinates, ..)| initial_puzzle.numbers.contains_key(coordinates));
+    if !matches_board {
         return;
     }
 
-    for (coordinates, mut value, mut is_fixed) in query.iter_mut() {
+    // Bookmarked puzzles are restored to the state they were left in
+    let restored_values = restored_values.0.take();
+
+    for (coordinates, mut value, mut is_fixed, mut is_locked, mut cell_color, mut provenance) in
+        query.iter_mut()
+    {
         let initial_value = initial_puzzle
             .numbers
             .get(coordinates)
@@ -112,15 +1569,41 @@ fn fill_puzzle(
         // Fill in cells from initial puzzle and mark non-empty cells as fixed
         *value = initial_value.clone();
         is_fixed.0 = !(*initial_value == Value::Empty);
+        *is_locked = Locked(false);
+        *cell_color = CellColor::default();
+        *provenance = Provenance::default();
+
+        if let Some((restored_value, restored_lock, restored_provenance)) = restored_values
+            .as_ref()
+            .and_then(|values| values.get(coordinates))
+        {
+            *value = restored_value.clone();
+            *is_locked = *restored_lock;
+            *provenance = *restored_provenance;
+        }
     }
 }
 
 /// Resets the puzzle to its original state
+///
+/// If the player has already made progress, they are asked to confirm first
 fn reset_sudoku(
     mut event_reader: EventReader<ResetPuzzle>,
+    mut confirmation_writer: EventWriter<DiscardConfirmation>,
     mut initial_puzzle: ResMut<InitialPuzzle>,
+    puzzle_timer: Res<PuzzleTimer>,
+    query: Query<(&Value, &Fixed), With<Cell>>,
 ) {
-    for _ in event_reader.iter() {
+    for event in event_reader.iter() {
+        if !event.confirmed && in_progress(query.iter()) {
+            confirmation_writer.send(DiscardConfirmation {
+                action: DiscardingAction::ResetPuzzle,
+                completion: completion(query.iter()),
+                puzzle_timer: puzzle_timer.clone(),
+            });
+            continue;
+        }
+
         // Flags the puzzle as having changed, causing the fill_puzzle system to reset all values
         // as if a new identical puzzle had been generated
         // QUALITY: use an explicit set_changed() method instead once added, see https://github.com/bevyengine/bevy/pull/2208
@@ -128,18 +1611,48 @@ fn reset_sudoku(
     }
 }
 
+/// Exits the game
+///
+/// If the player has already made progress, they are asked to confirm first
+fn quit_game(
+    mut event_reader: EventReader<QuitGame>,
+    mut confirmation_writer: EventWriter<DiscardConfirmation>,
+    mut exit_writer: EventWriter<AppExit>,
+    puzzle_timer: Res<PuzzleTimer>,
+    query: Query<(&Value, &Fixed), With<Cell>>,
+) {
+    for event in event_reader.iter() {
+        if !event.confirmed && in_progress(query.iter()) {
+            confirmation_writer.send(DiscardConfirmation {
+                action: DiscardingAction::QuitGame,
+                completion: completion(query.iter()),
+                puzzle_timer: puzzle_timer.clone(),
+            });
+            continue;
+        }
+
+        exit_writer.send(AppExit);
+    }
+}
+
 /// "Solves" the given Sudoku by looking up the solution
 fn solve_sudoku(
     mut event_reader: EventReader<SolvePuzzle>,
     complete_puzzle: Res<CompletePuzzle>,
-    mut query: Query<(&Coordinates, &mut Value), With<Cell>>,
+    mut query: Query<(&Coordinates, &mut Value, &Fixed, &mut Provenance), With<Cell>>,
 ) {
     for _ in event_reader.iter() {
-        for (coordinates, mut value) in query.iter_mut() {
-            let correct_value = complete_puzzle
-                .numbers
-                .get(coordinates)
-                .expect("No values found in puzzle for these coordinates");
+        for (coordinates, mut value, is_fixed, mut provenance) in query.iter_mut() {
+            // Puzzles being set or solved on paper have no solution to show
+            let correct_value = match complete_puzzle.numbers.get(coordinates) {
+                Some(correct_value) => correct_value,
+                None => continue,
+            };
+
+            // Cells the player already got right keep their credit
+            if !is_fixed.0 && *value != *correct_value {
+                *provenance = Provenance::Solver;
+            }
 
             // Fill in cells from initial puzzle and mark those cells as fixed
             *value = correct_value.clone();
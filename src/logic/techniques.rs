@@ -0,0 +1,357 @@
+/// Finding the solving techniques a player can use in a position, so that they can be taught
+///
+/// Techniques are found from the candidates of the position alone, the way a player would spot them,
+/// rather than by comparing against the solution
+use crate::logic::{grid::GridSize, notation::Position};
+
+/// A solving technique the trainer can teach
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Technique {
+    /// A cell with only one candidate left
+    NakedSingle,
+    /// A digit with only one possible cell left in a row, column or square
+    HiddenSingle,
+    /// A digit whose possible cells in a square all lie in one row or column,
+    /// so it can be removed from the rest of that row or column
+    PointingPair,
+    /// Two cells in a row, column or square with the same two candidates,
+    /// which can be removed from the other cells there
+    NakedPair,
+    /// Two digits with the same two possible cells in a row, column or square,
+    /// so every other candidate can be removed from those cells
+    HiddenPair,
+}
+
+impl Technique {
+    /// Every technique, from simplest to hardest
+    pub const ALL: [Technique; 5] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::PointingPair,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+    ];
+
+    /// Is this technique only needed once singles have run out
+    pub fn beyond_singles(self) -> bool {
+        !matches!(self, Technique::NakedSingle | Technique::HiddenSingle)
+    }
+
+    /// The id of the message naming this technique
+    pub fn name_id(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "technique-naked-single",
+            Technique::HiddenSingle => "technique-hidden-single",
+            Technique::PointingPair => "technique-pointing-pair",
+            Technique::NakedPair => "technique-naked-pair",
+            Technique::HiddenPair => "technique-hidden-pair",
+        }
+    }
+
+    /// The id of the message explaining a use of this technique,
+    /// given the technique, house, cell, cells, digit, digits and eliminations of the deduction
+    pub fn explanation_id(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "technique-naked-single-explanation",
+            Technique::HiddenSingle => "technique-hidden-single-explanation",
+            Technique::PointingPair => "technique-pointing-pair-explanation",
+            Technique::NakedPair => "technique-naked-pair-explanation",
+            Technique::HiddenPair => "technique-hidden-pair-explanation",
+        }
+    }
+
+    /// The id of the message with a short explanation of what to look for
+    pub fn description_id(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "technique-naked-single-description",
+            Technique::HiddenSingle => "technique-hidden-single-description",
+            Technique::PointingPair => "technique-pointing-pair-description",
+            Technique::NakedPair => "technique-naked-pair-description",
+            Technique::HiddenPair => "technique-hidden-pair-description",
+        }
+    }
+
+    /// Finds the first use of this technique in the position, searching in reading order
+    pub fn find(self, position: &Position) -> Option<Deduction> {
+        match self {
+            Technique::NakedSingle => find_naked_single(position),
+            Technique::HiddenSingle => find_hidden_single(position),
+            Technique::PointingPair => find_pointing_pair(position),
+            Technique::NakedPair => find_naked_pair(position),
+            Technique::HiddenPair => find_hidden_pair(position),
+        }
+    }
+}
+
+/// A row, column or square of a 9x9 board, numbered from 0 in reading order
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum House {
+    Row(usize),
+    Column(usize),
+    Square(usize),
+}
+
+impl House {
+    /// Every row, then every column, then every square
+    pub fn all() -> impl Iterator<Item = House> {
+        (0..9)
+            .map(House::Row)
+            .chain((0..9).map(House::Column))
+            .chain((0..9).map(House::Square))
+    }
+
+    /// The indexes of the cells in the house, in reading order
+    pub fn cells(self) -> Vec<usize> {
+        match self {
+            House::Row(row) => (0..9).map(|column| row * 9 + column).collect(),
+            House::Column(column) => (0..9).map(|row| row * 9 + column).collect(),
+            House::Square(square) => {
+                let (top, left) = (square / 3 * 3, square % 3 * 3);
+                (0..9).map(|k| (top + k / 3) * 9 + left + k % 3).collect()
+            }
+        }
+    }
+}
+
+/// Writes the cell in the notation players use, such as "r4c7" for the cell in row 4 and column 7
+pub fn cell_name(i: usize, grid: GridSize) -> String {
+    let coordinates = grid.coordinates(i);
+    format!("r{}c{}", coordinates.row, coordinates.column)
+}
+
+/// A use of a technique, and what it lets the player fill in or rule out
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Deduction {
+    pub technique: Technique,
+    /// Where the pattern was found
+    pub house: House,
+    /// The cells making up the pattern
+    pub cells: Vec<usize>,
+    /// The digits making up the pattern
+    pub digits: Vec<u8>,
+    /// The digit that can be placed, for singles
+    pub placement: Option<(usize, u8)>,
+    /// The candidates that can be removed, for every other technique
+    pub eliminations: Vec<(usize, u8)>,
+}
+
+/// The simplest single that places a digit in cell `i`, or `None` if the cell needs a harder technique
+///
+/// A naked single is preferred, as it is the easier of the two to spot
+pub fn find_single_for(position: &Position, i: usize) -> Option<Deduction> {
+    if position.digits[i].is_some() {
+        return None;
+    }
+
+    if position.candidates[i].count_ones() == 1 {
+        let num = position.candidates[i].trailing_zeros() as u8;
+        return Some(Deduction {
+            technique: Technique::NakedSingle,
+            house: House::Row(i / 9),
+            cells: vec![i],
+            digits: vec![num],
+            placement: Some((i, num)),
+            eliminations: Vec::new(),
+        });
+    }
+
+    let houses = [
+        House::Row(i / 9),
+        House::Column(i % 9),
+        House::Square(i / 27 * 3 + i % 9 / 3),
+    ];
+    for house in houses.iter().copied() {
+        for num in digits(position.candidates[i]) {
+            if possible_cells(position, house, num).as_deref() == Some(&[i]) {
+                return Some(Deduction {
+                    technique: Technique::HiddenSingle,
+                    house,
+                    cells: vec![i],
+                    digits: vec![num],
+                    placement: Some((i, num)),
+                    eliminations: Vec::new(),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Is the digit still a candidate of the empty cell
+fn is_candidate(position: &Position, i: usize, num: u8) -> bool {
+    position.digits[i].is_none() && position.candidates[i] & 1 << num != 0
+}
+
+/// The empty cells of the house where the digit is still a candidate,
+/// or `None` if the digit has already been placed in the house
+fn possible_cells(position: &Position, house: House, num: u8) -> Option<Vec<usize>> {
+    let cells = house.cells();
+    if cells.iter().any(|&i| position.digits[i] == Some(num)) {
+        return None;
+    }
+    Some(
+        cells
+            .into_iter()
+            .filter(|&i| is_candidate(position, i, num))
+            .collect(),
+    )
+}
+
+/// The digits set in a bitmask of candidates
+fn digits(mask: u32) -> Vec<u8> {
+    (1..=9).filter(|num| mask & 1 << num != 0).collect()
+}
+
+fn find_naked_single(position: &Position) -> Option<Deduction> {
+    (0..81)
+        .find(|&i| position.digits[i].is_none() && position.candidates[i].count_ones() == 1)
+        .map(|i| {
+            let num = position.candidates[i].trailing_zeros() as u8;
+            Deduction {
+                technique: Technique::NakedSingle,
+                house: House::Row(i / 9),
+                cells: vec![i],
+                digits: vec![num],
+                placement: Some((i, num)),
+                eliminations: Vec::new(),
+            }
+        })
+}
+
+fn find_hidden_single(position: &Position) -> Option<Deduction> {
+    for house in House::all() {
+        for num in 1..=9u8 {
+            match possible_cells(position, house, num).as_deref() {
+                // Cells with a single candidate are naked singles, which are easier to spot
+                Some(&[i]) if position.candidates[i].count_ones() > 1 => {
+                    return Some(Deduction {
+                        technique: Technique::HiddenSingle,
+                        house,
+                        cells: vec![i],
+                        digits: vec![num],
+                        placement: Some((i, num)),
+                        eliminations: Vec::new(),
+                    })
+                }
+                _ => (),
+            }
+        }
+    }
+    None
+}
+
+fn find_pointing_pair(position: &Position) -> Option<Deduction> {
+    for square in 0..9 {
+        for num in 1..=9u8 {
+            let cells = match possible_cells(position, House::Square(square), num) {
+                Some(cells) if (2..=3).contains(&cells.len()) => cells,
+                _ => continue,
+            };
+
+            let lines = [House::Row(cells[0] / 9), House::Column(cells[0] % 9)];
+            for line in lines.iter() {
+                let line_cells = line.cells();
+                if !cells.iter().all(|i| line_cells.contains(i)) {
+                    continue;
+                }
+
+                let eliminations: Vec<(usize, u8)> = line_cells
+                    .into_iter()
+                    .filter(|i| !cells.contains(i) && is_candidate(position, *i, num))
+                    .map(|i| (i, num))
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: Technique::PointingPair,
+                        house: House::Square(square),
+                        cells,
+                        digits: vec![num],
+                        placement: None,
+                        eliminations,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_naked_pair(position: &Position) -> Option<Deduction> {
+    for house in House::all() {
+        let cells = house.cells();
+        let pairs: Vec<usize> = cells
+            .iter()
+            .copied()
+            .filter(|&i| position.digits[i].is_none() && position.candidates[i].count_ones() == 2)
+            .collect();
+
+        for (k, &a) in pairs.iter().enumerate() {
+            for &b in pairs[k + 1..].iter() {
+                let mask = position.candidates[a];
+                if position.candidates[b] != mask {
+                    continue;
+                }
+
+                let eliminations: Vec<(usize, u8)> = cells
+                    .iter()
+                    .filter(|&&i| i != a && i != b)
+                    .flat_map(|&i| {
+                        digits(mask)
+                            .into_iter()
+                            .filter(move |num| is_candidate(position, i, *num))
+                            .map(move |num| (i, num))
+                    })
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: Technique::NakedPair,
+                        house,
+                        cells: vec![a, b],
+                        digits: digits(mask),
+                        placement: None,
+                        eliminations,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+fn find_hidden_pair(position: &Position) -> Option<Deduction> {
+    for house in House::all() {
+        for first in 1..=9u8 {
+            let cells = match possible_cells(position, house, first) {
+                Some(cells) if cells.len() == 2 => cells,
+                _ => continue,
+            };
+
+            for second in first + 1..=9 {
+                if possible_cells(position, house, second).as_ref() != Some(&cells) {
+                    continue;
+                }
+
+                let pair_mask = 1 << first | 1 << second;
+                let eliminations: Vec<(usize, u8)> = cells
+                    .iter()
+                    .flat_map(|&i| {
+                        digits(position.candidates[i] & !pair_mask)
+                            .into_iter()
+                            .map(move |num| (i, num))
+                    })
+                    .collect();
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique: Technique::HiddenPair,
+                        house,
+                        cells,
+                        digits: vec![first, second],
+                        placement: None,
+                        eliminations,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,117 @@
+/// Thermometers, whose digits must strictly increase from the bulb to the tip
+use crate::logic::{
+    constraints::{Constraint, LineColor, Overlay},
+    fpuzzles::write_cells,
+};
+use serde_json::{json, Value as JsonValue};
+
+/// A line of cells starting at its bulb, along which each digit is greater than the one before
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Thermometer {
+    /// The cells of the thermometer from the bulb to the tip, by their index in reading order
+    pub cells: Vec<usize>,
+}
+
+/// The digits from `low` to `high` inclusive, where bit `n` is set for digit `n`
+fn digit_range(low: u32, high: u32) -> u32 {
+    (low.max(1)..=high.min(9)).fold(0, |mask, num| mask | 1 << num)
+}
+
+/// The thermometers of a puzzle
+pub struct Thermometers(pub Vec<Thermometer>);
+
+impl Constraint for Thermometers {
+    /// Strictly increasing digits can never repeat
+    fn houses(&self) -> Vec<Vec<usize>> {
+        self.0
+            .iter()
+            .map(|thermometer| thermometer.cells.clone())
+            .collect()
+    }
+
+    /// Pairs of digits without enough room between them to keep increasing are both marked,
+    /// as are digits too small or too large for their place along the thermometer
+    fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = Vec::new();
+        for thermometer in self.0.iter() {
+            let length = thermometer.cells.len();
+            let filled: Vec<(usize, usize, u8)> = thermometer
+                .cells
+                .iter()
+                .enumerate()
+                .filter_map(|(k, &i)| digits[i].map(|num| (k, i, num)))
+                .collect();
+
+            for (a, &(k, i, num)) in filled.iter().enumerate() {
+                let (lowest, highest) = (k + 1, 9 - (length - 1 - k).min(8));
+                if (num as usize) < lowest || num as usize > highest {
+                    violating.push(i);
+                }
+
+                for &(other_k, other_i, other_num) in filled[a + 1..].iter() {
+                    if (other_num as usize) < num as usize + (other_k - k) {
+                        violating.push(i);
+                        violating.push(other_i);
+                    }
+                }
+            }
+        }
+        violating
+    }
+
+    /// Limits each cell to digits above the smallest that could come before it,
+    /// and below the largest that could come after it
+    fn prune(&self, candidates: &mut [u32]) -> bool {
+        for thermometer in self.0.iter() {
+            let cells = &thermometer.cells;
+
+            for pair in cells.windows(2) {
+                let smallest = candidates[pair[0]].trailing_zeros();
+                candidates[pair[1]] &= digit_range(smallest + 1, 9);
+                if candidates[pair[1]] == 0 {
+                    return false;
+                }
+            }
+            for pair in cells.windows(2).rev() {
+                let largest = 31 - candidates[pair[1]].leading_zeros();
+                candidates[pair[0]] &= digit_range(1, largest.saturating_sub(1));
+                if candidates[pair[0]] == 0 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        self.0
+            .iter()
+            .flat_map(|thermometer| {
+                vec![
+                    Overlay::Line {
+                        cells: thermometer.cells.clone(),
+                        color: LineColor::Gray,
+                    },
+                    Overlay::Bulb {
+                        cell: thermometer.cells[0],
+                        filled: true,
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        vec!["rules-thermometer"]
+    }
+
+    /// Each thermometer is written on its own, even where branches of one thermometer share a bulb
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let thermometers = self
+            .0
+            .iter()
+            .map(|thermometer| json!({ "lines": [write_cells(&thermometer.cells)] }))
+            .collect();
+        Some(vec![("thermometer", JsonValue::Array(thermometers))])
+    }
+}
@@ -0,0 +1,47 @@
+/// Tracks how long the player has spent on the current puzzle
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub struct TimerPlugin;
+
+impl Plugin for TimerPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<PuzzleTimer>()
+            .add_system(tick_puzzle_timer.system());
+    }
+}
+
+/// The time spent solving the current puzzle
+#[derive(Default, Clone)]
+pub struct PuzzleTimer {
+    pub elapsed: Duration,
+    /// Time added as a penalty for using hints
+    pub penalty: Duration,
+    /// The number of hints used on this puzzle
+    pub hints_used: u32,
+}
+
+impl PuzzleTimer {
+    /// The time the puzzle has taken, including penalties
+    pub fn total(&self) -> Duration {
+        self.elapsed + self.penalty
+    }
+}
+
+impl ToString for PuzzleTimer {
+    /// Formats the total time as minutes and seconds
+    fn to_string(&self) -> String {
+        format_duration(self.total())
+    }
+}
+
+/// Formats a duration as minutes and seconds, the way times are shown to the player
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Advances the puzzle timer by the time elapsed since the last frame
+fn tick_puzzle_timer(time: Res<Time>, mut puzzle_timer: ResMut<PuzzleTimer>) {
+    puzzle_timer.elapsed += time.delta();
+}
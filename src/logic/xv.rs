@@ -0,0 +1,143 @@
+/// XV sudoku, where an X or a V on the edge between two cells gives the sum of their digits
+use crate::logic::{
+    constraints::{neighbours, supported, Constraint, Overlay},
+    fpuzzles::write_cells,
+};
+use serde_json::{json, Value as JsonValue};
+
+/// The digits either side of an X add up to this
+pub const X_SUM: u8 = 15;
+/// The digits either side of a V add up to this
+pub const V_SUM: u8 = 5;
+
+/// The letter marking an edge, which gives the sum of the digits either side
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum XvKind {
+    X,
+    V,
+}
+
+impl XvKind {
+    pub fn sum(self) -> u8 {
+        match self {
+            XvKind::X => X_SUM,
+            XvKind::V => V_SUM,
+        }
+    }
+}
+
+/// An X or a V on the edge between two neighbouring cells, by their index in reading order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XvPair {
+    pub cells: (usize, usize),
+    pub kind: XvKind,
+}
+
+/// The Xs and Vs of a puzzle
+pub struct XvPairs {
+    pub pairs: Vec<XvPair>,
+    /// Every X and V is given, so neighbours without one can't add up to either sum
+    pub all_given: bool,
+}
+
+impl XvPairs {
+    /// Each pair of cells whose digits are related, along with the letter between them
+    ///
+    /// Pairs without a letter are only related when every letter is given
+    fn related(&self) -> Vec<((usize, usize), Option<XvKind>)> {
+        let mut related: Vec<_> = self
+            .pairs
+            .iter()
+            .map(|pair| (pair.cells, Some(pair.kind)))
+            .collect();
+
+        if self.all_given {
+            for (i, j) in neighbours() {
+                let has_letter = self
+                    .pairs
+                    .iter()
+                    .any(|pair| pair.cells == (i, j) || pair.cells == (j, i));
+                if !has_letter {
+                    related.push(((i, j), None));
+                }
+            }
+        }
+        related
+    }
+
+    /// Can these two digits sit either side of the letter, or either side of an edge without one
+    fn allows(kind: Option<XvKind>, a: u8, b: u8) -> bool {
+        match kind {
+            Some(kind) => a + b == kind.sum(),
+            None => a + b != X_SUM && a + b != V_SUM,
+        }
+    }
+}
+
+impl Constraint for XvPairs {
+    fn violations(&self, digits: &[Option<u8>]) -> Vec<usize> {
+        let mut violating = Vec::new();
+        for ((i, j), kind) in self.related() {
+            if let (Some(a), Some(b)) = (digits[i], digits[j]) {
+                if !XvPairs::allows(kind, a, b) {
+                    violating.push(i);
+                    violating.push(j);
+                }
+            }
+        }
+        violating
+    }
+
+    /// Keeps only the candidates that have a partner across each edge
+    fn prune(&self, candidates: &mut [u32]) -> bool {
+        for ((i, j), kind) in self.related() {
+            candidates[i] = supported(candidates[i], candidates[j], |a, b| {
+                XvPairs::allows(kind, a, b)
+            });
+            candidates[j] = supported(candidates[j], candidates[i], |b, a| {
+                XvPairs::allows(kind, a, b)
+            });
+            if candidates[i] == 0 || candidates[j] == 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn overlays(&self) -> Vec<Overlay> {
+        self.pairs
+            .iter()
+            .map(|pair| Overlay::EdgeLabel {
+                cells: pair.cells,
+                label: format!("{:?}", pair.kind),
+            })
+            .collect()
+    }
+
+    fn rules_ids(&self) -> Vec<&'static str> {
+        if self.all_given {
+            vec!["rules-xv", "rules-xv-all-given"]
+        } else {
+            vec!["rules-xv"]
+        }
+    }
+
+    fn fpuzzles(&self) -> Option<Vec<(&'static str, JsonValue)>> {
+        let letters = self
+            .pairs
+            .iter()
+            .map(|pair| {
+                let letter = match pair.kind {
+                    XvKind::X => "X",
+                    XvKind::V => "V",
+                };
+                json!({ "cells": write_cells(&[pair.cells.0, pair.cells.1]), "value": letter })
+            })
+            .collect();
+        let mut entries = vec![("xv", JsonValue::Array(letters))];
+        if self.all_given {
+            entries.push(("negative", json!(["xv"])));
+        }
+        Some(entries)
+    }
+}
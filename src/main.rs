@@ -1,21 +1,63 @@
 /// A simple but polished Sudoku game, written in Bevy
 use bevy::prelude::*;
 
+mod audio;
+mod campaign;
+mod companion;
+mod debug;
 mod graphics;
 mod input;
+mod localization;
 mod logic;
+mod persistence;
+mod profiles;
+mod settings;
+mod trainer;
+mod tutorial;
 
 fn main() {
     App::build()
         .insert_resource(ClearColor(graphics::BACKGROUND_COLOR))
         .add_plugins(DefaultPlugins)
+        .add_plugin(profiles::ProfilesPlugin)
+        .add_plugin(settings::SettingsPlugin)
+        .add_plugin(localization::LocalizationPlugin)
         .add_startup_system(graphics::spawn_cameras.system())
+        .add_system(graphics::integer_scaling.system())
         .add_plugin(graphics::board::BoardPlugin)
         .add_plugin(graphics::buttons::BoardButtonsPlugin)
+        .add_plugin(graphics::dialog::DialogPlugin)
+        .add_plugin(graphics::hud::HudPlugin)
+        .add_plugin(graphics::celebration::CelebrationPlugin)
+        .add_plugin(graphics::links::LinkDisplayPlugin)
+        .add_plugin(graphics::toasts::ToastPlugin)
+        .add_plugin(graphics::tooltips::TooltipPlugin)
+        .add_plugin(graphics::tutorial::TutorialDisplayPlugin)
+        .add_plugin(graphics::trainer::TrainerDisplayPlugin)
+        .add_plugin(graphics::rules::RulesDisplayPlugin)
+        .add_plugin(graphics::setter::SetterDisplayPlugin)
         .add_plugin(input::InteractionPlugin)
         .add_plugin(logic::board::LogicPlugin)
+        .add_plugin(logic::constraints::ConstraintPlugin)
         .add_plugin(logic::sudoku_generation::GenerationPlugin)
-        .add_system(bevy::input::system::exit_on_esc_system.system())
+        .add_plugin(logic::timer::TimerPlugin)
+        .add_plugin(logic::hints::HintPlugin)
+        .add_plugin(logic::conflicts::ConflictPlugin)
+        .add_plugin(logic::mistakes::MistakePlugin)
+        .add_plugin(logic::records::RecordsPlugin)
+        .add_plugin(logic::stats::StatsPlugin)
+        .add_plugin(logic::collections::CollectionPlugin)
+        .add_plugin(logic::archive::ArchivePlugin)
+        .add_plugin(logic::candidates::CandidatePlugin)
+        .add_plugin(logic::likelihood::LikelihoodPlugin)
+        .add_plugin(logic::links::LinkPlugin)
+        .add_plugin(logic::setter::SetterPlugin)
+        .add_plugin(audio::SoundPlugin)
+        .add_plugin(companion::CompanionPlugin)
+        .add_plugin(campaign::CampaignPlugin)
+        .add_plugin(tutorial::TutorialPlugin)
+        .add_plugin(trainer::TrainerPlugin)
+        .add_plugin(debug::DebugPlugin)
         .run();
 }
 
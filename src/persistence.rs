@@ -0,0 +1,339 @@
+/// Progress kept on disk between sessions, as JSON files stored next to the game
+///
+/// Each file is written along with a checksum, and the last few versions of it are kept as backups,
+/// so that a file that has been damaged can be set aside and recovered from the most recent intact backup
+use crate::graphics::toasts::Toasts;
+use crate::localization::Localization;
+use bevy::{
+    ecs::component::Component,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task},
+};
+use futures_lite::future;
+use serde::{de::DeserializeOwned, Serialize};
+use std::io;
+use std::marker::PhantomData;
+
+/// Resources that are saved to disk whenever they change, and loaded again at startup
+pub trait SaveFile: Serialize + DeserializeOwned + Default + Component {
+    /// The file the resource is saved in
+    const PATH: &'static str;
+    /// What the file holds, as named in warnings when it can't be read or written
+    const DESCRIPTION: &'static str;
+    /// The id of the message naming what the file holds, for telling the player it was damaged
+    const NAME_ID: &'static str;
+    /// Each profile keeps its own copy of the file, in the profile's directory
+    const PER_PROFILE: bool = true;
+}
+
+/// Resource storing the directory the current profile's files are saved in, ending in a slash
+///
+/// The first profile is saved next to the game, where files were kept before there were profiles
+#[derive(Default, Clone, PartialEq, Eq, Debug)]
+pub struct ProfileDirectory(pub String);
+
+/// Where the current profile's copy of the file is saved
+fn file_path<T: SaveFile>(directory: &ProfileDirectory) -> String {
+    if T::PER_PROFILE {
+        format!("{}{}", directory.0, T::PATH)
+    } else {
+        T::PATH.to_string()
+    }
+}
+
+/// Saved resources are loaded before the startup systems that read them
+#[derive(SystemLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+pub struct LoadLabel;
+
+/// The number of earlier versions of each file kept, named like `stats.json.1` from the most recent
+const BACKUPS: usize = 3;
+
+/// How often the saved files are checked against their checksums while the game runs, in seconds
+const INTEGRITY_CHECK_INTERVAL: f32 = 60.0;
+
+/// A 64-bit FNV-1a hash of the file's contents
+///
+/// This only needs to catch accidental damage, such as a file cut short when the game was closed mid-write
+fn checksum(contents: &str) -> u64 {
+    contents.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// The file storing the checksum of the file at `path`
+fn checksum_path(path: &str) -> String {
+    format!("{}.checksum", path)
+}
+
+/// The `n`th most recent backup of the file at `path`, counting from 1
+fn backup_path(path: &str, n: usize) -> String {
+    format!("{}.{}", path, n)
+}
+
+/// Where a damaged file is moved to, so that it is kept for inspection without being read again
+fn quarantine_path(path: &str) -> String {
+    format!("{}.corrupt", path)
+}
+
+/// The contents of the file at `path`, or `None` if there is no file
+///
+/// Fails if the contents don't match their checksum or can't be read as the resource.
+/// Files without a checksum, saved before checksums were written, are trusted
+fn read_verified<T: SaveFile>(path: &str) -> Result<Option<T>, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.to_string()),
+    };
+
+    if let Ok(expected) = std::fs::read_to_string(checksum_path(path)) {
+        if expected.trim() != checksum(&contents).to_string() {
+            return Err("the checksum does not match".to_string());
+        }
+    }
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|error| error.to_string())
+}
+
+/// Writes the contents to `path`, followed by their checksum
+fn write_with_checksum(path: &str, contents: &str) -> io::Result<()> {
+    // A profile's directory is only created once it has something to save
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, contents)?;
+    std::fs::write(checksum_path(path), checksum(contents).to_string())
+}
+
+/// Moves the file and its checksum from one path to another, replacing anything already there
+///
+/// A file without a checksum is moved on its own
+fn move_file(from: &str, to: &str) -> io::Result<()> {
+    std::fs::rename(from, to)?;
+    match std::fs::rename(checksum_path(from), checksum_path(to)) {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            // A stale checksum left at the destination would fail the file moved there
+            let _ = std::fs::remove_file(checksum_path(to));
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+/// Shifts each backup along by one, making the intact file on disk the most recent backup
+///
+/// The oldest backup is dropped, and a damaged file is never backed up
+fn rotate_backups<T: SaveFile>(path: &str) {
+    if !matches!(read_verified::<T>(path), Ok(Some(_))) {
+        return;
+    }
+
+    for n in (1..BACKUPS).rev() {
+        let from = backup_path(path, n);
+        if std::path::Path::new(&from).exists() {
+            if let Err(error) = move_file(&from, &backup_path(path, n + 1)) {
+                warn!(
+                    "Could not keep a backup of the {}: {}",
+                    T::DESCRIPTION,
+                    error
+                );
+            }
+        }
+    }
+    if let Err(error) = move_file(path, &backup_path(path, 1)) {
+        warn!(
+            "Could not keep a backup of the {}: {}",
+            T::DESCRIPTION,
+            error
+        );
+    }
+}
+
+/// Moves the damaged file aside, so that it is neither read nor backed up again
+fn quarantine<T: SaveFile>(path: &str, error: &str) {
+    warn!(
+        "The {} is damaged ({}), moving it to {}",
+        T::DESCRIPTION,
+        error,
+        quarantine_path(path)
+    );
+    if let Err(error) = move_file(path, &quarantine_path(path)) {
+        warn!(
+            "Could not move the damaged {} aside: {}",
+            T::DESCRIPTION,
+            error
+        );
+    }
+}
+
+/// Reads the resource saved at `path`, or `None` if nothing has been saved there yet
+///
+/// A damaged file is quarantined and the most recent intact backup is read instead,
+/// telling the player whether anything could be recovered
+fn load_file<T: SaveFile>(
+    path: &str,
+    toasts: &mut Toasts,
+    localization: &Localization,
+) -> Option<T> {
+    let error = match read_verified(path) {
+        Ok(loaded) => return loaded,
+        Err(error) => error,
+    };
+    quarantine::<T>(path, &error);
+
+    let name = localization.get(T::NAME_ID);
+    for n in 1..=BACKUPS {
+        let backup = backup_path(path, n);
+        match read_verified(&backup) {
+            Ok(Some(loaded)) => {
+                info!("Recovered the {} from {}", T::DESCRIPTION, backup);
+                toasts.push(localization.format("toast-save-recovered", &[("file", name.into())]));
+                return Some(loaded);
+            }
+            Ok(None) => (),
+            Err(error) => warn!("The backup in {} is damaged too: {}", backup, error),
+        }
+    }
+    toasts.push(localization.format("toast-save-lost", &[("file", name.into())]));
+    None
+}
+
+/// Reads the resource saved by previous sessions, if there is one
+pub fn load_json<T: SaveFile>(
+    mut resource: ResMut<T>,
+    directory: Res<ProfileDirectory>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    if let Some(loaded) = load_file(&file_path::<T>(&directory), &mut toasts, &localization) {
+        *resource = loaded;
+    }
+}
+
+/// Replaces the resource with the new profile's copy of it whenever the player switches profiles
+///
+/// Profiles that haven't saved the file yet start again from the default.
+/// Runs before the frame's other systems, so that none of them see one profile's directory with another's resource
+pub fn reload_json<T: SaveFile>(
+    mut resource: ResMut<T>,
+    directory: Res<ProfileDirectory>,
+    mut loaded_from: Local<Option<ProfileDirectory>>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    // The file was loaded from the directory chosen at startup
+    let previous = loaded_from.get_or_insert_with(|| directory.clone());
+    if *previous == *directory {
+        return;
+    }
+
+    *previous = directory.clone();
+    *resource =
+        load_file(&file_path::<T>(&directory), &mut toasts, &localization).unwrap_or_default();
+}
+
+/// Writes the resource to disk whenever it changes, after backing up the version it replaces
+///
+/// The file is compared against rather than skipping the change made by loading it,
+/// so that changes made on the first frame are saved too, and unchanged files aren't written again.
+/// The contents are remembered along with the path they were saved at, which changes with the profile
+pub fn save_json<T: SaveFile>(
+    resource: Res<T>,
+    directory: Res<ProfileDirectory>,
+    mut saved: Local<Option<(String, String)>>,
+) {
+    if !resource.is_changed() {
+        return;
+    }
+
+    let path = file_path::<T>(&directory);
+    let json = to_json(&*resource);
+    if !matches!(&*saved, Some((saved_path, _)) if *saved_path == path) {
+        // A missing file holds the same as the default, which doesn't need saving
+        let on_disk = std::fs::read_to_string(&path).unwrap_or_else(|_| to_json(&T::default()));
+        *saved = Some((path.clone(), on_disk));
+    }
+    let (_, on_disk) = saved.as_mut().unwrap();
+    if *on_disk == json {
+        return;
+    }
+
+    rotate_backups::<T>(&path);
+    match write_with_checksum(&path, &json) {
+        Ok(()) => *on_disk = json,
+        Err(error) => warn!("Could not save the {}: {}", T::DESCRIPTION, error),
+    }
+}
+
+/// The state of the periodic check of one saved file
+pub struct IntegrityCheck<T> {
+    timer: Timer,
+    /// The check underway in the background of the file at the given path,
+    /// which fails with a description of the damage found
+    task: Option<(String, Task<Result<(), String>>)>,
+    resource: PhantomData<T>,
+}
+
+impl<T> Default for IntegrityCheck<T> {
+    fn default() -> Self {
+        IntegrityCheck {
+            timer: Timer::from_seconds(INTEGRITY_CHECK_INTERVAL, true),
+            task: None,
+            resource: PhantomData,
+        }
+    }
+}
+
+/// Checks the saved file against its checksum in the background every so often,
+/// so that damage done while the game is running is noticed before the file is next needed
+///
+/// The resource in memory is still intact, so a damaged file is quarantined and written again from it
+pub fn check_integrity<T: SaveFile>(
+    resource: Res<T>,
+    directory: Res<ProfileDirectory>,
+    mut check: Local<IntegrityCheck<T>>,
+    time: Res<Time>,
+    task_pool: Res<AsyncComputeTaskPool>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    let path = file_path::<T>(&directory);
+    if check.timer.tick(time.delta()).just_finished() && check.task.is_none() {
+        let checked = path.clone();
+        let task = task_pool.spawn(async move { read_verified::<T>(&checked).map(|_| ()) });
+        check.task = Some((path.clone(), task));
+    }
+
+    let (checked, result) = match check.task.as_mut() {
+        Some((checked, task)) => match future::block_on(future::poll_once(task)) {
+            Some(result) => (checked.clone(), result),
+            None => return,
+        },
+        None => return,
+    };
+    check.task = None;
+
+    // The resource in memory only matches the file of the profile still being played
+    if checked != path {
+        return;
+    }
+
+    // The file may have been caught halfway through being saved, so it is read again before being set aside
+    if result.is_err() {
+        if let Err(error) = read_verified::<T>(&path) {
+            quarantine::<T>(&path, &error);
+            if let Err(error) = write_with_checksum(&path, &to_json(&*resource)) {
+                warn!("Could not save the {}: {}", T::DESCRIPTION, error);
+            }
+            toasts.push(localization.format(
+                "toast-save-repaired",
+                &[("file", localization.get(T::NAME_ID).into())],
+            ));
+        }
+    }
+}
+
+fn to_json<T: SaveFile>(resource: &T) -> String {
+    serde_json::to_string_pretty(resource).expect("Saved resources can always be written as JSON")
+}
@@ -0,0 +1,241 @@
+/// Local profiles, so that players sharing a computer each keep their own settings, statistics and saves
+///
+/// Each profile's files are saved in a directory of its own, except for the first profile's,
+/// which are kept next to the game, where they were saved before there were profiles
+use crate::{
+    companion::CompanionMode,
+    graphics::{dialog::ProfileNameDialog, toasts::Toasts},
+    input::buttons::{AddProfile, ChooseProfile, CreateProfile, ShowProfiles},
+    localization::Localization,
+    persistence::{check_integrity, load_json, save_json, ProfileDirectory, SaveFile},
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct ProfilesPlugin;
+
+impl Plugin for ProfilesPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Profiles>()
+            .init_resource::<ProfileDirectory>()
+            .init_resource::<ProfileNameEntry>()
+            // Every other saved file is loaded from the directory of the profile played last
+            .add_startup_system_to_stage(
+                StartupStage::PreStartup,
+                load_json::<Profiles>.system().label(ProfileLabel),
+            )
+            .add_startup_system_to_stage(
+                StartupStage::PreStartup,
+                set_profile_directory.system().after(ProfileLabel),
+            )
+            .add_startup_system(ask_for_profile.system())
+            .add_system(choose_profile.system())
+            .add_system(type_profile_name.system())
+            .add_system(create_profile.system())
+            // The directory only changes once the frame's saves have been made to the previous one,
+            // and the files of the new profile are loaded at the start of the next frame
+            .add_system_to_stage(CoreStage::PostUpdate, set_profile_directory.system())
+            .add_system(save_json::<Profiles>.system())
+            .add_system(check_integrity::<Profiles>.system());
+    }
+}
+
+/// The profile must be known before the files it saved are loaded
+#[derive(SystemLabel, Clone, Hash, Copy, PartialEq, Eq, Debug)]
+struct ProfileLabel;
+
+/// The colors a profile's avatar can be, handed out in turn to new profiles
+pub const AVATAR_COLORS: [Color; 6] = [
+    Color::rgb(0.35, 0.55, 0.9),
+    Color::rgb(0.9, 0.45, 0.4),
+    Color::rgb(0.4, 0.75, 0.45),
+    Color::rgb(0.95, 0.75, 0.3),
+    Color::rgb(0.65, 0.45, 0.85),
+    Color::rgb(0.35, 0.75, 0.8),
+];
+
+/// The longest name a profile can be given, so that it fits on its button
+const NAME_LENGTH: usize = 12;
+
+/// A player sharing the game
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Profile {
+    /// The name the player chose, which may be left empty
+    pub name: String,
+    /// The index of the avatar's color in `AVATAR_COLORS`
+    pub color: usize,
+    /// Names the directory the profile's files are saved in, and is never reused
+    id: u32,
+}
+
+impl Profile {
+    /// The directory the profile's files are saved in
+    fn directory(&self) -> String {
+        match self.id {
+            0 => String::new(),
+            id => format!("profiles/{}/", id),
+        }
+    }
+
+    /// The name shown for the profile, falling back to its place in the list when it was left unnamed
+    pub fn display_name(&self, index: usize, localization: &Localization) -> String {
+        if self.name.is_empty() {
+            localization.format("profile-unnamed", &[("number", (index + 1).into())])
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// The color of the profile's avatar, shown next to its name
+    pub fn avatar_color(&self) -> Color {
+        AVATAR_COLORS[self.color % AVATAR_COLORS.len()]
+    }
+}
+
+/// Resource storing every profile, and which of them is being played
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub profiles: Vec<Profile>,
+    /// The index of the profile being played, which is played again at the next launch
+    pub current: usize,
+    next_id: u32,
+}
+
+impl Default for Profiles {
+    /// A single unnamed profile, which owns the files saved before there were profiles
+    fn default() -> Self {
+        Profiles {
+            profiles: vec![Profile {
+                name: String::new(),
+                color: 0,
+                id: 0,
+            }],
+            current: 0,
+            next_id: 1,
+        }
+    }
+}
+
+impl Profiles {
+    /// The profile being played
+    pub fn current(&self) -> &Profile {
+        // A damaged index falls back to the first profile rather than losing track of every save
+        self.profiles
+            .get(self.current)
+            .or_else(|| self.profiles.first())
+            .expect("There is always at least one profile")
+    }
+}
+
+/// The list of profiles is shared by every player, so it is stored next to the game
+impl SaveFile for Profiles {
+    const PATH: &'static str = "profiles.json";
+    const DESCRIPTION: &'static str = "profiles";
+    const NAME_ID: &'static str = "save-profiles";
+    const PER_PROFILE: bool = false;
+}
+
+/// Resource storing the name typed into the profile name dialog so far
+#[derive(Default)]
+pub struct ProfileNameEntry(pub String);
+
+/// Points the saved files at the directory of the profile being played
+fn set_profile_directory(profiles: Res<Profiles>, mut directory: ResMut<ProfileDirectory>) {
+    let current = profiles.current().directory();
+    if directory.0 != current {
+        directory.0 = current;
+    }
+}
+
+/// Lets the player pick who is playing as the game starts, when there is more than one profile
+fn ask_for_profile(
+    profiles: Res<Profiles>,
+    companion_mode: Res<CompanionMode>,
+    mut event_writer: EventWriter<ShowProfiles>,
+) {
+    if profiles.profiles.len() > 1 && !companion_mode.0 {
+        event_writer.send(ShowProfiles);
+    }
+}
+
+/// Switches to the profile picked from the profile list
+fn choose_profile(
+    mut event_reader: EventReader<ChooseProfile>,
+    mut profiles: ResMut<Profiles>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    if let Some(ChooseProfile(index)) = event_reader.iter().last() {
+        if *index != profiles.current && *index < profiles.profiles.len() {
+            profiles.current = *index;
+            let name = profiles.current().display_name(*index, &localization);
+            toasts.push(localization.format("toast-profile-switched", &[("name", name.into())]));
+        }
+    }
+}
+
+/// Records the letters typed while the profile name dialog is open
+fn type_profile_name(
+    mut char_reader: EventReader<ReceivedCharacter>,
+    mut open_reader: EventReader<AddProfile>,
+    keyboard_input: Res<Input<KeyCode>>,
+    dialog_query: Query<(), With<ProfileNameDialog>>,
+    mut name_entry: ResMut<ProfileNameEntry>,
+) {
+    // Every character must be read, so that letters typed into the board aren't entered once the dialog opens.
+    // Spaces are left out, as they press the focused button
+    let typed: String = char_reader
+        .iter()
+        .map(|event| event.char)
+        .filter(|c| c.is_alphanumeric())
+        .collect();
+
+    if open_reader.iter().count() > 0 {
+        name_entry.0.clear();
+        return;
+    }
+    if dialog_query.iter().next().is_none() {
+        return;
+    }
+
+    let mut name = name_entry.0.clone();
+    name.extend(
+        typed
+            .chars()
+            .take(NAME_LENGTH.saturating_sub(name.chars().count())),
+    );
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        name.pop();
+    }
+    if name != name_entry.0 {
+        name_entry.0 = name;
+    }
+}
+
+/// Adds a profile with the name typed into the profile name dialog, and switches to it
+///
+/// The new profile starts with the default settings and no saved progress
+fn create_profile(
+    mut event_reader: EventReader<CreateProfile>,
+    name_entry: Res<ProfileNameEntry>,
+    mut profiles: ResMut<Profiles>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for _ in event_reader.iter() {
+        let profile = Profile {
+            name: name_entry.0.clone(),
+            color: profiles.profiles.len() % AVATAR_COLORS.len(),
+            id: profiles.next_id,
+        };
+        profiles.next_id += 1;
+        profiles.profiles.push(profile);
+        profiles.current = profiles.profiles.len() - 1;
+
+        let name = profiles
+            .current()
+            .display_name(profiles.current, &localization);
+        toasts.push(localization.format("toast-profile-switched", &[("name", name.into())]));
+    }
+}
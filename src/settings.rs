@@ -0,0 +1,267 @@
+/// Player preferences that change how the game behaves
+use crate::audio::SoundEffect;
+use crate::input::{
+    buttons::{ChooseLanguage, ShowSettings, ToggleSound},
+    focus::{no_menu_open, Focusable},
+    shortcuts::{Shortcut, Shortcuts},
+};
+use crate::localization::Language;
+use crate::persistence::{check_integrity, load_json, reload_json, save_json, LoadLabel, SaveFile};
+use crate::CommonLabels;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Settings>()
+            .add_startup_system(load_json::<Settings>.system().label(LoadLabel))
+            // Letters typed into menus, such as the archive's search, shouldn't change settings
+            .add_system(
+                toggle_settings
+                    .system()
+                    .with_run_criteria(no_menu_open.system()),
+            )
+            .add_system(open_settings.system())
+            .add_system(
+                choose_language
+                    .system()
+                    .label(CommonLabels::Action)
+                    .after(CommonLabels::Input),
+            )
+            .add_system(
+                toggle_sounds
+                    .system()
+                    .label(CommonLabels::Action)
+                    .after(CommonLabels::Input),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, reload_json::<Settings>.system())
+            .add_system(save_json::<Settings>.system())
+            .add_system(check_integrity::<Settings>.system());
+    }
+}
+
+/// Resource storing the player's preferences
+///
+/// Settings added since the file was saved take their default values
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Double-clicking a digit also selects cells with that digit in their marks
+    pub match_marks: bool,
+    /// Placing a digit removes it from the marks of every cell that can see it
+    pub remove_candidates: bool,
+    /// Corner marks are added automatically when a digit has only two possible cells left in a square
+    pub snyder_marks: bool,
+    /// Skips purely decorative rendering, such as the overlay behind dialogs,
+    /// and keeps the UI at a whole-number scale, for weak machines and software renderers
+    ///
+    /// Animations and other rich graphics should be disabled while this is set
+    pub low_spec: bool,
+    /// Animations are skipped, and the state they lead to is shown straight away, for motion-sensitive players
+    ///
+    /// Cells that can't be changed don't flash either, leaving the sound of the blocked edit to explain it
+    pub reduce_motion: bool,
+    /// Digits that have been correctly placed in all nine of their cells can no longer be entered
+    pub disable_completed_digits: bool,
+    /// The game ends after a few wrong digits are placed, for an arcade-style challenge
+    pub limited_mistakes: bool,
+    /// Digits that don't match the solution are highlighted as soon as they are entered
+    pub auto_check: bool,
+    /// Single cells and multi-cell selections each remember their own input mode
+    pub input_mode_per_selection: bool,
+    /// Digits filled in by hints or the solver are tinted, to tell them apart from the player's own
+    pub show_provenance: bool,
+    /// Controls the thickness of the grid and the size of the digits
+    pub display_preset: DisplayPreset,
+    /// The kinds of sound effect that aren't played
+    pub muted_sounds: MutedSounds,
+    /// The language text is shown in
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            match_marks: false,
+            remove_candidates: true,
+            snyder_marks: false,
+            low_spec: false,
+            reduce_motion: false,
+            disable_completed_digits: true,
+            limited_mistakes: false,
+            auto_check: false,
+            input_mode_per_selection: false,
+            show_provenance: false,
+            display_preset: DisplayPreset::Comfortable,
+            muted_sounds: MutedSounds::default(),
+            language: Language::English,
+        }
+    }
+}
+
+/// Each profile keeps its own preferences
+impl SaveFile for Settings {
+    const PATH: &'static str = "settings.json";
+    const DESCRIPTION: &'static str = "settings";
+    const NAME_ID: &'static str = "save-settings";
+}
+
+impl Settings {
+    /// Should animations play, which are skipped both on weak machines and for motion-sensitive players
+    pub fn animated(&self) -> bool {
+        !self.low_spec && !self.reduce_motion
+    }
+}
+
+/// Whether each category of sound effect is muted
+///
+/// QUALITY: give each category a volume instead once Bevy's Audio can play sounds quieter than full volume
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MutedSounds {
+    pub placement: bool,
+    pub erase: bool,
+    pub invalid: bool,
+    pub hint: bool,
+    pub completion: bool,
+}
+
+impl MutedSounds {
+    /// Is this category of sound effect muted
+    pub fn is_muted(&self, effect: SoundEffect) -> bool {
+        use SoundEffect::*;
+        match effect {
+            Placement => self.placement,
+            Erase => self.erase,
+            Invalid => self.invalid,
+            Hint => self.hint,
+            Completion => self.completion,
+        }
+    }
+
+    /// Mutes this category of sound effect if it is playing, or unmutes it if not
+    pub fn toggle(&mut self, effect: SoundEffect) {
+        use SoundEffect::*;
+        let field = match effect {
+            Placement => &mut self.placement,
+            Erase => &mut self.erase,
+            Invalid => &mut self.invalid,
+            Hint => &mut self.hint,
+            Completion => &mut self.completion,
+        };
+        *field = !*field;
+    }
+}
+
+/// Sets of display sizes, chosen to suit different screens
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum DisplayPreset {
+    /// Thin lines and large digits, for small screens
+    Compact,
+    /// The standard appearance
+    Comfortable,
+    /// Heavy lines and larger marks, which survive video compression when streaming or recording
+    Broadcast,
+}
+
+impl DisplayPreset {
+    /// The preset that follows this one when cycling through them
+    pub fn next(self) -> DisplayPreset {
+        use DisplayPreset::*;
+        match self {
+            Compact => Comfortable,
+            Comfortable => Broadcast,
+            Broadcast => Compact,
+        }
+    }
+}
+
+/// Toggles settings using the function keys, and a few letters once those ran out
+fn toggle_settings(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut settings: ResMut<Settings>,
+) {
+    let pressed = |shortcut| shortcuts.just_pressed(shortcut, &keyboard_input);
+
+    if pressed(Shortcut::MatchMarks) {
+        settings.match_marks = !settings.match_marks;
+    }
+
+    if pressed(Shortcut::DisplayPreset) {
+        settings.display_preset = settings.display_preset.next();
+    }
+
+    if pressed(Shortcut::RemoveCandidates) {
+        settings.remove_candidates = !settings.remove_candidates;
+    }
+
+    if pressed(Shortcut::SnyderMarks) {
+        settings.snyder_marks = !settings.snyder_marks;
+    }
+
+    if pressed(Shortcut::LowSpec) {
+        settings.low_spec = !settings.low_spec;
+    }
+
+    if pressed(Shortcut::DisableCompletedDigits) {
+        settings.disable_completed_digits = !settings.disable_completed_digits;
+    }
+
+    if pressed(Shortcut::LimitedMistakes) {
+        settings.limited_mistakes = !settings.limited_mistakes;
+    }
+
+    if pressed(Shortcut::AutoCheck) {
+        settings.auto_check = !settings.auto_check;
+    }
+
+    if pressed(Shortcut::ShowProvenance) {
+        settings.show_provenance = !settings.show_provenance;
+    }
+
+    if pressed(Shortcut::InputModePerSelection) {
+        settings.input_mode_per_selection = !settings.input_mode_per_selection;
+    }
+
+    if pressed(Shortcut::CycleLanguage) {
+        settings.language = settings.language.next();
+    }
+
+    if pressed(Shortcut::ReduceMotion) {
+        settings.reduce_motion = !settings.reduce_motion;
+    }
+}
+
+/// Opens the settings menu when Ctrl + Comma is pressed
+fn open_settings(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    focusable_query: Query<(), With<Focusable>>,
+    mut event_writer: EventWriter<ShowSettings>,
+) {
+    // Don't open the settings on top of another menu
+    if shortcuts.just_pressed(Shortcut::ShowSettings, &keyboard_input)
+        && focusable_query.iter().next().is_none()
+    {
+        event_writer.send(ShowSettings);
+    }
+}
+
+/// Switches to the language picked in the settings menu
+fn choose_language(mut event_reader: EventReader<ChooseLanguage>, mut settings: ResMut<Settings>) {
+    if let Some(event) = event_reader.iter().last() {
+        if settings.language != event.0 {
+            settings.language = event.0;
+        }
+    }
+}
+
+/// Mutes or unmutes the categories of sound effect picked in the settings menu
+fn toggle_sounds(mut event_reader: EventReader<ToggleSound>, mut settings: ResMut<Settings>) {
+    for event in event_reader.iter() {
+        settings.muted_sounds.toggle(event.0);
+    }
+}
@@ -0,0 +1,410 @@
+/// Practice puzzles that need a chosen solving technique, with coaching on how to spot it
+///
+/// The player is given a position where the technique can be used, and the trainer checks the board
+/// until the player has placed the digit or removed the candidates that the technique gives.
+/// Any other candidates removed along the way count against the player's precision
+use crate::graphics::toasts::Toasts;
+use crate::input::{
+    buttons::{CoachPlayer, ShowTechniques, StopTraining, TrainTechnique},
+    focus::Focusable,
+    shortcuts::{Shortcut, Shortcuts},
+};
+use crate::localization::Localization;
+use crate::logic::{
+    board::{marks::Marks, Cell, Coordinates, Value},
+    constraints::ConstraintSet,
+    grid::GridSize,
+    notation::Position,
+    sudoku_generation::{generate_from_seed, ImportPuzzle, InitialPuzzle},
+    techniques::{cell_name, Deduction, House, Technique},
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub struct TrainerPlugin;
+
+impl Plugin for TrainerPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Trainer>()
+            .add_system(open_technique_select.system())
+            .add_system(start_training.system())
+            .add_system(coach_player.system())
+            .add_system(stop_training.system())
+            .add_system(leave_training.system())
+            .add_system(check_technique.system());
+    }
+}
+
+/// How many puzzles are generated in search of one that needs the technique, before giving up
+const TRAINING_RETRIES: u32 = 100;
+
+/// The most detailed coaching available, which spells out the answer
+pub const MAX_COACHING: u8 = 3;
+
+/// Requests for coaching sent more rapidly than this are ignored, so that holding the button gives one step at a time
+const COACHING_THROTTLE: Duration = Duration::from_millis(500);
+
+/// A practice puzzle in progress
+pub struct TrainingSession {
+    pub deduction: Deduction,
+    /// The givens of the practice puzzle, to tell when the player has moved on to another puzzle
+    givens: String,
+    /// How much help the player has asked for, from 0 to `MAX_COACHING`
+    pub coaching: u8,
+    /// Has the player applied the technique
+    pub completed: bool,
+    /// The candidates of each cell when the practice puzzle started, where bit `n` is set for digit `n`
+    start_candidates: Vec<u32>,
+    /// The candidates the player had removed that the technique doesn't eliminate, once they applied it
+    pub wrong_eliminations: usize,
+}
+
+impl TrainingSession {
+    /// The instructions shown to the player, which grow more specific with each request for coaching
+    pub fn message(&self, localization: &Localization) -> String {
+        let deduction = &self.deduction;
+        let technique = localization.get(deduction.technique.name_id());
+        if self.completed {
+            let completed =
+                localization.format("trainer-completed", &[("technique", technique.into())]);
+            // Placing a single's digit leaves nothing to be imprecise about
+            if deduction.placement.is_some() {
+                return completed;
+            }
+            let precision = if self.wrong_eliminations == 0 {
+                localization.get("trainer-precise")
+            } else {
+                let correct = deduction.eliminations.len();
+                let percent = 100 * correct / (correct + self.wrong_eliminations);
+                localization.format(
+                    "trainer-imprecise",
+                    &[
+                        ("wrong", self.wrong_eliminations.into()),
+                        ("precision", percent.into()),
+                    ],
+                )
+            };
+            return format!("{}\n{}", completed, precision);
+        }
+
+        let mut lines = vec![
+            localization.format("trainer-introduction", &[("technique", technique.into())]),
+            localization.get(deduction.technique.description_id()),
+        ];
+        if deduction.technique.beyond_singles() {
+            lines.push(localization.get("trainer-fill-candidates"));
+        }
+        if self.coaching >= 1 {
+            lines.push(localization.format(
+                "trainer-look-in",
+                &[("house", house_name(deduction.house, localization).into())],
+            ));
+        }
+        if self.coaching >= 2 {
+            let cells: Vec<String> = deduction
+                .cells
+                .iter()
+                .map(|i| cell_name(*i, GridSize::Nine))
+                .collect();
+            let digits: Vec<String> = deduction.digits.iter().map(u8::to_string).collect();
+            lines.push(localization.format(
+                "trainer-look-at",
+                &[
+                    ("cells", cells.join(", ").into()),
+                    ("digits", join_list(digits, localization).into()),
+                ],
+            ));
+        }
+        if self.coaching >= MAX_COACHING {
+            match deduction.placement {
+                Some((i, num)) => {
+                    lines.push(localization.format(
+                        "trainer-placement",
+                        &[
+                            ("digit", num.into()),
+                            ("cell", cell_name(i, GridSize::Nine).into()),
+                        ],
+                    ));
+                }
+                None => {
+                    let eliminations = elimination_names(deduction, localization);
+                    lines.push(localization.format(
+                        "trainer-eliminations",
+                        &[("eliminations", eliminations.join(", ").into())],
+                    ));
+                }
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// Explains a use of a technique in a sentence, through the template of its technique,
+/// such as "In row 4, 7 can only go in r4c2."
+///
+/// Deductions are on a 9x9 board, like every technique
+pub fn explain(deduction: &Deduction, localization: &Localization) -> String {
+    let cells: Vec<String> = deduction
+        .cells
+        .iter()
+        .map(|i| cell_name(*i, GridSize::Nine))
+        .collect();
+    let digits: Vec<String> = deduction.digits.iter().map(u8::to_string).collect();
+    let (cell, digit) = deduction.placement.unwrap_or((
+        deduction.cells.first().copied().unwrap_or_default(),
+        deduction.digits.first().copied().unwrap_or_default(),
+    ));
+    let eliminations = elimination_names(deduction, localization);
+
+    localization.format(
+        deduction.technique.explanation_id(),
+        &[
+            (
+                "technique",
+                localization.get(deduction.technique.name_id()).into(),
+            ),
+            ("house", house_name(deduction.house, localization).into()),
+            ("cell", cell_name(cell, GridSize::Nine).into()),
+            ("cells", join_list(cells, localization).into()),
+            ("digit", digit.into()),
+            ("digits", join_list(digits, localization).into()),
+            ("eliminations", join_list(eliminations, localization).into()),
+        ],
+    )
+}
+
+/// Names each candidate the deduction removes, such as "7 from r4c2"
+fn elimination_names(deduction: &Deduction, localization: &Localization) -> Vec<String> {
+    deduction
+        .eliminations
+        .iter()
+        .map(|(i, num)| {
+            localization.format(
+                "trainer-elimination",
+                &[
+                    ("digit", (*num).into()),
+                    ("cell", cell_name(*i, GridSize::Nine).into()),
+                ],
+            )
+        })
+        .collect()
+}
+
+/// Names the house the way the player would, such as "row 4" or "the center square"
+fn house_name(house: House, localization: &Localization) -> String {
+    match house {
+        House::Row(row) => localization.format("house-row", &[("number", (row + 1).into())]),
+        House::Column(column) => {
+            localization.format("house-column", &[("number", (column + 1).into())])
+        }
+        House::Square(square) => localization.format("house-square", &[("square", square.into())]),
+    }
+}
+
+/// Joins the items into a list such as "1, 2 and 3"
+fn join_list(mut items: Vec<String>, localization: &Localization) -> String {
+    let last = match items.pop() {
+        Some(last) => last,
+        None => return String::new(),
+    };
+    if items.is_empty() {
+        return last;
+    }
+    localization.format(
+        "list-and",
+        &[("items", items.join(", ").into()), ("last", last.into())],
+    )
+}
+
+/// Resource storing the practice puzzle being played, if any
+#[derive(Default)]
+pub struct Trainer {
+    pub session: Option<TrainingSession>,
+}
+
+/// Generates puzzles until one has a position where the technique is needed,
+/// returning that position and the use of the technique found in it
+///
+/// Techniques beyond singles are looked for once singles have run out, so that they are really needed
+fn training_position(technique: Technique) -> Option<(Position, Deduction)> {
+    // Techniques are only taught for the rows, columns and squares of classic sudoku
+    let constraints = ConstraintSet::classic();
+    for _ in 0..TRAINING_RETRIES {
+        let (initial, _) = generate_from_seed(rand::random(), &constraints)?;
+        let puzzle = initial.position();
+
+        let start = if technique.beyond_singles() {
+            puzzle.apply_singles(&constraints)
+        } else {
+            puzzle.eliminate(&constraints)
+        };
+        let start = match start {
+            Some(start) => start,
+            None => continue,
+        };
+        // Singles could finish this puzzle, so nothing more is needed
+        if start.digits.iter().all(Option::is_some) {
+            continue;
+        }
+
+        if let Some(deduction) = technique.find(&start) {
+            return Some((start, deduction));
+        }
+    }
+    None
+}
+
+/// Opens the list of techniques to practise when G is pressed
+fn open_technique_select(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    focusable_query: Query<(), With<Focusable>>,
+    mut event_writer: EventWriter<ShowTechniques>,
+) {
+    // Don't open the list on top of another menu
+    if shortcuts.just_pressed(Shortcut::Techniques, &keyboard_input)
+        && focusable_query.iter().next().is_none()
+    {
+        event_writer.send(ShowTechniques);
+    }
+}
+
+/// Starts a practice puzzle for the chosen technique
+fn start_training(
+    mut event_reader: EventReader<TrainTechnique>,
+    mut trainer: ResMut<Trainer>,
+    mut import_writer: EventWriter<ImportPuzzle>,
+    mut toasts: ResMut<Toasts>,
+    localization: Res<Localization>,
+) {
+    for event in event_reader.iter() {
+        let technique = match Technique::ALL.get(event.0) {
+            Some(technique) => *technique,
+            None => continue,
+        };
+
+        match training_position(technique) {
+            Some((start, deduction)) => {
+                let givens = start.to_givens();
+                import_writer.send(ImportPuzzle(givens.clone()));
+                trainer.session = Some(TrainingSession {
+                    deduction,
+                    givens,
+                    coaching: 0,
+                    completed: false,
+                    start_candidates: start.candidates,
+                    wrong_eliminations: 0,
+                });
+            }
+            None => {
+                warn!("Could not find a puzzle that needs a {:?}", technique);
+                toasts.push(localization.format(
+                    "toast-no-training-puzzle",
+                    &[("technique", localization.get(technique.name_id()).into())],
+                ));
+            }
+        }
+    }
+}
+
+/// Gives more specific instructions each time the coaching button is pressed
+fn coach_player(
+    mut event_reader: EventReader<CoachPlayer>,
+    mut trainer: ResMut<Trainer>,
+    mut last_request: Local<Option<Duration>>,
+    time: Res<Time>,
+) {
+    if event_reader.iter().count() == 0 {
+        return;
+    }
+
+    let now = time.time_since_startup();
+    let throttled = matches!(*last_request, Some(last) if now - last < COACHING_THROTTLE);
+    *last_request = Some(now);
+    if throttled {
+        return;
+    }
+
+    if let Some(session) = trainer.session.as_mut() {
+        session.coaching = (session.coaching + 1).min(MAX_COACHING);
+    }
+}
+
+/// Ends the practice session when its stop button is pressed, leaving the puzzle to be finished
+fn stop_training(mut event_reader: EventReader<StopTraining>, mut trainer: ResMut<Trainer>) {
+    if event_reader.iter().count() > 0 {
+        trainer.session = None;
+    }
+}
+
+/// Ends the practice session once a different puzzle is started
+fn leave_training(initial_puzzle: Res<InitialPuzzle>, mut trainer: ResMut<Trainer>) {
+    if !initial_puzzle.is_changed() {
+        return;
+    }
+
+    let givens = initial_puzzle.position().to_givens();
+    if matches!(&trainer.session, Some(session) if session.givens != givens) {
+        trainer.session = None;
+    }
+}
+
+/// Checks whether the player has applied the technique, by placing its digit
+/// or removing its candidates from the center marks of the cells involved,
+/// counting any other candidates removed once it has been applied
+fn check_technique(
+    mut trainer: ResMut<Trainer>,
+    cell_query: Query<(&Coordinates, &Value), With<Cell>>,
+    changed_query: Query<(), (With<Cell>, Changed<Value>)>,
+) {
+    if changed_query.iter().next().is_none() {
+        return;
+    }
+    let session = match &trainer.session {
+        Some(session) if !session.completed => session,
+        _ => return,
+    };
+    let deduction = &session.deduction;
+
+    // Techniques are only taught on 9x9 grids
+    let grid = GridSize::Nine;
+    let mut values = vec![Value::Empty; grid.cell_count()];
+    for (coordinates, value) in cell_query.iter() {
+        if grid.contains(coordinates) {
+            values[grid.index(coordinates)] = value.clone();
+        }
+    }
+
+    let applied = match deduction.placement {
+        Some((i, num)) => values[i] == Value::Filled(num),
+        None => deduction.eliminations.iter().all(|(i, num)| {
+            matches!(&values[*i], Value::Marked(center, _) if !center.is_empty() && !center.contains(*num))
+        }),
+    };
+    if !applied {
+        return;
+    }
+
+    // Cells whose candidates haven't been filled in have had nothing removed
+    let wrong_eliminations = values
+        .iter()
+        .enumerate()
+        .filter_map(|(i, value)| match value {
+            Value::Marked(center, _) if !center.is_empty() => Some((i, center)),
+            _ => None,
+        })
+        .map(|(i, center)| {
+            (1..=grid.digits())
+                .filter(|num| session.start_candidates[i] & 1 << num != 0)
+                .filter(|num| !center.contains(*num))
+                .filter(|num| !deduction.eliminations.contains(&(i, *num)))
+                .count()
+        })
+        .sum();
+
+    // Only mark the trainer as changed when the session is over, so its instructions aren't redrawn needlessly
+    if let Some(session) = trainer.session.as_mut() {
+        session.completed = true;
+        session.wrong_eliminations = wrong_eliminations;
+    }
+}
@@ -0,0 +1,177 @@
+/// A guided tour of the controls, shown the first time the game is launched
+///
+/// The tour is a sequence of steps, each asking the player to do one thing,
+/// and only moves on to the next step once the player has done it
+use crate::{
+    companion::CompanionMode,
+    input::{
+        buttons::{GetHint, SkipTutorial},
+        input_mode::InputMode,
+        shortcuts::{Shortcut, Shortcuts},
+        Selected,
+    },
+    logic::board::{marks::Marks, Cell, Fixed, Value},
+    persistence::{check_integrity, load_json, reload_json, save_json, LoadLabel, SaveFile},
+    CommonLabels,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Tutorial>()
+            .add_startup_system(load_json::<Tutorial>.system().label(LoadLabel))
+            .add_startup_system(start_tutorial.system().after(LoadLabel))
+            .add_system(restart_tutorial.system())
+            .add_system(skip_tutorial.system())
+            // Must check the effects of the player's input once they have been applied
+            .add_system(advance_tutorial.system().after(CommonLabels::Action))
+            .add_system_to_stage(CoreStage::PreUpdate, reload_json::<Tutorial>.system())
+            .add_system(save_json::<Tutorial>.system())
+            .add_system(check_integrity::<Tutorial>.system());
+    }
+}
+
+/// A step of the tutorial, each of which waits for the player to do one thing
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TutorialStep {
+    SelectCell,
+    FillCell,
+    CenterMarkMode,
+    AddCenterMark,
+    CornerMarkMode,
+    AddCornerMark,
+    FillMode,
+    AskForHint,
+    Finish,
+}
+
+impl TutorialStep {
+    /// The step that follows this one, or None once the tutorial is over
+    pub fn next(self) -> Option<TutorialStep> {
+        use TutorialStep::*;
+        match self {
+            SelectCell => Some(FillCell),
+            FillCell => Some(CenterMarkMode),
+            CenterMarkMode => Some(AddCenterMark),
+            AddCenterMark => Some(CornerMarkMode),
+            CornerMarkMode => Some(AddCornerMark),
+            AddCornerMark => Some(FillMode),
+            FillMode => Some(AskForHint),
+            AskForHint => Some(Finish),
+            Finish => None,
+        }
+    }
+
+    /// The id of the message saying what the player is asked to do
+    pub fn instructions_id(self) -> &'static str {
+        use TutorialStep::*;
+        match self {
+            SelectCell => "tutorial-select-cell",
+            FillCell => "tutorial-fill-cell",
+            CenterMarkMode => "tutorial-center-mark-mode",
+            AddCenterMark => "tutorial-add-center-mark",
+            CornerMarkMode => "tutorial-corner-mark-mode",
+            AddCornerMark => "tutorial-add-corner-mark",
+            FillMode => "tutorial-fill-mode",
+            AskForHint => "tutorial-ask-for-hint",
+            Finish => "tutorial-finish",
+        }
+    }
+}
+
+/// Resource storing how far the player has got through the tutorial
+#[derive(Serialize, Deserialize, Default)]
+pub struct Tutorial {
+    /// Has the player finished or skipped the tutorial, so it isn't shown at launch again
+    pub completed: bool,
+    /// The step being shown, if the tutorial is running
+    #[serde(skip)]
+    pub step: Option<TutorialStep>,
+}
+
+/// Whether the tutorial has been seen is stored next to the game, like the puzzle archive
+impl SaveFile for Tutorial {
+    const PATH: &'static str = "tutorial.json";
+    const DESCRIPTION: &'static str = "tutorial progress";
+    const NAME_ID: &'static str = "save-tutorial";
+}
+
+impl Tutorial {
+    fn finish(&mut self) {
+        self.completed = true;
+        self.step = None;
+    }
+}
+
+/// Starts the tutorial unless the player has already been through it
+fn start_tutorial(companion_mode: Res<CompanionMode>, mut tutorial: ResMut<Tutorial>) {
+    // Players solving on paper don't use most of the controls
+    if !tutorial.completed && !companion_mode.0 {
+        tutorial.step = Some(TutorialStep::SelectCell);
+    }
+}
+
+/// Starts the tutorial again from the beginning when / is pressed
+fn restart_tutorial(
+    keyboard_input: Res<Input<KeyCode>>,
+    shortcuts: Res<Shortcuts>,
+    mut tutorial: ResMut<Tutorial>,
+) {
+    if shortcuts.just_pressed(Shortcut::RestartTutorial, &keyboard_input) && tutorial.step.is_none()
+    {
+        tutorial.step = Some(TutorialStep::SelectCell);
+    }
+}
+
+/// Ends the tutorial early when its skip button is pressed
+fn skip_tutorial(mut event_reader: EventReader<SkipTutorial>, mut tutorial: ResMut<Tutorial>) {
+    if event_reader.iter().count() > 0 && tutorial.step.is_some() {
+        tutorial.finish();
+    }
+}
+
+/// Moves on to the next step once the player has done what the current step asks
+fn advance_tutorial(
+    mut tutorial: ResMut<Tutorial>,
+    input_mode: Res<InputMode>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut hint_reader: EventReader<GetHint>,
+    selected_query: Query<(), With<Selected>>,
+    cell_query: Query<(&Value, &Fixed), With<Cell>>,
+) {
+    // Hints asked for before their step don't count
+    let hinted = hint_reader.iter().count() > 0;
+    let step = match tutorial.step {
+        Some(step) => step,
+        None => return,
+    };
+
+    use TutorialStep::*;
+    let done = match step {
+        SelectCell => selected_query.iter().next().is_some(),
+        FillCell => cell_query
+            .iter()
+            .any(|(value, is_fixed)| !is_fixed.0 && matches!(value, Value::Filled(_))),
+        CenterMarkMode => *input_mode == InputMode::CenterMark,
+        AddCenterMark => cell_query
+            .iter()
+            .any(|(value, _)| matches!(value, Value::Marked(center, _) if !center.is_empty())),
+        CornerMarkMode => *input_mode == InputMode::CornerMark,
+        AddCornerMark => cell_query
+            .iter()
+            .any(|(value, _)| matches!(value, Value::Marked(_, corner) if !corner.is_empty())),
+        FillMode => *input_mode == InputMode::Fill,
+        AskForHint => hinted,
+        Finish => keyboard_input.just_pressed(KeyCode::Return),
+    };
+
+    if done {
+        match step.next() {
+            Some(next) => tutorial.step = Some(next),
+            None => tutorial.finish(),
+        }
+    }
+}